@@ -18,7 +18,7 @@
 
 mod common;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use ratatui::style::{Modifier, Style};
@@ -162,8 +162,9 @@ fn bench_selected_line_rendering(c: &mut Criterion) {
                     black_box(render_cached_lines(
                         black_box(cache),
                         0..cache.lines.len(),
-                        selected,
+                        selected..=selected,
                         comments,
+                        &HashMap::new(),
                     ))
                 });
             },
@@ -235,8 +236,9 @@ fn bench_visible_range_processing(c: &mut Criterion) {
                     black_box(render_cached_lines(
                         black_box(cache),
                         visible_start..visible_end,
-                        scroll_offset,
+                        scroll_offset..=scroll_offset,
                         comments,
+                        &HashMap::new(),
                     ))
                 });
             },
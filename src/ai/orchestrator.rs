@@ -1,13 +1,13 @@
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::time::timeout;
 use tracing::warn;
 
 use crate::config::AiConfig;
-use crate::github;
-use crate::github::comment::{fetch_discussion_comments, fetch_review_comments};
+use crate::github::{self, GitHubApi};
 
 use super::adapter::{
     AgentAdapter, Context, ExternalComment, ReviewAction, RevieweeOutput, RevieweeStatus,
@@ -113,6 +113,7 @@ pub struct Orchestrator {
     repo: String,
     pr_number: u32,
     config: AiConfig,
+    github: Arc<dyn GitHubApi>,
     reviewer_adapter: Box<dyn AgentAdapter>,
     reviewee_adapter: Box<dyn AgentAdapter>,
     session: RallySession,
@@ -130,6 +131,7 @@ impl Orchestrator {
         repo: &str,
         pr_number: u32,
         config: AiConfig,
+        github: Arc<dyn GitHubApi>,
         event_sender: mpsc::Sender<RallyEvent>,
         command_receiver: Option<mpsc::Receiver<OrchestratorCommand>>,
     ) -> Result<Self> {
@@ -140,13 +142,15 @@ impl Orchestrator {
         reviewer_adapter.set_event_sender(event_sender.clone());
         reviewee_adapter.set_event_sender(event_sender.clone());
 
-        let session = RallySession::new(repo, pr_number);
+        // ホスト違いの同名リポジトリでセッションディレクトリが衝突しないようにする
+        let session = RallySession::new(&github::qualified_repo(github.host(), repo), pr_number);
         let prompt_loader = PromptLoader::new(&config);
 
         Ok(Self {
             repo: repo.to_string(),
             pr_number,
             config,
+            github,
             reviewer_adapter,
             reviewee_adapter,
             session,
@@ -221,7 +225,7 @@ impl Orchestrator {
 
             // Store the review for later use
             if let Err(e) = write_history_entry(
-                &self.repo,
+                &self.session.repo,
                 self.pr_number,
                 iteration,
                 &HistoryEntryType::Review(review_result.clone()),
@@ -321,7 +325,7 @@ impl Orchestrator {
             };
 
             if let Err(e) = write_history_entry(
-                &self.repo,
+                &self.session.repo,
                 self.pr_number,
                 iteration,
                 &HistoryEntryType::Fix(fix_result.clone()),
@@ -400,7 +404,7 @@ impl Orchestrator {
                                     Ok(output) => {
                                         // Write history entry for the follow-up fix
                                         if let Err(e) = write_history_entry(
-                                            &self.repo,
+                                            &self.session.repo,
                                             self.pr_number,
                                             iteration,
                                             &HistoryEntryType::Fix(output.clone()),
@@ -508,7 +512,7 @@ impl Orchestrator {
                                         Ok(output) => {
                                             // Write history entry for the follow-up fix
                                             if let Err(e) = write_history_entry(
-                                                &self.repo,
+                                                &self.session.repo,
                                                 self.pr_number,
                                                 iteration,
                                                 &HistoryEntryType::Fix(output.clone()),
@@ -777,46 +781,48 @@ impl Orchestrator {
         // Add prefix to summary
         let summary_with_prefix = format!("[AI Rally - Reviewer]\n\n{}", review.summary);
 
-        // Post summary comment using gh pr review
+        // Post summary comment as a review
         // If approve fails (e.g., can't approve own PR), fall back to comment
-        let result =
-            github::submit_review(&self.repo, self.pr_number, app_action, &summary_with_prefix)
-                .await;
+        let result = self
+            .github
+            .submit_review(&self.repo, self.pr_number, app_action, &summary_with_prefix)
+            .await;
 
         if result.is_err() && matches!(app_action_for_fallback, crate::app::ReviewAction::Approve) {
             warn!("Approve failed, falling back to comment");
-            github::submit_review(
-                &self.repo,
-                self.pr_number,
-                crate::app::ReviewAction::Comment,
-                &summary_with_prefix,
-            )
-            .await?;
+            self.github
+                .submit_review(
+                    &self.repo,
+                    self.pr_number,
+                    crate::app::ReviewAction::Comment,
+                    &summary_with_prefix,
+                )
+                .await?;
         } else {
             result?;
         }
 
-        // Post inline comments with rate limit handling
+        // Post inline comments (the GitHub client queues writes and backs off on rate limits)
         for comment in &review.comments {
             // Add prefix to inline comment
             let body_with_prefix = format!("[AI Rally - Reviewer]\n\n{}", comment.body);
-            if let Err(e) = github::create_review_comment(
-                &self.repo,
-                self.pr_number,
-                &context.head_sha,
-                &comment.path,
-                comment.line,
-                &body_with_prefix,
-            )
-            .await
+            if let Err(e) = self
+                .github
+                .create_review_comment(
+                    &self.repo,
+                    self.pr_number,
+                    &context.head_sha,
+                    &comment.path,
+                    github::CommentRange::single(comment.line),
+                    &body_with_prefix,
+                )
+                .await
             {
                 warn!(
                     "Failed to post inline comment on {}:{}: {}",
                     comment.path, comment.line, e
                 );
             }
-            // Rate limit mitigation: small delay between API calls
-            tokio::time::sleep(Duration::from_millis(100)).await;
         }
 
         Ok(())
@@ -841,13 +847,14 @@ impl Orchestrator {
         );
 
         // Post as a comment (not a review)
-        github::submit_review(
-            &self.repo,
-            self.pr_number,
-            crate::app::ReviewAction::Comment,
-            &comment_body,
-        )
-        .await?;
+        self.github
+            .submit_review(
+                &self.repo,
+                self.pr_number,
+                crate::app::ReviewAction::Comment,
+                &comment_body,
+            )
+            .await?;
 
         Ok(())
     }
@@ -857,7 +864,11 @@ impl Orchestrator {
         let mut comments = Vec::new();
 
         // Fetch review comments (inline comments on diff)
-        if let Ok(review_comments) = fetch_review_comments(&self.repo, self.pr_number).await {
+        if let Ok(review_comments) = self
+            .github
+            .fetch_review_comments(&self.repo, self.pr_number)
+            .await
+        {
             for c in review_comments {
                 if is_bot_user(&c.user.login) {
                     comments.push(ExternalComment {
@@ -871,7 +882,11 @@ impl Orchestrator {
         }
 
         // Fetch discussion comments (general PR comments)
-        if let Ok(discussion) = fetch_discussion_comments(&self.repo, self.pr_number).await {
+        if let Ok(discussion) = self
+            .github
+            .fetch_discussion_comments(&self.repo, self.pr_number)
+            .await
+        {
             for c in discussion {
                 if is_bot_user(&c.user.login) {
                     comments.push(ExternalComment {
@@ -895,7 +910,7 @@ impl Orchestrator {
     /// This update is for when the user manually pushes between iterations,
     /// or when external tools/CI update the PR branch.
     async fn update_head_sha(&mut self) -> Result<()> {
-        let pr = github::fetch_pr(&self.repo, self.pr_number).await?;
+        let pr = self.github.fetch_pr(&self.repo, self.pr_number).await?;
         if let Some(ref mut ctx) = self.context {
             ctx.head_sha = pr.head.sha.clone();
         }
@@ -984,7 +999,7 @@ impl Orchestrator {
         }
 
         // Fallback to GitHub API
-        github::fetch_pr_diff(&self.repo, self.pr_number).await
+        self.github.fetch_pr_diff(&self.repo, self.pr_number).await
     }
 
     // For debugging and session inspection
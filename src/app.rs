@@ -8,6 +8,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::io::Stdout;
+use std::sync::Arc;
 use tokio::sync::mpsc;
 use tokio::task::AbortHandle;
 
@@ -15,12 +16,22 @@ use crate::ai::orchestrator::{OrchestratorCommand, RallyEvent};
 use crate::ai::{Context, Orchestrator, RallyState};
 use crate::cache::{PrCacheKey, PrData, SessionCache};
 use crate::config::Config;
+use crate::diff::{DiffSide, LineType};
+use crate::editor::CommitMessage;
+use crate::github::checks::{AnnotationLevel, CheckAnnotation, CheckRun, Checks};
 use crate::github::comment::{DiscussionComment, ReviewComment};
-use crate::github::{self, ChangedFile, PrStateFilter, PullRequest, PullRequestSummary};
+use crate::github::metadata::PrMetadata;
+use crate::github::reaction::{ReactionKind, ReactionTarget};
+use crate::github::{
+    self, ChangedFile, CommentRange, DraftComment, GitHubApi, MergeMethod, PrCommit, PrListQuery,
+    PrStateFilter, PullRequest, PullRequestSummary, RetryPolicy, ViewedFiles, MAX_CHANGED_FILES,
+    MAX_COMPARE_FILES,
+};
 use crate::keybinding::{
     event_to_keybinding, KeyBinding, KeySequence, SequenceMatch, SEQUENCE_TIMEOUT,
 };
-use crate::loader::{CommentSubmitResult, DataLoadResult};
+use crate::loader::{CommentSubmitResult, DataLoadResult, LoadError};
+use crate::suggestion::Suggestion;
 use crate::syntax::ParserPool;
 use crate::ui;
 use crate::ui::text_area::{TextArea, TextAreaAction};
@@ -38,8 +49,18 @@ const MAX_HIGHLIGHTED_CACHE_ENTRIES: usize = 50;
 /// 大規模PRで全ファイルをクローンしないよう制限。
 const MAX_PREFETCH_FILES: usize = 50;
 
+/// 読み込みエラーを自動で再試行する最大回数（以降は `r` で手動再試行）
+const MAX_LOAD_AUTO_RETRIES: u32 = 3;
+
 /// PR番号と紐づいたレシーバー（発信元PRを追跡してクロスPRキャッシュ汚染を防止）
 type PrReceiver<T> = Option<(u32, mpsc::Receiver<T>)>;
+/// アノテーション取得結果（対象チェックのインデックス付き）
+type CheckAnnotationsResult = (usize, Result<Vec<CheckAnnotation>, String>);
+/// コミット範囲の変更ファイル取得結果（要求した範囲付き）
+/// コミット範囲の変更ファイル（GitHub の上限で打ち切られたか付き）
+type CommitFilesResult = (CommitScope, Result<(Vec<ChangedFile>, bool), String>);
+/// Viewed 切り替えの結果（パス・切り替え後の状態付き）
+type ViewedToggleResult = (String, bool, Result<(), String>);
 
 /// コメントのdiff内位置を表す構造体
 #[derive(Debug, Clone)]
@@ -48,6 +69,25 @@ pub struct CommentPosition {
     pub comment_index: usize,
 }
 
+/// コミット単位・コミット範囲で diff を表示しているときの範囲
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitScope {
+    /// 範囲の最初のコミットの親（比較元）
+    pub base_sha: String,
+    pub head_sha: String,
+    /// 表示用（"abc1234" / "abc1234..def5678"）
+    pub label: String,
+    /// 「前回のレビュー以降の変更」モードか
+    pub since_review: bool,
+}
+
+/// CI アノテーションの diff 内位置
+#[derive(Debug, Clone)]
+pub struct AnnotationPosition {
+    pub diff_line_index: usize,
+    pub annotation_index: usize,
+}
+
 /// ジャンプ履歴の1エントリ（Go to Definition / Jump Back 用）
 #[derive(Debug, Clone)]
 pub struct JumpLocation {
@@ -65,6 +105,99 @@ pub struct SymbolPopupState {
     pub selected: usize,
 }
 
+/// リアクション選択ポップアップの状態
+#[derive(Debug, Clone)]
+pub struct ReactionPopupState {
+    /// リアクションを付ける対象
+    pub target: ReactionTarget,
+    /// 選択中のインデックス（`ReactionKind::ALL` の位置）
+    pub selected: usize,
+}
+
+/// メタデータ画面のピッカーの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataPickerKind {
+    Labels,
+    Reviewers,
+}
+
+/// マージ方法の選択ダイアログの状態
+#[derive(Debug, Clone, Default)]
+pub struct MergeDialogState {
+    /// 選択中のマージ方法（`MergeMethod::ALL` の位置）
+    pub selected: usize,
+}
+
+/// 確認待ちのマージ操作
+#[derive(Debug, Clone)]
+pub struct MergeRequest {
+    pub method: MergeMethod,
+    /// 今すぐマージせず、自動マージを有効にする
+    pub auto_merge: bool,
+    /// None の場合は GitHub のデフォルトメッセージ
+    pub message: Option<CommitMessage>,
+}
+
+/// メタデータ画面からの更新操作
+#[derive(Debug, Clone)]
+enum MetadataUpdate {
+    Label {
+        name: String,
+        add: bool,
+    },
+    Reviewer {
+        login: String,
+        add: bool,
+    },
+    Draft {
+        pull_request_id: String,
+        draft: bool,
+    },
+}
+
+/// ラベル・レビュワーを選ぶファジーピッカーの状態
+#[derive(Debug, Clone)]
+pub struct MetadataPickerState {
+    pub kind: MetadataPickerKind,
+    pub query: String,
+    /// 候補（取得中は None）
+    pub candidates: Option<Result<Vec<String>, String>>,
+    /// 絞り込み後の一覧での選択位置
+    pub selected: usize,
+}
+
+impl MetadataPickerState {
+    fn new(kind: MetadataPickerKind) -> Self {
+        Self {
+            kind,
+            query: String::new(),
+            candidates: None,
+            selected: 0,
+        }
+    }
+
+    /// クエリで絞り込んだ候補（スコア順）
+    pub fn filtered(&self) -> Vec<&str> {
+        match self.candidates {
+            Some(Ok(ref candidates)) => crate::fuzzy::fuzzy_filter(&self.query, candidates)
+                .into_iter()
+                .map(|i| candidates[i].as_str())
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// CI チェック画面で開いている詳細ペインの状態
+#[derive(Debug, Clone)]
+pub struct CheckDetailState {
+    /// 対象チェックのインデックス（Check Run → Commit Status の通し番号）
+    pub index: usize,
+    /// アノテーション（取得中は None）
+    pub annotations: Option<Result<Vec<CheckAnnotation>, String>>,
+    pub scroll: usize,
+}
+
 /// インターン済みの Span（アロケーション削減）
 ///
 /// 文字列をインターナーに格納し、4バイトの Spur で参照することで
@@ -118,7 +251,22 @@ pub fn hash_string(s: &str) -> u64 {
 #[derive(Debug, Clone)]
 pub struct LineInputContext {
     pub file_index: usize,
-    pub line_number: u32,
+    pub range: CommentRange,
+}
+
+/// PR レビュー本文をレビューコメント一覧に混ぜる際の疑似パス
+const PR_REVIEW_PATH: &str = "[PR Review]";
+
+/// 投稿結果を待っている楽観的挿入中のディスカッションコメントの ID
+const OPTIMISTIC_COMMENT_ID: u64 = 0;
+
+/// 投稿済みコメント（編集・削除の対象）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostedComment {
+    /// インラインのレビューコメント
+    Review(u64),
+    /// ディスカッション（会話タブ）のコメント
+    Discussion(u64),
 }
 
 /// 統一入力モード
@@ -134,6 +282,18 @@ pub enum InputMode {
         reply_to_user: String,
         reply_to_body: String,
     },
+    /// 保留中レビューのコメントを編集
+    EditDraft {
+        index: usize,
+    },
+    /// PR の会話タブへの新規コメント
+    DiscussionComment,
+    /// 投稿済みの自分のコメントを編集
+    EditComment {
+        target: PostedComment,
+        /// 表示用の位置（`path:line` または `Discussion`）
+        location: String,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -147,6 +307,9 @@ pub enum AppState {
     AiRally,
     SplitViewFileList,
     SplitViewDiff,
+    Checks,
+    CommitList,
+    Metadata,
 }
 
 /// Variant for diff view handling (fullscreen vs split pane)
@@ -256,6 +419,34 @@ pub enum CommentTab {
     #[default]
     Review,
     Discussion,
+    Pending,
+}
+
+impl CommentTab {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Review => Self::Discussion,
+            Self::Discussion => Self::Pending,
+            Self::Pending => Self::Review,
+        }
+    }
+
+    pub fn prev(self) -> Self {
+        match self {
+            Self::Review => Self::Pending,
+            Self::Discussion => Self::Review,
+            Self::Pending => Self::Discussion,
+        }
+    }
+}
+
+/// ローカルに蓄積中の保留レビュー（GitHub の "Start a review" 相当）
+///
+/// 保留中はインラインコメントを即時投稿せずここに積み、Approve/Request changes/
+/// Comment のレビュー送信時にまとめて送る。
+#[derive(Debug, Clone, Default)]
+pub struct PendingReview {
+    pub comments: Vec<DraftComment>,
 }
 
 /// PRデータの読み込み状態。
@@ -270,7 +461,7 @@ pub enum DataState {
         pr: Box<PullRequest>,
         files: Vec<ChangedFile>,
     },
-    Error(String),
+    Error(LoadError),
 }
 
 pub struct App {
@@ -285,7 +476,14 @@ pub struct App {
     pub pr_list_scroll_offset: usize,
     pub pr_list_loading: bool,
     pub pr_list_has_more: bool,
-    pub pr_list_state_filter: PrStateFilter,
+    /// 追加ロードで続きを取得するためのカーソル
+    pr_list_cursor: Option<String>,
+    /// 条件に一致する PR の総数（取得できた場合）
+    pr_list_total: Option<u32>,
+    /// 状態フィルタ・検索修飾子・並び順
+    pub pr_list_query: PrListQuery,
+    /// 検索バーの入力中テキスト（Some の間はキー入力を検索バーに送る）
+    pub pr_list_search_input: Option<String>,
     /// PR一覧から開始したかどうか（戻り先判定用）
     pub started_from_pr_list: bool,
     pr_list_receiver: Option<mpsc::Receiver<Result<github::PrListPage, String>>>,
@@ -305,6 +503,8 @@ pub struct App {
     /// 統一入力テキストエリア
     pub input_text_area: TextArea,
     pub config: Config,
+    /// GitHub API クライアント（`[github] backend` で選択）
+    github: Arc<dyn GitHubApi>,
     pub should_quit: bool,
     // Review comments (inline comments + reviews)
     pub review_comments: Option<Vec<ReviewComment>>,
@@ -340,6 +540,9 @@ pub struct App {
     // cross-PR cache contamination when the user switches PRs mid-flight.
     data_receiver: PrReceiver<DataLoadResult>,
     retry_sender: Option<mpsc::Sender<()>>,
+    /// 読み込みエラーの自動再試行の予定時刻と、連続で自動再試行した回数
+    load_retry_at: Option<Instant>,
+    load_auto_retries: u32,
     comment_receiver: PrReceiver<Result<Vec<ReviewComment>, String>>,
     diff_cache_receiver: Option<mpsc::Receiver<DiffCache>>,
     prefetch_receiver: Option<mpsc::Receiver<DiffCache>>,
@@ -374,6 +577,92 @@ pub struct App {
     pub symbol_popup: Option<SymbolPopupState>,
     /// インメモリセッションキャッシュ
     pub session_cache: SessionCache,
+    /// PRごとの保留レビュー（存在する間はコメントを即時投稿しない）
+    pending_reviews: HashMap<PrCacheKey, PendingReview>,
+    pub selected_pending_comment: usize,
+    pub pending_comment_list_scroll_offset: usize,
+    /// ビジュアル選択の起点行（diff上のインデックス、選択中のみ Some）
+    pub visual_anchor: Option<usize>,
+    /// 変更ファイル一覧が GitHub の上限（3000 件）で打ち切られているか
+    pub files_truncated: bool,
+    /// 折りたたみ状態を既定（解決済みは折りたたみ）から反転したスレッドの先頭コメント ID
+    collapse_toggled_threads: HashSet<u64>,
+    /// スレッドの解決・解除の結果（切り替え後に解決済みか）
+    thread_resolve_receiver: PrReceiver<(bool, Result<(), String>)>,
+    /// 認証中ユーザーのログイン名（自分のコメントだけ編集・削除できる）
+    viewer_login: Option<String>,
+    viewer_receiver: Option<mpsc::Receiver<Result<String, String>>>,
+    /// 削除確認中のコメント（y で削除、それ以外のキーでキャンセル）
+    pub confirm_delete: Option<PostedComment>,
+    /// リアクション選択ポップアップ
+    pub reaction_popup: Option<ReactionPopupState>,
+    /// 会話タブへのコメント投稿結果（楽観的挿入の確定 / ロールバック用）
+    discussion_post_receiver: PrReceiver<Result<DiscussionComment, String>>,
+    /// head コミットの CI 結果（Check Run / Commit Status）
+    pub checks: Option<Checks>,
+    pub checks_loading: bool,
+    pub checks_error: Option<String>,
+    checks_receiver: PrReceiver<Result<Checks, String>>,
+    pub selected_check: usize,
+    /// チェック画面の詳細ペイン（開いている間は j/k でスクロール）
+    pub check_detail: Option<CheckDetailState>,
+    check_annotations_receiver: PrReceiver<CheckAnnotationsResult>,
+    /// head コミットの全 Check Run のアノテーション（diff 上に重ねて表示する）
+    pub diff_annotations: Vec<CheckAnnotation>,
+    diff_annotations_receiver: PrReceiver<Result<Vec<CheckAnnotation>, String>>,
+    /// 現在のファイルのアノテーション位置（diff 行順）
+    pub file_annotation_positions: Vec<AnnotationPosition>,
+    /// アノテーションのある diff 行と、その行で最も重いレベル
+    pub file_annotation_lines: HashMap<usize, AnnotationLevel>,
+    /// PR のコミット一覧（コミット単位でのレビュー用）
+    pub pr_commits: Option<Vec<PrCommit>>,
+    pub pr_commits_loading: bool,
+    pr_commits_receiver: PrReceiver<Result<Vec<PrCommit>, String>>,
+    /// コミット一覧の選択行（0 行目は「全変更」）
+    pub selected_commit: usize,
+    /// コミット一覧での範囲選択の起点行
+    pub commit_range_anchor: Option<usize>,
+    /// 表示中のコミット範囲（None は PR 全体の diff）
+    pub commit_scope: Option<CommitScope>,
+    pub commit_files_loading: bool,
+    commit_files_receiver: PrReceiver<CommitFilesResult>,
+    /// 自分が最後にレビューを送信した時点の head（PR ごと）
+    last_reviewed_shas: HashMap<PrCacheKey, String>,
+    last_reviewed_sha_receiver: PrReceiver<Result<Option<String>, String>>,
+    /// GitHub の「Viewed」チェック状態（None は未取得）
+    pub viewed_files: Option<ViewedFiles>,
+    viewed_files_receiver: PrReceiver<Result<ViewedFiles, String>>,
+    viewed_toggle_receiver: PrReceiver<ViewedToggleResult>,
+    /// レビュワー・ラベルなどのメタデータ
+    pub pr_metadata: Option<PrMetadata>,
+    pub pr_metadata_loading: bool,
+    pr_metadata_receiver: PrReceiver<Result<PrMetadata, String>>,
+    /// メタデータ更新中（更新後のメタデータを受け取る）
+    pub pr_metadata_updating: bool,
+    pr_metadata_update_receiver: PrReceiver<Result<PrMetadata, String>>,
+    pub metadata_picker: Option<MetadataPickerState>,
+    metadata_picker_receiver: PrReceiver<Result<Vec<String>, String>>,
+    pub merge_dialog: Option<MergeDialogState>,
+    /// y で実行するマージ操作（確認待ち）
+    pub confirm_merge: Option<MergeRequest>,
+    pub merging: bool,
+    merge_receiver: PrReceiver<Result<String, String>>,
+    merge_status_receiver: PrReceiver<Result<PullRequest, String>>,
+    /// まとめてコミットする suggestion のコメント ID
+    pub suggestion_batch: Vec<u64>,
+    /// l / g で適用先を選ぶ suggestion（確認待ち）
+    pub confirm_suggestions: Option<Vec<Suggestion>>,
+    pub applying_suggestions: bool,
+    suggestion_apply_receiver: PrReceiver<Result<SuggestionApplied, String>>,
+}
+
+/// suggestion の適用結果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuggestionApplied {
+    /// `working_dir` にコミットした（短縮 SHA）
+    Local(String),
+    /// PR の head ブランチにコミットした（SHA）
+    Remote(String),
 }
 
 impl App {
@@ -395,7 +684,9 @@ impl App {
             pr_list_scroll_offset: 0,
             pr_list_loading: false,
             pr_list_has_more: false,
-            pr_list_state_filter: PrStateFilter::default(),
+            pr_list_cursor: None,
+            pr_list_query: PrListQuery::default(),
+            pr_list_search_input: None,
             started_from_pr_list: false,
             pr_list_receiver: None,
             diff_view_return_state: AppState::FileList,
@@ -408,6 +699,7 @@ impl App {
             scroll_offset: 0,
             input_mode: None,
             input_text_area: TextArea::with_submit_key(config.keybindings.submit.clone()),
+            github: github::new_client(&config.github),
             config,
             should_quit: false,
             review_comments: None,
@@ -451,6 +743,61 @@ impl App {
             pending_since: None,
             symbol_popup: None,
             session_cache: SessionCache::new(),
+            pending_reviews: HashMap::new(),
+            selected_pending_comment: 0,
+            pending_comment_list_scroll_offset: 0,
+            visual_anchor: None,
+            files_truncated: false,
+            collapse_toggled_threads: HashSet::new(),
+            viewer_login: None,
+            viewer_receiver: None,
+            confirm_delete: None,
+            discussion_post_receiver: None,
+            reaction_popup: None,
+            checks: None,
+            checks_loading: false,
+            checks_error: None,
+            checks_receiver: None,
+            selected_check: 0,
+            check_detail: None,
+            check_annotations_receiver: None,
+            diff_annotations: vec![],
+            diff_annotations_receiver: None,
+            file_annotation_positions: vec![],
+            file_annotation_lines: HashMap::new(),
+            pr_commits: None,
+            pr_commits_loading: false,
+            pr_commits_receiver: None,
+            selected_commit: 0,
+            commit_range_anchor: None,
+            commit_scope: None,
+            commit_files_loading: false,
+            commit_files_receiver: None,
+            last_reviewed_shas: HashMap::new(),
+            last_reviewed_sha_receiver: None,
+            viewed_files: None,
+            viewed_files_receiver: None,
+            viewed_toggle_receiver: None,
+            pr_metadata: None,
+            pr_metadata_loading: false,
+            pr_metadata_receiver: None,
+            pr_metadata_updating: false,
+            pr_metadata_update_receiver: None,
+            metadata_picker: None,
+            metadata_picker_receiver: None,
+            merge_dialog: None,
+            confirm_merge: None,
+            merging: false,
+            merge_receiver: None,
+            merge_status_receiver: None,
+            load_retry_at: None,
+            load_auto_retries: 0,
+            suggestion_batch: Vec::new(),
+            confirm_suggestions: None,
+            applying_suggestions: false,
+            suggestion_apply_receiver: None,
+            thread_resolve_receiver: None,
+            pr_list_total: None,
         };
 
         (app, tx)
@@ -468,7 +815,9 @@ impl App {
             pr_list_scroll_offset: 0,
             pr_list_loading: true,
             pr_list_has_more: false,
-            pr_list_state_filter: PrStateFilter::default(),
+            pr_list_cursor: None,
+            pr_list_query: PrListQuery::default(),
+            pr_list_search_input: None,
             started_from_pr_list: true,
             pr_list_receiver: None,
             diff_view_return_state: AppState::FileList,
@@ -481,6 +830,7 @@ impl App {
             scroll_offset: 0,
             input_mode: None,
             input_text_area: TextArea::with_submit_key(config.keybindings.submit.clone()),
+            github: github::new_client(&config.github),
             config,
             should_quit: false,
             review_comments: None,
@@ -524,9 +874,73 @@ impl App {
             pending_since: None,
             symbol_popup: None,
             session_cache: SessionCache::new(),
+            pending_reviews: HashMap::new(),
+            selected_pending_comment: 0,
+            pending_comment_list_scroll_offset: 0,
+            visual_anchor: None,
+            files_truncated: false,
+            collapse_toggled_threads: HashSet::new(),
+            viewer_login: None,
+            viewer_receiver: None,
+            confirm_delete: None,
+            discussion_post_receiver: None,
+            reaction_popup: None,
+            checks: None,
+            checks_loading: false,
+            checks_error: None,
+            checks_receiver: None,
+            selected_check: 0,
+            check_detail: None,
+            check_annotations_receiver: None,
+            diff_annotations: vec![],
+            diff_annotations_receiver: None,
+            file_annotation_positions: vec![],
+            file_annotation_lines: HashMap::new(),
+            pr_commits: None,
+            pr_commits_loading: false,
+            pr_commits_receiver: None,
+            selected_commit: 0,
+            commit_range_anchor: None,
+            commit_scope: None,
+            commit_files_loading: false,
+            commit_files_receiver: None,
+            last_reviewed_shas: HashMap::new(),
+            last_reviewed_sha_receiver: None,
+            viewed_files: None,
+            viewed_files_receiver: None,
+            viewed_toggle_receiver: None,
+            pr_metadata: None,
+            pr_metadata_loading: false,
+            pr_metadata_receiver: None,
+            pr_metadata_updating: false,
+            pr_metadata_update_receiver: None,
+            metadata_picker: None,
+            metadata_picker_receiver: None,
+            merge_dialog: None,
+            confirm_merge: None,
+            merging: false,
+            merge_receiver: None,
+            merge_status_receiver: None,
+            load_retry_at: None,
+            load_auto_retries: 0,
+            suggestion_batch: Vec::new(),
+            confirm_suggestions: None,
+            applying_suggestions: false,
+            suggestion_apply_receiver: None,
+            thread_resolve_receiver: None,
+            pr_list_total: None,
         }
     }
 
+    /// リポジトリ横断のレビュー受信箱モードで開始（--inbox）
+    ///
+    /// リポジトリは PR を選択した時点で決まる（`switch_repo`）。
+    pub fn new_inbox(config: Config) -> Self {
+        let mut app = Self::new_pr_list("", config);
+        app.pr_list_query.inbox = true;
+        app
+    }
+
     /// PR一覧受信チャンネルを設定
     pub fn set_pr_list_receiver(&mut self, rx: mpsc::Receiver<Result<github::PrListPage, String>>) {
         self.pr_list_receiver = Some(rx);
@@ -537,6 +951,20 @@ impl App {
         self.data_receiver = Some((pr_number, rx));
     }
 
+    /// GitHub API クライアントを差し替える（テスト用のモックなど）
+    pub fn set_github_client(&mut self, client: Arc<dyn GitHubApi>) {
+        self.github = client;
+    }
+
+    pub fn github_client(&self) -> Arc<dyn GitHubApi> {
+        Arc::clone(&self.github)
+    }
+
+    /// API の残りクォータ（フッター表示用）
+    pub fn rate_limit(&self) -> Option<github::RateLimit> {
+        self.github.rate_limit()
+    }
+
     pub fn set_retry_sender(&mut self, tx: mpsc::Sender<()>) {
         self.retry_sender = Some(tx);
     }
@@ -559,11 +987,26 @@ impl App {
             self.spinner_frame = self.spinner_frame.wrapping_add(1);
             self.poll_pr_list_updates();
             self.poll_data_updates();
+            self.poll_load_retry();
             self.poll_comment_updates();
             self.poll_diff_cache_updates();
             self.poll_prefetch_updates();
             self.poll_discussion_comment_updates();
             self.poll_comment_submit_updates();
+            self.poll_discussion_post_updates();
+            self.poll_viewer_login();
+            self.poll_checks_updates();
+            self.poll_check_annotations();
+            self.poll_diff_annotations();
+            self.poll_pr_commits();
+            self.poll_commit_files();
+            self.poll_last_reviewed_sha();
+            self.poll_viewed_files();
+            self.poll_viewed_toggle();
+            self.poll_thread_resolve();
+            self.poll_pr_metadata();
+            self.poll_merge_updates();
+            self.poll_suggestion_apply();
             self.poll_rally_events();
             terminal.draw(|frame| ui::render(frame, self))?;
             self.handle_input(&mut terminal).await?;
@@ -625,6 +1068,8 @@ impl App {
                     self.pr_list = Some(page.items);
                 }
                 self.pr_list_has_more = page.has_more;
+                self.pr_list_cursor = page.end_cursor;
+                self.pr_list_total = page.total_count;
                 self.pr_list_loading = false;
                 self.pr_list_receiver = None;
             }
@@ -661,17 +1106,20 @@ impl App {
                     self.handle_data_result(origin_pr, result);
                 } else {
                     // 異なるPRのデータ: セッションキャッシュにのみ格納
-                    if let DataLoadResult::Success { pr, files } = result {
-                        let cache_key = PrCacheKey {
-                            repo: self.repo.clone(),
-                            pr_number: origin_pr,
-                        };
+                    if let DataLoadResult::Success {
+                        pr,
+                        files,
+                        truncated,
+                    } = result
+                    {
+                        let cache_key = self.pr_cache_key(origin_pr);
                         self.session_cache.put_pr_data(
                             cache_key,
                             PrData {
                                 pr_updated_at: pr.updated_at.clone(),
                                 pr,
                                 files,
+                                files_truncated: truncated,
                             },
                         );
                     }
@@ -695,10 +1143,7 @@ impl App {
         match rx.try_recv() {
             Ok(Ok(comments)) => {
                 // セッションキャッシュに格納（発信元PRのキーで保存）
-                let cache_key = PrCacheKey {
-                    repo: self.repo.clone(),
-                    pr_number: origin_pr,
-                };
+                let cache_key = self.pr_cache_key(origin_pr);
                 self.session_cache
                     .put_review_comments(cache_key, comments.clone());
                 // PR が切り替わっていなければ UI 状態にも反映
@@ -885,10 +1330,7 @@ impl App {
         match rx.try_recv() {
             Ok(Ok(comments)) => {
                 // セッションキャッシュに格納（発信元PRのキーで保存）
-                let cache_key = PrCacheKey {
-                    repo: self.repo.clone(),
-                    pr_number: origin_pr,
-                };
+                let cache_key = self.pr_cache_key(origin_pr);
                 self.session_cache
                     .put_discussion_comments(cache_key, comments.clone());
                 // PR が切り替わっていなければ UI 状態にも反映
@@ -944,10 +1386,7 @@ impl App {
                 self.submission_result = Some((true, "Submitted".to_string()));
                 self.submission_result_time = Some(Instant::now());
                 // インメモリキャッシュを破棄してコメントを再取得
-                let cache_key = PrCacheKey {
-                    repo: self.repo.clone(),
-                    pr_number: origin_pr,
-                };
+                let cache_key = self.pr_cache_key(origin_pr);
                 self.session_cache.remove_review_comments(&cache_key);
                 // PR が切り替わっていなければコメントを再取得
                 if self.pr_number == Some(origin_pr) {
@@ -956,6 +1395,24 @@ impl App {
                     self.update_file_comment_positions();
                 }
             }
+            Ok(CommentSubmitResult::DiscussionUpdated) => {
+                self.comment_submitting = false;
+                self.comment_submit_receiver = None;
+                self.submission_result = Some((true, "Submitted".to_string()));
+                self.submission_result_time = Some(Instant::now());
+                let cache_key = self.pr_cache_key(origin_pr);
+                self.session_cache.remove_discussion_comments(&cache_key);
+                if self.pr_number == Some(origin_pr) {
+                    self.discussion_comments = None;
+                    self.load_discussion_comments();
+                }
+            }
+            Ok(CommentSubmitResult::SuccessNoRefresh) => {
+                self.comment_submitting = false;
+                self.comment_submit_receiver = None;
+                self.submission_result = Some((true, "Submitted".to_string()));
+                self.submission_result_time = Some(Instant::now());
+            }
             Ok(CommentSubmitResult::Error(e)) => {
                 self.comment_submitting = false;
                 self.comment_submit_receiver = None;
@@ -970,1764 +1427,3964 @@ impl App {
         }
     }
 
-    /// コメント送信中かどうか
-    pub fn is_submitting_comment(&self) -> bool {
-        self.comment_submitting
-    }
-
-    /// AI Rally イベントのポーリング
-    fn poll_rally_events(&mut self) {
-        let Some(ref mut rx) = self.rally_event_receiver else {
+    /// 会話タブへのコメント投稿結果のポーリング
+    ///
+    /// 成功時は楽観的に挿入したコメントを API の結果で置き換え、失敗時は取り除く。
+    fn poll_discussion_post_updates(&mut self) {
+        let Some((origin_pr, rx)) = self.discussion_post_receiver.as_mut() else {
             return;
         };
+        let origin_pr = *origin_pr;
 
-        // Process all available events
-        loop {
-            match rx.try_recv() {
-                Ok(event) => {
-                    if let Some(ref mut rally_state) = self.ai_rally_state {
-                        match &event {
-                            RallyEvent::StateChanged(state) => {
-                                rally_state.state = *state;
-                            }
-                            RallyEvent::IterationStarted(i) => {
-                                rally_state.iteration = *i;
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::Info,
-                                    format!("Starting iteration {}", i),
-                                ));
-                            }
-                            RallyEvent::Log(msg) => {
-                                rally_state
-                                    .push_log(LogEntry::new(LogEventType::Info, msg.clone()));
-                            }
-                            RallyEvent::AgentThinking(content) => {
-                                // Store full content; truncation happens at display time
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::Thinking,
-                                    content.clone(),
-                                ));
-                            }
-                            RallyEvent::AgentToolUse(tool_name, input) => {
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::ToolUse,
-                                    format!("{}: {}", tool_name, input),
-                                ));
-                            }
-                            RallyEvent::AgentToolResult(tool_name, result) => {
-                                // Store full content; truncation happens at display time
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::ToolResult,
-                                    format!("{}: {}", tool_name, result),
-                                ));
-                            }
-                            RallyEvent::AgentText(text) => {
-                                // Store full content; truncation happens at display time
-                                rally_state
-                                    .push_log(LogEntry::new(LogEventType::Text, text.clone()));
-                            }
-                            RallyEvent::ReviewCompleted(_) => {
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::Review,
-                                    "Review completed".to_string(),
-                                ));
-                            }
-                            RallyEvent::FixCompleted(fix) => {
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::Fix,
-                                    format!("Fix completed: {}", fix.summary),
-                                ));
-                            }
-                            RallyEvent::Error(e) => {
-                                rally_state.push_log(LogEntry::new(LogEventType::Error, e.clone()));
-                            }
-                            RallyEvent::ClarificationNeeded(question) => {
-                                rally_state.pending_question = Some(question.clone());
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::Info,
-                                    format!("Clarification needed: {}", question),
-                                ));
-                            }
-                            RallyEvent::PermissionNeeded(action, reason) => {
-                                rally_state.pending_permission = Some(PermissionInfo {
-                                    action: action.clone(),
-                                    reason: reason.clone(),
-                                });
-                                rally_state.push_log(LogEntry::new(
-                                    LogEventType::Info,
-                                    format!("Permission needed: {} - {}", action, reason),
-                                ));
-                            }
-                            _ => {}
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                Err("Discussion comment submission was interrupted".to_string())
+            }
+        };
+        self.discussion_post_receiver = None;
+        self.comment_submitting = false;
+        self.submission_result_time = Some(Instant::now());
+
+        let cache_key = self.pr_cache_key(origin_pr);
+        let is_current_pr = self.pr_number == Some(origin_pr);
+        let comments = self.discussion_comments.as_mut().filter(|_| is_current_pr);
+
+        match result {
+            Ok(created) => {
+                self.submission_result = Some((true, "Submitted".to_string()));
+                match comments {
+                    Some(comments) => {
+                        if let Some(slot) =
+                            comments.iter_mut().find(|c| c.id == OPTIMISTIC_COMMENT_ID)
+                        {
+                            *slot = created;
                         }
-                        rally_state.history.push(event);
+                        self.session_cache
+                            .put_discussion_comments(cache_key, comments.clone());
                     }
+                    // PR が切り替わっていたら次回表示時に再取得させる
+                    None => self.session_cache.remove_discussion_comments(&cache_key),
                 }
-                Err(mpsc::error::TryRecvError::Empty) => break,
-                Err(mpsc::error::TryRecvError::Disconnected) => {
-                    self.rally_event_receiver = None;
-                    if let Some(ref mut rally_state) = self.ai_rally_state {
-                        if rally_state.state.is_active() {
-                            rally_state.state = RallyState::Error;
-                            rally_state.push_log(LogEntry::new(
-                                LogEventType::Error,
-                                "Rally process terminated unexpectedly".to_string(),
-                            ));
-                        }
-                    }
-                    break;
+            }
+            Err(e) => {
+                self.submission_result = Some((false, format!("Failed: {}", e)));
+                if let Some(comments) = comments {
+                    comments.retain(|c| c.id != OPTIMISTIC_COMMENT_ID);
+                    let remaining = comments.len();
+                    self.selected_discussion_comment = self
+                        .selected_discussion_comment
+                        .min(remaining.saturating_sub(1));
                 }
             }
         }
     }
 
-    fn handle_data_result(&mut self, origin_pr: u32, result: DataLoadResult) {
-        match result {
-            DataLoadResult::Success { pr, files } => {
-                // ファイル数が減った場合、selected_file をクランプ
-                let old_selected = self.selected_file;
-                if !files.is_empty() {
-                    self.selected_file = self.selected_file.min(files.len() - 1);
-                } else {
-                    self.selected_file = 0;
-                }
-                // selected_file が変わった場合、diff 側の状態を再同期
-                if self.selected_file != old_selected {
-                    self.diff_cache = None;
-                    self.diff_cache_receiver = None;
-                    self.selected_line = 0;
-                    self.scroll_offset = 0;
-                    self.comment_panel_open = false;
-                    self.comment_panel_scroll = 0;
-                }
-                self.file_list_scroll_offset = self.file_list_scroll_offset.min(self.selected_file);
-                self.diff_line_count = Self::calc_diff_line_count(&files, self.selected_file);
-                // ファイル一覧が変わるため、ハイライトキャッシュストアをクリア
-                self.highlighted_cache_store.clear();
-                // Check if we need to start AI Rally (--ai-rally flag was passed)
-                let should_start_rally =
-                    self.start_ai_rally_on_load && matches!(self.data_state, DataState::Loading);
-                // clone() でキャッシュと DataState の両方にデータを格納（Arc不使用）
-                let cache_key = PrCacheKey {
-                    repo: self.repo.clone(),
-                    pr_number: origin_pr,
-                };
-                self.session_cache.put_pr_data(
-                    cache_key,
-                    PrData {
-                        pr: pr.clone(),
-                        files: files.clone(),
-                        pr_updated_at: pr.updated_at.clone(),
-                    },
-                );
-                self.data_state = DataState::Loaded { pr, files };
-                // selected_file がクランプで変わった場合、コメント位置キャッシュを再計算
-                if self.selected_file != old_selected {
-                    self.update_file_comment_positions();
-                }
-                // 全ファイルのハイライトキャッシュを事前構築
-                self.start_prefetch_all_files();
-                if should_start_rally {
-                    self.start_ai_rally_on_load = false; // Clear the flag
-                    self.start_ai_rally();
-                }
+    /// 認証中ユーザー取得結果のポーリング
+    fn poll_viewer_login(&mut self) {
+        let Some(ref mut rx) = self.viewer_receiver else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(login)) => {
+                self.viewer_login = Some(login);
+                self.viewer_receiver = None;
             }
-            DataLoadResult::Error(msg) => {
-                // Loading状態の場合のみエラー表示（既にデータがある場合は無視）
-                if matches!(self.data_state, DataState::Loading) {
-                    self.data_state = DataState::Error(msg);
-                }
+            Ok(Err(e)) => {
+                eprintln!("Warning: Failed to fetch authenticated user: {}", e);
+                self.viewer_receiver = None;
+            }
+            Err(mpsc::error::TryRecvError::Empty) => {}
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.viewer_receiver = None;
             }
         }
     }
 
-    fn calc_diff_line_count(files: &[ChangedFile], selected: usize) -> usize {
-        files
-            .get(selected)
-            .and_then(|f| f.patch.as_ref())
-            .map(|p| p.lines().count())
-            .unwrap_or(0)
-    }
+    /// CI 結果取得のポーリング
+    fn poll_checks_updates(&mut self) {
+        let Some((origin_pr, rx)) = self.checks_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
 
-    pub fn files(&self) -> &[ChangedFile] {
-        match &self.data_state {
-            DataState::Loaded { files, .. } => files,
-            _ => &[],
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => Err("Check fetch aborted".to_string()),
+        };
+        self.checks_receiver = None;
+        // PR が切り替わっている場合は破棄
+        if self.pr_number != Some(origin_pr) {
+            return;
         }
-    }
-
-    pub fn pr(&self) -> Option<&PullRequest> {
-        match &self.data_state {
-            DataState::Loaded { pr, .. } => Some(pr.as_ref()),
-            _ => None,
+        self.checks_loading = false;
+        match result {
+            Ok(checks) => {
+                self.selected_check = self.selected_check.min(checks.len().saturating_sub(1));
+                self.load_diff_annotations(&checks.runs);
+                self.checks = Some(checks);
+                self.checks_error = None;
+                self.check_detail = None;
+            }
+            Err(e) => self.checks_error = Some(e),
         }
     }
 
-    #[allow(dead_code)]
-    pub fn is_data_available(&self) -> bool {
-        matches!(self.data_state, DataState::Loaded { .. })
+    /// アノテーション取得のポーリング
+    fn poll_check_annotations(&mut self) {
+        let Some((origin_pr, rx)) = self.check_annotations_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
+        let (index, result) = match rx.try_recv() {
+            Ok(received) => received,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.check_annotations_receiver = None;
+                return;
+            }
+        };
+        self.check_annotations_receiver = None;
+        // 取得中に別の PR・別のチェックへ切り替えていれば破棄
+        if self.pr_number != Some(origin_pr) {
+            return;
+        }
+        if let Some(detail) = self.check_detail.as_mut() {
+            if detail.index == index {
+                detail.annotations = Some(result);
+            }
+        }
     }
 
-    async fn handle_input(
-        &mut self,
-        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<()> {
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                // PR一覧画面は独自のLoading処理があるためスキップ
-                if self.state != AppState::PullRequestList {
-                    // Error状態でのリトライ処理
-                    if let DataState::Error(_) = &self.data_state {
-                        match key.code {
-                            KeyCode::Char('q') => self.should_quit = true,
-                            KeyCode::Char('r') => self.retry_load(),
-                            _ => {}
-                        }
-                        return Ok(());
-                    }
-
-                    // Loading状態ではqのみ受け付け
-                    if matches!(self.data_state, DataState::Loading) {
-                        if key.code == KeyCode::Char('q') {
-                            self.should_quit = true;
-                        }
-                        return Ok(());
-                    }
-                }
+    /// diff 用アノテーション取得のポーリング
+    fn poll_diff_annotations(&mut self) {
+        let Some((origin_pr, rx)) = self.diff_annotations_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
 
-                match self.state {
-                    AppState::PullRequestList => self.handle_pr_list_input(key).await?,
-                    AppState::FileList => self.handle_file_list_input(key, terminal).await?,
-                    AppState::DiffView => self.handle_diff_view_input(key, terminal).await?,
-                    AppState::TextInput => self.handle_text_input(key)?,
-                    AppState::CommentList => self.handle_comment_list_input(key, terminal).await?,
-                    AppState::Help => self.handle_help_input(key)?,
-                    AppState::AiRally => self.handle_ai_rally_input(key, terminal).await?,
-                    AppState::SplitViewFileList => {
-                        self.handle_split_view_file_list_input(key, terminal)
-                            .await?
-                    }
-                    AppState::SplitViewDiff => {
-                        self.handle_split_view_diff_input(key, terminal).await?
-                    }
+        match rx.try_recv() {
+            Ok(result) => {
+                self.diff_annotations_receiver = None;
+                // アノテーションは補助情報のため、取得失敗時は表示しないだけにする
+                if let (Ok(annotations), true) = (result, self.pr_number == Some(origin_pr)) {
+                    self.diff_annotations = annotations;
+                    self.update_file_annotation_positions();
                 }
             }
+            Err(mpsc::error::TryRecvError::Empty) => {}
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.diff_annotations_receiver = None;
+            }
         }
-        Ok(())
     }
 
-    fn retry_load(&mut self) {
-        if let Some(ref tx) = self.retry_sender {
-            self.data_state = DataState::Loading;
-            let _ = tx.try_send(());
+    /// コミット一覧取得のポーリング
+    fn poll_pr_commits(&mut self) {
+        let Some((origin_pr, rx)) = self.pr_commits_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
+
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => Err("Commit fetch aborted".to_string()),
+        };
+        self.pr_commits_receiver = None;
+        if self.pr_number != Some(origin_pr) {
+            return;
+        }
+        self.pr_commits_loading = false;
+        match result {
+            Ok(commits) => {
+                self.selected_commit = self.selected_commit.min(commits.len());
+                self.pr_commits = Some(commits);
+            }
+            Err(e) => {
+                self.submission_result = Some((false, format!("Failed to load commits: {}", e)));
+                self.submission_result_time = Some(Instant::now());
+            }
         }
     }
 
-    async fn handle_file_list_input(
-        &mut self,
-        key: event::KeyEvent,
-        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<()> {
-        let kb = &self.config.keybindings;
+    /// コミット範囲の変更ファイル取得のポーリング
+    fn poll_commit_files(&mut self) {
+        let Some((origin_pr, rx)) = self.commit_files_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
 
-        // Quit or back to PR list
-        if self.matches_single_key(&key, &kb.quit) {
-            if self.started_from_pr_list {
-                self.back_to_pr_list();
-            } else {
-                self.should_quit = true;
+        let (scope, result) = match rx.try_recv() {
+            Ok(received) => received,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.commit_files_receiver = None;
+                self.commit_files_loading = false;
+                return;
             }
-            return Ok(());
+        };
+        self.commit_files_receiver = None;
+        if self.pr_number != Some(origin_pr) {
+            return;
         }
-
-        // Move down (j or Down arrow - arrows always work)
-        if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
-            if !self.files().is_empty() {
-                self.selected_file =
-                    (self.selected_file + 1).min(self.files().len().saturating_sub(1));
+        self.commit_files_loading = false;
+        match result {
+            Ok((files, truncated)) => {
+                self.commit_scope = Some(scope);
+                self.files_truncated = truncated;
+                self.replace_files(files);
+                if self.state == AppState::CommitList {
+                    self.state = self.previous_state;
+                }
+            }
+            Err(e) => {
+                self.submission_result = Some((false, format!("Failed to load diff: {}", e)));
+                self.submission_result_time = Some(Instant::now());
             }
-            return Ok(());
-        }
-
-        // Move up (k or Up arrow)
-        if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
-            self.selected_file = self.selected_file.saturating_sub(1);
-            return Ok(());
         }
+    }
 
-        // Open split view (Enter, Right arrow, or l)
-        if self.matches_single_key(&key, &kb.open_panel)
-            || self.matches_single_key(&key, &kb.move_right)
-            || key.code == KeyCode::Right
-        {
-            if !self.files().is_empty() {
-                self.state = AppState::SplitViewDiff;
-                self.sync_diff_to_selected_file();
+    /// マージ可否の再取得・マージ結果のポーリング
+    fn poll_merge_updates(&mut self) {
+        if let Some((origin_pr, rx)) = self.merge_status_receiver.as_mut() {
+            let origin_pr = *origin_pr;
+            match rx.try_recv() {
+                Ok(result) => {
+                    self.merge_status_receiver = None;
+                    if self.pr_number == Some(origin_pr) {
+                        // 取得に失敗しても手元の情報でダイアログは使える
+                        if let (Ok(latest), DataState::Loaded { pr, .. }) =
+                            (result, &mut self.data_state)
+                        {
+                            pr.node_id = latest.node_id;
+                            pr.mergeable = latest.mergeable;
+                            pr.mergeable_state = latest.mergeable_state;
+                            pr.protection = latest.protection;
+                        }
+                    }
+                }
+                Err(mpsc::error::TryRecvError::Empty) => {}
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    self.merge_status_receiver = None;
+                }
             }
-            return Ok(());
         }
 
-        // Actions
-        if self.matches_single_key(&key, &kb.approve) {
-            self.submit_review(ReviewAction::Approve, terminal).await?;
-            return Ok(());
+        let Some((origin_pr, rx)) = self.merge_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => Err("Merge aborted".to_string()),
+        };
+        self.merge_receiver = None;
+        if self.pr_number != Some(origin_pr) {
+            return;
         }
-
-        if self.matches_single_key(&key, &kb.request_changes) {
-            self.submit_review(ReviewAction::RequestChanges, terminal)
-                .await?;
-            return Ok(());
+        self.merging = false;
+        match result {
+            Ok(message) => {
+                self.refresh_all();
+                self.submission_result = Some((true, message));
+            }
+            Err(e) => self.submission_result = Some((false, format!("Merge failed: {}", e))),
         }
+        self.submission_result_time = Some(Instant::now());
+    }
 
-        // Note: In FileList, 'comment' key triggers review comment (not inline comment)
-        // Using separate check for review comment in FileList context
-        if self.matches_single_key(&key, &kb.comment) {
-            self.submit_review(ReviewAction::Comment, terminal).await?;
-            return Ok(());
+    /// メタデータ取得・更新・ピッカー候補取得のポーリング
+    fn poll_pr_metadata(&mut self) {
+        if let Some((origin_pr, rx)) = self.pr_metadata_receiver.as_mut() {
+            let origin_pr = *origin_pr;
+            let received = match rx.try_recv() {
+                Ok(result) => Some(result),
+                Err(mpsc::error::TryRecvError::Empty) => None,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    Some(Err("Metadata fetch aborted".to_string()))
+                }
+            };
+            if let Some(result) = received {
+                self.pr_metadata_receiver = None;
+                if self.pr_number == Some(origin_pr) {
+                    self.pr_metadata_loading = false;
+                    match result {
+                        Ok(metadata) => self.pr_metadata = Some(metadata),
+                        Err(e) => {
+                            self.submission_result =
+                                Some((false, format!("Failed to load metadata: {}", e)));
+                            self.submission_result_time = Some(Instant::now());
+                        }
+                    }
+                }
+            }
         }
 
-        // Comment list
-        if self.matches_single_key(&key, &kb.comment_list) {
-            self.previous_state = AppState::FileList;
-            self.open_comment_list();
-            return Ok(());
+        if let Some((origin_pr, rx)) = self.pr_metadata_update_receiver.as_mut() {
+            let origin_pr = *origin_pr;
+            let received = match rx.try_recv() {
+                Ok(result) => Some(result),
+                Err(mpsc::error::TryRecvError::Empty) => None,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    Some(Err("Update aborted".to_string()))
+                }
+            };
+            if let Some(result) = received {
+                self.pr_metadata_update_receiver = None;
+                if self.pr_number == Some(origin_pr) {
+                    self.pr_metadata_updating = false;
+                    self.submission_result = Some(match result {
+                        Ok(metadata) => {
+                            self.pr_metadata = Some(metadata);
+                            (true, "Updated".to_string())
+                        }
+                        Err(e) => (false, format!("Failed: {}", e)),
+                    });
+                    self.submission_result_time = Some(Instant::now());
+                }
+            }
         }
 
-        // Refresh
-        if self.matches_single_key(&key, &kb.refresh) {
-            self.refresh_all();
-            return Ok(());
+        if let Some((origin_pr, rx)) = self.metadata_picker_receiver.as_mut() {
+            let origin_pr = *origin_pr;
+            let received = match rx.try_recv() {
+                Ok(result) => Some(result),
+                Err(mpsc::error::TryRecvError::Empty) => None,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    Some(Err("Fetch aborted".to_string()))
+                }
+            };
+            if let Some(result) = received {
+                self.metadata_picker_receiver = None;
+                if self.pr_number == Some(origin_pr) {
+                    if let Some(ref mut picker) = self.metadata_picker {
+                        picker.candidates = Some(result);
+                    }
+                }
+            }
         }
+    }
 
-        // AI Rally
-        if self.matches_single_key(&key, &kb.ai_rally) {
-            self.resume_or_start_ai_rally();
-            return Ok(());
-        }
+    /// Viewed 状態取得のポーリング
+    fn poll_viewed_files(&mut self) {
+        let Some((origin_pr, rx)) = self.viewed_files_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
 
-        // Help
-        if self.matches_single_key(&key, &kb.help) {
-            self.previous_state = AppState::FileList;
-            self.state = AppState::Help;
-            return Ok(());
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => Err("Viewed fetch aborted".to_string()),
+        };
+        self.viewed_files_receiver = None;
+        if self.pr_number != Some(origin_pr) {
+            return;
+        }
+        match result {
+            Ok(viewed) => self.viewed_files = Some(viewed),
+            // Viewed 状態が取れなくても一覧表示は続行する
+            Err(e) => eprintln!("Warning: Failed to fetch viewed files: {}", e),
         }
-
-        Ok(())
     }
 
-    /// FileList 系状態で共通のキーを処理する。処理した場合は true を返す。
-    async fn handle_common_file_list_keys(
-        &mut self,
-        key: event::KeyEvent,
-        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<bool> {
-        let kb = &self.config.keybindings;
+    /// Viewed 切り替え結果のポーリング（失敗時は楽観的更新を戻す）
+    fn poll_viewed_toggle(&mut self) {
+        let Some((origin_pr, rx)) = self.viewed_toggle_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
 
-        if self.matches_single_key(&key, &kb.approve) {
-            self.submit_review(ReviewAction::Approve, terminal).await?;
-            return Ok(true);
+        let (path, viewed, result) = match rx.try_recv() {
+            Ok(received) => received,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.viewed_toggle_receiver = None;
+                return;
+            }
+        };
+        self.viewed_toggle_receiver = None;
+        if self.pr_number != Some(origin_pr) {
+            return;
         }
-
-        if self.matches_single_key(&key, &kb.request_changes) {
-            self.submit_review(ReviewAction::RequestChanges, terminal)
-                .await?;
-            return Ok(true);
+        if let Err(e) = result {
+            if let Some(ref mut files) = self.viewed_files {
+                if viewed {
+                    files.paths.remove(&path);
+                } else {
+                    files.paths.insert(path);
+                }
+            }
+            self.submission_result = Some((false, format!("Failed to update viewed: {}", e)));
+            self.submission_result_time = Some(Instant::now());
         }
+    }
 
-        if self.matches_single_key(&key, &kb.comment) {
-            self.submit_review(ReviewAction::Comment, terminal).await?;
-            return Ok(true);
-        }
+    /// 前回レビュー時点の head 取得のポーリング
+    fn poll_last_reviewed_sha(&mut self) {
+        let Some((origin_pr, rx)) = self.last_reviewed_sha_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
 
-        if self.matches_single_key(&key, &kb.refresh) {
-            self.refresh_all();
-            return Ok(true);
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => Err("Review fetch aborted".to_string()),
+        };
+        self.last_reviewed_sha_receiver = None;
+        if self.pr_number != Some(origin_pr) {
+            return;
         }
+        match result {
+            Ok(Some(sha)) => {
+                let key = self.pr_cache_key(origin_pr);
+                self.last_reviewed_shas.insert(key, sha.clone());
+                self.show_changes_since(sha);
+            }
+            Ok(None) => {
+                self.submission_result =
+                    Some((false, "You have not reviewed this PR yet".to_string()));
+                self.submission_result_time = Some(Instant::now());
+            }
+            Err(e) => {
+                self.submission_result = Some((false, format!("Failed to load reviews: {}", e)));
+                self.submission_result_time = Some(Instant::now());
+            }
+        }
+    }
 
-        if self.matches_single_key(&key, &kb.ai_rally) {
-            self.resume_or_start_ai_rally();
-            return Ok(true);
+    /// 認証中ユーザーが未取得なら取得を開始する
+    fn ensure_viewer_login(&mut self) {
+        if self.viewer_login.is_some() || self.viewer_receiver.is_some() {
+            return;
         }
+        let (tx, rx) = mpsc::channel(1);
+        self.viewer_receiver = Some(rx);
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = client.fetch_viewer_login().await.map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
+        });
+    }
 
-        Ok(false)
+    /// コメント送信中かどうか
+    pub fn is_submitting_comment(&self) -> bool {
+        self.comment_submitting
     }
 
-    async fn handle_split_view_file_list_input(
-        &mut self,
-        key: event::KeyEvent,
-        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<()> {
-        let kb = &self.config.keybindings;
+    /// AI Rally イベントのポーリング
+    fn poll_rally_events(&mut self) {
+        let Some(ref mut rx) = self.rally_event_receiver else {
+            return;
+        };
 
-        // Move down
-        if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
-            if !self.files().is_empty() {
-                self.selected_file =
-                    (self.selected_file + 1).min(self.files().len().saturating_sub(1));
-                self.sync_diff_to_selected_file();
+        // Process all available events
+        loop {
+            match rx.try_recv() {
+                Ok(event) => {
+                    if let Some(ref mut rally_state) = self.ai_rally_state {
+                        match &event {
+                            RallyEvent::StateChanged(state) => {
+                                rally_state.state = *state;
+                            }
+                            RallyEvent::IterationStarted(i) => {
+                                rally_state.iteration = *i;
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::Info,
+                                    format!("Starting iteration {}", i),
+                                ));
+                            }
+                            RallyEvent::Log(msg) => {
+                                rally_state
+                                    .push_log(LogEntry::new(LogEventType::Info, msg.clone()));
+                            }
+                            RallyEvent::AgentThinking(content) => {
+                                // Store full content; truncation happens at display time
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::Thinking,
+                                    content.clone(),
+                                ));
+                            }
+                            RallyEvent::AgentToolUse(tool_name, input) => {
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::ToolUse,
+                                    format!("{}: {}", tool_name, input),
+                                ));
+                            }
+                            RallyEvent::AgentToolResult(tool_name, result) => {
+                                // Store full content; truncation happens at display time
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::ToolResult,
+                                    format!("{}: {}", tool_name, result),
+                                ));
+                            }
+                            RallyEvent::AgentText(text) => {
+                                // Store full content; truncation happens at display time
+                                rally_state
+                                    .push_log(LogEntry::new(LogEventType::Text, text.clone()));
+                            }
+                            RallyEvent::ReviewCompleted(_) => {
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::Review,
+                                    "Review completed".to_string(),
+                                ));
+                            }
+                            RallyEvent::FixCompleted(fix) => {
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::Fix,
+                                    format!("Fix completed: {}", fix.summary),
+                                ));
+                            }
+                            RallyEvent::Error(e) => {
+                                rally_state.push_log(LogEntry::new(LogEventType::Error, e.clone()));
+                            }
+                            RallyEvent::ClarificationNeeded(question) => {
+                                rally_state.pending_question = Some(question.clone());
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::Info,
+                                    format!("Clarification needed: {}", question),
+                                ));
+                            }
+                            RallyEvent::PermissionNeeded(action, reason) => {
+                                rally_state.pending_permission = Some(PermissionInfo {
+                                    action: action.clone(),
+                                    reason: reason.clone(),
+                                });
+                                rally_state.push_log(LogEntry::new(
+                                    LogEventType::Info,
+                                    format!("Permission needed: {} - {}", action, reason),
+                                ));
+                            }
+                            _ => {}
+                        }
+                        rally_state.history.push(event);
+                    }
+                }
+                Err(mpsc::error::TryRecvError::Empty) => break,
+                Err(mpsc::error::TryRecvError::Disconnected) => {
+                    self.rally_event_receiver = None;
+                    if let Some(ref mut rally_state) = self.ai_rally_state {
+                        if rally_state.state.is_active() {
+                            rally_state.state = RallyState::Error;
+                            rally_state.push_log(LogEntry::new(
+                                LogEventType::Error,
+                                "Rally process terminated unexpectedly".to_string(),
+                            ));
+                        }
+                    }
+                    break;
+                }
             }
-            return Ok(());
         }
+    }
 
-        // Move up
-        if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
-            if self.selected_file > 0 {
-                self.selected_file = self.selected_file.saturating_sub(1);
-                self.sync_diff_to_selected_file();
+    fn handle_data_result(&mut self, origin_pr: u32, result: DataLoadResult) {
+        match result {
+            DataLoadResult::Success {
+                pr,
+                files,
+                truncated,
+            } => {
+                self.load_retry_at = None;
+                self.load_auto_retries = 0;
+                // ファイル数が減った場合、selected_file をクランプ
+                let old_selected = self.selected_file;
+                if !files.is_empty() {
+                    self.selected_file = self.selected_file.min(files.len() - 1);
+                } else {
+                    self.selected_file = 0;
+                }
+                // selected_file が変わった場合、diff 側の状態を再同期
+                if self.selected_file != old_selected {
+                    self.diff_cache = None;
+                    self.diff_cache_receiver = None;
+                    self.selected_line = 0;
+                    self.visual_anchor = None;
+                    self.scroll_offset = 0;
+                    self.comment_panel_open = false;
+                    self.comment_panel_scroll = 0;
+                }
+                self.file_list_scroll_offset = self.file_list_scroll_offset.min(self.selected_file);
+                self.diff_line_count = Self::calc_diff_line_count(&files, self.selected_file);
+                // ファイル一覧が変わるため、ハイライトキャッシュストアをクリア
+                self.highlighted_cache_store.clear();
+                // Check if we need to start AI Rally (--ai-rally flag was passed)
+                let should_start_rally =
+                    self.start_ai_rally_on_load && matches!(self.data_state, DataState::Loading);
+                // clone() でキャッシュと DataState の両方にデータを格納（Arc不使用）
+                let cache_key = self.pr_cache_key(origin_pr);
+                self.session_cache.put_pr_data(
+                    cache_key,
+                    PrData {
+                        pr: pr.clone(),
+                        files: files.clone(),
+                        pr_updated_at: pr.updated_at.clone(),
+                        files_truncated: truncated,
+                    },
+                );
+                self.files_truncated = truncated;
+                self.data_state = DataState::Loaded { pr, files };
+                // PR が更新されるとコミット構成も変わりうるため、コミット範囲の表示は解除する
+                self.commit_scope = None;
+                self.pr_commits = None;
+                self.load_checks(false);
+                self.load_viewed_files();
+                // selected_file がクランプで変わった場合、コメント位置キャッシュを再計算
+                if self.selected_file != old_selected {
+                    self.update_file_comment_positions();
+                }
+                // 全ファイルのハイライトキャッシュを事前構築
+                self.start_prefetch_all_files();
+                if should_start_rally {
+                    self.start_ai_rally_on_load = false; // Clear the flag
+                    self.start_ai_rally();
+                }
             }
-            return Ok(());
-        }
-
-        // Focus diff pane
-        if self.matches_single_key(&key, &kb.open_panel)
-            || self.matches_single_key(&key, &kb.move_right)
-            || key.code == KeyCode::Right
-        {
-            if !self.files().is_empty() {
-                self.state = AppState::SplitViewDiff;
+            DataLoadResult::Error(error) => {
+                // Loading状態の場合のみエラー表示（既にデータがある場合は無視）
+                if matches!(self.data_state, DataState::Loading) {
+                    self.load_retry_at = match error.retry_policy() {
+                        RetryPolicy::Auto(wait)
+                            if self.load_auto_retries < MAX_LOAD_AUTO_RETRIES =>
+                        {
+                            self.load_auto_retries += 1;
+                            Some(Instant::now() + wait)
+                        }
+                        _ => None,
+                    };
+                    self.data_state = DataState::Error(error);
+                }
             }
-            return Ok(());
         }
+    }
 
-        // Back to file list
-        if self.matches_single_key(&key, &kb.quit)
-            || self.matches_single_key(&key, &kb.move_left)
-            || key.code == KeyCode::Left
-            || key.code == KeyCode::Esc
-        {
-            self.state = AppState::FileList;
-            return Ok(());
-        }
+    fn calc_diff_line_count(files: &[ChangedFile], selected: usize) -> usize {
+        files
+            .get(selected)
+            .and_then(|f| f.patch.as_ref())
+            .map(|p| p.lines().count())
+            .unwrap_or(0)
+    }
 
-        // Comment list
-        if self.matches_single_key(&key, &kb.comment_list) {
-            self.previous_state = AppState::SplitViewFileList;
-            self.open_comment_list();
-            return Ok(());
+    pub fn files(&self) -> &[ChangedFile] {
+        match &self.data_state {
+            DataState::Loaded { files, .. } => files,
+            _ => &[],
         }
+    }
 
-        // Help
-        if self.matches_single_key(&key, &kb.help) {
-            self.previous_state = AppState::SplitViewFileList;
-            self.state = AppState::Help;
-            return Ok(());
+    pub fn pr(&self) -> Option<&PullRequest> {
+        match &self.data_state {
+            DataState::Loaded { pr, .. } => Some(pr.as_ref()),
+            _ => None,
         }
+    }
 
-        // Fallback to common file list keys
-        self.handle_common_file_list_keys(key, terminal).await?;
-
-        Ok(())
+    #[allow(dead_code)]
+    pub fn is_data_available(&self) -> bool {
+        matches!(self.data_state, DataState::Loaded { .. })
     }
 
-    /// Common handler for diff view input (both fullscreen and split pane)
-    ///
-    /// The `variant` parameter determines:
-    /// - visible_lines calculation
-    /// - state transitions (back, quit, panel navigation)
-    async fn handle_diff_input_common(
+    async fn handle_input(
         &mut self,
-        key: event::KeyEvent,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-        variant: DiffViewVariant,
     ) -> Result<()> {
-        // シンボルポップアップ表示中
-        if self.symbol_popup.is_some() {
-            self.handle_symbol_popup_input(key, terminal).await?;
-            return Ok(());
-        }
-
-        let term_size = terminal.size()?;
-        let term_h = term_size.height as usize;
-        let term_w = term_size.width as usize;
-
-        // Calculate visible_lines based on variant
-        let visible_lines = match variant {
-            DiffViewVariant::SplitPane => {
-                // Header(3) + Footer(3) + border(2) = 8 を差し引き、65%の高さ
-                (term_h * 65 / 100).saturating_sub(8)
-            }
-            DiffViewVariant::Fullscreen => term_h.saturating_sub(8),
-        };
-        let panel_inner_width = self.comment_panel_inner_width(term_w);
-
-        // Clone keybindings to avoid borrow issues with self
-        let kb = self.config.keybindings.clone();
-
-        // コメントパネルフォーカス中
-        if self.comment_panel_open {
-            // Move down in panel
-            if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
-                let max_scroll = self.max_comment_panel_scroll(term_h, term_w);
-                self.comment_panel_scroll =
-                    self.comment_panel_scroll.saturating_add(1).min(max_scroll);
-                return Ok(());
-            }
-
-            // Move up in panel
-            if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
-                self.comment_panel_scroll = self.comment_panel_scroll.saturating_sub(1);
-                return Ok(());
-            }
-
-            // Next comment
-            if self.matches_single_key(&key, &kb.next_comment) {
-                let prev_line = self.selected_line;
-                self.jump_to_next_comment();
-                if self.selected_line != prev_line {
-                    self.comment_panel_scroll = 0;
-                    self.selected_inline_comment = 0;
-                    self.adjust_scroll(visible_lines);
-                }
-                return Ok(());
-            }
+        if event::poll(std::time::Duration::from_millis(100))? {
+            if let Event::Key(key) = event::read()? {
+                // PR一覧画面は独自のLoading処理があるためスキップ
+                if self.state != AppState::PullRequestList {
+                    // Error状態でのリトライ処理
+                    if let DataState::Error(error) = &self.data_state {
+                        match key.code {
+                            KeyCode::Char('q') => self.should_quit = true,
+                            KeyCode::Char('r') if error.retry_policy() != RetryPolicy::Never => {
+                                self.retry_load()
+                            }
+                            _ => {}
+                        }
+                        return Ok(());
+                    }
 
-            // Previous comment
-            if self.matches_single_key(&key, &kb.prev_comment) {
-                let prev_line = self.selected_line;
-                self.jump_to_prev_comment();
-                if self.selected_line != prev_line {
-                    self.comment_panel_scroll = 0;
-                    self.selected_inline_comment = 0;
-                    self.adjust_scroll(visible_lines);
+                    // Loading状態ではqのみ受け付け
+                    if matches!(self.data_state, DataState::Loading) {
+                        if key.code == KeyCode::Char('q') {
+                            self.should_quit = true;
+                        }
+                        return Ok(());
+                    }
                 }
-                return Ok(());
-            }
-
-            // Add comment
-            if self.matches_single_key(&key, &kb.comment) {
-                self.enter_comment_input();
-                return Ok(());
-            }
-
-            // Add suggestion
-            if self.matches_single_key(&key, &kb.suggestion) {
-                self.enter_suggestion_input();
-                return Ok(());
-            }
 
-            // Reply
-            if self.matches_single_key(&key, &kb.reply) {
-                if self.has_comment_at_current_line() {
-                    self.enter_reply_input();
+                // コメント削除の確認中は y で削除、それ以外のキーはキャンセル
+                if let Some(target) = self.confirm_delete.take() {
+                    if key.code == KeyCode::Char('y') {
+                        self.delete_posted_comment(target);
+                    }
+                    return Ok(());
                 }
-                return Ok(());
-            }
 
-            // Tab - select next inline comment
-            if key.code == KeyCode::Tab {
-                if self.has_comment_at_current_line() {
-                    let count = self.get_comment_indices_at_current_line().len();
-                    if count > 1 && self.selected_inline_comment + 1 < count {
-                        self.selected_inline_comment += 1;
-                        self.comment_panel_scroll = self.comment_panel_offset_for(
-                            self.selected_inline_comment,
-                            panel_inner_width,
-                        );
+                // suggestion の適用先の選択中は l / g で実行、それ以外のキーはキャンセル
+                if let Some(suggestions) = self.confirm_suggestions.take() {
+                    match key.code {
+                        KeyCode::Char('l') if self.working_dir.is_some() => {
+                            self.start_apply_suggestions(suggestions, true)
+                        }
+                        KeyCode::Char('g') => self.start_apply_suggestions(suggestions, false),
+                        _ => {}
                     }
+                    return Ok(());
                 }
-                return Ok(());
-            }
 
-            // Shift-Tab - select previous inline comment
-            if key.code == KeyCode::BackTab {
-                if self.has_comment_at_current_line() {
-                    let count = self.get_comment_indices_at_current_line().len();
-                    if count > 1 && self.selected_inline_comment > 0 {
-                        self.selected_inline_comment -= 1;
-                        self.comment_panel_scroll = self.comment_panel_offset_for(
-                            self.selected_inline_comment,
-                            panel_inner_width,
-                        );
+                // マージの確認中は y で実行、それ以外のキーはキャンセル
+                if let Some(request) = self.confirm_merge.take() {
+                    if key.code == KeyCode::Char('y') {
+                        self.start_merge(request);
                     }
+                    return Ok(());
                 }
-                return Ok(());
-            }
 
-            // Variant-specific panel navigation
-            match variant {
-                DiffViewVariant::SplitPane => {
-                    // Go to fullscreen diff
-                    if self.matches_single_key(&key, &kb.move_right) || key.code == KeyCode::Right {
-                        self.diff_view_return_state = AppState::SplitViewDiff;
-                        self.preview_return_state = AppState::DiffView;
-                        self.state = AppState::DiffView;
-                        return Ok(());
-                    }
+                if self.reaction_popup.is_some() {
+                    self.handle_reaction_popup_input(key);
+                    return Ok(());
+                }
 
-                    // Close panel
-                    if self.matches_single_key(&key, &kb.quit)
-                        || self.matches_single_key(&key, &kb.move_left)
-                        || key.code == KeyCode::Left
-                        || key.code == KeyCode::Esc
-                    {
-                        self.comment_panel_open = false;
-                        self.comment_panel_scroll = 0;
-                        return Ok(());
-                    }
+                if self.merge_dialog.is_some() {
+                    self.handle_merge_dialog_input(key, terminal).await?;
+                    return Ok(());
                 }
-                DiffViewVariant::Fullscreen => {
-                    // Back
-                    if self.matches_single_key(&key, &kb.move_left) || key.code == KeyCode::Left {
-                        self.state = self.diff_view_return_state;
-                        return Ok(());
-                    }
 
-                    // Close panel
-                    if self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc {
-                        self.comment_panel_open = false;
-                        self.comment_panel_scroll = 0;
-                        return Ok(());
+                match self.state {
+                    AppState::PullRequestList => self.handle_pr_list_input(key).await?,
+                    AppState::FileList => self.handle_file_list_input(key, terminal).await?,
+                    AppState::DiffView => self.handle_diff_view_input(key, terminal).await?,
+                    AppState::TextInput => self.handle_text_input(key)?,
+                    AppState::CommentList => self.handle_comment_list_input(key, terminal).await?,
+                    AppState::Checks => self.handle_checks_input(key, terminal).await?,
+                    AppState::CommitList => self.handle_commit_list_input(key),
+                    AppState::Metadata => self.handle_metadata_input(key),
+                    AppState::Help => self.handle_help_input(key)?,
+                    AppState::AiRally => self.handle_ai_rally_input(key, terminal).await?,
+                    AppState::SplitViewFileList => {
+                        self.handle_split_view_file_list_input(key, terminal)
+                            .await?
+                    }
+                    AppState::SplitViewDiff => {
+                        self.handle_split_view_diff_input(key, terminal).await?
                     }
                 }
             }
-
-            return Ok(());
         }
+        Ok(())
+    }
 
-        // Check for sequence timeout
-        self.check_sequence_timeout();
-
-        // Get KeyBinding for current event
-        let current_kb = event_to_keybinding(&key);
-
-        // Try to match two-key sequences (gd, gf, gg)
-        if let Some(kb_event) = current_kb {
-            // Check if this key continues a pending sequence
-            if !self.pending_keys.is_empty() {
-                self.push_pending_key(kb_event);
+    fn retry_load(&mut self) {
+        self.load_retry_at = None;
+        if let Some(ref tx) = self.retry_sender {
+            self.data_state = DataState::Loading;
+            let _ = tx.try_send(());
+        }
+    }
 
-                // Check for go_to_definition (gd)
-                if self.try_match_sequence(&kb.go_to_definition) == SequenceMatch::Full {
-                    self.clear_pending_keys();
-                    self.open_symbol_popup(terminal).await?;
-                    return Ok(());
-                }
+    /// 自動再試行の予定時刻を過ぎていれば再読み込みする
+    fn poll_load_retry(&mut self) {
+        let Some(at) = self.load_retry_at else {
+            return;
+        };
+        if Instant::now() >= at && matches!(self.data_state, DataState::Error(_)) {
+            self.retry_load();
+        }
+    }
 
-                // Check for go_to_file (gf)
-                if self.try_match_sequence(&kb.go_to_file) == SequenceMatch::Full {
-                    self.clear_pending_keys();
-                    self.open_current_file_in_editor(terminal).await?;
-                    return Ok(());
-                }
+    /// 自動再試行までの残り時間（エラー画面のカウントダウン用）
+    pub fn load_retry_in(&self) -> Option<std::time::Duration> {
+        self.load_retry_at
+            .map(|at| at.saturating_duration_since(Instant::now()))
+    }
 
-                // Check for jump_to_first (gg)
-                if self.try_match_sequence(&kb.jump_to_first) == SequenceMatch::Full {
-                    self.clear_pending_keys();
-                    self.selected_line = 0;
-                    self.scroll_offset = 0;
-                    return Ok(());
-                }
+    async fn handle_file_list_input(
+        &mut self,
+        key: event::KeyEvent,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        let kb = &self.config.keybindings;
 
-                // No match - clear pending keys and fall through
-                self.clear_pending_keys();
+        // Quit or back to PR list
+        if self.matches_single_key(&key, &kb.quit) {
+            if self.started_from_pr_list {
+                self.back_to_pr_list();
             } else {
-                // Check if this key could start a sequence
-                let could_start_gd = self.key_could_match_sequence(&key, &kb.go_to_definition);
-                let could_start_gf = self.key_could_match_sequence(&key, &kb.go_to_file);
-                let could_start_gg = self.key_could_match_sequence(&key, &kb.jump_to_first);
+                self.should_quit = true;
+            }
+            return Ok(());
+        }
 
-                if could_start_gd || could_start_gf || could_start_gg {
-                    self.push_pending_key(kb_event);
-                    return Ok(());
-                }
+        // Move down (j or Down arrow - arrows always work)
+        if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
+            if !self.files().is_empty() {
+                self.selected_file =
+                    (self.selected_file + 1).min(self.files().len().saturating_sub(1));
             }
+            return Ok(());
         }
 
-        // Variant-specific quit/back handling (outside panel)
-        match variant {
-            DiffViewVariant::SplitPane => {
-                // Go to fullscreen diff
-                if self.matches_single_key(&key, &kb.move_right) || key.code == KeyCode::Right {
-                    self.diff_view_return_state = AppState::SplitViewDiff;
-                    self.preview_return_state = AppState::DiffView;
-                    self.state = AppState::DiffView;
-                    return Ok(());
-                }
-
-                // Back to file list focus
-                if self.matches_single_key(&key, &kb.move_left) || key.code == KeyCode::Left {
-                    self.state = AppState::SplitViewFileList;
-                    return Ok(());
-                }
+        // Move up (k or Up arrow)
+        if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
+            self.selected_file = self.selected_file.saturating_sub(1);
+            return Ok(());
+        }
 
-                // Quit to file list
-                if self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc {
-                    self.state = AppState::FileList;
-                    return Ok(());
-                }
+        // Open split view (Enter, Right arrow, or l)
+        if self.matches_single_key(&key, &kb.open_panel)
+            || self.matches_single_key(&key, &kb.move_right)
+            || key.code == KeyCode::Right
+        {
+            if !self.files().is_empty() {
+                self.state = AppState::SplitViewDiff;
+                self.sync_diff_to_selected_file();
+            }
+            return Ok(());
+        }
 
-                // Add comment (without panel)
-                if self.matches_single_key(&key, &kb.comment) {
-                    self.enter_comment_input();
-                    return Ok(());
-                }
+        // Actions
+        if self.matches_single_key(&key, &kb.approve) {
+            self.submit_review(ReviewAction::Approve, terminal).await?;
+            return Ok(());
+        }
 
-                // Add suggestion (without panel)
-                if self.matches_single_key(&key, &kb.suggestion) {
-                    self.enter_suggestion_input();
-                    return Ok(());
-                }
-            }
-            DiffViewVariant::Fullscreen => {
-                // Quit/back
-                if self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc {
-                    // If started from PR list and we're at the file list level, go back to PR list
-                    if self.started_from_pr_list
-                        && self.diff_view_return_state == AppState::FileList
-                    {
-                        self.back_to_pr_list();
-                    } else {
-                        self.state = self.diff_view_return_state;
-                    }
-                    return Ok(());
-                }
+        if self.matches_single_key(&key, &kb.request_changes) {
+            self.submit_review(ReviewAction::RequestChanges, terminal)
+                .await?;
+            return Ok(());
+        }
 
-                // Back (left arrow or h) - goes to file list, not PR list
-                if self.matches_single_key(&key, &kb.move_left) || key.code == KeyCode::Left {
-                    self.state = self.diff_view_return_state;
-                    return Ok(());
-                }
-            }
+        // Note: In FileList, 'comment' key triggers review comment (not inline comment)
+        // Using separate check for review comment in FileList context
+        if self.matches_single_key(&key, &kb.comment) {
+            self.submit_review(ReviewAction::Comment, terminal).await?;
+            return Ok(());
         }
 
-        // Common single-key bindings
+        // Comment list
+        if self.matches_single_key(&key, &kb.comment_list) {
+            self.previous_state = AppState::FileList;
+            self.open_comment_list();
+            return Ok(());
+        }
 
-        // Move down
-        if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
-            if self.diff_line_count > 0 {
-                self.selected_line =
-                    (self.selected_line + 1).min(self.diff_line_count.saturating_sub(1));
-                self.adjust_scroll(visible_lines);
-            }
+        // CI checks
+        if self.matches_single_key(&key, &kb.checks) {
+            self.previous_state = AppState::FileList;
+            self.open_checks();
             return Ok(());
         }
 
-        // Move up
-        if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
-            self.selected_line = self.selected_line.saturating_sub(1);
-            self.adjust_scroll(visible_lines);
+        // Commits
+        if self.matches_single_key(&key, &kb.commit_list) {
+            self.previous_state = AppState::FileList;
+            self.open_commit_list();
             return Ok(());
         }
 
-        // Jump to last
-        if self.matches_single_key(&key, &kb.jump_to_last) {
-            if self.diff_line_count > 0 {
-                self.selected_line = self.diff_line_count.saturating_sub(1);
-                self.adjust_scroll(visible_lines);
-            }
+        // Changes since my last review
+        if self.matches_single_key(&key, &kb.since_review) {
+            self.toggle_since_review();
             return Ok(());
         }
 
-        // Jump back
-        if self.matches_single_key(&key, &kb.jump_back) {
-            self.jump_back();
+        // Merge
+        if self.matches_single_key(&key, &kb.merge) {
+            self.open_merge_dialog();
             return Ok(());
         }
 
-        // Page down
-        if self.matches_single_key(&key, &kb.page_down) {
-            if self.diff_line_count > 0 {
-                self.selected_line =
-                    (self.selected_line + 20).min(self.diff_line_count.saturating_sub(1));
-                self.adjust_scroll(visible_lines);
-            }
+        // PR metadata
+        if self.matches_single_key(&key, &kb.pr_metadata) {
+            self.previous_state = AppState::FileList;
+            self.open_metadata();
             return Ok(());
         }
 
-        // Page up
-        if self.matches_single_key(&key, &kb.page_up) {
-            self.selected_line = self.selected_line.saturating_sub(20);
-            self.adjust_scroll(visible_lines);
+        // Viewed
+        if self.matches_single_key(&key, &kb.toggle_viewed) {
+            self.toggle_file_viewed();
+            return Ok(());
+        }
+        if self.matches_single_key(&key, &kb.next_unviewed) {
+            self.select_next_unviewed_file();
             return Ok(());
         }
 
-        // Next comment
-        if self.matches_single_key(&key, &kb.next_comment) {
-            self.jump_to_next_comment();
+        // Start/discard pending review
+        if self.matches_single_key(&key, &kb.pending_review) {
+            self.toggle_pending_review();
             return Ok(());
         }
 
-        // Previous comment
-        if self.matches_single_key(&key, &kb.prev_comment) {
-            self.jump_to_prev_comment();
+        // React to the PR
+        if self.matches_single_key(&key, &kb.add_reaction) {
+            self.open_reaction_popup();
             return Ok(());
         }
 
-        // Open panel
-        if self.matches_single_key(&key, &kb.open_panel) {
-            self.comment_panel_open = true;
-            self.comment_panel_scroll = 0;
-            self.selected_inline_comment = 0;
+        // Refresh
+        if self.matches_single_key(&key, &kb.refresh) {
+            self.refresh_all();
             return Ok(());
         }
 
-        // Fullscreen-only: Add comment (without panel)
-        if variant == DiffViewVariant::Fullscreen {
-            if self.matches_single_key(&key, &kb.comment) {
-                self.enter_comment_input();
-                return Ok(());
-            }
+        // AI Rally
+        if self.matches_single_key(&key, &kb.ai_rally) {
+            self.resume_or_start_ai_rally();
+            return Ok(());
+        }
 
-            // Add suggestion (without panel)
-            if self.matches_single_key(&key, &kb.suggestion) {
-                self.enter_suggestion_input();
-                return Ok(());
-            }
+        // Help
+        if self.matches_single_key(&key, &kb.help) {
+            self.previous_state = AppState::FileList;
+            self.state = AppState::Help;
+            return Ok(());
         }
 
         Ok(())
     }
 
-    async fn handle_split_view_diff_input(
+    /// FileList 系状態で共通のキーを処理する。処理した場合は true を返す。
+    async fn handle_common_file_list_keys(
         &mut self,
         key: event::KeyEvent,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<()> {
-        self.handle_diff_input_common(key, terminal, DiffViewVariant::SplitPane)
-            .await
+    ) -> Result<bool> {
+        let kb = &self.config.keybindings;
+
+        if self.matches_single_key(&key, &kb.approve) {
+            self.submit_review(ReviewAction::Approve, terminal).await?;
+            return Ok(true);
+        }
+
+        if self.matches_single_key(&key, &kb.request_changes) {
+            self.submit_review(ReviewAction::RequestChanges, terminal)
+                .await?;
+            return Ok(true);
+        }
+
+        if self.matches_single_key(&key, &kb.comment) {
+            self.submit_review(ReviewAction::Comment, terminal).await?;
+            return Ok(true);
+        }
+
+        if self.matches_single_key(&key, &kb.refresh) {
+            self.refresh_all();
+            return Ok(true);
+        }
+
+        if self.matches_single_key(&key, &kb.ai_rally) {
+            self.resume_or_start_ai_rally();
+            return Ok(true);
+        }
+
+        if self.matches_single_key(&key, &kb.pending_review) {
+            self.toggle_pending_review();
+            return Ok(true);
+        }
+
+        if self.matches_single_key(&key, &kb.add_reaction) {
+            self.open_reaction_popup();
+            return Ok(true);
+        }
+
+        Ok(false)
     }
 
-    async fn handle_ai_rally_input(
+    async fn handle_split_view_file_list_input(
         &mut self,
         key: event::KeyEvent,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     ) -> Result<()> {
-        // Handle modal state first
-        if let Some(ref mut rally_state) = self.ai_rally_state {
-            if rally_state.showing_log_detail {
-                match key.code {
-                    KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
-                        rally_state.showing_log_detail = false;
-                    }
-                    _ => {}
-                }
-                return Ok(());
+        let kb = &self.config.keybindings;
+
+        // Move down
+        if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
+            if !self.files().is_empty() {
+                self.selected_file =
+                    (self.selected_file + 1).min(self.files().len().saturating_sub(1));
+                self.sync_diff_to_selected_file();
             }
+            return Ok(());
         }
 
-        match key.code {
-            KeyCode::Char('b') => {
-                // バックグラウンドで実行を継続したままFileListに戻る
-                // abort()を呼ばない、状態も保持したまま
-                self.state = AppState::FileList;
-            }
-            KeyCode::Char('q') | KeyCode::Esc => {
-                // Send abort command to orchestrator if in waiting state
-                if let Some(ref state) = self.ai_rally_state {
-                    if matches!(
-                        state.state,
-                        RallyState::WaitingForClarification | RallyState::WaitingForPermission
-                    ) {
-                        self.send_rally_command(OrchestratorCommand::Abort);
-                    }
-                }
-                // Abort the orchestrator task if running
-                if let Some(handle) = self.rally_abort_handle.take() {
-                    handle.abort();
-                }
-                // Abort rally and return to file list
-                self.cleanup_rally_state();
-                self.state = AppState::FileList;
+        // Move up
+        if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
+            if self.selected_file > 0 {
+                self.selected_file = self.selected_file.saturating_sub(1);
+                self.sync_diff_to_selected_file();
             }
-            KeyCode::Char('y') => {
-                // Grant permission or open clarification editor
-                let current_state = self
-                    .ai_rally_state
-                    .as_ref()
-                    .map(|s| s.state)
+            return Ok(());
+        }
+
+        // Focus diff pane
+        if self.matches_single_key(&key, &kb.open_panel)
+            || self.matches_single_key(&key, &kb.move_right)
+            || key.code == KeyCode::Right
+        {
+            if !self.files().is_empty() {
+                self.state = AppState::SplitViewDiff;
+            }
+            return Ok(());
+        }
+
+        // Back to file list
+        if self.matches_single_key(&key, &kb.quit)
+            || self.matches_single_key(&key, &kb.move_left)
+            || key.code == KeyCode::Left
+            || key.code == KeyCode::Esc
+        {
+            self.state = AppState::FileList;
+            return Ok(());
+        }
+
+        // Comment list
+        if self.matches_single_key(&key, &kb.comment_list) {
+            self.previous_state = AppState::SplitViewFileList;
+            self.open_comment_list();
+            return Ok(());
+        }
+
+        // CI checks
+        if self.matches_single_key(&key, &kb.checks) {
+            self.previous_state = AppState::SplitViewFileList;
+            self.open_checks();
+            return Ok(());
+        }
+
+        // Commits
+        if self.matches_single_key(&key, &kb.commit_list) {
+            self.previous_state = AppState::SplitViewFileList;
+            self.open_commit_list();
+            return Ok(());
+        }
+
+        // Changes since my last review
+        if self.matches_single_key(&key, &kb.since_review) {
+            self.toggle_since_review();
+            return Ok(());
+        }
+
+        // Merge
+        if self.matches_single_key(&key, &kb.merge) {
+            self.open_merge_dialog();
+            return Ok(());
+        }
+
+        // PR metadata
+        if self.matches_single_key(&key, &kb.pr_metadata) {
+            self.previous_state = AppState::SplitViewFileList;
+            self.open_metadata();
+            return Ok(());
+        }
+
+        // Viewed
+        if self.matches_single_key(&key, &kb.toggle_viewed) {
+            self.toggle_file_viewed();
+            return Ok(());
+        }
+        if self.matches_single_key(&key, &kb.next_unviewed) {
+            if self.select_next_unviewed_file() {
+                self.sync_diff_to_selected_file();
+            }
+            return Ok(());
+        }
+
+        // Help
+        if self.matches_single_key(&key, &kb.help) {
+            self.previous_state = AppState::SplitViewFileList;
+            self.state = AppState::Help;
+            return Ok(());
+        }
+
+        // Fallback to common file list keys
+        self.handle_common_file_list_keys(key, terminal).await?;
+
+        Ok(())
+    }
+
+    /// Common handler for diff view input (both fullscreen and split pane)
+    ///
+    /// The `variant` parameter determines:
+    /// - visible_lines calculation
+    /// - state transitions (back, quit, panel navigation)
+    async fn handle_diff_input_common(
+        &mut self,
+        key: event::KeyEvent,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+        variant: DiffViewVariant,
+    ) -> Result<()> {
+        // シンボルポップアップ表示中
+        if self.symbol_popup.is_some() {
+            self.handle_symbol_popup_input(key, terminal).await?;
+            return Ok(());
+        }
+
+        let term_size = terminal.size()?;
+        let term_h = term_size.height as usize;
+        let term_w = term_size.width as usize;
+
+        // Calculate visible_lines based on variant
+        let visible_lines = match variant {
+            DiffViewVariant::SplitPane => {
+                // Header(3) + Footer(3) + border(2) = 8 を差し引き、65%の高さ
+                (term_h * 65 / 100).saturating_sub(8)
+            }
+            DiffViewVariant::Fullscreen => term_h.saturating_sub(8),
+        };
+        let panel_inner_width = self.comment_panel_inner_width(term_w);
+
+        // Clone keybindings to avoid borrow issues with self
+        let kb = self.config.keybindings.clone();
+
+        // コメントパネルフォーカス中
+        if self.comment_panel_open {
+            // Move down in panel
+            if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
+                let max_scroll = self.max_comment_panel_scroll(term_h, term_w);
+                self.comment_panel_scroll =
+                    self.comment_panel_scroll.saturating_add(1).min(max_scroll);
+                return Ok(());
+            }
+
+            // Move up in panel
+            if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
+                self.comment_panel_scroll = self.comment_panel_scroll.saturating_sub(1);
+                return Ok(());
+            }
+
+            // Next comment
+            if self.matches_single_key(&key, &kb.next_comment) {
+                let prev_line = self.selected_line;
+                self.jump_to_next_comment();
+                if self.selected_line != prev_line {
+                    self.comment_panel_scroll = 0;
+                    self.selected_inline_comment = 0;
+                    self.adjust_scroll(visible_lines);
+                }
+                return Ok(());
+            }
+
+            // Previous comment
+            if self.matches_single_key(&key, &kb.prev_comment) {
+                let prev_line = self.selected_line;
+                self.jump_to_prev_comment();
+                if self.selected_line != prev_line {
+                    self.comment_panel_scroll = 0;
+                    self.selected_inline_comment = 0;
+                    self.adjust_scroll(visible_lines);
+                }
+                return Ok(());
+            }
+
+            // Add comment
+            if self.matches_single_key(&key, &kb.comment) {
+                self.enter_comment_input();
+                return Ok(());
+            }
+
+            // Add suggestion
+            if self.matches_single_key(&key, &kb.suggestion) {
+                self.enter_suggestion_input();
+                return Ok(());
+            }
+
+            // Reply
+            if self.matches_single_key(&key, &kb.reply) {
+                if self.has_comment_at_current_line() {
+                    self.enter_reply_input();
+                }
+                return Ok(());
+            }
+
+            // Resolve / unresolve thread
+            if self.matches_single_key(&key, &kb.resolve_thread) {
+                if let Some(idx) = self.selected_inline_comment_index() {
+                    self.toggle_thread_resolved(idx);
+                }
+                return Ok(());
+            }
+
+            // Edit own comment
+            if self.matches_single_key(&key, &kb.edit_comment) {
+                self.enter_comment_edit_input();
+                return Ok(());
+            }
+
+            // Delete own comment
+            if self.matches_single_key(&key, &kb.delete_comment) {
+                self.request_comment_delete();
+                return Ok(());
+            }
+
+            // React to comment
+            if self.matches_single_key(&key, &kb.add_reaction) {
+                self.open_reaction_popup();
+                return Ok(());
+            }
+
+            // Add / remove suggestion to the batch
+            if self.matches_single_key(&key, &kb.batch_suggestion) {
+                self.toggle_suggestion_batch();
+                return Ok(());
+            }
+
+            // Apply suggestion (or the batch)
+            if self.matches_single_key(&key, &kb.apply_suggestion) {
+                self.request_apply_suggestions();
+                return Ok(());
+            }
+
+            // Collapse / expand thread
+            if self.matches_single_key(&key, &kb.toggle_thread) {
+                if let Some(idx) = self.selected_inline_comment_index() {
+                    self.toggle_thread_collapsed(idx);
+                }
+                return Ok(());
+            }
+
+            // Tab - select next inline comment
+            if key.code == KeyCode::Tab {
+                if self.has_comment_at_current_line() {
+                    let count = self.get_comment_indices_at_current_line().len();
+                    if count > 1 && self.selected_inline_comment + 1 < count {
+                        self.selected_inline_comment += 1;
+                        self.comment_panel_scroll = self.comment_panel_offset_for(
+                            self.selected_inline_comment,
+                            panel_inner_width,
+                        );
+                    }
+                }
+                return Ok(());
+            }
+
+            // Shift-Tab - select previous inline comment
+            if key.code == KeyCode::BackTab {
+                if self.has_comment_at_current_line() {
+                    let count = self.get_comment_indices_at_current_line().len();
+                    if count > 1 && self.selected_inline_comment > 0 {
+                        self.selected_inline_comment -= 1;
+                        self.comment_panel_scroll = self.comment_panel_offset_for(
+                            self.selected_inline_comment,
+                            panel_inner_width,
+                        );
+                    }
+                }
+                return Ok(());
+            }
+
+            // Variant-specific panel navigation
+            match variant {
+                DiffViewVariant::SplitPane => {
+                    // Go to fullscreen diff
+                    if self.matches_single_key(&key, &kb.move_right) || key.code == KeyCode::Right {
+                        self.diff_view_return_state = AppState::SplitViewDiff;
+                        self.preview_return_state = AppState::DiffView;
+                        self.state = AppState::DiffView;
+                        return Ok(());
+                    }
+
+                    // Close panel
+                    if self.matches_single_key(&key, &kb.quit)
+                        || self.matches_single_key(&key, &kb.move_left)
+                        || key.code == KeyCode::Left
+                        || key.code == KeyCode::Esc
+                    {
+                        self.comment_panel_open = false;
+                        self.comment_panel_scroll = 0;
+                        return Ok(());
+                    }
+                }
+                DiffViewVariant::Fullscreen => {
+                    // Back
+                    if self.matches_single_key(&key, &kb.move_left) || key.code == KeyCode::Left {
+                        self.state = self.diff_view_return_state;
+                        return Ok(());
+                    }
+
+                    // Close panel
+                    if self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc {
+                        self.comment_panel_open = false;
+                        self.comment_panel_scroll = 0;
+                        return Ok(());
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+
+        // Check for sequence timeout
+        self.check_sequence_timeout();
+
+        // Get KeyBinding for current event
+        let current_kb = event_to_keybinding(&key);
+
+        // Try to match two-key sequences (gd, gf, gg)
+        if let Some(kb_event) = current_kb {
+            // Check if this key continues a pending sequence
+            if !self.pending_keys.is_empty() {
+                self.push_pending_key(kb_event);
+
+                // Check for go_to_definition (gd)
+                if self.try_match_sequence(&kb.go_to_definition) == SequenceMatch::Full {
+                    self.clear_pending_keys();
+                    self.open_symbol_popup(terminal).await?;
+                    return Ok(());
+                }
+
+                // Check for go_to_file (gf)
+                if self.try_match_sequence(&kb.go_to_file) == SequenceMatch::Full {
+                    self.clear_pending_keys();
+                    self.open_current_file_in_editor(terminal).await?;
+                    return Ok(());
+                }
+
+                // Check for jump_to_first (gg)
+                if self.try_match_sequence(&kb.jump_to_first) == SequenceMatch::Full {
+                    self.clear_pending_keys();
+                    self.selected_line = 0;
+                    self.scroll_offset = 0;
+                    return Ok(());
+                }
+
+                // Check for next/prev CI annotation (]d / [d)
+                if self.try_match_sequence(&kb.next_annotation) == SequenceMatch::Full {
+                    self.clear_pending_keys();
+                    self.jump_to_next_annotation();
+                    return Ok(());
+                }
+                if self.try_match_sequence(&kb.prev_annotation) == SequenceMatch::Full {
+                    self.clear_pending_keys();
+                    self.jump_to_prev_annotation();
+                    return Ok(());
+                }
+
+                // No match - clear pending keys and fall through
+                self.clear_pending_keys();
+            } else {
+                // Check if this key could start a sequence
+                let could_start_gd = self.key_could_match_sequence(&key, &kb.go_to_definition);
+                let could_start_gf = self.key_could_match_sequence(&key, &kb.go_to_file);
+                let could_start_gg = self.key_could_match_sequence(&key, &kb.jump_to_first);
+                let could_start_annotation = self
+                    .key_could_match_sequence(&key, &kb.next_annotation)
+                    || self.key_could_match_sequence(&key, &kb.prev_annotation);
+
+                if could_start_gd || could_start_gf || could_start_gg || could_start_annotation {
+                    self.push_pending_key(kb_event);
+                    return Ok(());
+                }
+            }
+        }
+
+        // Visual selection: toggle / cancel
+        if self.matches_single_key(&key, &kb.visual_select) {
+            self.visual_anchor = match self.visual_anchor {
+                Some(_) => None,
+                None => Some(self.selected_line),
+            };
+            return Ok(());
+        }
+        if self.visual_anchor.is_some()
+            && (self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc)
+        {
+            self.visual_anchor = None;
+            return Ok(());
+        }
+
+        // Variant-specific quit/back handling (outside panel)
+        match variant {
+            DiffViewVariant::SplitPane => {
+                // Go to fullscreen diff
+                if self.matches_single_key(&key, &kb.move_right) || key.code == KeyCode::Right {
+                    self.diff_view_return_state = AppState::SplitViewDiff;
+                    self.preview_return_state = AppState::DiffView;
+                    self.state = AppState::DiffView;
+                    return Ok(());
+                }
+
+                // Back to file list focus
+                if self.matches_single_key(&key, &kb.move_left) || key.code == KeyCode::Left {
+                    self.state = AppState::SplitViewFileList;
+                    return Ok(());
+                }
+
+                // Quit to file list
+                if self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc {
+                    self.state = AppState::FileList;
+                    return Ok(());
+                }
+
+                // Add comment (without panel)
+                if self.matches_single_key(&key, &kb.comment) {
+                    self.enter_comment_input();
+                    return Ok(());
+                }
+
+                // Add suggestion (without panel)
+                if self.matches_single_key(&key, &kb.suggestion) {
+                    self.enter_suggestion_input();
+                    return Ok(());
+                }
+            }
+            DiffViewVariant::Fullscreen => {
+                // Quit/back
+                if self.matches_single_key(&key, &kb.quit) || key.code == KeyCode::Esc {
+                    // If started from PR list and we're at the file list level, go back to PR list
+                    if self.started_from_pr_list
+                        && self.diff_view_return_state == AppState::FileList
+                    {
+                        self.back_to_pr_list();
+                    } else {
+                        self.state = self.diff_view_return_state;
+                    }
+                    return Ok(());
+                }
+
+                // Back (left arrow or h) - goes to file list, not PR list
+                if self.matches_single_key(&key, &kb.move_left) || key.code == KeyCode::Left {
+                    self.state = self.diff_view_return_state;
+                    return Ok(());
+                }
+            }
+        }
+
+        // Common single-key bindings
+
+        // Move down
+        if self.matches_single_key(&key, &kb.move_down) || key.code == KeyCode::Down {
+            if self.diff_line_count > 0 {
+                self.selected_line =
+                    (self.selected_line + 1).min(self.diff_line_count.saturating_sub(1));
+                self.adjust_scroll(visible_lines);
+            }
+            return Ok(());
+        }
+
+        // Move up
+        if self.matches_single_key(&key, &kb.move_up) || key.code == KeyCode::Up {
+            self.selected_line = self.selected_line.saturating_sub(1);
+            self.adjust_scroll(visible_lines);
+            return Ok(());
+        }
+
+        // Jump to last
+        if self.matches_single_key(&key, &kb.jump_to_last) {
+            if self.diff_line_count > 0 {
+                self.selected_line = self.diff_line_count.saturating_sub(1);
+                self.adjust_scroll(visible_lines);
+            }
+            return Ok(());
+        }
+
+        // Jump back
+        if self.matches_single_key(&key, &kb.jump_back) {
+            self.jump_back();
+            return Ok(());
+        }
+
+        // Page down
+        if self.matches_single_key(&key, &kb.page_down) {
+            if self.diff_line_count > 0 {
+                self.selected_line =
+                    (self.selected_line + 20).min(self.diff_line_count.saturating_sub(1));
+                self.adjust_scroll(visible_lines);
+            }
+            return Ok(());
+        }
+
+        // Page up
+        if self.matches_single_key(&key, &kb.page_up) {
+            self.selected_line = self.selected_line.saturating_sub(20);
+            self.adjust_scroll(visible_lines);
+            return Ok(());
+        }
+
+        // Next comment
+        if self.matches_single_key(&key, &kb.next_comment) {
+            self.jump_to_next_comment();
+            return Ok(());
+        }
+
+        // Previous comment
+        if self.matches_single_key(&key, &kb.prev_comment) {
+            self.jump_to_prev_comment();
+            return Ok(());
+        }
+
+        // Start/discard pending review
+        if self.matches_single_key(&key, &kb.pending_review) {
+            self.toggle_pending_review();
+            return Ok(());
+        }
+
+        // Open panel
+        if self.matches_single_key(&key, &kb.open_panel) {
+            self.comment_panel_open = true;
+            self.comment_panel_scroll = 0;
+            self.selected_inline_comment = 0;
+            return Ok(());
+        }
+
+        // Fullscreen-only: Add comment (without panel)
+        if variant == DiffViewVariant::Fullscreen {
+            if self.matches_single_key(&key, &kb.comment) {
+                self.enter_comment_input();
+                return Ok(());
+            }
+
+            // Add suggestion (without panel)
+            if self.matches_single_key(&key, &kb.suggestion) {
+                self.enter_suggestion_input();
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn handle_split_view_diff_input(
+        &mut self,
+        key: event::KeyEvent,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        self.handle_diff_input_common(key, terminal, DiffViewVariant::SplitPane)
+            .await
+    }
+
+    async fn handle_ai_rally_input(
+        &mut self,
+        key: event::KeyEvent,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        // Handle modal state first
+        if let Some(ref mut rally_state) = self.ai_rally_state {
+            if rally_state.showing_log_detail {
+                match key.code {
+                    KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => {
+                        rally_state.showing_log_detail = false;
+                    }
+                    _ => {}
+                }
+                return Ok(());
+            }
+        }
+
+        match key.code {
+            KeyCode::Char('b') => {
+                // バックグラウンドで実行を継続したままFileListに戻る
+                // abort()を呼ばない、状態も保持したまま
+                self.state = AppState::FileList;
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                // Send abort command to orchestrator if in waiting state
+                if let Some(ref state) = self.ai_rally_state {
+                    if matches!(
+                        state.state,
+                        RallyState::WaitingForClarification | RallyState::WaitingForPermission
+                    ) {
+                        self.send_rally_command(OrchestratorCommand::Abort);
+                    }
+                }
+                // Abort the orchestrator task if running
+                if let Some(handle) = self.rally_abort_handle.take() {
+                    handle.abort();
+                }
+                // Abort rally and return to file list
+                self.cleanup_rally_state();
+                self.state = AppState::FileList;
+            }
+            KeyCode::Char('y') => {
+                // Grant permission or open clarification editor
+                let current_state = self
+                    .ai_rally_state
+                    .as_ref()
+                    .map(|s| s.state)
+                    .unwrap_or(RallyState::Error);
+
+                match current_state {
+                    RallyState::WaitingForPermission => {
+                        // Send permission granted
+                        self.send_rally_command(OrchestratorCommand::PermissionResponse(true));
+                        // Clear pending permission and update state to prevent duplicate sends
+                        if let Some(ref mut rally_state) = self.ai_rally_state {
+                            rally_state.pending_permission = None;
+                            rally_state.state = RallyState::RevieweeFix;
+                            rally_state.push_log(LogEntry::new(
+                                LogEventType::Info,
+                                "Permission granted, continuing...".to_string(),
+                            ));
+                        }
+                    }
+                    RallyState::WaitingForClarification => {
+                        // Get the question for the editor
+                        let question = self
+                            .ai_rally_state
+                            .as_ref()
+                            .and_then(|s| s.pending_question.clone())
+                            .unwrap_or_default();
+
+                        // Open editor synchronously (restore terminal first)
+                        self.open_clarification_editor_sync(&question, terminal)?;
+                    }
+                    _ => {}
+                }
+            }
+            KeyCode::Char('n') => {
+                // Deny permission or skip clarification
+                let current_state = self
+                    .ai_rally_state
+                    .as_ref()
+                    .map(|s| s.state)
                     .unwrap_or(RallyState::Error);
 
-                match current_state {
-                    RallyState::WaitingForPermission => {
-                        // Send permission granted
-                        self.send_rally_command(OrchestratorCommand::PermissionResponse(true));
-                        // Clear pending permission and update state to prevent duplicate sends
-                        if let Some(ref mut rally_state) = self.ai_rally_state {
-                            rally_state.pending_permission = None;
-                            rally_state.state = RallyState::RevieweeFix;
-                            rally_state.push_log(LogEntry::new(
-                                LogEventType::Info,
-                                "Permission granted, continuing...".to_string(),
-                            ));
-                        }
-                    }
-                    RallyState::WaitingForClarification => {
-                        // Get the question for the editor
-                        let question = self
-                            .ai_rally_state
-                            .as_ref()
-                            .and_then(|s| s.pending_question.clone())
-                            .unwrap_or_default();
+                match current_state {
+                    RallyState::WaitingForPermission => {
+                        // Send permission denied
+                        self.send_rally_command(OrchestratorCommand::PermissionResponse(false));
+                        // Clear pending permission - state change is delegated to Orchestrator's StateChanged event
+                        if let Some(ref mut rally_state) = self.ai_rally_state {
+                            rally_state.pending_permission = None;
+                            // Do NOT change rally_state.state here - let Orchestrator's StateChanged event handle it
+                            rally_state.push_log(LogEntry::new(
+                                LogEventType::Info,
+                                "Permission denied, continuing without it...".to_string(),
+                            ));
+                        }
+                    }
+                    RallyState::WaitingForClarification => {
+                        // Send skip clarification (continue with best judgment)
+                        self.send_rally_command(OrchestratorCommand::SkipClarification);
+                        // Clear pending question - state change is delegated to Orchestrator's StateChanged event
+                        if let Some(ref mut rally_state) = self.ai_rally_state {
+                            rally_state.pending_question = None;
+                            // Do NOT change rally_state.state here - let Orchestrator's StateChanged event handle it
+                            rally_state.push_log(LogEntry::new(
+                                LogEventType::Info,
+                                "Clarification skipped, continuing with best judgment..."
+                                    .to_string(),
+                            ));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            KeyCode::Char('r') => {
+                // Retry on error state
+                if let Some(ref state) = self.ai_rally_state {
+                    if state.state == RallyState::Error {
+                        // Abort current handle if any
+                        if let Some(handle) = self.rally_abort_handle.take() {
+                            handle.abort();
+                        }
+                        // Clear state and restart
+                        self.ai_rally_state = None;
+                        self.rally_event_receiver = None;
+                        self.state = AppState::FileList;
+                        // Restart the rally
+                        self.start_ai_rally();
+                    }
+                }
+            }
+            // Log selection and scrolling
+            KeyCode::Char('j') | KeyCode::Down => {
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    let total_logs = rally_state.logs.len();
+                    if total_logs == 0 {
+                        return Ok(());
+                    }
+
+                    // Initialize selection if not set
+                    let current = rally_state.selected_log_index.unwrap_or(0);
+                    let new_index = (current + 1).min(total_logs.saturating_sub(1));
+                    rally_state.selected_log_index = Some(new_index);
+
+                    // Auto-scroll to keep selection visible
+                    self.adjust_log_scroll_to_selection();
+                }
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    let total_logs = rally_state.logs.len();
+                    if total_logs == 0 {
+                        return Ok(());
+                    }
+
+                    // Initialize selection if not set (start from last)
+                    let current = rally_state
+                        .selected_log_index
+                        .unwrap_or(total_logs.saturating_sub(1));
+                    let new_index = current.saturating_sub(1);
+                    rally_state.selected_log_index = Some(new_index);
+
+                    // Auto-scroll to keep selection visible
+                    self.adjust_log_scroll_to_selection();
+                }
+            }
+            KeyCode::Enter => {
+                // Show log detail modal
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    if rally_state.selected_log_index.is_some() && !rally_state.logs.is_empty() {
+                        rally_state.showing_log_detail = true;
+                    }
+                }
+            }
+            KeyCode::Char('G') => {
+                // Jump to bottom
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    let total_logs = rally_state.logs.len();
+                    if total_logs > 0 {
+                        rally_state.selected_log_index = Some(total_logs.saturating_sub(1));
+                        rally_state.log_scroll_offset = 0; // 0 means auto-scroll to bottom
+                    }
+                }
+            }
+            KeyCode::Char('g') => {
+                // Jump to top
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    if !rally_state.logs.is_empty() {
+                        rally_state.selected_log_index = Some(0);
+                        rally_state.log_scroll_offset = 1; // 1 is minimum (not 0 which means auto-scroll)
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Adjust log scroll offset to keep the selected log visible
+    fn adjust_log_scroll_to_selection(&mut self) {
+        if let Some(ref mut rally_state) = self.ai_rally_state {
+            let Some(selected) = rally_state.selected_log_index else {
+                return;
+            };
+
+            let visible_height = rally_state.last_visible_log_height;
+
+            // Calculate current scroll position
+            let total_logs = rally_state.logs.len();
+            let scroll_offset = if rally_state.log_scroll_offset == 0 {
+                total_logs.saturating_sub(visible_height)
+            } else {
+                rally_state.log_scroll_offset
+            };
+
+            // Adjust scroll to keep selection visible
+            if selected < scroll_offset {
+                // Selection is above visible area
+                rally_state.log_scroll_offset = selected.max(1);
+            } else if selected >= scroll_offset + visible_height {
+                // Selection is below visible area
+                rally_state.log_scroll_offset = selected.saturating_sub(visible_height - 1).max(1);
+            }
+        }
+    }
+
+    /// Send a command to the orchestrator
+    fn send_rally_command(&mut self, cmd: OrchestratorCommand) {
+        if let Some(ref sender) = self.rally_command_sender {
+            // Use try_send since we're not in an async context
+            if sender.try_send(cmd).is_err() {
+                // Orchestrator may have terminated, clean up state
+                self.cleanup_rally_state();
+            }
+        }
+    }
+
+    /// Clean up rally state when orchestrator terminates or user aborts
+    fn cleanup_rally_state(&mut self) {
+        self.ai_rally_state = None;
+        self.rally_command_sender = None;
+        self.rally_event_receiver = None;
+        if let Some(handle) = self.rally_abort_handle.take() {
+            handle.abort();
+        }
+    }
+
+    /// Open editor for clarification input synchronously
+    fn open_clarification_editor_sync(
+        &mut self,
+        question: &str,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        // Restore terminal before opening editor
+        ui::restore_terminal(terminal)?;
+
+        // Open editor (blocking)
+        let answer = crate::editor::open_clarification_editor(&self.config.editor, question)?;
+
+        // Re-setup terminal after editor closes
+        *terminal = ui::setup_terminal()?;
+
+        // Process result
+        if let Some(ref mut rally_state) = self.ai_rally_state {
+            rally_state.pending_question = None;
+        }
+
+        match answer {
+            Some(text) if !text.trim().is_empty() => {
+                // Send clarification response
+                self.send_rally_command(OrchestratorCommand::ClarificationResponse(text.clone()));
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    rally_state.push_log(LogEntry::new(
+                        LogEventType::Info,
+                        format!("Clarification provided: {}", text),
+                    ));
+                }
+            }
+            _ => {
+                // User cancelled (empty answer)
+                self.send_rally_command(OrchestratorCommand::Abort);
+                if let Some(ref mut rally_state) = self.ai_rally_state {
+                    rally_state.push_log(LogEntry::new(
+                        LogEventType::Info,
+                        "Clarification cancelled by user".to_string(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 既存のRallyがあれば画面遷移のみ、なければ新規Rally開始
+    fn resume_or_start_ai_rally(&mut self) {
+        // 既存のRallyがあれば画面遷移のみ（完了/エラー状態でも結果確認のため）
+        if self.ai_rally_state.is_some() {
+            self.state = AppState::AiRally;
+            return;
+        }
+        // そうでなければ新規Rally開始
+        self.start_ai_rally();
+    }
+
+    /// バックグラウンドでRallyが実行中かどうか（完了・エラー以外）
+    #[allow(dead_code)]
+    pub fn is_rally_running_in_background(&self) -> bool {
+        self.state != AppState::AiRally
+            && self
+                .ai_rally_state
+                .as_ref()
+                .map(|s| s.state.is_active())
+                .unwrap_or(false)
+    }
+
+    /// バックグラウンドでRallyが存在するかどうか（完了・エラー含む）
+    pub fn has_background_rally(&self) -> bool {
+        self.state != AppState::AiRally && self.ai_rally_state.is_some()
+    }
+
+    /// バックグラウンドRallyが完了またはエラーで終了したかどうか
+    #[allow(dead_code)]
+    pub fn is_background_rally_finished(&self) -> bool {
+        self.state != AppState::AiRally
+            && self
+                .ai_rally_state
+                .as_ref()
+                .map(|s| s.state.is_finished())
+                .unwrap_or(false)
+    }
+
+    fn start_ai_rally(&mut self) {
+        // 受信箱の PR はローカルのチェックアウトと別リポジトリのことがある
+        if self.pr_list_query.inbox {
+            self.submission_result = Some((
+                false,
+                "AI Rally is not available in inbox mode (open the PR with --repo)".to_string(),
+            ));
+            self.submission_result_time = Some(Instant::now());
+            return;
+        }
+
+        // Get PR data for context
+        let Some(pr) = self.pr() else {
+            return;
+        };
+
+        let diff = self
+            .files()
+            .iter()
+            .filter_map(|f| f.patch.as_ref())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let context = Context {
+            repo: self.repo.clone(),
+            pr_number: self.pr_number(),
+            pr_title: pr.title.clone(),
+            pr_body: pr.body.clone(),
+            diff,
+            working_dir: self.working_dir.clone(),
+            head_sha: pr.head.sha.clone(),
+            base_branch: pr.base.ref_name.clone(),
+            external_comments: Vec::new(),
+        };
+
+        let (event_tx, event_rx) = mpsc::channel(100);
+        let (cmd_tx, cmd_rx) = mpsc::channel(10);
+
+        // Store channels first to prevent race conditions
+        self.rally_event_receiver = Some(event_rx);
+        self.rally_command_sender = Some(cmd_tx);
+
+        // Initialize rally state
+        self.ai_rally_state = Some(AiRallyState {
+            iteration: 0,
+            max_iterations: self.config.ai.max_iterations,
+            state: RallyState::Initializing,
+            history: Vec::new(),
+            logs: Vec::new(),
+            log_scroll_offset: 0,
+            selected_log_index: None,
+            showing_log_detail: false,
+            pending_question: None,
+            pending_permission: None,
+            last_visible_log_height: 10,
+        });
+
+        self.state = AppState::AiRally;
+
+        // Spawn the orchestrator and store the abort handle
+        let config = self.config.ai.clone();
+        let repo = self.repo.clone();
+        let pr_number = self.pr_number();
+        let client = self.github_client();
+
+        let handle = tokio::spawn(async move {
+            let orchestrator_result = Orchestrator::new(
+                &repo,
+                pr_number,
+                config,
+                client,
+                event_tx.clone(),
+                Some(cmd_rx),
+            );
+            match orchestrator_result {
+                Ok(mut orchestrator) => {
+                    orchestrator.set_context(context);
+                    // Note: orchestrator.run() already emits RallyEvent::Error and
+                    // StateChanged(Error) when it fails, so we don't emit them again here
+                    // to avoid duplicate error logs in the UI
+                    let _ = orchestrator.run().await;
+                }
+                Err(e) => {
+                    // Send error via event channel so it displays in TUI
+                    let _ = event_tx
+                        .send(RallyEvent::Error(format!(
+                            "Failed to create orchestrator: {}",
+                            e
+                        )))
+                        .await;
+                }
+            }
+        });
+
+        // Store the abort handle so we can cancel the task when user presses 'q'
+        self.rally_abort_handle = Some(handle.abort_handle());
+    }
+
+    fn refresh_all(&mut self) {
+        // インメモリキャッシュを全削除
+        self.session_cache.invalidate_all();
+        // コメントデータをクリア
+        self.review_comments = None;
+        self.discussion_comments = None;
+        self.comments_loading = false;
+        self.discussion_comments_loading = false;
+        // CI 結果などは PR データ取得後に取り直す
+        self.reset_pr_data();
+        self.update_file_annotation_positions();
+        // PRデータを再取得
+        self.retry_load();
+    }
+
+    async fn handle_diff_view_input(
+        &mut self,
+        key: event::KeyEvent,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    ) -> Result<()> {
+        self.handle_diff_input_common(key, terminal, DiffViewVariant::Fullscreen)
+            .await
+    }
+
+    fn adjust_scroll(&mut self, visible_lines: usize) {
+        if visible_lines == 0 {
+            return;
+        }
+        if self.selected_line < self.scroll_offset {
+            self.scroll_offset = self.selected_line;
+        }
+        if self.selected_line >= self.scroll_offset + visible_lines {
+            self.scroll_offset = self.selected_line.saturating_sub(visible_lines) + 1;
+        }
+
+        // Allow additional scrolling when at the end (bottom padding)
+        // This enables showing empty space below the last line
+        let padding = visible_lines / 2;
+        let max_scroll_with_padding = self.diff_line_count.saturating_sub(1);
+        if self.selected_line >= self.diff_line_count.saturating_sub(padding) {
+            // When near the end, allow scroll_offset to go further
+            let target_scroll = self.selected_line.saturating_sub(visible_lines / 2);
+            self.scroll_offset = target_scroll.min(max_scroll_with_padding);
+        }
+    }
+
+    /// 統一入力ハンドラー（コメント/サジェスチョン/リプライ共通）
+    fn handle_text_input(&mut self, key: event::KeyEvent) -> Result<()> {
+        // 送信中は入力を無視
+        if self.comment_submitting {
+            return Ok(());
+        }
+
+        match self.input_text_area.input(key) {
+            TextAreaAction::Submit => {
+                let content = self.input_text_area.content();
+                if content.trim().is_empty() {
+                    // 空の場合はキャンセル扱い
+                    self.cancel_input();
+                    return Ok(());
+                }
+
+                match self.input_mode.take() {
+                    Some(InputMode::Comment(ctx)) => {
+                        self.submit_comment(ctx, content);
+                    }
+                    Some(InputMode::Suggestion {
+                        context,
+                        original_code: _,
+                    }) => {
+                        self.submit_suggestion(context, content);
+                    }
+                    Some(InputMode::Reply { comment_id, .. }) => {
+                        self.submit_reply(comment_id, content);
+                    }
+                    Some(InputMode::EditDraft { index }) => {
+                        self.update_draft_comment(index, content);
+                    }
+                    Some(InputMode::EditComment { target, .. }) => {
+                        self.submit_comment_edit(target, content);
+                    }
+                    Some(InputMode::DiscussionComment) => {
+                        self.submit_discussion_comment(content);
+                    }
+                    None => {}
+                }
+                self.state = self.preview_return_state;
+            }
+            TextAreaAction::Cancel => {
+                self.cancel_input();
+            }
+            TextAreaAction::Continue => {}
+            TextAreaAction::PendingSequence => {
+                // Waiting for more keys in a sequence, do nothing
+            }
+        }
+        Ok(())
+    }
+
+    fn cancel_input(&mut self) {
+        self.input_mode = None;
+        self.input_text_area.clear();
+        self.state = self.preview_return_state;
+    }
+
+    fn submit_comment(&mut self, ctx: LineInputContext, body: String) {
+        let Some(file) = self.files().get(ctx.file_index) else {
+            return;
+        };
+        let Some(pr) = self.pr() else {
+            return;
+        };
+
+        let commit_id = pr.head.sha.clone();
+        let filename = file.filename.clone();
+        let repo = self.repo.clone();
+        let pr_number = self.pr_number();
+        let range = ctx.range;
+
+        if self.queue_pending_comment(&filename, range, &body) {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel(1);
+        self.comment_submit_receiver = Some((pr_number, rx));
+        self.comment_submitting = true;
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            let result = client
+                .create_review_comment(&repo, pr_number, &commit_id, &filename, range, &body)
+                .await;
+
+            let _ = tx
+                .send(match result {
+                    Ok(_) => CommentSubmitResult::Success,
+                    Err(e) => CommentSubmitResult::Error(e.to_string()),
+                })
+                .await;
+        });
+    }
+
+    fn submit_suggestion(&mut self, ctx: LineInputContext, suggested_code: String) {
+        let Some(file) = self.files().get(ctx.file_index) else {
+            return;
+        };
+        let Some(pr) = self.pr() else {
+            return;
+        };
+
+        let commit_id = pr.head.sha.clone();
+        let filename = file.filename.clone();
+        let body = format!("```suggestion\n{}\n```", suggested_code.trim_end());
+        let repo = self.repo.clone();
+        let pr_number = self.pr_number();
+        let range = ctx.range;
+
+        if self.queue_pending_comment(&filename, range, &body) {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel(1);
+        self.comment_submit_receiver = Some((pr_number, rx));
+        self.comment_submitting = true;
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            let result = client
+                .create_review_comment(&repo, pr_number, &commit_id, &filename, range, &body)
+                .await;
+
+            let _ = tx
+                .send(match result {
+                    Ok(_) => CommentSubmitResult::Success,
+                    Err(e) => CommentSubmitResult::Error(e.to_string()),
+                })
+                .await;
+        });
+    }
+
+    /// 選択中のdiff行範囲（ビジュアル選択がなければ現在行のみ）
+    pub fn selected_line_range(&self) -> std::ops::RangeInclusive<usize> {
+        match self.visual_anchor {
+            Some(anchor) => anchor.min(self.selected_line)..=anchor.max(self.selected_line),
+            None => self.selected_line..=self.selected_line,
+        }
+    }
 
-                        // Open editor synchronously (restore terminal first)
-                        self.open_clarification_editor_sync(&question, terminal)?;
-                    }
-                    _ => {}
-                }
-            }
-            KeyCode::Char('n') => {
-                // Deny permission or skip clarification
-                let current_state = self
-                    .ai_rally_state
-                    .as_ref()
-                    .map(|s| s.state)
-                    .unwrap_or(RallyState::Error);
+    /// 現在のPRのキー（保留レビューの管理に使用）
+    /// セッションキャッシュのキー（ホスト + リポジトリ + PR番号）
+    fn pr_cache_key(&self, pr_number: u32) -> PrCacheKey {
+        PrCacheKey {
+            host: self.github.host().to_string(),
+            repo: self.repo.clone(),
+            pr_number,
+        }
+    }
 
-                match current_state {
-                    RallyState::WaitingForPermission => {
-                        // Send permission denied
-                        self.send_rally_command(OrchestratorCommand::PermissionResponse(false));
-                        // Clear pending permission - state change is delegated to Orchestrator's StateChanged event
-                        if let Some(ref mut rally_state) = self.ai_rally_state {
-                            rally_state.pending_permission = None;
-                            // Do NOT change rally_state.state here - let Orchestrator's StateChanged event handle it
-                            rally_state.push_log(LogEntry::new(
-                                LogEventType::Info,
-                                "Permission denied, continuing without it...".to_string(),
-                            ));
-                        }
-                    }
-                    RallyState::WaitingForClarification => {
-                        // Send skip clarification (continue with best judgment)
-                        self.send_rally_command(OrchestratorCommand::SkipClarification);
-                        // Clear pending question - state change is delegated to Orchestrator's StateChanged event
-                        if let Some(ref mut rally_state) = self.ai_rally_state {
-                            rally_state.pending_question = None;
-                            // Do NOT change rally_state.state here - let Orchestrator's StateChanged event handle it
-                            rally_state.push_log(LogEntry::new(
-                                LogEventType::Info,
-                                "Clarification skipped, continuing with best judgment..."
-                                    .to_string(),
-                            ));
-                        }
-                    }
-                    _ => {}
-                }
+    fn current_pr_key(&self) -> Option<PrCacheKey> {
+        self.pr_number.map(|pr_number| self.pr_cache_key(pr_number))
+    }
+
+    /// 現在のPRの保留レビュー（未開始なら None）
+    pub fn pending_review(&self) -> Option<&PendingReview> {
+        self.pending_reviews.get(&self.current_pr_key()?)
+    }
+
+    fn pending_review_mut(&mut self) -> Option<&mut PendingReview> {
+        let key = self.current_pr_key()?;
+        self.pending_reviews.get_mut(&key)
+    }
+
+    /// 保留レビューの開始/破棄を切り替える
+    ///
+    /// 未送信のコメントが残っている場合は破棄せず、送信か削除を促す。
+    fn toggle_pending_review(&mut self) {
+        let Some(key) = self.current_pr_key() else {
+            return;
+        };
+        let result = match self.pending_reviews.get(&key) {
+            None => {
+                self.pending_reviews.insert(key, PendingReview::default());
+                (
+                    true,
+                    "Review started: comments are held until submit".to_string(),
+                )
             }
-            KeyCode::Char('r') => {
-                // Retry on error state
-                if let Some(ref state) = self.ai_rally_state {
-                    if state.state == RallyState::Error {
-                        // Abort current handle if any
-                        if let Some(handle) = self.rally_abort_handle.take() {
-                            handle.abort();
-                        }
-                        // Clear state and restart
-                        self.ai_rally_state = None;
-                        self.rally_event_receiver = None;
-                        self.state = AppState::FileList;
-                        // Restart the rally
-                        self.start_ai_rally();
-                    }
-                }
+            Some(review) if review.comments.is_empty() => {
+                self.pending_reviews.remove(&key);
+                (true, "Pending review discarded".to_string())
             }
-            // Log selection and scrolling
-            KeyCode::Char('j') | KeyCode::Down => {
-                if let Some(ref mut rally_state) = self.ai_rally_state {
-                    let total_logs = rally_state.logs.len();
-                    if total_logs == 0 {
-                        return Ok(());
-                    }
+            Some(review) => (
+                false,
+                format!(
+                    "{} pending comment(s): submit the review or delete them first",
+                    review.comments.len()
+                ),
+            ),
+        };
+        self.submission_result = Some(result);
+        self.submission_result_time = Some(Instant::now());
+    }
 
-                    // Initialize selection if not set
-                    let current = rally_state.selected_log_index.unwrap_or(0);
-                    let new_index = (current + 1).min(total_logs.saturating_sub(1));
-                    rally_state.selected_log_index = Some(new_index);
+    /// 保留レビュー中であればコメントを積む。積んだ場合は true を返す。
+    fn queue_pending_comment(&mut self, path: &str, range: CommentRange, body: &str) -> bool {
+        let Some(review) = self.pending_review_mut() else {
+            return false;
+        };
+        review.comments.push(DraftComment {
+            path: path.to_string(),
+            range,
+            body: body.to_string(),
+        });
+        let count = review.comments.len();
+        self.submission_result = Some((true, format!("Added to pending review ({})", count)));
+        self.submission_result_time = Some(Instant::now());
+        true
+    }
 
-                    // Auto-scroll to keep selection visible
-                    self.adjust_log_scroll_to_selection();
+    /// 保留中コメントの本文を更新
+    fn update_draft_comment(&mut self, index: usize, body: String) {
+        if let Some(draft) = self
+            .pending_review_mut()
+            .and_then(|review| review.comments.get_mut(index))
+        {
+            draft.body = body;
+        }
+    }
+
+    /// 選択中の保留コメントを削除
+    fn delete_selected_draft_comment(&mut self) {
+        let index = self.selected_pending_comment;
+        let Some(review) = self.pending_review_mut() else {
+            return;
+        };
+        if index < review.comments.len() {
+            review.comments.remove(index);
+        }
+        let remaining = review.comments.len();
+        self.selected_pending_comment = index.min(remaining.saturating_sub(1));
+    }
+
+    /// 選択中の保留コメントを編集（TextArea に本文をプリフィル）
+    fn enter_draft_edit_input(&mut self) {
+        let index = self.selected_pending_comment;
+        let Some(body) = self
+            .pending_review()
+            .and_then(|review| review.comments.get(index))
+            .map(|draft| draft.body.clone())
+        else {
+            return;
+        };
+        self.input_mode = Some(InputMode::EditDraft { index });
+        self.input_text_area.set_content(&body);
+        self.preview_return_state = self.state;
+        self.state = AppState::TextInput;
+    }
+
+    /// 操作対象の投稿済みコメント（コメント一覧の選択、またはコメントパネルの選択）
+    ///
+    /// 戻り値は (対象, 投稿者, 本文, 表示用の位置)。PR レビュー本文は対象外。
+    fn selected_posted_comment(&self) -> Option<(PostedComment, String, String, String)> {
+        let review_index = if self.state == AppState::CommentList {
+            match self.comment_tab {
+                CommentTab::Review => Some(self.selected_comment),
+                CommentTab::Discussion => {
+                    let comment = self
+                        .discussion_comments
+                        .as_ref()?
+                        .get(self.selected_discussion_comment)
+                        .filter(|c| c.id != OPTIMISTIC_COMMENT_ID)?;
+                    return Some((
+                        PostedComment::Discussion(comment.id),
+                        comment.user.login.clone(),
+                        comment.body.clone(),
+                        "Discussion".to_string(),
+                    ));
                 }
+                CommentTab::Pending => None,
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if let Some(ref mut rally_state) = self.ai_rally_state {
-                    let total_logs = rally_state.logs.len();
-                    if total_logs == 0 {
-                        return Ok(());
-                    }
+        } else {
+            self.selected_inline_comment_index()
+        };
+        let comment = self.review_comments.as_ref()?.get(review_index?)?;
+        if comment.path == PR_REVIEW_PATH {
+            return None;
+        }
+        let location = match comment.line {
+            Some(line) => format!("{}:{}", comment.path, line),
+            None => comment.path.clone(),
+        };
+        Some((
+            PostedComment::Review(comment.id),
+            comment.user.login.clone(),
+            comment.body.clone(),
+            location,
+        ))
+    }
 
-                    // Initialize selection if not set (start from last)
-                    let current = rally_state
-                        .selected_log_index
-                        .unwrap_or(total_logs.saturating_sub(1));
-                    let new_index = current.saturating_sub(1);
-                    rally_state.selected_log_index = Some(new_index);
+    /// 選択中のコメントが自分のものなら返す（そうでなければ理由を表示）
+    fn own_selected_comment(&mut self) -> Option<(PostedComment, String, String)> {
+        let (target, author, body, location) = self.selected_posted_comment()?;
+        if self.viewer_login.as_deref() != Some(author.as_str()) {
+            let message = if self.viewer_login.is_none() {
+                "Authenticated user is not known yet"
+            } else {
+                "You can only edit or delete your own comments"
+            };
+            self.submission_result = Some((false, message.to_string()));
+            self.submission_result_time = Some(Instant::now());
+            return None;
+        }
+        Some((target, body, location))
+    }
 
-                    // Auto-scroll to keep selection visible
-                    self.adjust_log_scroll_to_selection();
+    /// 選択中のコメントに含まれる、適用できる suggestion
+    fn selected_suggestion(&self) -> Option<Suggestion> {
+        let (PostedComment::Review(id), ..) = self.selected_posted_comment()? else {
+            return None;
+        };
+        self.review_comments
+            .as_ref()?
+            .iter()
+            .find(|c| c.id == id)
+            .and_then(Suggestion::from_comment)
+    }
+
+    /// 選択中の suggestion をバッチに追加する（追加済みなら外す）
+    fn toggle_suggestion_batch(&mut self) {
+        let message = match self.selected_suggestion() {
+            None => "No applicable suggestion in this comment".to_string(),
+            Some(suggestion) => {
+                if let Some(pos) = self
+                    .suggestion_batch
+                    .iter()
+                    .position(|id| *id == suggestion.comment_id)
+                {
+                    self.suggestion_batch.remove(pos);
+                } else {
+                    self.suggestion_batch.push(suggestion.comment_id);
                 }
+                format!(
+                    "Suggestion batch: {} (a: commit)",
+                    self.suggestion_batch.len()
+                )
             }
-            KeyCode::Enter => {
-                // Show log detail modal
-                if let Some(ref mut rally_state) = self.ai_rally_state {
-                    if rally_state.selected_log_index.is_some() && !rally_state.logs.is_empty() {
-                        rally_state.showing_log_detail = true;
-                    }
+        };
+        self.submission_result = Some((true, message));
+        self.submission_result_time = Some(Instant::now());
+    }
+
+    /// バッチ（空なら選択中の suggestion）の適用先の選択を開始する
+    ///
+    /// GitHub と同様、suggestion を適用できるのは PR の作成者のみ。
+    fn request_apply_suggestions(&mut self) {
+        let Some(author) = self.pr().map(|pr| pr.user.login.clone()) else {
+            return;
+        };
+        let error = if self.applying_suggestions {
+            Some("Already applying suggestions")
+        } else if self.viewer_login.is_none() {
+            Some("Authenticated user is not known yet")
+        } else if self.viewer_login.as_deref() != Some(author.as_str()) {
+            Some("Only the PR author can apply suggestions")
+        } else {
+            None
+        };
+        let suggestions: Vec<Suggestion> = if self.suggestion_batch.is_empty() {
+            self.selected_suggestion().into_iter().collect()
+        } else {
+            let comments = self.review_comments.as_deref().unwrap_or_default();
+            self.suggestion_batch
+                .iter()
+                .filter_map(|id| comments.iter().find(|c| c.id == *id))
+                .filter_map(Suggestion::from_comment)
+                .collect()
+        };
+        let error = error.or(suggestions
+            .is_empty()
+            .then_some("No applicable suggestion in this comment"));
+        if let Some(message) = error {
+            self.submission_result = Some((false, message.to_string()));
+            self.submission_result_time = Some(Instant::now());
+            return;
+        }
+        self.confirm_suggestions = Some(suggestions);
+    }
+
+    /// suggestion を `working_dir`（`local`）または API 経由で PR の head ブランチにコミットする
+    fn start_apply_suggestions(&mut self, suggestions: Vec<Suggestion>, local: bool) {
+        let Some(pr) = self.pr().cloned() else {
+            return;
+        };
+        self.applying_suggestions = true;
+        let (tx, rx) = mpsc::channel(1);
+        self.suggestion_apply_receiver = Some((pr.number, rx));
+
+        let repo = self.repo.clone();
+        let working_dir = self.working_dir.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = match working_dir {
+                Some(dir) if local => {
+                    crate::suggestion::apply_local(&dir, &pr.head.sha, &suggestions)
+                        .await
+                        .map(SuggestionApplied::Local)
                 }
+                _ => crate::suggestion::apply_remote(client.as_ref(), &repo, &pr, &suggestions)
+                    .await
+                    .map(SuggestionApplied::Remote),
+            };
+            let _ = tx.send(result.map_err(|e| e.to_string())).await;
+        });
+    }
+
+    fn poll_suggestion_apply(&mut self) {
+        let Some((origin_pr, rx)) = self.suggestion_apply_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
+        let result = match rx.try_recv() {
+            Ok(result) => result,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                Err("Applying suggestions aborted".to_string())
             }
-            KeyCode::Char('G') => {
-                // Jump to bottom
-                if let Some(ref mut rally_state) = self.ai_rally_state {
-                    let total_logs = rally_state.logs.len();
-                    if total_logs > 0 {
-                        rally_state.selected_log_index = Some(total_logs.saturating_sub(1));
-                        rally_state.log_scroll_offset = 0; // 0 means auto-scroll to bottom
-                    }
-                }
+        };
+        self.suggestion_apply_receiver = None;
+        self.applying_suggestions = false;
+        if self.pr_number != Some(origin_pr) {
+            return;
+        }
+        self.submission_result = Some(match result {
+            Ok(SuggestionApplied::Local(sha)) => {
+                self.suggestion_batch.clear();
+                (
+                    true,
+                    format!(
+                        "Committed {} in the working directory; push to update the PR",
+                        sha
+                    ),
+                )
             }
-            KeyCode::Char('g') => {
-                // Jump to top
-                if let Some(ref mut rally_state) = self.ai_rally_state {
-                    if !rally_state.logs.is_empty() {
-                        rally_state.selected_log_index = Some(0);
-                        rally_state.log_scroll_offset = 1; // 1 is minimum (not 0 which means auto-scroll)
-                    }
-                }
+            Ok(SuggestionApplied::Remote(sha)) => {
+                self.suggestion_batch.clear();
+                // head が進んだので PR データとコメントを取り直す
+                self.refresh_all();
+                (
+                    true,
+                    format!(
+                        "Committed {} to the PR branch",
+                        crate::suggestion::short_sha(&sha)
+                    ),
+                )
             }
-            _ => {}
+            Err(e) => (false, format!("Failed to apply suggestions: {}", e)),
+        });
+        self.submission_result_time = Some(Instant::now());
+    }
+
+    /// 自分の投稿済みコメントを編集（TextArea に本文をプリフィル）
+    fn enter_comment_edit_input(&mut self) {
+        let Some((target, body, location)) = self.own_selected_comment() else {
+            return;
+        };
+        self.input_mode = Some(InputMode::EditComment { target, location });
+        self.input_text_area.set_content(&body);
+        self.preview_return_state = self.state;
+        self.state = AppState::TextInput;
+    }
+
+    /// 自分の投稿済みコメントの削除確認を開始
+    fn request_comment_delete(&mut self) {
+        if let Some((target, _, _)) = self.own_selected_comment() {
+            self.confirm_delete = Some(target);
+        }
+    }
+
+    fn submit_comment_edit(&mut self, target: PostedComment, body: String) {
+        self.spawn_posted_comment_update(target, Some(body));
+    }
+
+    fn delete_posted_comment(&mut self, target: PostedComment) {
+        self.spawn_posted_comment_update(target, None);
+    }
+
+    /// 投稿済みコメントを更新（`body` が None なら削除）し、結果をコメント送信結果として通知
+    fn spawn_posted_comment_update(&mut self, target: PostedComment, body: Option<String>) {
+        if self.comment_submitting {
+            return;
+        }
+        let repo = self.repo.clone();
+        let (tx, rx) = mpsc::channel(1);
+        self.comment_submit_receiver = Some((self.pr_number(), rx));
+        self.comment_submitting = true;
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            let result = match (target, body.as_deref()) {
+                (PostedComment::Review(id), Some(body)) => client
+                    .update_review_comment(&repo, id, body)
+                    .await
+                    .map(|_| CommentSubmitResult::Success),
+                (PostedComment::Review(id), None) => client
+                    .delete_review_comment(&repo, id)
+                    .await
+                    .map(|_| CommentSubmitResult::Success),
+                (PostedComment::Discussion(id), Some(body)) => client
+                    .update_discussion_comment(&repo, id, body)
+                    .await
+                    .map(|_| CommentSubmitResult::DiscussionUpdated),
+                (PostedComment::Discussion(id), None) => client
+                    .delete_discussion_comment(&repo, id)
+                    .await
+                    .map(|_| CommentSubmitResult::DiscussionUpdated),
+            };
+            let _ = tx
+                .send(result.unwrap_or_else(|e| CommentSubmitResult::Error(e.to_string())))
+                .await;
+        });
+    }
+
+    /// 会話タブへのコメント入力を開始
+    fn enter_discussion_comment_input(&mut self) {
+        self.input_mode = Some(InputMode::DiscussionComment);
+        self.input_text_area.clear();
+        self.preview_return_state = self.state;
+        self.state = AppState::TextInput;
+    }
+
+    /// 会話タブにコメントを投稿する
+    ///
+    /// 投稿完了を待たずに一覧へ仮のコメントを挿入し、結果は
+    /// `poll_discussion_post_updates` で確定またはロールバックする。
+    fn submit_discussion_comment(&mut self, body: String) {
+        if self.comment_submitting {
+            return;
         }
-        Ok(())
+        let repo = self.repo.clone();
+        let pr_number = self.pr_number();
+
+        let placeholder = DiscussionComment {
+            id: OPTIMISTIC_COMMENT_ID,
+            body: body.clone(),
+            user: github::User {
+                login: self
+                    .viewer_login
+                    .clone()
+                    .unwrap_or_else(|| "you".to_string()),
+            },
+            created_at: chrono::Utc::now().to_rfc3339(),
+            reactions: Default::default(),
+        };
+        let comments = self.discussion_comments.get_or_insert_with(Vec::new);
+        comments.push(placeholder);
+        self.selected_discussion_comment = comments.len() - 1;
+
+        let (tx, rx) = mpsc::channel(1);
+        self.discussion_post_receiver = Some((pr_number, rx));
+        self.comment_submitting = true;
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            let result = client
+                .create_discussion_comment(&repo, pr_number, &body)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
+        });
     }
 
-    /// Adjust log scroll offset to keep the selected log visible
-    fn adjust_log_scroll_to_selection(&mut self) {
-        if let Some(ref mut rally_state) = self.ai_rally_state {
-            let Some(selected) = rally_state.selected_log_index else {
-                return;
-            };
+    fn submit_reply(&mut self, comment_id: u64, body: String) {
+        let repo = self.repo.clone();
+        let pr_number = self.pr_number();
 
-            let visible_height = rally_state.last_visible_log_height;
+        let (tx, rx) = mpsc::channel(1);
+        self.comment_submit_receiver = Some((pr_number, rx));
+        self.comment_submitting = true;
+        let client = self.github_client();
 
-            // Calculate current scroll position
-            let total_logs = rally_state.logs.len();
-            let scroll_offset = if rally_state.log_scroll_offset == 0 {
-                total_logs.saturating_sub(visible_height)
-            } else {
-                rally_state.log_scroll_offset
-            };
+        tokio::spawn(async move {
+            let result = client
+                .create_reply_comment(&repo, pr_number, comment_id, &body)
+                .await;
 
-            // Adjust scroll to keep selection visible
-            if selected < scroll_offset {
-                // Selection is above visible area
-                rally_state.log_scroll_offset = selected.max(1);
-            } else if selected >= scroll_offset + visible_height {
-                // Selection is below visible area
-                rally_state.log_scroll_offset = selected.saturating_sub(visible_height - 1).max(1);
-            }
-        }
+            let _ = tx
+                .send(match result {
+                    Ok(_) => CommentSubmitResult::Success,
+                    Err(e) => CommentSubmitResult::Error(e.to_string()),
+                })
+                .await;
+        });
     }
 
-    /// Send a command to the orchestrator
-    fn send_rally_command(&mut self, cmd: OrchestratorCommand) {
-        if let Some(ref sender) = self.rally_command_sender {
-            // Use try_send since we're not in an async context
-            if sender.try_send(cmd).is_err() {
-                // Orchestrator may have terminated, clean up state
-                self.cleanup_rally_state();
+    fn handle_help_input(&mut self, key: event::KeyEvent) -> Result<()> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('?') => {
+                self.state = self.previous_state;
             }
+            _ => {}
         }
+        Ok(())
     }
 
-    /// Clean up rally state when orchestrator terminates or user aborts
-    fn cleanup_rally_state(&mut self) {
-        self.ai_rally_state = None;
-        self.rally_command_sender = None;
-        self.rally_event_receiver = None;
-        if let Some(handle) = self.rally_abort_handle.take() {
-            handle.abort();
+    /// コメント入力を開始（組み込みTextArea）
+    fn enter_comment_input(&mut self) {
+        if !self.ensure_commentable_diff() {
+            return;
+        }
+        let Some(file) = self.files().get(self.selected_file) else {
+            return;
+        };
+        let Some(patch) = file.patch.as_ref() else {
+            return;
+        };
+
+        // Get actual line numbers from diff (visual selection or current line).
+        // Only Added or Context lines can be range endpoints (not Removed/Header/Meta)
+        let selection = self.selected_line_range();
+        let Some(range_info) =
+            crate::diff::get_range_info(patch, *selection.start(), *selection.end())
+        else {
+            return;
+        };
+        if !self.ensure_range_in_pr_diff(&range_info) {
+            return;
         }
+
+        self.input_mode = Some(InputMode::Comment(LineInputContext {
+            file_index: self.selected_file,
+            range: CommentRange::between(
+                (range_info.start_side, range_info.start_line),
+                (range_info.end_side, range_info.end_line),
+            ),
+        }));
+        self.visual_anchor = None;
+        self.input_text_area.clear();
+        self.preview_return_state = self.state;
+        self.state = AppState::TextInput;
     }
 
-    /// Open editor for clarification input synchronously
-    fn open_clarification_editor_sync(
+    async fn submit_review(
         &mut self,
-        question: &str,
+        action: ReviewAction,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     ) -> Result<()> {
-        // Restore terminal before opening editor
         ui::restore_terminal(terminal)?;
 
-        // Open editor (blocking)
-        let answer = crate::editor::open_clarification_editor(&self.config.editor, question)?;
+        let body = crate::editor::open_review_editor(&self.config.editor)?;
 
-        // Re-setup terminal after editor closes
         *terminal = ui::setup_terminal()?;
 
-        // Process result
-        if let Some(ref mut rally_state) = self.ai_rally_state {
-            rally_state.pending_question = None;
-        }
+        let Some(body) = body else {
+            return Ok(());
+        };
 
-        match answer {
-            Some(text) if !text.trim().is_empty() => {
-                // Send clarification response
-                self.send_rally_command(OrchestratorCommand::ClarificationResponse(text.clone()));
-                if let Some(ref mut rally_state) = self.ai_rally_state {
-                    rally_state.push_log(LogEntry::new(
-                        LogEventType::Info,
-                        format!("Clarification provided: {}", text),
-                    ));
+        let Some(drafts) = self.pending_review().map(|review| review.comments.clone()) else {
+            self.github
+                .submit_review(&self.repo, self.pr_number(), action, &body)
+                .await?;
+            self.record_reviewed_head();
+            return Ok(());
+        };
+
+        // 保留レビュー: インラインコメントとレビュー本文をまとめて送信
+        let Some(commit_id) = self.pr().map(|pr| pr.head.sha.clone()) else {
+            return Ok(());
+        };
+        let pr_number = self.pr_number();
+        let result = self
+            .github
+            .create_review(&self.repo, pr_number, &commit_id, action, &body, &drafts)
+            .await;
+        match result {
+            Ok(()) => {
+                self.record_reviewed_head();
+                if let Some(key) = self.current_pr_key() {
+                    self.pending_reviews.remove(&key);
+                    self.session_cache.remove_review_comments(&key);
                 }
+                self.review_comments = None;
+                self.selected_pending_comment = 0;
+                self.submission_result = Some((
+                    true,
+                    format!("Review submitted with {} comment(s)", drafts.len()),
+                ));
             }
-            _ => {
-                // User cancelled (empty answer)
-                self.send_rally_command(OrchestratorCommand::Abort);
-                if let Some(ref mut rally_state) = self.ai_rally_state {
-                    rally_state.push_log(LogEntry::new(
-                        LogEventType::Info,
-                        "Clarification cancelled by user".to_string(),
-                    ));
-                }
+            Err(e) => {
+                // 失敗時は保留コメントを残し、再送できるようにする
+                self.submission_result = Some((false, format!("Failed: {}", e)));
             }
         }
-
+        self.submission_result_time = Some(Instant::now());
         Ok(())
     }
 
-    /// 既存のRallyがあれば画面遷移のみ、なければ新規Rally開始
-    fn resume_or_start_ai_rally(&mut self) {
-        // 既存のRallyがあれば画面遷移のみ（完了/エラー状態でも結果確認のため）
-        if self.ai_rally_state.is_some() {
-            self.state = AppState::AiRally;
-            return;
+    /// レビュー送信時の head を「前回のレビュー」として記録する
+    fn record_reviewed_head(&mut self) {
+        if let (Some(key), Some(pr)) = (self.current_pr_key(), self.pr()) {
+            let sha = pr.head.sha.clone();
+            self.last_reviewed_shas.insert(key, sha);
         }
-        // そうでなければ新規Rally開始
-        self.start_ai_rally();
     }
 
-    /// バックグラウンドでRallyが実行中かどうか（完了・エラー以外）
-    #[allow(dead_code)]
-    pub fn is_rally_running_in_background(&self) -> bool {
-        self.state != AppState::AiRally
-            && self
-                .ai_rally_state
-                .as_ref()
-                .map(|s| s.state.is_active())
-                .unwrap_or(false)
+    fn update_diff_line_count(&mut self) {
+        self.diff_line_count = Self::calc_diff_line_count(self.files(), self.selected_file);
     }
 
-    /// バックグラウンドでRallyが存在するかどうか（完了・エラー含む）
-    pub fn has_background_rally(&self) -> bool {
-        self.state != AppState::AiRally && self.ai_rally_state.is_some()
+    /// Split Viewでファイル選択変更時にdiff状態を同期
+    fn sync_diff_to_selected_file(&mut self) {
+        self.selected_line = 0;
+        self.scroll_offset = 0;
+        self.visual_anchor = None;
+        self.comment_panel_open = false;
+        self.comment_panel_scroll = 0;
+        self.clear_pending_keys();
+        self.symbol_popup = None;
+        self.update_diff_line_count();
+        if self.review_comments.is_none() {
+            self.load_review_comments();
+        }
+        self.update_file_comment_positions();
+        self.ensure_diff_cache();
     }
 
-    /// バックグラウンドRallyが完了またはエラーで終了したかどうか
-    #[allow(dead_code)]
-    pub fn is_background_rally_finished(&self) -> bool {
-        self.state != AppState::AiRally
-            && self
-                .ai_rally_state
-                .as_ref()
-                .map(|s| s.state.is_finished())
-                .unwrap_or(false)
-    }
+    /// サジェスチョン入力を開始（組み込みTextArea）
+    fn enter_suggestion_input(&mut self) {
+        if !self.ensure_commentable_diff() {
+            return;
+        }
+        let Some(file) = self.files().get(self.selected_file) else {
+            return;
+        };
+        let Some(patch) = file.patch.as_ref() else {
+            return;
+        };
 
-    fn start_ai_rally(&mut self) {
-        // Get PR data for context
-        let Some(pr) = self.pr() else {
+        // Check if the selected lines can have a suggestion (Added or Context endpoints)
+        let selection = self.selected_line_range();
+        let Some(range_info) =
+            crate::diff::get_range_info(patch, *selection.start(), *selection.end())
+        else {
             return;
         };
+        // Suggestions replace new file lines, so removed (LEFT side) lines can't be targeted
+        if range_info.start_side == DiffSide::Left || range_info.end_side == DiffSide::Left {
+            return;
+        }
+        if !self.ensure_range_in_pr_diff(&range_info) {
+            return;
+        }
+
+        // 範囲全体の新しい内容を置き換え対象にする
+        let original_code = range_info.content;
+
+        self.input_mode = Some(InputMode::Suggestion {
+            context: LineInputContext {
+                file_index: self.selected_file,
+                range: CommentRange::range(range_info.start_line, range_info.end_line),
+            },
+            original_code: original_code.clone(),
+        });
+        self.visual_anchor = None;
+        // サジェスチョンは元コードを初期値として設定
+        self.input_text_area.set_content(&original_code);
+        self.preview_return_state = self.state;
+        self.state = AppState::TextInput;
+    }
+
+    fn open_comment_list(&mut self) {
+        self.state = AppState::CommentList;
+        self.discussion_comment_detail_mode = false;
+        self.discussion_comment_detail_scroll = 0;
+
+        // Load review comments
+        self.load_review_comments();
+        // Load discussion comments
+        self.load_discussion_comments();
+    }
+
+    fn load_review_comments(&mut self) {
+        self.ensure_viewer_login();
+        let cache_key = self.pr_cache_key(self.pr_number());
+
+        // インメモリキャッシュを確認
+        if let Some(comments) = self.session_cache.get_review_comments(&cache_key) {
+            self.review_comments = Some(comments.to_vec());
+            self.selected_comment = 0;
+            self.comment_list_scroll_offset = 0;
+            self.comments_loading = false;
+            return;
+        }
+
+        // キャッシュミス: API取得
+        self.comments_loading = true;
+        let (tx, rx) = mpsc::channel(1);
+        let pr_number = self.pr_number();
+        self.comment_receiver = Some((pr_number, rx));
+
+        let repo = self.repo.clone();
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            // Fetch both review comments and reviews
+            let review_comments_result = client.fetch_review_comments(&repo, pr_number).await;
+            let reviews_result = client.fetch_reviews(&repo, pr_number).await;
+            // スレッド情報は取得できなくてもコメント表示は続行する
+            let threads = client
+                .fetch_review_threads(&repo, pr_number)
+                .await
+                .unwrap_or_default();
 
-        let diff = self
-            .files()
-            .iter()
-            .filter_map(|f| f.patch.as_ref())
-            .cloned()
-            .collect::<Vec<_>>()
-            .join("\n");
+            // Combine results
+            let mut all_comments: Vec<ReviewComment> = Vec::new();
 
-        let context = Context {
-            repo: self.repo.clone(),
-            pr_number: self.pr_number(),
-            pr_title: pr.title.clone(),
-            pr_body: pr.body.clone(),
-            diff,
-            working_dir: self.working_dir.clone(),
-            head_sha: pr.head.sha.clone(),
-            base_branch: pr.base.ref_name.clone(),
-            external_comments: Vec::new(),
-        };
+            // Add review comments (inline comments)
+            if let Ok(comments) = review_comments_result {
+                all_comments.extend(comments);
+            }
 
-        let (event_tx, event_rx) = mpsc::channel(100);
-        let (cmd_tx, cmd_rx) = mpsc::channel(10);
+            // Convert reviews to ReviewComment format (only those with body)
+            if let Ok(reviews) = reviews_result {
+                for review in reviews {
+                    if let Some(body) = review.body {
+                        if !body.trim().is_empty() {
+                            all_comments.push(ReviewComment {
+                                id: review.id,
+                                path: PR_REVIEW_PATH.to_string(),
+                                line: None,
+                                start_line: None,
+                                side: None,
+                                original_line: None,
+                                original_commit_id: review.commit_id,
+                                position: None,
+                                diff_hunk: None,
+                                body,
+                                user: review.user,
+                                created_at: review.submitted_at.unwrap_or_default(),
+                                in_reply_to_id: None,
+                                thread: None,
+                                reactions: Default::default(),
+                            });
+                        }
+                    }
+                }
+            }
 
-        // Store channels first to prevent race conditions
-        self.rally_event_receiver = Some(event_rx);
-        self.rally_command_sender = Some(cmd_tx);
+            // スレッド単位にまとめて作成日時順に並べる
+            let all_comments = github::comment::group_into_threads(all_comments, &threads);
 
-        // Initialize rally state
-        self.ai_rally_state = Some(AiRallyState {
-            iteration: 0,
-            max_iterations: self.config.ai.max_iterations,
-            state: RallyState::Initializing,
-            history: Vec::new(),
-            logs: Vec::new(),
-            log_scroll_offset: 0,
-            selected_log_index: None,
-            showing_log_detail: false,
-            pending_question: None,
-            pending_permission: None,
-            last_visible_log_height: 10,
+            let _ = tx.send(Ok(all_comments)).await;
         });
+    }
 
-        self.state = AppState::AiRally;
+    fn load_discussion_comments(&mut self) {
+        self.ensure_viewer_login();
+        let cache_key = self.pr_cache_key(self.pr_number());
 
-        // Spawn the orchestrator and store the abort handle
-        let config = self.config.ai.clone();
-        let repo = self.repo.clone();
+        // インメモリキャッシュを確認
+        if let Some(comments) = self.session_cache.get_discussion_comments(&cache_key) {
+            self.discussion_comments = Some(comments.to_vec());
+            self.selected_discussion_comment = 0;
+            self.discussion_comments_loading = false;
+            return;
+        }
+
+        // キャッシュミス: API取得
+        self.discussion_comments_loading = true;
+        let (tx, rx) = mpsc::channel(1);
         let pr_number = self.pr_number();
+        self.discussion_comment_receiver = Some((pr_number, rx));
 
-        let handle = tokio::spawn(async move {
-            let orchestrator_result =
-                Orchestrator::new(&repo, pr_number, config, event_tx.clone(), Some(cmd_rx));
-            match orchestrator_result {
-                Ok(mut orchestrator) => {
-                    orchestrator.set_context(context);
-                    // Note: orchestrator.run() already emits RallyEvent::Error and
-                    // StateChanged(Error) when it fails, so we don't emit them again here
-                    // to avoid duplicate error logs in the UI
-                    let _ = orchestrator.run().await;
+        let repo = self.repo.clone();
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            match client.fetch_discussion_comments(&repo, pr_number).await {
+                Ok(comments) => {
+                    let _ = tx.send(Ok(comments)).await;
                 }
                 Err(e) => {
-                    // Send error via event channel so it displays in TUI
-                    let _ = event_tx
-                        .send(RallyEvent::Error(format!(
-                            "Failed to create orchestrator: {}",
-                            e
-                        )))
-                        .await;
+                    let _ = tx.send(Err(e.to_string())).await;
                 }
             }
         });
-
-        // Store the abort handle so we can cancel the task when user presses 'q'
-        self.rally_abort_handle = Some(handle.abort_handle());
-    }
-
-    fn refresh_all(&mut self) {
-        // インメモリキャッシュを全削除
-        self.session_cache.invalidate_all();
-        // コメントデータをクリア
-        self.review_comments = None;
-        self.discussion_comments = None;
-        self.comments_loading = false;
-        self.discussion_comments_loading = false;
-        // PRデータを再取得
-        self.retry_load();
     }
 
-    async fn handle_diff_view_input(
+    async fn handle_comment_list_input(
         &mut self,
         key: event::KeyEvent,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     ) -> Result<()> {
-        self.handle_diff_input_common(key, terminal, DiffViewVariant::Fullscreen)
-            .await
-    }
-
-    fn adjust_scroll(&mut self, visible_lines: usize) {
-        if visible_lines == 0 {
-            return;
-        }
-        if self.selected_line < self.scroll_offset {
-            self.scroll_offset = self.selected_line;
-        }
-        if self.selected_line >= self.scroll_offset + visible_lines {
-            self.scroll_offset = self.selected_line.saturating_sub(visible_lines) + 1;
-        }
-
-        // Allow additional scrolling when at the end (bottom padding)
-        // This enables showing empty space below the last line
-        let padding = visible_lines / 2;
-        let max_scroll_with_padding = self.diff_line_count.saturating_sub(1);
-        if self.selected_line >= self.diff_line_count.saturating_sub(padding) {
-            // When near the end, allow scroll_offset to go further
-            let target_scroll = self.selected_line.saturating_sub(visible_lines / 2);
-            self.scroll_offset = target_scroll.min(max_scroll_with_padding);
-        }
-    }
+        let visible_lines = terminal.size()?.height.saturating_sub(8) as usize;
 
-    /// 統一入力ハンドラー（コメント/サジェスチョン/リプライ共通）
-    fn handle_text_input(&mut self, key: event::KeyEvent) -> Result<()> {
-        // 送信中は入力を無視
-        if self.comment_submitting {
-            return Ok(());
+        // Handle detail mode input separately
+        if self.discussion_comment_detail_mode {
+            return self.handle_discussion_detail_input(key, visible_lines);
         }
 
-        match self.input_text_area.input(key) {
-            TextAreaAction::Submit => {
-                let content = self.input_text_area.content();
-                if content.trim().is_empty() {
-                    // 空の場合はキャンセル扱い
-                    self.cancel_input();
-                    return Ok(());
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.state = self.previous_state;
+            }
+            KeyCode::Char('[') => {
+                self.comment_tab = self.comment_tab.prev();
+            }
+            KeyCode::Char(']') => {
+                self.comment_tab = self.comment_tab.next();
+            }
+            KeyCode::Char('j') | KeyCode::Down => match self.comment_tab {
+                CommentTab::Review => {
+                    let visible = self.visible_review_comment_indices();
+                    if let Some(&next) = visible.iter().find(|&&i| i > self.selected_comment) {
+                        self.selected_comment = next;
+                    }
                 }
-
-                match self.input_mode.take() {
-                    Some(InputMode::Comment(ctx)) => {
-                        self.submit_comment(ctx, content);
+                CommentTab::Discussion => {
+                    if let Some(ref comments) = self.discussion_comments {
+                        if !comments.is_empty() {
+                            self.selected_discussion_comment = (self.selected_discussion_comment
+                                + 1)
+                            .min(comments.len().saturating_sub(1));
+                        }
                     }
-                    Some(InputMode::Suggestion {
-                        context,
-                        original_code: _,
-                    }) => {
-                        self.submit_suggestion(context, content);
+                }
+                CommentTab::Pending => {
+                    let count = self.pending_review().map(|r| r.comments.len()).unwrap_or(0);
+                    if count > 0 {
+                        self.selected_pending_comment =
+                            (self.selected_pending_comment + 1).min(count - 1);
                     }
-                    Some(InputMode::Reply { comment_id, .. }) => {
-                        self.submit_reply(comment_id, content);
+                }
+            },
+            KeyCode::Char('k') | KeyCode::Up => match self.comment_tab {
+                CommentTab::Review => {
+                    let visible = self.visible_review_comment_indices();
+                    if let Some(&prev) = visible.iter().rev().find(|&&i| i < self.selected_comment)
+                    {
+                        self.selected_comment = prev;
                     }
-                    None => {}
                 }
-                self.state = self.preview_return_state;
+                CommentTab::Discussion => {
+                    self.selected_discussion_comment =
+                        self.selected_discussion_comment.saturating_sub(1);
+                }
+                CommentTab::Pending => {
+                    self.selected_pending_comment = self.selected_pending_comment.saturating_sub(1);
+                }
+            },
+            KeyCode::Enter => match self.comment_tab {
+                CommentTab::Review => {
+                    self.jump_to_comment();
+                }
+                CommentTab::Discussion => {
+                    // Enter detail mode for discussion comment
+                    if self
+                        .discussion_comments
+                        .as_ref()
+                        .map(|c| !c.is_empty())
+                        .unwrap_or(false)
+                    {
+                        self.discussion_comment_detail_mode = true;
+                        self.discussion_comment_detail_scroll = 0;
+                    }
+                }
+                CommentTab::Pending => {
+                    self.enter_draft_edit_input();
+                }
+            },
+            _ if self.comment_tab == CommentTab::Pending
+                && self.matches_single_key(&key, &self.config.keybindings.delete_comment) =>
+            {
+                self.delete_selected_draft_comment();
+            }
+            _ if self.comment_tab == CommentTab::Discussion
+                && self.matches_single_key(&key, &self.config.keybindings.comment) =>
+            {
+                self.enter_discussion_comment_input();
+            }
+            _ if self.comment_tab != CommentTab::Pending
+                && self.matches_single_key(&key, &self.config.keybindings.edit_comment) =>
+            {
+                self.enter_comment_edit_input();
+            }
+            _ if self.comment_tab != CommentTab::Pending
+                && self.matches_single_key(&key, &self.config.keybindings.delete_comment) =>
+            {
+                self.request_comment_delete();
+            }
+            _ if self.comment_tab != CommentTab::Pending
+                && self.matches_single_key(&key, &self.config.keybindings.add_reaction) =>
+            {
+                self.open_reaction_popup();
+            }
+            _ if self.comment_tab == CommentTab::Review
+                && self.matches_single_key(&key, &self.config.keybindings.resolve_thread) =>
+            {
+                self.toggle_thread_resolved(self.selected_comment);
+            }
+            _ if self.comment_tab == CommentTab::Review
+                && self.matches_single_key(&key, &self.config.keybindings.toggle_thread) =>
+            {
+                self.toggle_thread_collapsed(self.selected_comment);
+            }
+            _ if self.comment_tab == CommentTab::Review
+                && self.matches_single_key(&key, &self.config.keybindings.batch_suggestion) =>
+            {
+                self.toggle_suggestion_batch();
+            }
+            _ if self.comment_tab == CommentTab::Review
+                && self.matches_single_key(&key, &self.config.keybindings.apply_suggestion) =>
+            {
+                self.request_apply_suggestions();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn handle_discussion_detail_input(
+        &mut self,
+        key: event::KeyEvent,
+        visible_lines: usize,
+    ) -> Result<()> {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
+                self.discussion_comment_detail_mode = false;
+                self.discussion_comment_detail_scroll = 0;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.discussion_comment_detail_scroll =
+                    self.discussion_comment_detail_scroll.saturating_add(1);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.discussion_comment_detail_scroll =
+                    self.discussion_comment_detail_scroll.saturating_sub(1);
             }
-            TextAreaAction::Cancel => {
-                self.cancel_input();
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.discussion_comment_detail_scroll = self
+                    .discussion_comment_detail_scroll
+                    .saturating_add(visible_lines / 2);
             }
-            TextAreaAction::Continue => {}
-            TextAreaAction::PendingSequence => {
-                // Waiting for more keys in a sequence, do nothing
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.discussion_comment_detail_scroll = self
+                    .discussion_comment_detail_scroll
+                    .saturating_sub(visible_lines / 2);
+            }
+            _ if self.matches_single_key(&key, &self.config.keybindings.add_reaction) => {
+                self.open_reaction_popup();
             }
+            _ => {}
         }
         Ok(())
     }
 
-    fn cancel_input(&mut self) {
-        self.input_mode = None;
-        self.input_text_area.clear();
-        self.state = self.preview_return_state;
-    }
-
-    fn submit_comment(&mut self, ctx: LineInputContext, body: String) {
-        let Some(file) = self.files().get(ctx.file_index) else {
+    /// head コミットの CI 結果を取得する
+    ///
+    /// 同じ head の結果を取得済み・取得中なら `force` のときだけ再取得する。
+    fn load_checks(&mut self, force: bool) {
+        let Some(sha) = self.pr().map(|pr| pr.head.sha.clone()) else {
             return;
         };
-        let Some(pr) = self.pr() else {
+        let up_to_date = self.checks.as_ref().is_some_and(|c| c.sha == sha);
+        if !force && (up_to_date || self.checks_loading) {
             return;
-        };
-
-        let commit_id = pr.head.sha.clone();
-        let filename = file.filename.clone();
-        let repo = self.repo.clone();
-        let pr_number = self.pr_number();
-        let line_number = ctx.line_number;
+        }
 
+        self.checks_loading = true;
         let (tx, rx) = mpsc::channel(1);
-        self.comment_submit_receiver = Some((pr_number, rx));
-        self.comment_submitting = true;
+        let pr_number = self.pr_number();
+        self.checks_receiver = Some((pr_number, rx));
 
+        let repo = self.repo.clone();
+        let client = self.github_client();
         tokio::spawn(async move {
-            let result = github::create_review_comment(
-                &repo,
-                pr_number,
-                &commit_id,
-                &filename,
-                line_number,
-                &body,
-            )
-            .await;
+            let result = client
+                .fetch_checks(&repo, &sha)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
+        });
+    }
 
-            let _ = tx
-                .send(match result {
-                    Ok(_) => CommentSubmitResult::Success,
-                    Err(e) => CommentSubmitResult::Error(e.to_string()),
-                })
-                .await;
+    /// アノテーションのある Check Run から diff 用のアノテーションを取得する
+    fn load_diff_annotations(&mut self, runs: &[CheckRun]) {
+        let runs: Vec<CheckRun> = runs
+            .iter()
+            .filter(|r| r.output.annotations_count > 0)
+            .cloned()
+            .collect();
+        if runs.is_empty() {
+            self.diff_annotations.clear();
+            self.diff_annotations_receiver = None;
+            self.update_file_annotation_positions();
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel(1);
+        self.diff_annotations_receiver = Some((self.pr_number(), rx));
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = client
+                .fetch_annotations_for_runs(&repo, &runs)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
         });
     }
 
-    fn submit_suggestion(&mut self, ctx: LineInputContext, suggested_code: String) {
-        let Some(file) = self.files().get(ctx.file_index) else {
+    /// 表示中の PR に紐づく状態をすべてリセットする
+    ///
+    /// PR を切り替えるコード（`select_pr` / `switch_repo` / `back_to_pr_list`）は
+    /// ここを通す。PR 番号で照合している in-flight のレシーバー（`PrReceiver`）も
+    /// すべてここで破棄する。
+    fn reset_pr_state(&mut self) {
+        self.diff_cache = None;
+        self.diff_cache_receiver = None;
+        self.prefetch_receiver = None;
+        self.highlighted_cache_store.clear();
+        self.selected_file = 0;
+        self.file_list_scroll_offset = 0;
+        self.review_comments = None;
+        self.comment_receiver = None;
+        self.comments_loading = false;
+        self.discussion_comments = None;
+        self.discussion_comment_receiver = None;
+        self.discussion_comments_loading = false;
+        self.discussion_post_receiver = None;
+        self.comment_submit_receiver = None;
+        self.comment_submitting = false;
+        self.thread_resolve_receiver = None;
+        self.merge_dialog = None;
+        self.confirm_merge = None;
+        self.merge_status_receiver = None;
+        self.merge_receiver = None;
+        self.merging = false;
+        self.suggestion_batch.clear();
+        self.confirm_suggestions = None;
+        self.suggestion_apply_receiver = None;
+        self.applying_suggestions = false;
+        self.reset_pr_data();
+    }
+
+    /// PR に付随して取得したデータ（CI・メタデータ・Viewed・コミット）を破棄する
+    ///
+    /// PR の切り替え時に加え、同じ PR を取り直すとき（`refresh_all`）にも使う。
+    fn reset_pr_data(&mut self) {
+        self.checks = None;
+        self.checks_loading = false;
+        self.checks_error = None;
+        self.checks_receiver = None;
+        self.check_detail = None;
+        self.check_annotations_receiver = None;
+        self.diff_annotations.clear();
+        self.diff_annotations_receiver = None;
+        self.pr_metadata = None;
+        self.pr_metadata_loading = false;
+        self.pr_metadata_receiver = None;
+        self.pr_metadata_updating = false;
+        self.pr_metadata_update_receiver = None;
+        self.metadata_picker = None;
+        self.metadata_picker_receiver = None;
+        self.viewed_files = None;
+        self.viewed_files_receiver = None;
+        self.viewed_toggle_receiver = None;
+        self.last_reviewed_sha_receiver = None;
+        self.reset_commit_state();
+    }
+
+    /// コミット一覧とコミット範囲の表示をリセットする
+    fn reset_commit_state(&mut self) {
+        self.pr_commits = None;
+        self.pr_commits_loading = false;
+        self.pr_commits_receiver = None;
+        self.selected_commit = 0;
+        self.commit_range_anchor = None;
+        self.commit_scope = None;
+        self.commit_files_loading = false;
+        self.commit_files_receiver = None;
+    }
+
+    /// 表示する変更ファイル一覧を差し替え、diff 関連の状態をリセットする
+    fn replace_files(&mut self, new_files: Vec<ChangedFile>) {
+        let DataState::Loaded { files, .. } = &mut self.data_state else {
             return;
         };
-        let Some(pr) = self.pr() else {
+        *files = new_files;
+        self.selected_file = 0;
+        self.file_list_scroll_offset = 0;
+        self.diff_cache = None;
+        self.diff_cache_receiver = None;
+        self.prefetch_receiver = None;
+        self.highlighted_cache_store.clear();
+        self.selected_line = 0;
+        self.scroll_offset = 0;
+        self.visual_anchor = None;
+        self.comment_panel_open = false;
+        self.comment_panel_scroll = 0;
+        self.diff_line_count = Self::calc_diff_line_count(self.files(), 0);
+        self.update_file_comment_positions();
+        self.start_prefetch_all_files();
+    }
+
+    /// 「前回のレビュー以降の変更」表示を切り替える
+    ///
+    /// 前回レビュー時の head は、このセッションで送信したレビューがあればそれを、
+    /// なければ Reviews API の `commit_id` を使う。
+    fn toggle_since_review(&mut self) {
+        if self.commit_files_loading || self.last_reviewed_sha_receiver.is_some() {
+            return;
+        }
+        if self.commit_scope.as_ref().is_some_and(|s| s.since_review) {
+            self.clear_commit_scope();
+            return;
+        }
+        let Some(key) = self.current_pr_key() else {
             return;
         };
-
-        let commit_id = pr.head.sha.clone();
-        let filename = file.filename.clone();
-        let body = format!("```suggestion\n{}\n```", suggested_code.trim_end());
-        let repo = self.repo.clone();
-        let pr_number = self.pr_number();
-        let line_number = ctx.line_number;
+        if let Some(sha) = self.last_reviewed_shas.get(&key).cloned() {
+            self.show_changes_since(sha);
+            return;
+        }
 
         let (tx, rx) = mpsc::channel(1);
-        self.comment_submit_receiver = Some((pr_number, rx));
-        self.comment_submitting = true;
-
+        let pr_number = self.pr_number();
+        self.last_reviewed_sha_receiver = Some((pr_number, rx));
+        let repo = self.repo.clone();
+        let client = self.github_client();
         tokio::spawn(async move {
-            let result = github::create_review_comment(
-                &repo,
-                pr_number,
-                &commit_id,
-                &filename,
-                line_number,
-                &body,
-            )
-            .await;
-
-            let _ = tx
-                .send(match result {
-                    Ok(_) => CommentSubmitResult::Success,
-                    Err(e) => CommentSubmitResult::Error(e.to_string()),
-                })
-                .await;
+            let result = client
+                .fetch_last_reviewed_sha(&repo, pr_number)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
         });
     }
 
-    fn submit_reply(&mut self, comment_id: u64, body: String) {
-        let repo = self.repo.clone();
-        let pr_number = self.pr_number();
+    /// `base_sha` から PR head までの変更に切り替える
+    fn show_changes_since(&mut self, base_sha: String) {
+        let Some(head_sha) = self.pr().map(|pr| pr.head.sha.clone()) else {
+            return;
+        };
+        if base_sha == head_sha {
+            self.submission_result = Some((true, "No changes since your last review".to_string()));
+            self.submission_result_time = Some(Instant::now());
+            return;
+        }
+        let label = format!("Since review @ {}", &base_sha[..base_sha.len().min(7)]);
+        self.load_commit_scope(CommitScope {
+            base_sha,
+            head_sha,
+            label,
+            since_review: true,
+        });
+    }
 
-        let (tx, rx) = mpsc::channel(1);
-        self.comment_submit_receiver = Some((pr_number, rx));
-        self.comment_submitting = true;
+    /// 表示中の diff の RIGHT 側が PR head と一致するか
+    ///
+    /// コメント・アノテーションの行番号は PR head 基準なので、一致しない範囲では表示しない。
+    fn diff_matches_head(&self) -> bool {
+        match (&self.commit_scope, self.pr()) {
+            (Some(scope), Some(pr)) => scope.head_sha == pr.head.sha,
+            _ => true,
+        }
+    }
 
-        tokio::spawn(async move {
-            let result = github::create_reply_comment(&repo, pr_number, comment_id, &body).await;
+    /// コメントを追加できる diff か確認する（できなければ理由を表示）
+    fn ensure_commentable_diff(&mut self) -> bool {
+        if self.diff_matches_head() {
+            return true;
+        }
+        self.submission_result = Some((
+            false,
+            "Comments can only be added when the diff ends at the PR head".to_string(),
+        ));
+        self.submission_result_time = Some(Instant::now());
+        false
+    }
 
-            let _ = tx
-                .send(match result {
-                    Ok(_) => CommentSubmitResult::Success,
-                    Err(e) => CommentSubmitResult::Error(e.to_string()),
-                })
-                .await;
+    /// コミット範囲の diff で選んだ範囲の両端が PR 全体の diff にもあるか確認する
+    /// （なければ理由を表示）
+    ///
+    /// コメントは PR 全体の diff に付くため、範囲の base 側（LEFT）の行や
+    /// PR の diff に含まれない行は API に 422 で拒否される。
+    fn ensure_range_in_pr_diff(&mut self, range: &crate::diff::DiffRangeInfo) -> bool {
+        if self.commit_scope.is_none() {
+            return true;
+        }
+        let ends = [
+            (range.start_side, range.start_line),
+            (range.end_side, range.end_line),
+        ];
+        let filename = self
+            .files()
+            .get(self.selected_file)
+            .map(|f| f.filename.clone());
+        let cache_key = self.pr_cache_key(self.pr_number());
+        let pr_patch = self.session_cache.get_pr_data(&cache_key).and_then(|data| {
+            data.files
+                .iter()
+                .find(|f| Some(&f.filename) == filename.as_ref())
+                .and_then(|f| f.patch.clone())
         });
+        let error = if ends.iter().any(|(side, _)| *side == DiffSide::Left) {
+            "Removed lines can only be commented on in the full PR diff"
+        } else if pr_patch.is_some_and(|patch| {
+            ends.iter()
+                .all(|(side, line)| Self::find_diff_line_index(&patch, *line, *side).is_some())
+        }) {
+            return true;
+        } else {
+            "This line is not part of the PR diff"
+        };
+        self.submission_result = Some((false, error.to_string()));
+        self.submission_result_time = Some(Instant::now());
+        false
     }
 
-    fn handle_help_input(&mut self, key: event::KeyEvent) -> Result<()> {
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('?') => {
-                self.state = self.previous_state;
-            }
-            _ => {}
+    fn open_commit_list(&mut self) {
+        self.state = AppState::CommitList;
+        self.commit_range_anchor = None;
+        if self.pr_commits.is_some() || self.pr_commits_loading {
+            return;
         }
-        Ok(())
+        self.load_pr_commits();
     }
 
-    /// コメント入力を開始（組み込みTextArea）
-    fn enter_comment_input(&mut self) {
-        let Some(file) = self.files().get(self.selected_file) else {
+    fn load_pr_commits(&mut self) {
+        self.pr_commits_loading = true;
+        let (tx, rx) = mpsc::channel(1);
+        let pr_number = self.pr_number();
+        self.pr_commits_receiver = Some((pr_number, rx));
+
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = client
+                .fetch_pr_commits(&repo, pr_number)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
+        });
+    }
+
+    /// 選択中のコミット（または範囲）の diff に切り替える。0 行目は PR 全体に戻す
+    fn apply_commit_selection(&mut self) {
+        let Some(ref commits) = self.pr_commits else {
             return;
         };
-        let Some(patch) = file.patch.as_ref() else {
+        let selected = self.selected_commit;
+        if selected == 0 {
+            self.commit_range_anchor = None;
+            self.clear_commit_scope();
+            self.state = self.previous_state;
+            return;
+        }
+        let anchor = self
+            .commit_range_anchor
+            .filter(|&a| a > 0)
+            .unwrap_or(selected);
+        let (from, to) = (anchor.min(selected) - 1, anchor.max(selected) - 1);
+        let (Some(first), Some(last)) = (commits.get(from), commits.get(to)) else {
             return;
         };
-
-        // Get actual line number from diff
-        let Some(line_info) = crate::diff::get_line_info(patch, self.selected_line) else {
+        let Some(parent) = first.parents.first() else {
+            self.submission_result = Some((false, "Cannot compare a root commit".to_string()));
+            self.submission_result_time = Some(Instant::now());
             return;
         };
+        let label = if from == to {
+            first.short_sha().to_string()
+        } else {
+            format!("{}..{}", first.short_sha(), last.short_sha())
+        };
+        let scope = CommitScope {
+            base_sha: parent.sha.clone(),
+            head_sha: last.sha.clone(),
+            label,
+            since_review: false,
+        };
+        self.commit_range_anchor = None;
+        self.load_commit_scope(scope);
+    }
+
+    /// 指定範囲の diff を取得し、取得後にファイル一覧を差し替える
+    ///
+    /// compare は 300 件で打ち切られるため、1 コミット分の範囲はページを辿れるコミットの API で取得する。
+    fn load_commit_scope(&mut self, scope: CommitScope) {
+        self.commit_files_loading = true;
+        let single_commit = self.pr_commits.as_ref().is_some_and(|commits| {
+            commits.iter().any(|c| {
+                c.sha == scope.head_sha
+                    && c.parents.first().is_some_and(|p| p.sha == scope.base_sha)
+            })
+        });
+        let (tx, rx) = mpsc::channel(1);
+        self.commit_files_receiver = Some((self.pr_number(), rx));
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = if single_commit {
+                client
+                    .fetch_commit_files(&repo, &scope.head_sha)
+                    .await
+                    .map(|files| {
+                        let truncated = files.len() >= MAX_CHANGED_FILES;
+                        (files, truncated)
+                    })
+            } else {
+                client
+                    .fetch_compare_files(&repo, &scope.base_sha, &scope.head_sha)
+                    .await
+                    .map(|files| {
+                        let truncated = files.len() >= MAX_COMPARE_FILES;
+                        (files, truncated)
+                    })
+            };
+            let _ = tx.send((scope, result.map_err(|e| e.to_string()))).await;
+        });
+    }
 
-        // Only allow comments on Added or Context lines (not Removed/Header/Meta)
-        if !matches!(
-            line_info.line_type,
-            crate::diff::LineType::Added | crate::diff::LineType::Context
-        ) {
+    /// コミット範囲の表示を解除し、PR 全体の変更ファイルに戻す
+    fn clear_commit_scope(&mut self) {
+        if self.commit_scope.take().is_none() {
             return;
         }
+        let cache_key = self.pr_cache_key(self.pr_number());
+        let cached = self
+            .session_cache
+            .get_pr_data(&cache_key)
+            .map(|data| (data.files.clone(), data.files_truncated));
+        match cached {
+            Some((files, truncated)) => {
+                self.files_truncated = truncated;
+                self.replace_files(files);
+            }
+            None => self.retry_load(),
+        }
+    }
+
+    fn handle_commit_list_input(&mut self, key: event::KeyEvent) {
+        let rows = self.pr_commits.as_ref().map(|c| c.len() + 1).unwrap_or(0);
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc if self.commit_range_anchor.is_some() => {
+                self.commit_range_anchor = None;
+            }
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.state = self.previous_state;
+            }
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.selected_commit = (self.selected_commit + 1).min(rows.saturating_sub(1));
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.selected_commit = self.selected_commit.saturating_sub(1);
+            }
+            KeyCode::Enter if !self.commit_files_loading => {
+                self.apply_commit_selection();
+            }
+            _ if self.matches_single_key(&key, &self.config.keybindings.visual_select) => {
+                self.commit_range_anchor = match self.commit_range_anchor {
+                    Some(_) => None,
+                    None => (self.selected_commit > 0).then_some(self.selected_commit),
+                };
+            }
+            _ if self.matches_single_key(&key, &self.config.keybindings.refresh) => {
+                self.load_pr_commits();
+            }
+            _ => {}
+        }
+    }
 
-        let Some(line_number) = line_info.new_line_number else {
+    /// マージ方法の選択ダイアログを開き、最新のマージ可否とベースブランチの保護ルールを取得する
+    ///
+    /// `mergeable` は GitHub がバックグラウンドで計算するため、開くたびに取り直す。
+    fn open_merge_dialog(&mut self) {
+        if self.merging || self.pr().is_none() {
             return;
-        };
+        }
+        self.merge_dialog = Some(MergeDialogState::default());
 
-        self.input_mode = Some(InputMode::Comment(LineInputContext {
-            file_index: self.selected_file,
-            line_number,
-        }));
-        self.input_text_area.clear();
-        self.preview_return_state = self.state;
-        self.state = AppState::TextInput;
+        let (tx, rx) = mpsc::channel(1);
+        let pr_number = self.pr_number();
+        self.merge_status_receiver = Some((pr_number, rx));
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = match client.fetch_pr(&repo, pr_number).await {
+                Ok(mut pr) => {
+                    // 保護ルールが読めなくてもマージ可否は使える
+                    pr.protection = client
+                        .fetch_branch_protection(&repo, &pr.base.ref_name)
+                        .await
+                        .ok();
+                    Ok(pr)
+                }
+                Err(e) => Err(e.to_string()),
+            };
+            let _ = tx.send(result).await;
+        });
     }
 
-    async fn submit_review(
+    async fn handle_merge_dialog_input(
         &mut self,
-        action: ReviewAction,
+        key: event::KeyEvent,
         terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     ) -> Result<()> {
-        ui::restore_terminal(terminal)?;
-
-        let body = crate::editor::open_review_editor(&self.config.editor)?;
-
-        *terminal = ui::setup_terminal()?;
-
-        if let Some(body) = body {
-            github::submit_review(&self.repo, self.pr_number(), action, &body).await?;
-        }
-        Ok(())
-    }
-
-    fn update_diff_line_count(&mut self) {
-        self.diff_line_count = Self::calc_diff_line_count(self.files(), self.selected_file);
-    }
-
-    /// Split Viewでファイル選択変更時にdiff状態を同期
-    fn sync_diff_to_selected_file(&mut self) {
-        self.selected_line = 0;
-        self.scroll_offset = 0;
-        self.comment_panel_open = false;
-        self.comment_panel_scroll = 0;
-        self.clear_pending_keys();
-        self.symbol_popup = None;
-        self.update_diff_line_count();
-        if self.review_comments.is_none() {
-            self.load_review_comments();
+        let Some(dialog) = self.merge_dialog.as_mut() else {
+            return Ok(());
+        };
+        let auto_merge = match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                dialog.selected = (dialog.selected + 1).min(MergeMethod::ALL.len() - 1);
+                return Ok(());
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                dialog.selected = dialog.selected.saturating_sub(1);
+                return Ok(());
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.merge_dialog = None;
+                return Ok(());
+            }
+            KeyCode::Enter => false,
+            KeyCode::Char('a') => true,
+            _ => return Ok(()),
+        };
+        let method = MergeMethod::ALL[dialog.selected];
+        let Some(pr) = self.pr() else {
+            return Ok(());
+        };
+        let mergeability = pr.mergeability();
+        let allowed = if auto_merge {
+            mergeability.can_enable_auto_merge()
+        } else {
+            mergeability.can_merge()
+        };
+        if !allowed {
+            self.submission_result = Some((
+                false,
+                format!("Cannot merge: {}", mergeability.description()),
+            ));
+            self.submission_result_time = Some(Instant::now());
+            return Ok(());
         }
-        self.update_file_comment_positions();
-        self.ensure_diff_cache();
-    }
+        let default_title = format!("{} (#{})", pr.title, pr.number);
+        self.merge_dialog = None;
 
-    /// サジェスチョン入力を開始（組み込みTextArea）
-    fn enter_suggestion_input(&mut self) {
-        let Some(file) = self.files().get(self.selected_file) else {
-            return;
-        };
-        let Some(patch) = file.patch.as_ref() else {
-            return;
+        let message = if method.has_commit_message() {
+            ui::restore_terminal(terminal)?;
+            let message =
+                crate::editor::open_merge_commit_editor(&self.config.editor, &default_title, "")?;
+            *terminal = ui::setup_terminal()?;
+            // 内容を全て消した場合はキャンセル
+            let Some(message) = message else {
+                return Ok(());
+            };
+            Some(message)
+        } else {
+            None
         };
+        self.confirm_merge = Some(MergeRequest {
+            method,
+            auto_merge,
+            message,
+        });
+        Ok(())
+    }
 
-        // Check if this line can have a suggestion
-        let Some(line_info) = crate::diff::get_line_info(patch, self.selected_line) else {
+    /// 確認済みのマージ（または自動マージの有効化）を実行する
+    fn start_merge(&mut self, request: MergeRequest) {
+        let Some(pr) = self.pr() else {
             return;
         };
+        let head_sha = pr.head.sha.clone();
+        let node_id = pr.node_id.clone();
+        let pr_number = pr.number;
+        self.merging = true;
 
-        // Only allow suggestions on Added or Context lines
-        if !matches!(
-            line_info.line_type,
-            crate::diff::LineType::Added | crate::diff::LineType::Context
-        ) {
-            return;
-        }
+        let (tx, rx) = mpsc::channel(1);
+        self.merge_receiver = Some((pr_number, rx));
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let message = request.message.as_ref();
+            let result = if request.auto_merge {
+                client
+                    .enable_auto_merge(&node_id, request.method, message)
+                    .await
+                    .map(|()| format!("Auto-merge enabled for PR #{}", pr_number))
+            } else {
+                client
+                    .merge_pr(&repo, pr_number, request.method, &head_sha, message)
+                    .await
+                    .map(|()| format!("Merged PR #{}", pr_number))
+            };
+            let _ = tx.send(result.map_err(|e| e.to_string())).await;
+        });
+    }
 
-        let Some(line_number) = line_info.new_line_number else {
-            return;
-        };
+    fn open_metadata(&mut self) {
+        self.state = AppState::Metadata;
+        self.metadata_picker = None;
+        if self.pr_metadata.is_none() && !self.pr_metadata_loading {
+            self.load_pr_metadata();
+        }
+    }
 
-        let original_code = line_info.line_content.clone();
+    fn load_pr_metadata(&mut self) {
+        self.pr_metadata_loading = true;
+        let (tx, rx) = mpsc::channel(1);
+        let pr_number = self.pr_number();
+        self.pr_metadata_receiver = Some((pr_number, rx));
 
-        self.input_mode = Some(InputMode::Suggestion {
-            context: LineInputContext {
-                file_index: self.selected_file,
-                line_number,
-            },
-            original_code: original_code.clone(),
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = client
+                .fetch_pr_metadata(&repo, pr_number)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
         });
-        // サジェスチョンは元コードを初期値として設定
-        self.input_text_area.set_content(&original_code);
-        self.preview_return_state = self.state;
-        self.state = AppState::TextInput;
     }
 
-    fn open_comment_list(&mut self) {
-        self.state = AppState::CommentList;
-        self.discussion_comment_detail_mode = false;
-        self.discussion_comment_detail_scroll = 0;
+    /// ラベル / レビュワーのピッカーを開き、候補を取得する
+    fn open_metadata_picker(&mut self, kind: MetadataPickerKind) {
+        self.metadata_picker = Some(MetadataPickerState::new(kind));
+        let (tx, rx) = mpsc::channel(1);
+        let pr_number = self.pr_number();
+        self.metadata_picker_receiver = Some((pr_number, rx));
 
-        // Load review comments
-        self.load_review_comments();
-        // Load discussion comments
-        self.load_discussion_comments();
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = match kind {
+                MetadataPickerKind::Labels => client.fetch_repo_labels(&repo).await,
+                MetadataPickerKind::Reviewers => client.fetch_assignable_users(&repo).await,
+            };
+            let _ = tx.send(result.map_err(|e| e.to_string())).await;
+        });
     }
 
-    fn load_review_comments(&mut self) {
-        let cache_key = PrCacheKey {
-            repo: self.repo.clone(),
-            pr_number: self.pr_number(),
-        };
-
-        // インメモリキャッシュを確認
-        if let Some(comments) = self.session_cache.get_review_comments(&cache_key) {
-            self.review_comments = Some(comments.to_vec());
-            self.selected_comment = 0;
-            self.comment_list_scroll_offset = 0;
-            self.comments_loading = false;
+    /// メタデータの更新操作
+    fn update_pr_metadata(&mut self, update: MetadataUpdate) {
+        if self.pr_metadata_updating {
             return;
         }
-
-        // キャッシュミス: API取得
-        self.comments_loading = true;
+        self.pr_metadata_updating = true;
         let (tx, rx) = mpsc::channel(1);
         let pr_number = self.pr_number();
-        self.comment_receiver = Some((pr_number, rx));
+        self.pr_metadata_update_receiver = Some((pr_number, rx));
 
         let repo = self.repo.clone();
-
+        let client = self.github_client();
         tokio::spawn(async move {
-            // Fetch both review comments and reviews
-            let review_comments_result =
-                github::comment::fetch_review_comments(&repo, pr_number).await;
-            let reviews_result = github::comment::fetch_reviews(&repo, pr_number).await;
-
-            // Combine results
-            let mut all_comments: Vec<ReviewComment> = Vec::new();
+            let result = match update {
+                MetadataUpdate::Label { name, add: true } => {
+                    client.add_label(&repo, pr_number, &name).await
+                }
+                MetadataUpdate::Label { name, add: false } => {
+                    client.remove_label(&repo, pr_number, &name).await
+                }
+                MetadataUpdate::Reviewer { login, add } => {
+                    client
+                        .set_review_requested(&repo, pr_number, &login, add)
+                        .await
+                }
+                MetadataUpdate::Draft {
+                    pull_request_id,
+                    draft,
+                } => client.set_draft(&pull_request_id, draft).await,
+            };
+            // 更新後の状態を取り直して反映する
+            let result = match result {
+                Ok(()) => client.fetch_pr_metadata(&repo, pr_number).await,
+                Err(e) => Err(e),
+            };
+            let _ = tx.send(result.map_err(|e| e.to_string())).await;
+        });
+    }
 
-            // Add review comments (inline comments)
-            if let Ok(comments) = review_comments_result {
-                all_comments.extend(comments);
+    fn handle_metadata_input(&mut self, key: event::KeyEvent) {
+        if self.metadata_picker.is_some() {
+            self.handle_metadata_picker_input(key);
+            return;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.state = self.previous_state;
             }
-
-            // Convert reviews to ReviewComment format (only those with body)
-            if let Ok(reviews) = reviews_result {
-                for review in reviews {
-                    if let Some(body) = review.body {
-                        if !body.trim().is_empty() {
-                            all_comments.push(ReviewComment {
-                                id: review.id,
-                                path: "[PR Review]".to_string(),
-                                line: None,
-                                body,
-                                user: review.user,
-                                created_at: review.submitted_at.unwrap_or_default(),
-                            });
-                        }
-                    }
+            KeyCode::Char('l') => self.open_metadata_picker(MetadataPickerKind::Labels),
+            KeyCode::Char('r') => self.open_metadata_picker(MetadataPickerKind::Reviewers),
+            KeyCode::Char('d') => {
+                if let Some(ref metadata) = self.pr_metadata {
+                    let update = MetadataUpdate::Draft {
+                        pull_request_id: metadata.node_id.clone(),
+                        draft: !metadata.draft,
+                    };
+                    self.update_pr_metadata(update);
                 }
             }
-
-            // Sort by created_at
-            all_comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
-
-            let _ = tx.send(Ok(all_comments)).await;
-        });
+            _ if self.matches_single_key(&key, &self.config.keybindings.refresh) => {
+                self.load_pr_metadata();
+            }
+            _ => {}
+        }
     }
 
-    fn load_discussion_comments(&mut self) {
-        let cache_key = PrCacheKey {
-            repo: self.repo.clone(),
-            pr_number: self.pr_number(),
+    /// ピッカー: 文字入力で絞り込み、Enter で選択中の項目を付け外しする
+    fn handle_metadata_picker_input(&mut self, key: event::KeyEvent) {
+        let Some(picker) = self.metadata_picker.as_mut() else {
+            return;
         };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => {
+                self.metadata_picker = None;
+                self.metadata_picker_receiver = None;
+            }
+            KeyCode::Down | KeyCode::Tab => {
+                let count = picker.filtered().len();
+                picker.selected = (picker.selected + 1).min(count.saturating_sub(1));
+            }
+            KeyCode::Char('n') if ctrl => {
+                let count = picker.filtered().len();
+                picker.selected = (picker.selected + 1).min(count.saturating_sub(1));
+            }
+            KeyCode::Up | KeyCode::BackTab => {
+                picker.selected = picker.selected.saturating_sub(1);
+            }
+            KeyCode::Char('p') if ctrl => {
+                picker.selected = picker.selected.saturating_sub(1);
+            }
+            KeyCode::Backspace => {
+                picker.query.pop();
+                picker.selected = 0;
+            }
+            KeyCode::Enter => {
+                let Some(item) = picker
+                    .filtered()
+                    .get(picker.selected)
+                    .map(|s| s.to_string())
+                else {
+                    return;
+                };
+                let Some(ref metadata) = self.pr_metadata else {
+                    return;
+                };
+                let update = match picker.kind {
+                    MetadataPickerKind::Labels => MetadataUpdate::Label {
+                        add: !metadata.has_label(&item),
+                        name: item,
+                    },
+                    MetadataPickerKind::Reviewers => MetadataUpdate::Reviewer {
+                        add: !metadata.has_requested_reviewer(&item),
+                        login: item,
+                    },
+                };
+                self.update_pr_metadata(update);
+            }
+            KeyCode::Char(c) if !ctrl => {
+                picker.query.push(c);
+                picker.selected = 0;
+            }
+            _ => {}
+        }
+    }
 
-        // インメモリキャッシュを確認
-        if let Some(comments) = self.session_cache.get_discussion_comments(&cache_key) {
-            self.discussion_comments = Some(comments.to_vec());
-            self.selected_discussion_comment = 0;
-            self.discussion_comments_loading = false;
+    /// GitHub の Viewed 状態を取得する（取得済み・取得中なら何もしない）
+    fn load_viewed_files(&mut self) {
+        if self.viewed_files.is_some() || self.viewed_files_receiver.is_some() {
             return;
         }
-
-        // キャッシュミス: API取得
-        self.discussion_comments_loading = true;
         let (tx, rx) = mpsc::channel(1);
         let pr_number = self.pr_number();
-        self.discussion_comment_receiver = Some((pr_number, rx));
+        self.viewed_files_receiver = Some((pr_number, rx));
 
         let repo = self.repo.clone();
-
+        let client = self.github_client();
         tokio::spawn(async move {
-            match github::comment::fetch_discussion_comments(&repo, pr_number).await {
-                Ok(comments) => {
-                    let _ = tx.send(Ok(comments)).await;
-                }
-                Err(e) => {
-                    let _ = tx.send(Err(e.to_string())).await;
-                }
-            }
+            let result = client
+                .fetch_viewed_files(&repo, pr_number)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(result).await;
         });
     }
 
-    async fn handle_comment_list_input(
-        &mut self,
-        key: event::KeyEvent,
-        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
-    ) -> Result<()> {
-        let visible_lines = terminal.size()?.height.saturating_sub(8) as usize;
+    /// 現在のファイル一覧のうち Viewed のファイル数
+    pub fn viewed_count(&self) -> Option<usize> {
+        let viewed = self.viewed_files.as_ref()?;
+        Some(
+            self.files()
+                .iter()
+                .filter(|f| viewed.paths.contains(&f.filename))
+                .count(),
+        )
+    }
 
-        // Handle detail mode input separately
-        if self.discussion_comment_detail_mode {
-            return self.handle_discussion_detail_input(key, visible_lines);
+    pub fn is_file_viewed(&self, path: &str) -> bool {
+        self.viewed_files
+            .as_ref()
+            .is_some_and(|v| v.paths.contains(path))
+    }
+
+    /// 選択中ファイルの Viewed を切り替える（楽観的に反映し、GitHub へ送信する）
+    fn toggle_file_viewed(&mut self) {
+        if self.viewed_toggle_receiver.is_some() {
+            return;
+        }
+        let Some(path) = self
+            .files()
+            .get(self.selected_file)
+            .map(|f| f.filename.clone())
+        else {
+            return;
+        };
+        let Some(ref mut viewed_files) = self.viewed_files else {
+            self.submission_result = Some((false, "Viewed state is not loaded yet".to_string()));
+            self.submission_result_time = Some(Instant::now());
+            return;
+        };
+        let viewed = !viewed_files.paths.contains(&path);
+        if viewed {
+            viewed_files.paths.insert(path.clone());
+        } else {
+            viewed_files.paths.remove(&path);
         }
+        let pull_request_id = viewed_files.pull_request_id.clone();
 
-        match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => {
-                self.state = self.previous_state;
-            }
-            KeyCode::Char('[') => {
-                self.comment_tab = match self.comment_tab {
-                    CommentTab::Review => CommentTab::Discussion,
-                    CommentTab::Discussion => CommentTab::Review,
-                };
+        let (tx, rx) = mpsc::channel(1);
+        self.viewed_toggle_receiver = Some((self.pr_number(), rx));
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = client
+                .set_file_viewed(&pull_request_id, &path, viewed)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send((path, viewed, result)).await;
+        });
+    }
+
+    /// 選択中の次から順に（末尾で先頭に戻って）未 Viewed のファイルを選択する
+    ///
+    /// 移動したら true を返す。
+    fn select_next_unviewed_file(&mut self) -> bool {
+        let files = self.files();
+        let len = files.len();
+        let next = (1..=len)
+            .map(|offset| (self.selected_file + offset) % len)
+            .find(|&i| !self.is_file_viewed(&files[i].filename));
+        match next {
+            Some(index) => {
+                self.selected_file = index;
+                true
             }
-            KeyCode::Char(']') => {
-                self.comment_tab = match self.comment_tab {
-                    CommentTab::Review => CommentTab::Discussion,
-                    CommentTab::Discussion => CommentTab::Review,
-                };
+            None => {
+                self.submission_result = Some((true, "All files are viewed".to_string()));
+                self.submission_result_time = Some(Instant::now());
+                false
             }
-            KeyCode::Char('j') | KeyCode::Down => match self.comment_tab {
-                CommentTab::Review => {
-                    if let Some(ref comments) = self.review_comments {
-                        if !comments.is_empty() {
-                            self.selected_comment =
-                                (self.selected_comment + 1).min(comments.len().saturating_sub(1));
-                        }
-                    }
-                }
-                CommentTab::Discussion => {
-                    if let Some(ref comments) = self.discussion_comments {
-                        if !comments.is_empty() {
-                            self.selected_discussion_comment = (self.selected_discussion_comment
-                                + 1)
-                            .min(comments.len().saturating_sub(1));
-                        }
-                    }
-                }
-            },
-            KeyCode::Char('k') | KeyCode::Up => match self.comment_tab {
-                CommentTab::Review => {
-                    self.selected_comment = self.selected_comment.saturating_sub(1);
-                }
-                CommentTab::Discussion => {
-                    self.selected_discussion_comment =
-                        self.selected_discussion_comment.saturating_sub(1);
-                }
-            },
-            KeyCode::Enter => match self.comment_tab {
-                CommentTab::Review => {
-                    self.jump_to_comment();
-                }
-                CommentTab::Discussion => {
-                    // Enter detail mode for discussion comment
-                    if self
-                        .discussion_comments
-                        .as_ref()
-                        .map(|c| !c.is_empty())
-                        .unwrap_or(false)
-                    {
-                        self.discussion_comment_detail_mode = true;
-                        self.discussion_comment_detail_scroll = 0;
-                    }
-                }
-            },
-            _ => {}
         }
-        Ok(())
     }
 
-    fn handle_discussion_detail_input(
+    fn open_checks(&mut self) {
+        self.state = AppState::Checks;
+        self.check_detail = None;
+        self.load_checks(false);
+    }
+
+    /// 選択中のチェックの詳細ペインを開く（Check Run はアノテーションを取得する）
+    fn open_check_detail(&mut self) {
+        let Some(ref checks) = self.checks else {
+            return;
+        };
+        let index = self.selected_check;
+        if index >= checks.len() {
+            return;
+        }
+        let run = checks.runs.get(index);
+        let needs_fetch = run.is_some_and(|r| r.output.annotations_count > 0);
+        self.check_detail = Some(CheckDetailState {
+            index,
+            annotations: (!needs_fetch).then(|| Ok(vec![])),
+            scroll: 0,
+        });
+        let Some(check_run_id) = run.filter(|_| needs_fetch).map(|r| r.id) else {
+            self.check_annotations_receiver = None;
+            return;
+        };
+
+        let (tx, rx) = mpsc::channel(1);
+        self.check_annotations_receiver = Some((self.pr_number(), rx));
+        let repo = self.repo.clone();
+        let client = self.github_client();
+        tokio::spawn(async move {
+            let result = client
+                .fetch_check_annotations(&repo, check_run_id)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send((index, result)).await;
+        });
+    }
+
+    async fn handle_checks_input(
         &mut self,
         key: event::KeyEvent,
-        visible_lines: usize,
+        terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     ) -> Result<()> {
+        let visible_lines = terminal.size()?.height.saturating_sub(8) as usize / 2;
+
+        // 詳細ペイン表示中はスクロール操作
+        if let Some(detail) = self.check_detail.as_mut() {
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('h') | KeyCode::Left => {
+                    self.check_detail = None;
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    detail.scroll = detail.scroll.saturating_add(1);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    detail.scroll = detail.scroll.saturating_sub(1);
+                }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    detail.scroll = detail.scroll.saturating_add(visible_lines / 2);
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    detail.scroll = detail.scroll.saturating_sub(visible_lines / 2);
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        let count = self.checks.as_ref().map(Checks::len).unwrap_or(0);
         match key.code {
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
-                self.discussion_comment_detail_mode = false;
-                self.discussion_comment_detail_scroll = 0;
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.state = self.previous_state;
             }
             KeyCode::Char('j') | KeyCode::Down => {
-                self.discussion_comment_detail_scroll =
-                    self.discussion_comment_detail_scroll.saturating_add(1);
+                self.selected_check = (self.selected_check + 1).min(count.saturating_sub(1));
             }
             KeyCode::Char('k') | KeyCode::Up => {
-                self.discussion_comment_detail_scroll =
-                    self.discussion_comment_detail_scroll.saturating_sub(1);
+                self.selected_check = self.selected_check.saturating_sub(1);
             }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.discussion_comment_detail_scroll = self
-                    .discussion_comment_detail_scroll
-                    .saturating_add(visible_lines / 2);
+            KeyCode::Enter | KeyCode::Char('l') | KeyCode::Right => {
+                self.open_check_detail();
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.discussion_comment_detail_scroll = self
-                    .discussion_comment_detail_scroll
-                    .saturating_sub(visible_lines / 2);
+            _ if self.matches_single_key(&key, &self.config.keybindings.refresh) => {
+                self.load_checks(true);
             }
             _ => {}
         }
@@ -2743,6 +5400,7 @@ impl App {
         };
 
         let target_path = &comment.path;
+        let outdated = comment.is_outdated();
 
         // Find file index by path
         let file_index = self.files().iter().position(|f| &f.filename == target_path);
@@ -2752,6 +5410,7 @@ impl App {
             self.diff_view_return_state = AppState::FileList;
             self.state = AppState::DiffView;
             self.selected_line = 0;
+            self.visual_anchor = None;
             self.scroll_offset = 0;
             self.update_diff_line_count();
             self.update_file_comment_positions();
@@ -2767,6 +5426,12 @@ impl App {
             if let Some(line_idx) = diff_line_index {
                 self.selected_line = line_idx;
                 self.scroll_offset = line_idx;
+            } else if outdated {
+                self.submission_result = Some((
+                    false,
+                    "This comment is outdated; its line is no longer in the diff".to_string(),
+                ));
+                self.submission_result_time = Some(Instant::now());
             }
         }
     }
@@ -2775,6 +5440,10 @@ impl App {
     fn update_file_comment_positions(&mut self) {
         self.file_comment_positions.clear();
         self.file_comment_lines.clear();
+        self.update_file_annotation_positions();
+        if !self.diff_matches_head() {
+            return;
+        }
 
         let Some(file) = self.files().get(self.selected_file) else {
             return;
@@ -2784,6 +5453,7 @@ impl App {
         };
         let filename = file.filename.clone();
 
+        let hidden = self.hidden_comment_flags();
         let Some(ref comments) = self.review_comments else {
             return;
         };
@@ -2793,11 +5463,26 @@ impl App {
             if comment.path != filename {
                 continue;
             }
-            // Skip PR-level comments (line: None)
+            // 折りたたまれたスレッドの返信はパネルに出さない
+            if hidden[i] {
+                continue;
+            }
+            // Skip PR-level and outdated comments (line: None)
             let Some(line_num) = comment.line else {
                 continue;
             };
-            if let Some(diff_index) = Self::find_diff_line_index(&patch, line_num) {
+            let side = comment.side.unwrap_or_default();
+            // コミット範囲表示中の LEFT 側は PR の base と一致しないため表示しない
+            if self.commit_scope.is_some() && side == DiffSide::Left {
+                continue;
+            }
+            // push で行がずれていても同じコードに付くよう、diff_hunk の内容で位置を探す
+            let diff_index = comment
+                .diff_hunk
+                .as_deref()
+                .and_then(|hunk| crate::diff::find_hunk_anchor(&patch, hunk, side, line_num))
+                .or_else(|| Self::find_diff_line_index(&patch, line_num, side));
+            if let Some(diff_index) = diff_index {
                 self.file_comment_positions.push(CommentPosition {
                     diff_line_index: diff_index,
                     comment_index: i,
@@ -2809,30 +5494,98 @@ impl App {
             .sort_by_key(|pos| pos.diff_line_index);
     }
 
+    /// 現在のファイルに対する CI アノテーションの diff 内位置を再計算する
+    ///
+    /// アノテーションは head 側の行番号なので RIGHT として扱い、開始行が diff に
+    /// 含まれなければ終了行で探す。
+    fn update_file_annotation_positions(&mut self) {
+        self.file_annotation_positions.clear();
+        self.file_annotation_lines.clear();
+        if !self.diff_matches_head() {
+            return;
+        }
+
+        let Some(file) = self.files().get(self.selected_file) else {
+            return;
+        };
+        let Some(ref patch) = file.patch else {
+            return;
+        };
+
+        let mut positions = Vec::new();
+        let mut lines = HashMap::new();
+        for (i, annotation) in self.diff_annotations.iter().enumerate() {
+            if annotation.path != file.filename {
+                continue;
+            }
+            let Some(diff_index) = [annotation.start_line, annotation.end_line]
+                .into_iter()
+                .find_map(|line| Self::find_diff_line_index(patch, line, DiffSide::Right))
+            else {
+                continue;
+            };
+            positions.push(AnnotationPosition {
+                diff_line_index: diff_index,
+                annotation_index: i,
+            });
+            lines
+                .entry(diff_index)
+                .and_modify(|level: &mut AnnotationLevel| *level = (*level).max(annotation.level()))
+                .or_insert(annotation.level());
+        }
+        positions.sort_by_key(|pos| pos.diff_line_index);
+        self.file_annotation_positions = positions;
+        self.file_annotation_lines = lines;
+    }
+
+    /// 現在行の CI アノテーション
+    pub fn annotations_at_current_line(&self) -> Vec<&CheckAnnotation> {
+        self.file_annotation_positions
+            .iter()
+            .filter(|pos| pos.diff_line_index == self.selected_line)
+            .filter_map(|pos| self.diff_annotations.get(pos.annotation_index))
+            .collect()
+    }
+
     /// Static helper to find diff line index for a given line number
-    fn find_diff_line_index(patch: &str, target_line: u32) -> Option<usize> {
-        let lines: Vec<&str> = patch.lines().collect();
+    ///
+    /// `side` selects which file the line number refers to: RIGHT matches
+    /// added/context lines by new line number, LEFT matches removed/context
+    /// lines by old line number.
+    fn find_diff_line_index(patch: &str, target_line: u32, side: DiffSide) -> Option<usize> {
+        let mut old_line_number: Option<u32> = None;
         let mut new_line_number: Option<u32> = None;
 
-        for (i, line) in lines.iter().enumerate() {
-            if line.starts_with("@@") {
-                // Parse hunk header to get starting line number
-                if let Some(plus_pos) = line.find('+') {
-                    let after_plus = &line[plus_pos + 1..];
-                    let end_pos = after_plus.find([',', ' ']).unwrap_or(after_plus.len());
-                    if let Ok(num) = after_plus[..end_pos].parse::<u32>() {
-                        new_line_number = Some(num);
-                    }
-                }
-            } else if line.starts_with('+') || line.starts_with(' ') {
-                if let Some(current) = new_line_number {
-                    if current == target_line {
-                        return Some(i);
+        for (i, line) in patch.lines().enumerate() {
+            let (line_type, _) = crate::diff::classify_line(line);
+            match line_type {
+                LineType::Header => {
+                    // Parse hunk header to get starting line numbers
+                    if let Some((old, new)) = crate::diff::parse_hunk_header(line) {
+                        old_line_number = Some(old);
+                        new_line_number = Some(new);
                     }
-                    new_line_number = Some(current + 1);
+                    continue;
                 }
+                LineType::Meta => continue,
+                _ => {}
+            }
+
+            let current = match (side, line_type) {
+                (DiffSide::Right, LineType::Added | LineType::Context) => new_line_number,
+                (DiffSide::Left, LineType::Removed | LineType::Context) => old_line_number,
+                _ => None,
+            };
+            if current == Some(target_line) {
+                return Some(i);
+            }
+
+            if matches!(line_type, LineType::Added | LineType::Context) {
+                new_line_number = new_line_number.map(|n| n + 1);
+            }
+            if matches!(line_type, LineType::Removed | LineType::Context) {
+                old_line_number = old_line_number.map(|n| n + 1);
             }
-            // Removed lines don't increment new_line_number
         }
 
         None
@@ -2847,6 +5600,172 @@ impl App {
             .collect()
     }
 
+    /// スレッドが折りたたまれているか（解決済みスレッドは既定で折りたたむ）
+    pub fn is_thread_collapsed(&self, comment: &ReviewComment) -> bool {
+        comment.is_resolved()
+            != self
+                .collapse_toggled_threads
+                .contains(&comment.thread_root_id())
+    }
+
+    /// レビューコメントごとの非表示フラグ（折りたたまれたスレッドの返信）
+    ///
+    /// コメントはスレッド単位に並んでいる前提で、先頭コメントが見つからない返信は表示する。
+    fn hidden_comment_flags(&self) -> Vec<bool> {
+        let Some(ref comments) = self.review_comments else {
+            return Vec::new();
+        };
+        comments
+            .iter()
+            .enumerate()
+            .map(|(i, comment)| {
+                i > 0
+                    && comments[i - 1].thread_root_id() == comment.thread_root_id()
+                    && self.is_thread_collapsed(comment)
+            })
+            .collect()
+    }
+
+    /// コメント一覧に表示するレビューコメントのインデックス
+    pub fn visible_review_comment_indices(&self) -> Vec<usize> {
+        self.hidden_comment_flags()
+            .iter()
+            .enumerate()
+            .filter(|(_, hidden)| !**hidden)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// スレッド先頭コメントの ID ごとの返信数
+    pub fn thread_reply_counts(&self) -> HashMap<u64, usize> {
+        let mut counts = HashMap::new();
+        for comment in self.review_comments.iter().flatten() {
+            if let Some(root_id) = comment.in_reply_to_id {
+                *counts.entry(root_id).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// 指定コメントが属するスレッドの折りたたみを切り替える
+    fn toggle_thread_collapsed(&mut self, comment_index: usize) {
+        let Some(root_id) = self
+            .review_comments
+            .as_ref()
+            .and_then(|comments| comments.get(comment_index))
+            .map(|c| c.thread_root_id())
+        else {
+            return;
+        };
+        if !self.collapse_toggled_threads.remove(&root_id) {
+            self.collapse_toggled_threads.insert(root_id);
+        }
+
+        // 非表示になった返信を選択していた場合は表示中の直前のコメント（スレッド先頭）へ
+        let visible = self.visible_review_comment_indices();
+        if !visible.contains(&self.selected_comment) {
+            self.selected_comment = visible
+                .iter()
+                .rev()
+                .find(|&&i| i < self.selected_comment)
+                .copied()
+                .unwrap_or(0);
+        }
+
+        let selected_id = self
+            .comment_panel_open
+            .then(|| self.selected_inline_comment_index())
+            .flatten();
+        self.update_file_comment_positions();
+        if let Some(selected_id) = selected_id {
+            let indices = self.get_comment_indices_at_current_line();
+            self.selected_inline_comment =
+                indices.iter().rposition(|&i| i <= selected_id).unwrap_or(0);
+            self.comment_panel_scroll = 0;
+        }
+    }
+
+    /// 指定コメントが属するスレッドの解決状態を切り替える
+    fn toggle_thread_resolved(&mut self, comment_index: usize) {
+        if self.thread_resolve_receiver.is_some() {
+            return;
+        }
+        let Some(comment) = self
+            .review_comments
+            .as_ref()
+            .and_then(|comments| comments.get(comment_index))
+        else {
+            return;
+        };
+        let Some(thread) = comment.thread.clone() else {
+            self.submission_result = Some((false, "Not a review thread".to_string()));
+            self.submission_result_time = Some(Instant::now());
+            return;
+        };
+        // 解決状態が変わったら折りたたみも既定に戻す
+        self.collapse_toggled_threads
+            .remove(&comment.thread_root_id());
+
+        let resolved = !thread.is_resolved;
+        let (tx, rx) = mpsc::channel(1);
+        self.thread_resolve_receiver = Some((self.pr_number(), rx));
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            let result = client
+                .set_thread_resolved(&thread.id, resolved)
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send((resolved, result)).await;
+        });
+    }
+
+    /// スレッドの解決・解除結果のポーリング（成功したらコメントを取り直す）
+    fn poll_thread_resolve(&mut self) {
+        let Some((origin_pr, rx)) = self.thread_resolve_receiver.as_mut() else {
+            return;
+        };
+        let origin_pr = *origin_pr;
+
+        let (resolved, result) = match rx.try_recv() {
+            Ok(received) => received,
+            Err(mpsc::error::TryRecvError::Empty) => return,
+            Err(mpsc::error::TryRecvError::Disconnected) => {
+                self.thread_resolve_receiver = None;
+                return;
+            }
+        };
+        self.thread_resolve_receiver = None;
+        if self.pr_number != Some(origin_pr) {
+            return;
+        }
+        let action = if resolved { "resolve" } else { "unresolve" };
+        self.submission_result = Some(match result {
+            Ok(()) => {
+                let cache_key = self.pr_cache_key(origin_pr);
+                self.session_cache.remove_review_comments(&cache_key);
+                self.review_comments = None;
+                self.load_review_comments();
+                self.update_file_comment_positions();
+                let message = if resolved { "Resolved" } else { "Unresolved" };
+                (true, message.to_string())
+            }
+            Err(e) => (false, format!("Failed to {} thread: {}", action, e)),
+        });
+        self.submission_result_time = Some(Instant::now());
+    }
+
+    /// コメントパネルで選択中のインラインコメント（review_comments のインデックス）
+    fn selected_inline_comment_index(&self) -> Option<usize> {
+        let indices = self.get_comment_indices_at_current_line();
+        indices
+            .get(
+                self.selected_inline_comment
+                    .min(indices.len().saturating_sub(1)),
+            )
+            .copied()
+    }
+
     /// Check if current line has any comments
     pub fn has_comment_at_current_line(&self) -> bool {
         self.file_comment_positions
@@ -2874,11 +5793,29 @@ impl App {
             .max(1) // 空の本文でも最低1行
     }
 
+    /// レビューコメント本文の表示行数（suggestion のミニ diff を含む）
+    fn review_comment_body_lines(&self, comment: &ReviewComment, panel_width: usize) -> usize {
+        ui::common::comment_body_lines(self, comment)
+            .iter()
+            .map(|line| {
+                let text: String = line.spans.iter().map(|s| s.content.as_ref()).collect();
+                Self::wrapped_line_count(&text, panel_width)
+            })
+            .sum::<usize>()
+            .max(1) // 空の本文でも最低1行
+    }
+
+    /// リアクション表示行の行数（リアクションがなければ 0）
+    fn comment_reaction_lines(comment: &ReviewComment) -> usize {
+        usize::from(comment.reactions.summary().is_some())
+    }
+
     /// コメントパネルのコンテンツ行数を計算（スクロール上限算出用）
     fn comment_panel_content_lines(&self, panel_inner_width: usize) -> usize {
+        let annotation_lines = self.annotation_panel_lines(panel_inner_width);
         let indices = self.get_comment_indices_at_current_line();
         if indices.is_empty() {
-            return 1; // "No comments..." message
+            return 1 + annotation_lines; // "No comments..." message
         }
         let Some(ref comments) = self.review_comments else {
             return 0;
@@ -2892,10 +5829,23 @@ impl App {
                 count += 1; // separator
             }
             count += 1; // header
-            count += Self::comment_body_wrapped_lines(&comment.body, panel_inner_width);
+            count += self.review_comment_body_lines(comment, panel_inner_width);
+            count += Self::comment_reaction_lines(comment);
             count += 1; // spacing
         }
-        count
+        count + annotation_lines
+    }
+
+    /// コメントパネル末尾の CI アノテーション欄の行数（アノテーションがなければ 0）
+    fn annotation_panel_lines(&self, panel_inner_width: usize) -> usize {
+        let annotations = self.annotations_at_current_line();
+        if annotations.is_empty() {
+            return 0;
+        }
+        1 + annotations // section header
+            .iter()
+            .map(|a| 1 + Self::comment_body_wrapped_lines(&a.message, panel_inner_width) + 1)
+            .sum::<usize>()
     }
 
     /// 指定インラインコメントのパネル内行オフセットを計算（スクロール追従用）
@@ -2916,7 +5866,8 @@ impl App {
                 offset += 1; // separator
             }
             offset += 1; // header
-            offset += Self::comment_body_wrapped_lines(&comment.body, panel_inner_width);
+            offset += self.review_comment_body_lines(comment, panel_inner_width);
+            offset += Self::comment_reaction_lines(comment);
             offset += 1; // spacing
         }
         if target > 0 {
@@ -2970,6 +5921,33 @@ impl App {
         }
     }
 
+    /// Jump to next CI annotation in the diff (no wrap-around, scroll to top)
+    fn jump_to_next_annotation(&mut self) {
+        let next = self
+            .file_annotation_positions
+            .iter()
+            .find(|pos| pos.diff_line_index > self.selected_line);
+
+        if let Some(pos) = next {
+            self.selected_line = pos.diff_line_index;
+            self.scroll_offset = self.selected_line;
+        }
+    }
+
+    /// Jump to previous CI annotation in the diff (no wrap-around, scroll to top)
+    fn jump_to_prev_annotation(&mut self) {
+        let prev = self
+            .file_annotation_positions
+            .iter()
+            .rev()
+            .find(|pos| pos.diff_line_index < self.selected_line);
+
+        if let Some(pos) = prev {
+            self.selected_line = pos.diff_line_index;
+            self.scroll_offset = self.selected_line;
+        }
+    }
+
     /// 返信入力モードに遷移（統一TextArea）
     fn enter_reply_input(&mut self) {
         let indices = self.get_comment_indices_at_current_line();
@@ -2989,8 +5967,9 @@ impl App {
             return;
         };
 
+        // GitHub は返信への返信を受け付けないため、スレッド先頭に返信する
         self.input_mode = Some(InputMode::Reply {
-            comment_id: comment.id,
+            comment_id: comment.thread_root_id(),
             reply_to_user: comment.user.login.clone(),
             reply_to_body: comment.body.clone(),
         });
@@ -3101,6 +6080,90 @@ impl App {
         Ok(())
     }
 
+    /// 現在の画面で選択中の対象にリアクション選択ポップアップを開く
+    ///
+    /// ファイル一覧では PR 本体、コメント一覧・コメントパネルでは選択中のコメントが対象。
+    fn open_reaction_popup(&mut self) {
+        let target = match self.state {
+            AppState::FileList | AppState::SplitViewFileList => {
+                Some(ReactionTarget::PullRequest(self.pr_number()))
+            }
+            _ => self
+                .selected_posted_comment()
+                .map(|(comment, ..)| match comment {
+                    PostedComment::Review(id) => ReactionTarget::ReviewComment(id),
+                    PostedComment::Discussion(id) => ReactionTarget::DiscussionComment(id),
+                }),
+        };
+        if let Some(target) = target {
+            self.reaction_popup = Some(ReactionPopupState {
+                target,
+                selected: 0,
+            });
+        }
+    }
+
+    fn handle_reaction_popup_input(&mut self, key: event::KeyEvent) {
+        let Some(popup) = self.reaction_popup.as_mut() else {
+            return;
+        };
+        let last = ReactionKind::ALL.len() - 1;
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Char('l') | KeyCode::Down | KeyCode::Right => {
+                popup.selected = (popup.selected + 1).min(last);
+            }
+            KeyCode::Char('k') | KeyCode::Char('h') | KeyCode::Up | KeyCode::Left => {
+                popup.selected = popup.selected.saturating_sub(1);
+            }
+            KeyCode::Enter => {
+                let target = popup.target;
+                let kind = ReactionKind::ALL[popup.selected];
+                self.reaction_popup = None;
+                self.add_reaction(target, kind);
+            }
+            // 数字キーで直接選択
+            KeyCode::Char(c @ '1'..='8') => {
+                let target = popup.target;
+                let kind = ReactionKind::ALL[c as usize - '1' as usize];
+                self.reaction_popup = None;
+                self.add_reaction(target, kind);
+            }
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.reaction_popup = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// リアクションを送信し、対象に応じて一覧を再取得する
+    fn add_reaction(&mut self, target: ReactionTarget, kind: ReactionKind) {
+        if self.comment_submitting {
+            return;
+        }
+        let repo = self.repo.clone();
+        let (tx, rx) = mpsc::channel(1);
+        self.comment_submit_receiver = Some((self.pr_number(), rx));
+        self.comment_submitting = true;
+        let client = self.github_client();
+
+        tokio::spawn(async move {
+            let result = client.add_reaction(&repo, target, kind).await;
+            let _ = tx
+                .send(match result {
+                    Ok(()) => match target {
+                        ReactionTarget::ReviewComment(_) => CommentSubmitResult::Success,
+                        ReactionTarget::DiscussionComment(_) => {
+                            CommentSubmitResult::DiscussionUpdated
+                        }
+                        ReactionTarget::PullRequest(_) => CommentSubmitResult::SuccessNoRefresh,
+                    },
+                    Err(e) => CommentSubmitResult::Error(e.to_string()),
+                })
+                .await;
+        });
+    }
+
     /// シンボルの定義元へジャンプ（diff パッチ内 → リポジトリ全体、非同期）
     async fn jump_to_symbol_definition_async(
         &mut self,
@@ -3394,6 +6457,29 @@ impl App {
         // Clone keybindings to avoid borrow conflicts
         let kb = self.config.keybindings.clone();
 
+        // 検索バー入力中
+        if let Some(ref mut input) = self.pr_list_search_input {
+            match key.code {
+                KeyCode::Enter => {
+                    let search = input.trim().to_string();
+                    self.pr_list_search_input = None;
+                    if search != self.pr_list_query.search {
+                        self.pr_list_query.search = search;
+                        self.reload_pr_list();
+                    }
+                }
+                KeyCode::Esc => self.pr_list_search_input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    input.push(c);
+                }
+                _ => {}
+            }
+            return Ok(());
+        }
+
         // Quit
         if self.matches_single_key(&key, &kb.quit) {
             self.should_quit = true;
@@ -3461,20 +6547,26 @@ impl App {
             return Ok(());
         }
 
-        // Enter: PR選択
+        // Enter: PR選択（受信箱では PR のリポジトリに切り替えてから開く）
         if self.matches_single_key(&key, &kb.open_panel) {
-            if let Some(ref prs) = self.pr_list {
-                if let Some(pr) = prs.get(self.selected_pr) {
-                    self.select_pr(pr.number);
+            let selected = self
+                .pr_list
+                .as_ref()
+                .and_then(|prs| prs.get(self.selected_pr))
+                .map(|pr| (pr.number, pr.repo.clone()));
+            if let Some((pr_number, repo)) = selected {
+                if let Some(repo) = repo.filter(|r| *r != self.repo) {
+                    self.switch_repo(repo);
                 }
+                self.select_pr(pr_number);
             }
             return Ok(());
         }
 
         // o: open PRのみ
         if key.code == KeyCode::Char('o') {
-            if self.pr_list_state_filter != PrStateFilter::Open {
-                self.pr_list_state_filter = PrStateFilter::Open;
+            if self.pr_list_query.state != PrStateFilter::Open {
+                self.pr_list_query.state = PrStateFilter::Open;
                 self.reload_pr_list();
             }
             return Ok(());
@@ -3482,8 +6574,8 @@ impl App {
 
         // c: closed PRのみ
         if key.code == KeyCode::Char('c') {
-            if self.pr_list_state_filter != PrStateFilter::Closed {
-                self.pr_list_state_filter = PrStateFilter::Closed;
+            if self.pr_list_query.state != PrStateFilter::Closed {
+                self.pr_list_query.state = PrStateFilter::Closed;
                 self.reload_pr_list();
             }
             return Ok(());
@@ -3491,13 +6583,36 @@ impl App {
 
         // a: all PRs
         if key.code == KeyCode::Char('a') {
-            if self.pr_list_state_filter != PrStateFilter::All {
-                self.pr_list_state_filter = PrStateFilter::All;
+            if self.pr_list_query.state != PrStateFilter::All {
+                self.pr_list_query.state = PrStateFilter::All;
                 self.reload_pr_list();
             }
             return Ok(());
         }
 
+        // /: 検索バー（現在の検索条件を編集）
+        if key.code == KeyCode::Char('/') {
+            self.pr_list_search_input = Some(self.pr_list_query.search.clone());
+            return Ok(());
+        }
+
+        // n / m: 「自分のレビュー待ち」「自分の PR」プリセット（もう一度押すと解除）
+        if key.code == KeyCode::Char('n') {
+            self.toggle_pr_list_preset(PrListQuery::NEEDS_MY_REVIEW);
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('m') {
+            self.toggle_pr_list_preset(PrListQuery::MY_PRS);
+            return Ok(());
+        }
+
+        // s: 並び順を切り替え
+        if key.code == KeyCode::Char('s') {
+            self.pr_list_query.sort = self.pr_list_query.sort.next();
+            self.reload_pr_list();
+            return Ok(());
+        }
+
         // r: リフレッシュ
         if self.matches_single_key(&key, &kb.refresh) {
             self.reload_pr_list();
@@ -3514,6 +6629,15 @@ impl App {
         Ok(())
     }
 
+    fn toggle_pr_list_preset(&mut self, preset: &str) {
+        self.pr_list_query.search = if self.pr_list_query.search == preset {
+            String::new()
+        } else {
+            preset.to_string()
+        };
+        self.reload_pr_list();
+    }
+
     /// PR一覧を再読み込み
     fn reload_pr_list(&mut self) {
         // 既存のリストをクリアせず、ローディング状態のみ設定
@@ -3522,26 +6646,41 @@ impl App {
         self.pr_list_scroll_offset = 0;
         self.pr_list_loading = true;
         self.pr_list_has_more = false;
+        self.pr_list_cursor = None;
+        self.pr_list_total = None;
 
         let (tx, rx) = mpsc::channel(2);
         self.pr_list_receiver = Some(rx);
 
         let repo = self.repo.clone();
-        let state = self.pr_list_state_filter;
+        let query = self.pr_list_query.clone();
+        let client = self.github_client();
 
         tokio::spawn(async move {
-            let result = github::fetch_pr_list(&repo, state, 30).await;
+            let result = client.fetch_pr_list(&repo, &query, 30).await;
             let _ = tx.send(result.map_err(|e| e.to_string())).await;
         });
     }
 
+    /// 検索 API の上限で PR 一覧が打ち切られた場合の総数
+    ///
+    /// 検索 API は 1,000 件を超えると次のページがないものとして返すため、
+    /// 読み込んだ件数が総数に届かないまま終わったときだけ Some を返す。
+    pub fn pr_list_truncated_total(&self) -> Option<u32> {
+        let loaded = self.pr_list.as_ref().map_or(0, |prs| prs.len());
+        let total = self.pr_list_total?;
+        (!self.pr_list_has_more && loaded >= github::SEARCH_RESULT_LIMIT && total as usize > loaded)
+            .then_some(total)
+    }
+
     /// 追加のPRを読み込み（無限スクロール用）
     fn load_more_prs(&mut self) {
         if self.pr_list_loading {
             return;
         }
-
-        let offset = self.pr_list.as_ref().map(|l| l.len()).unwrap_or(0) as u32;
+        let Some(cursor) = self.pr_list_cursor.clone() else {
+            return;
+        };
 
         self.pr_list_loading = true;
 
@@ -3549,14 +6688,26 @@ impl App {
         self.pr_list_receiver = Some(rx);
 
         let repo = self.repo.clone();
-        let state = self.pr_list_state_filter;
+        let query = self.pr_list_query.clone();
+        let client = self.github_client();
 
         tokio::spawn(async move {
-            let result = github::fetch_pr_list_with_offset(&repo, state, offset, 30).await;
+            let result = client
+                .fetch_pr_list_page(&repo, &query, Some(&cursor), 30)
+                .await;
             let _ = tx.send(result.map_err(|e| e.to_string())).await;
         });
     }
 
+    /// 対象リポジトリを切り替え、リポジトリに紐づく状態を初期化する
+    ///
+    /// PR 番号はリポジトリ間で重複するため、PR 番号で照合している in-flight の
+    /// レシーバーもここで破棄する。
+    fn switch_repo(&mut self, repo: String) {
+        self.repo = repo;
+        self.reset_pr_state();
+    }
+
     /// PR選択時の処理
     ///
     /// L1キャッシュを確認し、Hit/Stale時はキャッシュデータで即座にUI表示しつつ
@@ -3566,12 +6717,7 @@ impl App {
         self.state = AppState::FileList;
 
         // PR遷移時にバックグラウンドキャッシュをクリア（staleキャッシュ防止）
-        self.diff_cache_receiver = None;
-        self.prefetch_receiver = None;
-        self.highlighted_cache_store.clear();
-        self.diff_cache = None;
-        self.selected_file = 0;
-        self.file_list_scroll_offset = 0;
+        self.reset_pr_state();
 
         // Apply pending AI Rally flag
         if self.pending_ai_rally {
@@ -3579,10 +6725,7 @@ impl App {
         }
 
         // インメモリキャッシュを確認し、Hit/Missに応じて分岐
-        let cache_key = PrCacheKey {
-            repo: self.repo.clone(),
-            pr_number,
-        };
+        let cache_key = self.pr_cache_key(pr_number);
         let fetch_mode = if let Some(cached) = self.session_cache.get_pr_data(&cache_key) {
             let pr_updated_at = cached.pr_updated_at.clone();
             let diff_line_count = Self::calc_diff_line_count(&cached.files, 0);
@@ -3590,8 +6733,11 @@ impl App {
                 pr: cached.pr.clone(),
                 files: cached.files.clone(),
             };
+            self.files_truncated = cached.files_truncated;
             self.diff_line_count = diff_line_count;
             self.start_prefetch_all_files();
+            self.load_checks(false);
+            self.load_viewed_files();
             // キャッシュHit時はhandle_data_resultを経由しないため、ここでRally起動
             if self.start_ai_rally_on_load {
                 self.start_ai_rally_on_load = false;
@@ -3600,6 +6746,9 @@ impl App {
             crate::loader::FetchMode::CheckUpdate(pr_updated_at)
         } else {
             self.data_state = DataState::Loading;
+            self.files_truncated = false;
+            self.load_retry_at = None;
+            self.load_auto_retries = 0;
             crate::loader::FetchMode::Fresh
         };
 
@@ -3612,15 +6761,24 @@ impl App {
         self.retry_sender = Some(retry_tx);
 
         let repo = self.repo.clone();
+        let client = self.github_client();
 
         tokio::spawn(async move {
             // Initial fetch
-            crate::loader::fetch_pr_data(repo.clone(), pr_number, fetch_mode, tx.clone()).await;
+            crate::loader::fetch_pr_data(
+                client.clone(),
+                repo.clone(),
+                pr_number,
+                fetch_mode,
+                tx.clone(),
+            )
+            .await;
 
             // Retry loop
             while retry_rx.recv().await.is_some() {
                 let tx_retry = tx.clone();
                 crate::loader::fetch_pr_data(
+                    client.clone(),
                     repo.clone(),
                     pr_number,
                     crate::loader::FetchMode::Fresh,
@@ -3634,27 +6792,14 @@ impl App {
     /// FileListからPR一覧に戻る
     pub fn back_to_pr_list(&mut self) {
         if self.started_from_pr_list {
-            // PR固有の状態をリセット
+            // PR固有の状態と全ての in-flight レシーバーをクリア（late response による panic 防止）
+            self.reset_pr_state();
             self.pr_number = None;
             self.data_state = DataState::Loading;
-            self.review_comments = None;
-            self.discussion_comments = None;
-            self.diff_cache = None;
-            // 全ての in-flight レシーバーをクリア（late response による panic 防止）
             self.data_receiver = None;
             self.retry_sender = None;
-            self.comment_receiver = None;
-            self.diff_cache_receiver = None;
-            self.prefetch_receiver = None;
-            self.discussion_comment_receiver = None;
-            self.comment_submit_receiver = None;
-            self.comment_submitting = false;
-            self.comments_loading = false;
-            self.discussion_comments_loading = false;
-            self.highlighted_cache_store.clear();
-            self.selected_file = 0;
-            self.file_list_scroll_offset = 0;
             self.selected_line = 0;
+            self.visual_anchor = None;
             self.scroll_offset = 0;
 
             self.state = AppState::PullRequestList;
@@ -3675,7 +6820,9 @@ impl App {
             pr_list_scroll_offset: 0,
             pr_list_loading: false,
             pr_list_has_more: false,
-            pr_list_state_filter: PrStateFilter::default(),
+            pr_list_cursor: None,
+            pr_list_query: PrListQuery::default(),
+            pr_list_search_input: None,
             started_from_pr_list: false,
             pr_list_receiver: None,
             diff_view_return_state: AppState::FileList,
@@ -3688,6 +6835,7 @@ impl App {
             scroll_offset: 0,
             input_mode: None,
             input_text_area: TextArea::with_submit_key(config.keybindings.submit.clone()),
+            github: github::new_client(&config.github),
             config,
             should_quit: false,
             review_comments: None,
@@ -3731,6 +6879,61 @@ impl App {
             pending_since: None,
             symbol_popup: None,
             session_cache: SessionCache::new(),
+            pending_reviews: HashMap::new(),
+            selected_pending_comment: 0,
+            pending_comment_list_scroll_offset: 0,
+            visual_anchor: None,
+            files_truncated: false,
+            collapse_toggled_threads: HashSet::new(),
+            viewer_login: None,
+            viewer_receiver: None,
+            confirm_delete: None,
+            discussion_post_receiver: None,
+            reaction_popup: None,
+            checks: None,
+            checks_loading: false,
+            checks_error: None,
+            checks_receiver: None,
+            selected_check: 0,
+            check_detail: None,
+            check_annotations_receiver: None,
+            diff_annotations: vec![],
+            diff_annotations_receiver: None,
+            file_annotation_positions: vec![],
+            file_annotation_lines: HashMap::new(),
+            pr_commits: None,
+            pr_commits_loading: false,
+            pr_commits_receiver: None,
+            selected_commit: 0,
+            commit_range_anchor: None,
+            commit_scope: None,
+            commit_files_loading: false,
+            commit_files_receiver: None,
+            last_reviewed_shas: HashMap::new(),
+            last_reviewed_sha_receiver: None,
+            viewed_files: None,
+            viewed_files_receiver: None,
+            viewed_toggle_receiver: None,
+            pr_metadata: None,
+            pr_metadata_loading: false,
+            pr_metadata_receiver: None,
+            pr_metadata_updating: false,
+            pr_metadata_update_receiver: None,
+            metadata_picker: None,
+            metadata_picker_receiver: None,
+            merge_dialog: None,
+            confirm_merge: None,
+            merging: false,
+            merge_receiver: None,
+            merge_status_receiver: None,
+            load_retry_at: None,
+            load_auto_retries: 0,
+            suggestion_batch: Vec::new(),
+            confirm_suggestions: None,
+            applying_suggestions: false,
+            suggestion_apply_receiver: None,
+            thread_resolve_receiver: None,
+            pr_list_total: None,
         }
     }
 
@@ -3739,12 +6942,32 @@ impl App {
     pub fn set_submitting_for_test(&mut self, submitting: bool) {
         self.comment_submitting = submitting;
     }
+
+    /// Start an empty pending review for the current PR for testing.
+    #[cfg(test)]
+    pub fn start_pending_review_for_test(&mut self) {
+        if let Some(key) = self.current_pr_key() {
+            self.pending_reviews.insert(key, PendingReview::default());
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// `done` になるまで `poll` を繰り返す（バックグラウンドタスクの完了待ち）
+    async fn poll_until(app: &mut App, poll: impl Fn(&mut App), done: impl Fn(&App) -> bool) {
+        for _ in 0..100 {
+            poll(app);
+            if done(app) {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+        }
+        panic!("background task did not finish");
+    }
+
     #[test]
     fn test_find_diff_line_index_basic() {
         let patch = r#"@@ -1,3 +1,4 @@
@@ -3754,13 +6977,22 @@ mod tests {
 -removed line"#;
 
         // Line 1 (context) is at diff index 1
-        assert_eq!(App::find_diff_line_index(patch, 1), Some(1));
+        assert_eq!(
+            App::find_diff_line_index(patch, 1, DiffSide::Right),
+            Some(1)
+        );
         // Line 2 (added) is at diff index 2
-        assert_eq!(App::find_diff_line_index(patch, 2), Some(2));
+        assert_eq!(
+            App::find_diff_line_index(patch, 2, DiffSide::Right),
+            Some(2)
+        );
         // Line 3 (context) is at diff index 3
-        assert_eq!(App::find_diff_line_index(patch, 3), Some(3));
+        assert_eq!(
+            App::find_diff_line_index(patch, 3, DiffSide::Right),
+            Some(3)
+        );
         // Line 5 doesn't exist in new file
-        assert_eq!(App::find_diff_line_index(patch, 5), None);
+        assert_eq!(App::find_diff_line_index(patch, 5, DiffSide::Right), None);
     }
 
     #[test]
@@ -3773,11 +7005,42 @@ mod tests {
 +new line11"#;
 
         // First hunk: line 1 at index 1, line 2 at index 2
-        assert_eq!(App::find_diff_line_index(patch, 1), Some(1));
-        assert_eq!(App::find_diff_line_index(patch, 2), Some(2));
+        assert_eq!(
+            App::find_diff_line_index(patch, 1, DiffSide::Right),
+            Some(1)
+        );
+        assert_eq!(
+            App::find_diff_line_index(patch, 2, DiffSide::Right),
+            Some(2)
+        );
         // Second hunk: line 10 at index 4, line 11 at index 5
-        assert_eq!(App::find_diff_line_index(patch, 10), Some(4));
-        assert_eq!(App::find_diff_line_index(patch, 11), Some(5));
+        assert_eq!(
+            App::find_diff_line_index(patch, 10, DiffSide::Right),
+            Some(4)
+        );
+        assert_eq!(
+            App::find_diff_line_index(patch, 11, DiffSide::Right),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn test_find_diff_line_index_left_side() {
+        let patch = r#"@@ -5,3 +5,2 @@
+ context line
+-removed line
+ another context"#;
+
+        // Old line 5 (context) is at diff index 1
+        assert_eq!(App::find_diff_line_index(patch, 5, DiffSide::Left), Some(1));
+        // Old line 6 (removed) is at diff index 2
+        assert_eq!(App::find_diff_line_index(patch, 6, DiffSide::Left), Some(2));
+        // Old line 7 (context) is at diff index 3, which is new line 6
+        assert_eq!(App::find_diff_line_index(patch, 7, DiffSide::Left), Some(3));
+        assert_eq!(
+            App::find_diff_line_index(patch, 6, DiffSide::Right),
+            Some(3)
+        );
     }
 
     #[test]
@@ -3805,6 +7068,140 @@ mod tests {
         assert!(!app.has_comment_at_current_line());
     }
 
+    fn thread_comment(id: u64, in_reply_to_id: Option<u64>, resolved: bool) -> ReviewComment {
+        ReviewComment {
+            id,
+            path: "a.rs".to_string(),
+            line: Some(1),
+            start_line: None,
+            side: None,
+            original_line: None,
+            original_commit_id: None,
+            position: None,
+            diff_hunk: None,
+            body: String::new(),
+            user: crate::github::User {
+                login: "reviewer".to_string(),
+            },
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            in_reply_to_id,
+            thread: Some(crate::github::comment::ThreadState {
+                id: format!("T_{}", in_reply_to_id.unwrap_or(id)),
+                is_resolved: resolved,
+                is_outdated: false,
+            }),
+            reactions: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_resolved_threads_are_collapsed_by_default() {
+        let config = Config::default();
+        let (mut app, _) = App::new_loading("owner/repo", 1, config);
+        app.review_comments = Some(vec![
+            thread_comment(1, None, true),
+            thread_comment(2, Some(1), true),
+            thread_comment(3, Some(1), true),
+            thread_comment(4, None, false),
+            thread_comment(5, Some(4), false),
+        ]);
+
+        assert_eq!(app.visible_review_comment_indices(), vec![0, 3, 4]);
+        assert_eq!(app.thread_reply_counts().get(&1), Some(&2));
+
+        // 解決済みスレッドを展開
+        app.toggle_thread_collapsed(0);
+        assert_eq!(app.visible_review_comment_indices(), vec![0, 1, 2, 3, 4]);
+
+        // 未解決スレッドを返信選択中に折りたたむと先頭コメントに選択が移る
+        app.selected_comment = 4;
+        app.toggle_thread_collapsed(4);
+        assert_eq!(app.visible_review_comment_indices(), vec![0, 1, 2, 3]);
+        assert_eq!(app.selected_comment, 3);
+    }
+
+    #[tokio::test]
+    async fn test_comment_positions_follow_diff_hunk() {
+        let mut app = loaded_app_with_file();
+        // 行番号は古いが、hunk の内容から +new の行に付く
+        let mut shifted = thread_comment(1, None, false);
+        shifted.path = "src/main.rs".to_string();
+        shifted.line = Some(5);
+        shifted.diff_hunk = Some("@@ -1 +1 @@\n-old\n+new".to_string());
+        // outdated（line が消えた）コメントは diff に出さない
+        let mut outdated = thread_comment(2, None, false);
+        outdated.path = "src/main.rs".to_string();
+        outdated.line = None;
+        outdated.original_line = Some(1);
+        app.review_comments = Some(vec![shifted, outdated]);
+
+        app.update_file_comment_positions();
+        let positions: Vec<(usize, usize)> = app
+            .file_comment_positions
+            .iter()
+            .map(|pos| (pos.diff_line_index, pos.comment_index))
+            .collect();
+        assert_eq!(positions, vec![(2, 0)]);
+        assert!(app.review_comments.as_ref().unwrap()[1].is_outdated());
+    }
+
+    #[tokio::test]
+    async fn test_resolving_thread_does_not_touch_comment_submission() {
+        use crate::github::mock::MockGitHub;
+
+        let mut app = loaded_app_with_file();
+        app.review_comments = Some(vec![thread_comment(1, None, false)]);
+        app.set_github_client(Arc::new(
+            MockGitHub::new().with_graphql_response(serde_json::json!({})),
+        ));
+        // 返信の送信中でも解決できる
+        let (_submit_tx, submit_rx) = mpsc::channel(1);
+        app.comment_submit_receiver = Some((1, submit_rx));
+        app.comment_submitting = true;
+
+        app.toggle_thread_resolved(0);
+        assert!(app.thread_resolve_receiver.is_some());
+        poll_until(&mut app, App::poll_thread_resolve, |app| {
+            app.thread_resolve_receiver.is_none()
+        })
+        .await;
+        assert_eq!(app.submission_result, Some((true, "Resolved".to_string())));
+        assert!(app.comment_submitting);
+        assert!(app.comment_submit_receiver.is_some());
+    }
+
+    #[test]
+    fn test_edit_and_delete_only_own_comments() {
+        let config = Config::default();
+        let (mut app, _) = App::new_loading("owner/repo", 1, config);
+        app.state = AppState::CommentList;
+        app.comment_tab = CommentTab::Review;
+        app.review_comments = Some(vec![thread_comment(1, None, false)]);
+
+        // 他人のコメントは編集できない
+        app.viewer_login = Some("me".to_string());
+        app.enter_comment_edit_input();
+        assert!(app.input_mode.is_none());
+        assert_eq!(app.submission_result.as_ref().map(|r| r.0), Some(false));
+
+        app.viewer_login = Some("reviewer".to_string());
+        app.review_comments.as_mut().unwrap()[0].body = "typo".to_string();
+        app.enter_comment_edit_input();
+        assert!(matches!(
+            app.input_mode,
+            Some(InputMode::EditComment {
+                target: PostedComment::Review(1),
+                ..
+            })
+        ));
+        assert_eq!(app.input_text_area.content(), "typo");
+        assert_eq!(app.state, AppState::TextInput);
+
+        app.state = AppState::CommentList;
+        app.request_comment_delete();
+        assert_eq!(app.confirm_delete, Some(PostedComment::Review(1)));
+    }
+
     #[test]
     fn test_get_comment_indices_at_current_line() {
         let config = Config::default();
@@ -4026,6 +7423,176 @@ mod tests {
         assert_eq!(app.state, AppState::PullRequestList);
     }
 
+    #[tokio::test]
+    async fn test_reaction_popup_targets_pr_or_selected_comment() {
+        use crate::github::mock::MockGitHub;
+
+        let config = Config::default();
+        let (mut app, _) = App::new_loading("owner/repo", 1, config);
+        let mock = Arc::new(MockGitHub::new().with_response(
+            "repos/owner/repo/issues/1/reactions",
+            serde_json::Value::Null,
+        ));
+        app.set_github_client(mock.clone());
+
+        app.state = AppState::FileList;
+        app.open_reaction_popup();
+        assert_eq!(
+            app.reaction_popup.as_ref().map(|p| p.target),
+            Some(ReactionTarget::PullRequest(1))
+        );
+
+        // 数字キーで直接選択して送信
+        app.handle_reaction_popup_input(KeyEvent::new(KeyCode::Char('5'), KeyModifiers::NONE));
+        assert!(app.reaction_popup.is_none());
+        poll_until(&mut app, |_| {}, |_| !mock.calls().is_empty()).await;
+        let calls = mock.calls();
+        assert_eq!(calls[0].1, "repos/owner/repo/issues/1/reactions");
+        assert_eq!(calls[0].2.as_ref().unwrap()["content"], "confused");
+
+        app.state = AppState::CommentList;
+        app.comment_tab = CommentTab::Review;
+        app.review_comments = Some(vec![thread_comment(7, None, false)]);
+        app.open_reaction_popup();
+        assert_eq!(
+            app.reaction_popup.as_ref().map(|p| p.target),
+            Some(ReactionTarget::ReviewComment(7))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_discussion_comment_is_inserted_optimistically() {
+        use crate::github::mock::MockGitHub;
+
+        let config = Config::default();
+        let (mut app, _) = App::new_loading("owner/repo", 1, config);
+        app.discussion_comments = Some(vec![]);
+        app.set_github_client(Arc::new(MockGitHub::new().with_response(
+            "repos/owner/repo/issues/1/comments",
+            serde_json::json!({
+                "id": 42, "body": "LGTM", "user": { "login": "me" }, "created_at": "t"
+            }),
+        )));
+
+        app.submit_discussion_comment("LGTM".to_string());
+        // 投稿完了前から一覧に表示される
+        let comments = app.discussion_comments.as_ref().unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].id, OPTIMISTIC_COMMENT_ID);
+
+        poll_until(&mut app, App::poll_discussion_post_updates, |app| {
+            app.discussion_post_receiver.is_none()
+        })
+        .await;
+        assert_eq!(app.discussion_comments.as_ref().unwrap()[0].id, 42);
+        assert!(!app.is_submitting_comment());
+
+        // 失敗時は仮のコメントを取り除く
+        app.set_github_client(Arc::new(MockGitHub::new()));
+        app.submit_discussion_comment("again".to_string());
+        assert_eq!(app.discussion_comments.as_ref().unwrap().len(), 2);
+        poll_until(&mut app, App::poll_discussion_post_updates, |app| {
+            app.discussion_post_receiver.is_none()
+        })
+        .await;
+        assert_eq!(app.discussion_comments.as_ref().unwrap().len(), 1);
+        assert_eq!(app.submission_result.as_ref().map(|r| r.0), Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_checks_load_once_per_head_and_fetch_annotations() {
+        use crate::github::mock::MockGitHub;
+
+        let mut app = loaded_app_with_file();
+        let mock = Arc::new(
+            MockGitHub::new()
+                .with_response(
+                    "repos/owner/repo/commits/abc123/check-runs?per_page=100&page=1",
+                    serde_json::json!({
+                        "total_count": 1,
+                        "check_runs": [{
+                            "id": 9, "name": "test", "status": "completed",
+                            "conclusion": "failure", "started_at": null, "completed_at": null,
+                            "html_url": null, "output": { "annotations_count": 1 }
+                        }]
+                    }),
+                )
+                .with_response(
+                    "repos/owner/repo/commits/abc123/status",
+                    serde_json::json!({ "state": "pending", "statuses": [] }),
+                )
+                .with_response(
+                    "repos/owner/repo/check-runs/9/annotations?per_page=100&page=1",
+                    serde_json::json!([{
+                        "path": "src/main.rs", "start_line": 1, "end_line": 1,
+                        "annotation_level": "failure", "title": null, "message": "boom"
+                    }]),
+                ),
+        );
+        app.set_github_client(mock.clone());
+
+        app.open_checks();
+        assert_eq!(app.state, AppState::Checks);
+        assert!(app.checks_loading);
+        poll_until(&mut app, App::poll_checks_updates, |app| {
+            app.checks.is_some()
+        })
+        .await;
+        let checks = app.checks.as_ref().unwrap();
+        assert_eq!(
+            checks.overall(),
+            Some(crate::github::checks::CheckState::Failure)
+        );
+        assert!(!app.checks_loading);
+
+        // 同じ head なら再取得しない
+        let calls = mock.calls().len();
+        app.load_checks(false);
+        assert!(!app.checks_loading);
+        assert_eq!(mock.calls().len(), calls);
+
+        app.open_check_detail();
+        assert!(app.check_detail.as_ref().unwrap().annotations.is_none());
+        poll_until(&mut app, App::poll_check_annotations, |app| {
+            app.check_annotations_receiver.is_none()
+        })
+        .await;
+        let annotations = app.check_detail.as_ref().unwrap().annotations.clone();
+        assert_eq!(annotations.unwrap().unwrap()[0].message, "boom");
+    }
+
+    #[tokio::test]
+    async fn test_check_annotations_from_previous_pr_are_discarded() {
+        let mut app = loaded_app_with_file();
+        let (tx, rx) = mpsc::channel(1);
+        app.check_annotations_receiver = Some((1, rx));
+
+        // PR #1 のアノテーション取得中に PR #2 へ切り替え、同じ位置のチェックを開いた
+        app.pr_number = Some(2);
+        app.check_detail = Some(CheckDetailState {
+            index: 0,
+            annotations: Some(Ok(vec![])),
+            scroll: 0,
+        });
+        let stale: CheckAnnotation = serde_json::from_value(serde_json::json!({
+            "path": "src/main.rs", "start_line": 1, "end_line": 1,
+            "annotation_level": "failure", "title": null, "message": "from PR 1"
+        }))
+        .unwrap();
+        tx.send((0, Ok(vec![stale]))).await.unwrap();
+        app.poll_check_annotations();
+
+        assert!(app.check_annotations_receiver.is_none());
+        let annotations = app.check_detail.as_ref().unwrap().annotations.clone();
+        assert!(annotations.unwrap().unwrap().is_empty());
+
+        // PR の切り替えで取得中の受信も破棄される
+        let (_tx, rx) = mpsc::channel(1);
+        app.check_annotations_receiver = Some((2, rx));
+        app.reset_pr_state();
+        assert!(app.check_annotations_receiver.is_none());
+    }
+
     #[tokio::test]
     async fn test_poll_data_updates_discards_stale_pr_data() {
         let config = Config::default();
@@ -4054,10 +7621,15 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            node_id: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            protection: None,
         };
         tx.send(DataLoadResult::Success {
             pr: Box::new(pr),
             files: vec![],
+            truncated: false,
         })
         .await
         .unwrap();
@@ -4071,12 +7643,57 @@ mod tests {
         assert!(matches!(app.data_state, DataState::Loading));
         // But session cache should have the data under PR #1 key
         let cache_key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
         assert!(app.session_cache.get_pr_data(&cache_key).is_some());
     }
 
+    #[tokio::test]
+    async fn test_load_error_schedules_retry_by_error_kind() {
+        let (mut app, _tx) = App::new_loading("owner/repo", 1, Config::default());
+        let (retry_tx, mut retry_rx) = mpsc::channel(1);
+        app.set_retry_sender(retry_tx);
+        let network_error = || {
+            DataLoadResult::Error(LoadError::from(&anyhow::Error::from(
+                crate::github::GitHubError::Network("connection reset".to_string()),
+            )))
+        };
+
+        // ネットワークエラーは自動再試行を予約し、期限が来たら再読み込みする
+        app.handle_data_result(1, network_error());
+        assert!(matches!(app.data_state, DataState::Error(_)));
+        assert!(app.load_retry_in().is_some());
+        app.load_retry_at = Some(Instant::now());
+        app.poll_load_retry();
+        assert!(matches!(app.data_state, DataState::Loading));
+        assert!(retry_rx.try_recv().is_ok());
+        assert!(app.load_retry_in().is_none());
+
+        // 自動再試行は回数に上限がある
+        for _ in 1..MAX_LOAD_AUTO_RETRIES {
+            app.handle_data_result(1, network_error());
+            app.retry_load();
+        }
+        app.handle_data_result(1, network_error());
+        assert!(app.load_retry_in().is_none());
+
+        // 404 は再試行しても変わらないので予約しない
+        app.data_state = DataState::Loading;
+        app.load_auto_retries = 0;
+        let not_found = anyhow::Error::from(crate::github::GitHubError::NotFound(
+            "GitHub API request failed (404 Not Found): Not Found".to_string(),
+        ))
+        .context("Failed to fetch PR");
+        app.handle_data_result(1, DataLoadResult::Error(LoadError::from(&not_found)));
+        let DataState::Error(ref error) = app.data_state else {
+            panic!("expected error state");
+        };
+        assert_eq!(error.retry_policy(), RetryPolicy::Never);
+        assert!(app.load_retry_in().is_none());
+    }
+
     #[tokio::test]
     async fn test_poll_comment_updates_discards_stale_pr_comments() {
         let config = Config::default();
@@ -4103,6 +7720,7 @@ mod tests {
         // Session cache should NOT have comments for PR #1 since pr_data was never stored
         // (comments are only cached for keys that have an existing pr_data entry)
         let cache_key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -4110,11 +7728,162 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_handle_data_result_clamps_selected_file_when_files_shrink() {
+    async fn test_handle_data_result_clamps_selected_file_when_files_shrink() {
+        let config = Config::default();
+        let (mut app, _tx) = App::new_loading("owner/repo", 1, config);
+
+        // Simulate initial state with 5 files, selected_file pointing to file index 4
+        let make_file = |name: &str| ChangedFile {
+            filename: name.to_string(),
+            status: "modified".to_string(),
+            additions: 1,
+            deletions: 1,
+            patch: Some("@@ -1,1 +1,1 @@\n-old\n+new".to_string()),
+        };
+
+        let initial_files: Vec<ChangedFile> = (0..5)
+            .map(|i| make_file(&format!("file_{}.rs", i)))
+            .collect();
+
+        let pr = Box::new(PullRequest {
+            number: 1,
+            title: "Test PR".to_string(),
+            body: None,
+            state: "open".to_string(),
+            head: crate::github::Branch {
+                ref_name: "feature".to_string(),
+                sha: "abc123".to_string(),
+            },
+            base: crate::github::Branch {
+                ref_name: "main".to_string(),
+                sha: "def456".to_string(),
+            },
+            user: crate::github::User {
+                login: "user".to_string(),
+            },
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            node_id: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            protection: None,
+        });
+
+        // Set initial loaded state with 5 files
+        app.data_state = DataState::Loaded {
+            pr: pr.clone(),
+            files: initial_files,
+        };
+        app.selected_file = 4; // Last file selected
+
+        // Now simulate refresh with only 2 files (file count shrank)
+        let fewer_files: Vec<ChangedFile> = (0..2)
+            .map(|i| make_file(&format!("file_{}.rs", i)))
+            .collect();
+
+        app.handle_data_result(
+            1,
+            DataLoadResult::Success {
+                pr,
+                files: fewer_files,
+                truncated: false,
+            },
+        );
+
+        // selected_file should be clamped to 1 (last valid index for 2 files)
+        assert_eq!(app.selected_file, 1);
+        // Should be able to access the file without panic
+        assert!(app.files().get(app.selected_file).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_handle_data_result_resyncs_diff_state_when_selected_file_changes() {
+        let config = Config::default();
+        let (mut app, _tx) = App::new_loading("owner/repo", 1, config);
+
+        let make_file = |name: &str| ChangedFile {
+            filename: name.to_string(),
+            status: "modified".to_string(),
+            additions: 1,
+            deletions: 1,
+            patch: Some("@@ -1,1 +1,1 @@\n-old\n+new".to_string()),
+        };
+
+        let initial_files: Vec<ChangedFile> = (0..5)
+            .map(|i| make_file(&format!("file_{}.rs", i)))
+            .collect();
+
+        let pr = Box::new(PullRequest {
+            number: 1,
+            title: "Test PR".to_string(),
+            body: None,
+            state: "open".to_string(),
+            head: crate::github::Branch {
+                ref_name: "feature".to_string(),
+                sha: "abc123".to_string(),
+            },
+            base: crate::github::Branch {
+                ref_name: "main".to_string(),
+                sha: "def456".to_string(),
+            },
+            user: crate::github::User {
+                login: "user".to_string(),
+            },
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            node_id: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            protection: None,
+        });
+
+        // Set initial loaded state with 5 files
+        app.data_state = DataState::Loaded {
+            pr: pr.clone(),
+            files: initial_files,
+        };
+        app.selected_file = 4;
+        app.selected_line = 10;
+        app.scroll_offset = 5;
+
+        // Set a stale diff_cache pointing to old file index 4
+        app.diff_cache = Some(DiffCache {
+            file_index: 4,
+            patch_hash: 0,
+            lines: vec![],
+            interner: Rodeo::default(),
+            highlighted: false,
+        });
+
+        // Refresh with only 2 files (selected_file will be clamped from 4 to 1)
+        let fewer_files: Vec<ChangedFile> = (0..2)
+            .map(|i| make_file(&format!("file_{}.rs", i)))
+            .collect();
+
+        app.handle_data_result(
+            1,
+            DataLoadResult::Success {
+                pr,
+                files: fewer_files,
+                truncated: false,
+            },
+        );
+
+        // selected_file clamped
+        assert_eq!(app.selected_file, 1);
+        // diff_cache must be invalidated (was pointing to old file index 4)
+        assert!(
+            app.diff_cache.is_none(),
+            "diff_cache should be None after selected_file changes"
+        );
+        // selected_line and scroll_offset must be reset
+        assert_eq!(app.selected_line, 0, "selected_line should be reset to 0");
+        assert_eq!(app.scroll_offset, 0, "scroll_offset should be reset to 0");
+    }
+
+    #[tokio::test]
+    async fn test_handle_data_result_resyncs_comment_positions_when_selected_file_changes() {
         let config = Config::default();
         let (mut app, _tx) = App::new_loading("owner/repo", 1, config);
 
-        // Simulate initial state with 5 files, selected_file pointing to file index 4
         let make_file = |name: &str| ChangedFile {
             filename: name.to_string(),
             status: "modified".to_string(),
@@ -4144,16 +7913,50 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            node_id: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            protection: None,
         });
 
-        // Set initial loaded state with 5 files
+        // Set initial loaded state with 5 files, selected_file = 4
         app.data_state = DataState::Loaded {
             pr: pr.clone(),
             files: initial_files,
         };
-        app.selected_file = 4; // Last file selected
+        app.selected_file = 4;
 
-        // Now simulate refresh with only 2 files (file count shrank)
+        // Set up review comments for file_4.rs (the old selected file)
+        app.review_comments = Some(vec![ReviewComment {
+            id: 1,
+            path: "file_4.rs".to_string(),
+            line: Some(1),
+            start_line: None,
+            side: None,
+            original_line: None,
+            original_commit_id: None,
+            position: None,
+            diff_hunk: None,
+            body: "comment on old file".to_string(),
+            user: crate::github::User {
+                login: "reviewer".to_string(),
+            },
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            in_reply_to_id: None,
+            thread: None,
+            reactions: Default::default(),
+        }]);
+
+        // Pre-populate stale comment positions for the old file
+        app.file_comment_positions = vec![CommentPosition {
+            diff_line_index: 2,
+            comment_index: 0,
+        }];
+        app.file_comment_lines.insert(2);
+        app.comment_panel_open = true;
+        app.comment_panel_scroll = 5;
+
+        // Refresh with only 2 files (selected_file will be clamped from 4 to 1)
         let fewer_files: Vec<ChangedFile> = (0..2)
             .map(|i| make_file(&format!("file_{}.rs", i)))
             .collect();
@@ -4163,17 +7966,36 @@ mod tests {
             DataLoadResult::Success {
                 pr,
                 files: fewer_files,
+                truncated: false,
             },
         );
 
-        // selected_file should be clamped to 1 (last valid index for 2 files)
+        // selected_file clamped to 1
         assert_eq!(app.selected_file, 1);
-        // Should be able to access the file without panic
-        assert!(app.files().get(app.selected_file).is_some());
+
+        // file_comment_positions should be recalculated for file_1.rs (no matching comments)
+        assert!(
+            app.file_comment_positions.is_empty(),
+            "file_comment_positions should be recalculated for new file (no comments for file_1.rs)"
+        );
+        assert!(
+            app.file_comment_lines.is_empty(),
+            "file_comment_lines should be recalculated for new file"
+        );
+
+        // comment_panel should be closed
+        assert!(
+            !app.comment_panel_open,
+            "comment_panel_open should be reset when selected_file changes"
+        );
+        assert_eq!(
+            app.comment_panel_scroll, 0,
+            "comment_panel_scroll should be reset when selected_file changes"
+        );
     }
 
     #[tokio::test]
-    async fn test_handle_data_result_resyncs_diff_state_when_selected_file_changes() {
+    async fn test_handle_data_result_preserves_diff_state_when_selected_file_unchanged() {
         let config = Config::default();
         let (mut app, _tx) = App::new_loading("owner/repo", 1, config);
 
@@ -4206,228 +8028,1113 @@ mod tests {
                 login: "user".to_string(),
             },
             updated_at: "2024-01-01T00:00:00Z".to_string(),
+            node_id: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            protection: None,
         });
 
-        // Set initial loaded state with 5 files
+        // Set initial loaded state
         app.data_state = DataState::Loaded {
             pr: pr.clone(),
             files: initial_files,
         };
-        app.selected_file = 4;
+        app.selected_file = 1;
         app.selected_line = 10;
         app.scroll_offset = 5;
 
-        // Set a stale diff_cache pointing to old file index 4
+        // Set diff_cache for file index 1
         app.diff_cache = Some(DiffCache {
-            file_index: 4,
+            file_index: 1,
             patch_hash: 0,
             lines: vec![],
             interner: Rodeo::default(),
             highlighted: false,
         });
 
-        // Refresh with only 2 files (selected_file will be clamped from 4 to 1)
-        let fewer_files: Vec<ChangedFile> = (0..2)
-            .map(|i| make_file(&format!("file_{}.rs", i)))
-            .collect();
+        // Refresh with same or more files (selected_file stays at 1)
+        let same_files: Vec<ChangedFile> = (0..5)
+            .map(|i| make_file(&format!("file_{}.rs", i)))
+            .collect();
+
+        app.handle_data_result(
+            1,
+            DataLoadResult::Success {
+                pr,
+                files: same_files,
+                truncated: false,
+            },
+        );
+
+        // selected_file unchanged
+        assert_eq!(app.selected_file, 1);
+        // diff_cache should NOT be invalidated (selected_file didn't change)
+        assert!(
+            app.diff_cache.is_some(),
+            "diff_cache should be preserved when selected_file is unchanged"
+        );
+        // selected_line and scroll_offset should be preserved
+        assert_eq!(app.selected_line, 10);
+        assert_eq!(app.scroll_offset, 5);
+    }
+
+    /// PR と1ファイルを読み込み済みの App を作成
+    fn loaded_app_with_file() -> App {
+        let (mut app, _tx) = App::new_loading("owner/repo", 1, Config::default());
+        let pr = Box::new(PullRequest {
+            number: 1,
+            title: "Test PR".to_string(),
+            body: None,
+            state: "open".to_string(),
+            head: crate::github::Branch {
+                ref_name: "feature".to_string(),
+                sha: "abc123".to_string(),
+            },
+            base: crate::github::Branch {
+                ref_name: "main".to_string(),
+                sha: "def456".to_string(),
+            },
+            user: crate::github::User {
+                login: "user".to_string(),
+            },
+            updated_at: "2024-01-01T00:00:00Z".to_string(),
+            node_id: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            protection: None,
+        });
+        app.data_state = DataState::Loaded {
+            pr,
+            files: vec![ChangedFile {
+                filename: "src/main.rs".to_string(),
+                status: "modified".to_string(),
+                additions: 1,
+                deletions: 1,
+                patch: Some("@@ -1,1 +1,1 @@\n-old\n+new".to_string()),
+            }],
+        };
+        app
+    }
+
+    #[tokio::test]
+    async fn test_commit_range_comments_must_be_in_pr_diff() {
+        let mut app = loaded_app_with_file();
+        let cache_key = app.pr_cache_key(1);
+        let (pr, files) = match &app.data_state {
+            DataState::Loaded { pr, files } => (pr.clone(), files.clone()),
+            _ => unreachable!(),
+        };
+        app.session_cache.put_pr_data(
+            cache_key,
+            crate::cache::PrData {
+                pr,
+                files,
+                pr_updated_at: String::new(),
+                files_truncated: false,
+            },
+        );
+        // head で終わるコミット範囲の diff
+        app.commit_scope = Some(CommitScope {
+            base_sha: "c0ffee".to_string(),
+            head_sha: "abc123".to_string(),
+            label: "c0ffee".to_string(),
+            since_review: false,
+        });
+        app.replace_files(vec![ChangedFile {
+            filename: "src/main.rs".to_string(),
+            status: "modified".to_string(),
+            additions: 1,
+            deletions: 1,
+            patch: Some("@@ -1,2 +1,2 @@\n new\n-tmp\n+tmp2".to_string()),
+        }]);
+
+        // 範囲の base 側（LEFT）の行
+        app.selected_line = 2;
+        app.enter_comment_input();
+        assert!(app.input_mode.is_none());
+        assert_eq!(
+            app.submission_result,
+            Some((
+                false,
+                "Removed lines can only be commented on in the full PR diff".to_string()
+            ))
+        );
+
+        // PR 全体の diff にない行
+        app.selected_line = 3;
+        app.enter_comment_input();
+        assert!(app.input_mode.is_none());
+        assert_eq!(
+            app.submission_result,
+            Some((false, "This line is not part of the PR diff".to_string()))
+        );
+
+        // PR の diff にもある行にはコメントできる
+        app.selected_line = 1;
+        app.enter_comment_input();
+        assert!(matches!(app.input_mode, Some(InputMode::Comment(_))));
+    }
+
+    #[tokio::test]
+    async fn test_toggle_pending_review_start_and_discard() {
+        let mut app = loaded_app_with_file();
+        assert!(app.pending_review().is_none());
+
+        app.toggle_pending_review();
+        assert!(app.pending_review().is_some());
+
+        app.toggle_pending_review();
+        assert!(app.pending_review().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_toggle_pending_review_keeps_non_empty_review() {
+        let mut app = loaded_app_with_file();
+        app.toggle_pending_review();
+        assert!(app.queue_pending_comment("src/main.rs", CommentRange::single(1), "nit"));
+
+        app.toggle_pending_review();
+
+        assert_eq!(app.pending_review().unwrap().comments.len(), 1);
+        assert!(matches!(app.submission_result, Some((false, _))));
+    }
+
+    #[tokio::test]
+    async fn test_submit_comment_queues_into_pending_review() {
+        let mut app = loaded_app_with_file();
+        app.toggle_pending_review();
+
+        app.submit_comment(
+            LineInputContext {
+                file_index: 0,
+                range: CommentRange::single(1),
+            },
+            "looks odd".to_string(),
+        );
+
+        // 即時投稿されない
+        assert!(!app.is_submitting_comment());
+        assert!(app.comment_submit_receiver.is_none());
+        let drafts = &app.pending_review().unwrap().comments;
+        assert_eq!(
+            drafts,
+            &vec![DraftComment {
+                path: "src/main.rs".to_string(),
+                range: CommentRange::single(1),
+                body: "looks odd".to_string(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_submit_comment_without_pending_review_does_not_queue() {
+        let mut app = loaded_app_with_file();
+        assert!(!app.queue_pending_comment("src/main.rs", CommentRange::single(1), "nit"));
+        assert!(app.pending_review().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_edit_and_delete_draft_comments() {
+        let mut app = loaded_app_with_file();
+        app.toggle_pending_review();
+        app.queue_pending_comment("src/main.rs", CommentRange::single(1), "first");
+        app.queue_pending_comment("src/main.rs", CommentRange::single(2), "second");
+
+        app.update_draft_comment(1, "edited".to_string());
+        assert_eq!(app.pending_review().unwrap().comments[1].body, "edited");
+
+        app.selected_pending_comment = 1;
+        app.delete_selected_draft_comment();
+        let drafts = &app.pending_review().unwrap().comments;
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].body, "first");
+        assert_eq!(app.selected_pending_comment, 0);
+    }
+
+    /// 複数行のパッチを持つ App（visual selection のテスト用）
+    fn loaded_app_with_multiline_patch() -> App {
+        let mut app = loaded_app_with_file();
+        if let DataState::Loaded { files, .. } = &mut app.data_state {
+            files[0].patch = Some(
+                "@@ -1,3 +1,4 @@\n fn main() {\n-    old();\n+    new();\n+    more();\n }"
+                    .to_string(),
+            );
+        }
+        app
+    }
+
+    #[tokio::test]
+    async fn test_annotations_map_to_diff_lines_and_navigate() {
+        let annotation = |path: &str, line: u32, level: &str| CheckAnnotation {
+            path: path.to_string(),
+            start_line: line,
+            end_line: line,
+            annotation_level: level.to_string(),
+            title: None,
+            message: "msg".to_string(),
+            check_name: "clippy".to_string(),
+        };
+        let mut app = loaded_app_with_multiline_patch();
+        app.diff_annotations = vec![
+            annotation("src/main.rs", 3, "warning"),
+            annotation("src/main.rs", 4, "notice"),
+            annotation("src/main.rs", 3, "failure"),
+            annotation("other.rs", 1, "failure"),
+            // diff に含まれない行は表示しない
+            annotation("src/main.rs", 99, "failure"),
+        ];
+        app.update_file_annotation_positions();
+
+        assert_eq!(app.file_annotation_positions.len(), 3);
+        assert_eq!(
+            app.file_annotation_lines.get(&4),
+            Some(&AnnotationLevel::Failure)
+        );
+        assert_eq!(
+            app.file_annotation_lines.get(&5),
+            Some(&AnnotationLevel::Notice)
+        );
+
+        app.jump_to_next_annotation();
+        assert_eq!(app.selected_line, 4);
+        assert_eq!(app.annotations_at_current_line().len(), 2);
+        app.jump_to_next_annotation();
+        assert_eq!(app.selected_line, 5);
+        app.jump_to_next_annotation();
+        assert_eq!(app.selected_line, 5);
+        app.jump_to_prev_annotation();
+        assert_eq!(app.selected_line, 4);
+    }
+
+    #[tokio::test]
+    async fn test_commit_range_loads_compare_files_and_hides_comments() {
+        use crate::github::mock::MockGitHub;
+
+        let commit = |sha: &str, parent: &str| {
+            serde_json::json!({
+                "sha": sha,
+                "commit": { "message": format!("{}\n\nbody", sha), "author": null },
+                "author": { "login": "dev" },
+                "parents": [{ "sha": parent }]
+            })
+        };
+        let file = |name: &str| {
+            serde_json::json!({
+                "filename": name, "status": "modified", "additions": 1, "deletions": 0,
+                "patch": "@@ -1,1 +1,2 @@\n a\n+b"
+            })
+        };
+        let mut app = loaded_app_with_file();
+        let mock = Arc::new(
+            MockGitHub::new()
+                .with_response(
+                    "repos/owner/repo/pulls/1/commits?per_page=100&page=1",
+                    serde_json::json!([
+                        commit("c1", "p0"),
+                        commit("c2", "c1"),
+                        commit("abc123", "c2")
+                    ]),
+                )
+                .with_response(
+                    "repos/owner/repo/compare/p0...c2",
+                    serde_json::json!({ "files": [file("a.rs"), file("b.rs")] }),
+                )
+                .with_response(
+                    "repos/owner/repo/commits/abc123?per_page=100&page=1",
+                    serde_json::json!({ "sha": "abc123", "files": [file("c.rs")] }),
+                ),
+        );
+        app.set_github_client(mock.clone());
+
+        app.previous_state = AppState::FileList;
+        app.open_commit_list();
+        assert_eq!(app.state, AppState::CommitList);
+        poll_until(&mut app, App::poll_pr_commits, |app| {
+            app.pr_commits.is_some()
+        })
+        .await;
+        assert_eq!(app.pr_commits.as_ref().unwrap()[0].summary(), "c1");
+
+        // 1..2 行目（c1, c2）を範囲選択
+        app.selected_commit = 1;
+        app.commit_range_anchor = Some(1);
+        app.selected_commit = 2;
+        app.apply_commit_selection();
+        poll_until(&mut app, App::poll_commit_files, |app| {
+            !app.commit_files_loading
+        })
+        .await;
+
+        assert_eq!(app.state, AppState::FileList);
+        assert_eq!(app.files().len(), 2);
+        assert!(!app.files_truncated);
+        assert_eq!(app.commit_scope.as_ref().unwrap().label, "c1..c2");
+        // head で終わらない範囲にはコメントを付けられない
+        assert!(!app.diff_matches_head());
+        app.enter_comment_input();
+        assert!(app.input_mode.is_none());
+        assert_eq!(app.submission_result.as_ref().map(|r| r.0), Some(false));
+
+        // head コミット単体なら PR head と一致する
+        app.selected_commit = 3;
+        app.apply_commit_selection();
+        poll_until(&mut app, App::poll_commit_files, |app| {
+            !app.commit_files_loading
+        })
+        .await;
+        assert_eq!(app.files()[0].filename, "c.rs");
+        assert!(app.diff_matches_head());
+    }
+
+    #[tokio::test]
+    async fn test_commit_range_at_compare_limit_is_marked_truncated() {
+        use crate::github::mock::MockGitHub;
+
+        let files: Vec<_> = (0..MAX_COMPARE_FILES)
+            .map(|i| {
+                serde_json::json!({
+                    "filename": format!("f{}.rs", i), "status": "added",
+                    "additions": 1, "deletions": 0
+                })
+            })
+            .collect();
+        let mut app = loaded_app_with_file();
+        app.set_github_client(Arc::new(MockGitHub::new().with_response(
+            "repos/owner/repo/compare/old999...abc123",
+            serde_json::json!({ "files": files }),
+        )));
+
+        app.load_commit_scope(CommitScope {
+            base_sha: "old999".to_string(),
+            head_sha: "abc123".to_string(),
+            label: "old999..abc123".to_string(),
+            since_review: true,
+        });
+        poll_until(&mut app, App::poll_commit_files, |app| {
+            !app.commit_files_loading
+        })
+        .await;
+        assert_eq!(app.files().len(), MAX_COMPARE_FILES);
+        assert!(app.files_truncated);
+    }
+
+    #[tokio::test]
+    async fn test_since_review_toggles_compare_diff_from_reviewed_head() {
+        use crate::github::mock::MockGitHub;
+
+        let mut app = loaded_app_with_file();
+        let key = app.current_pr_key().unwrap();
+        app.session_cache.put_pr_data(
+            key.clone(),
+            PrData {
+                pr: Box::new(app.pr().unwrap().clone()),
+                files: app.files().to_vec(),
+                pr_updated_at: String::new(),
+                files_truncated: false,
+            },
+        );
+        app.set_github_client(Arc::new(
+            MockGitHub::new()
+                .with_response("user", serde_json::json!({ "login": "me" }))
+                .with_response(
+                    "repos/owner/repo/pulls/1/reviews?per_page=100&page=1",
+                    serde_json::json!([{
+                        "id": 1, "body": "", "state": "COMMENTED", "user": { "login": "me" },
+                        "submitted_at": null, "commit_id": "old999"
+                    }]),
+                )
+                .with_response(
+                    "repos/owner/repo/compare/old999...abc123",
+                    serde_json::json!({ "files": [{
+                        "filename": "fixed.rs", "status": "modified",
+                        "additions": 1, "deletions": 0, "patch": "@@ -1 +1,2 @@\n a\n+b"
+                    }] }),
+                ),
+        ));
+
+        app.toggle_since_review();
+        poll_until(
+            &mut app,
+            |app| {
+                app.poll_last_reviewed_sha();
+                app.poll_commit_files();
+            },
+            |app| app.last_reviewed_sha_receiver.is_none() && !app.commit_files_loading,
+        )
+        .await;
+        assert_eq!(app.files()[0].filename, "fixed.rs");
+        assert!(app.commit_scope.as_ref().unwrap().since_review);
+        // head までの diff なのでコメントはそのまま表示できる
+        assert!(app.diff_matches_head());
+        assert_eq!(
+            app.last_reviewed_shas.get(&key).map(String::as_str),
+            Some("old999")
+        );
+
+        // もう一度押すと PR 全体の diff に戻る
+        app.toggle_since_review();
+        assert!(app.commit_scope.is_none());
+        assert_eq!(app.files()[0].filename, "src/main.rs");
+
+        // このセッションでレビューを送信した後は、その時点の head が基準になる
+        app.record_reviewed_head();
+        app.toggle_since_review();
+        assert!(app.commit_scope.is_none());
+        assert_eq!(app.submission_result.as_ref().map(|r| r.0), Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_toggle_viewed_is_optimistic_and_reverts_on_failure() {
+        use crate::github::mock::MockGitHub;
+
+        let mut app = loaded_app_with_file();
+        if let DataState::Loaded { files, .. } = &mut app.data_state {
+            for name in ["b.rs", "c.rs"] {
+                let mut file = files[0].clone();
+                file.filename = name.to_string();
+                files.push(file);
+            }
+        }
+        app.viewed_files = Some(ViewedFiles {
+            pull_request_id: "PR_1".to_string(),
+            paths: ["b.rs".to_string()].into_iter().collect(),
+        });
+        assert_eq!(app.viewed_count(), Some(1));
+
+        // 次の未 Viewed ファイルへ（Viewed はスキップし、末尾で先頭に戻る）
+        assert!(app.select_next_unviewed_file());
+        assert_eq!(app.selected_file, 2);
+        assert!(app.select_next_unviewed_file());
+        assert_eq!(app.selected_file, 0);
+
+        let mock = Arc::new(MockGitHub::new().with_graphql_response(serde_json::Value::Null));
+        app.set_github_client(mock.clone());
+        app.toggle_file_viewed();
+        assert!(app.is_file_viewed("src/main.rs"));
+        poll_until(&mut app, App::poll_viewed_toggle, |app| {
+            app.viewed_toggle_receiver.is_none()
+        })
+        .await;
+        assert!(app.is_file_viewed("src/main.rs"));
+        let calls = mock.calls();
+        let body = calls[0].2.as_ref().unwrap();
+        assert!(body["query"]
+            .as_str()
+            .unwrap()
+            .contains("markFileAsViewed("));
+        assert_eq!(body["variables"]["pullRequestId"], "PR_1");
+
+        // GraphQL のレスポンスがなければ失敗し、元に戻す
+        app.selected_file = 1;
+        app.toggle_file_viewed();
+        assert!(!app.is_file_viewed("b.rs"));
+        poll_until(&mut app, App::poll_viewed_toggle, |app| {
+            app.viewed_toggle_receiver.is_none()
+        })
+        .await;
+        assert!(app.is_file_viewed("b.rs"));
+        assert_eq!(app.submission_result.as_ref().map(|r| r.0), Some(false));
+    }
+
+    #[tokio::test]
+    async fn test_metadata_picker_filters_and_toggles_label() {
+        use crate::github::mock::MockGitHub;
+        use crate::github::HttpMethod;
+
+        let metadata_settled = |app: &App| {
+            let picker_loading = app
+                .metadata_picker
+                .as_ref()
+                .is_some_and(|p| p.candidates.is_none());
+            !app.pr_metadata_loading && !app.pr_metadata_updating && !picker_loading
+        };
+
+        let key = |code: KeyCode| KeyEvent::new(code, KeyModifiers::NONE);
+        let mut app = loaded_app_with_file();
+        let mock = Arc::new(
+            MockGitHub::new()
+                .with_response(
+                    "repos/owner/repo/pulls/1",
+                    serde_json::json!({
+                        "node_id": "PR_1", "draft": false, "requested_reviewers": [],
+                        "assignees": [], "labels": [{ "name": "bug" }], "milestone": null
+                    }),
+                )
+                .with_response(
+                    "repos/owner/repo/labels?per_page=100&page=1",
+                    serde_json::json!([
+                        { "name": "bug" }, { "name": "enhancement" }, { "name": "good first issue" }
+                    ]),
+                )
+                .with_response("repos/owner/repo/issues/1/labels", serde_json::json!([])),
+        );
+        app.set_github_client(mock.clone());
+
+        app.previous_state = AppState::FileList;
+        app.open_metadata();
+        poll_until(&mut app, App::poll_pr_metadata, metadata_settled).await;
+        assert!(app.pr_metadata.as_ref().unwrap().has_label("bug"));
+
+        app.handle_metadata_input(key(KeyCode::Char('l')));
+        poll_until(&mut app, App::poll_pr_metadata, metadata_settled).await;
+        for c in "gfi".chars() {
+            app.handle_metadata_input(key(KeyCode::Char(c)));
+        }
+        let picker = app.metadata_picker.as_ref().unwrap();
+        assert_eq!(picker.filtered(), vec!["good first issue"]);
+
+        app.handle_metadata_input(key(KeyCode::Enter));
+        poll_until(&mut app, App::poll_pr_metadata, metadata_settled).await;
+        let add = mock
+            .calls()
+            .into_iter()
+            .find(|(method, endpoint, _)| {
+                *method == HttpMethod::Post && endpoint == "repos/owner/repo/issues/1/labels"
+            })
+            .expect("label was not added");
+        assert_eq!(add.2.unwrap()["labels"][0], "good first issue");
+        assert_eq!(app.submission_result.as_ref().map(|r| r.0), Some(true));
+
+        // Esc でピッカー、もう一度で元の画面に戻る
+        app.handle_metadata_input(key(KeyCode::Esc));
+        assert!(app.metadata_picker.is_none());
+        app.handle_metadata_input(key(KeyCode::Esc));
+        assert_eq!(app.state, AppState::FileList);
+    }
+
+    #[tokio::test]
+    async fn test_pr_list_search_presets_and_sort_reload_with_query() {
+        use crate::github::mock::MockGitHub;
+
+        // 検索とリポジトリの PR 一覧のどちらで取得しても空
+        let empty = || {
+            let connection = serde_json::json!({
+                "pageInfo": { "hasNextPage": false, "endCursor": null },
+                "nodes": []
+            });
+            serde_json::json!({
+                "search": connection.clone(),
+                "repository": { "pullRequests": connection }
+            })
+        };
+        let mock = Arc::new(
+            MockGitHub::new()
+                .with_graphql_response(empty())
+                .with_graphql_response(empty())
+                .with_graphql_response(empty())
+                .with_graphql_response(empty()),
+        );
+        let mut app = App::new_pr_list("owner/repo", Config::default());
+        app.pr_list_loading = false;
+        app.set_github_client(mock.clone());
+        let last_query = || {
+            let calls = mock.calls();
+            calls.last().unwrap().2.as_ref().unwrap()["variables"]["query"]
+                .as_str()
+                .unwrap()
+                .to_string()
+        };
+        let key = |code: KeyCode| KeyEvent::new(code, KeyModifiers::NONE);
+
+        // 検索バーに入力して Enter で検索
+        app.handle_pr_list_input(key(KeyCode::Char('/')))
+            .await
+            .unwrap();
+        for c in "label:bug".chars() {
+            app.handle_pr_list_input(key(KeyCode::Char(c)))
+                .await
+                .unwrap();
+        }
+        assert!(mock.calls().is_empty());
+        app.handle_pr_list_input(key(KeyCode::Enter)).await.unwrap();
+        poll_until(&mut app, App::poll_pr_list_updates, |app| {
+            !app.pr_list_loading
+        })
+        .await;
+        assert_eq!(app.pr_list_query.search, "label:bug");
+        assert_eq!(
+            last_query(),
+            "repo:owner/repo is:pr is:open label:bug sort:updated-desc"
+        );
+
+        // プリセットは検索条件を置き換え、もう一度押すと解除
+        app.handle_pr_list_input(key(KeyCode::Char('n')))
+            .await
+            .unwrap();
+        poll_until(&mut app, App::poll_pr_list_updates, |app| {
+            !app.pr_list_loading
+        })
+        .await;
+        assert_eq!(
+            last_query(),
+            "repo:owner/repo is:pr is:open review-requested:@me sort:updated-desc"
+        );
+        app.handle_pr_list_input(key(KeyCode::Char('n')))
+            .await
+            .unwrap();
+        poll_until(&mut app, App::poll_pr_list_updates, |app| {
+            !app.pr_list_loading
+        })
+        .await;
+        assert!(app.pr_list_query.search.is_empty());
+
+        app.handle_pr_list_input(key(KeyCode::Char('s')))
+            .await
+            .unwrap();
+        poll_until(&mut app, App::poll_pr_list_updates, |app| {
+            !app.pr_list_loading
+        })
+        .await;
+        // 検索条件がなければ検索 API を使わず、並び順は orderBy で指定する
+        let calls = mock.calls();
+        let variables = &calls.last().unwrap().2.as_ref().unwrap()["variables"];
+        assert!(variables.get("query").is_none());
+        assert_eq!(variables["orderBy"]["field"], "CREATED_AT");
+        assert_eq!(variables["states"], serde_json::json!(["OPEN"]));
+
+        // Esc は入力を破棄する
+        app.handle_pr_list_input(key(KeyCode::Char('/')))
+            .await
+            .unwrap();
+        app.handle_pr_list_input(key(KeyCode::Char('x')))
+            .await
+            .unwrap();
+        app.handle_pr_list_input(key(KeyCode::Esc)).await.unwrap();
+        assert!(app.pr_list_search_input.is_none());
+        assert_eq!(mock.calls().len(), 4);
+    }
 
-        app.handle_data_result(
-            1,
-            DataLoadResult::Success {
-                pr,
-                files: fewer_files,
-            },
+    #[tokio::test]
+    async fn test_pr_list_infinite_scroll_requests_next_page_by_cursor() {
+        use crate::github::mock::MockGitHub;
+
+        let page = |range: std::ops::RangeInclusive<u32>, has_next: bool, cursor: &str| {
+            let nodes: Vec<serde_json::Value> = range
+                .map(|n| {
+                    serde_json::json!({
+                        "number": n, "title": "t", "state": "OPEN", "author": { "login": "me" },
+                        "isDraft": false, "labels": { "nodes": [] },
+                        "updatedAt": "2024-01-01T00:00:00Z", "reviewDecision": null,
+                        "additions": 0, "deletions": 0, "commits": { "nodes": [] },
+                        "repository": { "nameWithOwner": "owner/repo" }
+                    })
+                })
+                .collect();
+            serde_json::json!({ "repository": { "pullRequests": {
+                "totalCount": 45,
+                "pageInfo": { "hasNextPage": has_next, "endCursor": cursor },
+                "nodes": nodes
+            } } })
+        };
+        let mock = Arc::new(
+            MockGitHub::new()
+                .with_graphql_response(page(1..=30, true, "c30"))
+                .with_graphql_response(page(31..=45, false, "c45")),
         );
+        let mut app = App::new_pr_list("owner/repo", Config::default());
+        app.set_github_client(mock.clone());
+        app.pr_list_loading = false;
+        app.reload_pr_list();
+        poll_until(&mut app, App::poll_pr_list_updates, |app| {
+            !app.pr_list_loading
+        })
+        .await;
+        assert!(app.pr_list_has_more);
 
-        // selected_file clamped
-        assert_eq!(app.selected_file, 1);
-        // diff_cache must be invalidated (was pointing to old file index 4)
-        assert!(
-            app.diff_cache.is_none(),
-            "diff_cache should be None after selected_file changes"
-        );
-        // selected_line and scroll_offset must be reset
-        assert_eq!(app.selected_line, 0, "selected_line should be reset to 0");
-        assert_eq!(app.scroll_offset, 0, "scroll_offset should be reset to 0");
+        // 残り5件に達したら次のページをカーソルで取得
+        app.selected_pr = 24;
+        app.handle_pr_list_input(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE))
+            .await
+            .unwrap();
+        poll_until(&mut app, App::poll_pr_list_updates, |app| {
+            !app.pr_list_loading
+        })
+        .await;
+        assert_eq!(app.pr_list.as_ref().unwrap().len(), 45);
+        assert!(!app.pr_list_has_more);
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 2);
+        let variables = &calls[1].2.as_ref().unwrap()["variables"];
+        assert_eq!(variables["cursor"], "c30");
+        assert_eq!(variables["owner"], "owner");
+        assert!(app.pr_list_truncated_total().is_none());
+    }
+
+    #[test]
+    fn test_pr_list_reports_search_truncation() {
+        let (mut app, _) = App::new_loading("owner/repo", 1, Config::default());
+        let summary: PullRequestSummary = serde_json::from_value(serde_json::json!({
+            "number": 1, "title": "t", "state": "open", "author": { "login": "me" },
+            "isDraft": false, "labels": [], "updatedAt": "2024-01-01T00:00:00Z"
+        }))
+        .unwrap();
+        app.pr_list = Some(vec![summary; github::SEARCH_RESULT_LIMIT]);
+        app.pr_list_total = Some(2345);
+
+        app.pr_list_has_more = true;
+        assert_eq!(app.pr_list_truncated_total(), None);
+        // 上限に達して次のページがなくなった
+        app.pr_list_has_more = false;
+        assert_eq!(app.pr_list_truncated_total(), Some(2345));
+        app.pr_list_total = Some(github::SEARCH_RESULT_LIMIT as u32);
+        assert_eq!(app.pr_list_truncated_total(), None);
     }
 
     #[tokio::test]
-    async fn test_handle_data_result_resyncs_comment_positions_when_selected_file_changes() {
-        let config = Config::default();
-        let (mut app, _tx) = App::new_loading("owner/repo", 1, config);
+    async fn test_switch_repo_drops_in_flight_pr_receivers() {
+        let mut app = loaded_app_with_file();
+        let (_submit_tx, submit_rx) = mpsc::channel(1);
+        app.comment_submit_receiver = Some((1, submit_rx));
+        app.comment_submitting = true;
+        let (_post_tx, post_rx) = mpsc::channel(1);
+        app.discussion_post_receiver = Some((1, post_rx));
 
-        let make_file = |name: &str| ChangedFile {
-            filename: name.to_string(),
-            status: "modified".to_string(),
-            additions: 1,
-            deletions: 1,
-            patch: Some("@@ -1,1 +1,1 @@\n-old\n+new".to_string()),
-        };
+        // 別リポジトリの同じ番号の PR に結果が届かない
+        app.switch_repo("other/repo".to_string());
+        assert_eq!(app.repo, "other/repo");
+        assert!(app.comment_submit_receiver.is_none());
+        assert!(!app.comment_submitting);
+        assert!(app.discussion_post_receiver.is_none());
+    }
 
-        let initial_files: Vec<ChangedFile> = (0..5)
-            .map(|i| make_file(&format!("file_{}.rs", i)))
-            .collect();
+    #[tokio::test]
+    async fn test_opening_another_pr_drops_suggestion_batch() {
+        use crate::github::mock::MockGitHub;
 
-        let pr = Box::new(PullRequest {
-            number: 1,
-            title: "Test PR".to_string(),
-            body: None,
-            state: "open".to_string(),
-            head: crate::github::Branch {
-                ref_name: "feature".to_string(),
-                sha: "abc123".to_string(),
-            },
-            base: crate::github::Branch {
-                ref_name: "main".to_string(),
-                sha: "def456".to_string(),
-            },
-            user: crate::github::User {
-                login: "user".to_string(),
-            },
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
+        let mut app = loaded_app_with_file();
+        app.set_github_client(Arc::new(MockGitHub::new()));
+        app.started_from_pr_list = true;
+        app.suggestion_batch = vec![10];
+        app.confirm_suggestions = Some(Vec::new());
+        let (_apply_tx, apply_rx) = mpsc::channel(1);
+        app.suggestion_apply_receiver = Some((1, apply_rx));
+        app.applying_suggestions = true;
+
+        app.back_to_pr_list();
+        app.select_pr(2);
+        assert!(app.suggestion_batch.is_empty());
+        assert!(app.confirm_suggestions.is_none());
+        assert!(app.suggestion_apply_receiver.is_none());
+        assert!(!app.applying_suggestions);
+    }
+
+    #[tokio::test]
+    async fn test_inbox_opens_selected_pr_in_its_repository() {
+        use crate::github::mock::MockGitHub;
+
+        let node = serde_json::json!({
+            "number": 5, "title": "Fix", "state": "OPEN", "author": { "login": "alice" },
+            "isDraft": false, "labels": { "nodes": [] }, "updatedAt": "2024-01-01T00:00:00Z",
+            "reviewDecision": "REVIEW_REQUIRED", "additions": 3, "deletions": 1,
+            "commits": { "nodes": [] }, "repository": { "nameWithOwner": "other/service" }
         });
+        let mock = Arc::new(MockGitHub::new().with_graphql_response(serde_json::json!({
+            "search": { "pageInfo": { "hasNextPage": false, "endCursor": null }, "nodes": [node] }
+        })));
+        let mut app = App::new_inbox(Config::default());
+        app.set_github_client(mock.clone());
+        app.pr_list_loading = false;
+        app.reload_pr_list();
+        poll_until(&mut app, App::poll_pr_list_updates, |app| {
+            !app.pr_list_loading
+        })
+        .await;
+        let query = mock.calls()[0].2.as_ref().unwrap()["variables"]["query"].clone();
+        assert_eq!(
+            query,
+            "is:pr archived:false is:open review-requested:@me sort:updated-desc"
+        );
+        assert_eq!(app.pr_list.as_ref().unwrap().len(), 1);
 
-        // Set initial loaded state with 5 files, selected_file = 4
-        app.data_state = DataState::Loaded {
-            pr: pr.clone(),
-            files: initial_files,
-        };
-        app.selected_file = 4;
+        app.handle_pr_list_input(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .await
+            .unwrap();
+        assert_eq!(app.repo, "other/service");
+        assert_eq!(app.pr_number, Some(5));
+        assert_eq!(app.state, AppState::FileList);
 
-        // Set up review comments for file_4.rs (the old selected file)
-        app.review_comments = Some(vec![ReviewComment {
-            id: 1,
-            path: "file_4.rs".to_string(),
-            line: Some(1),
-            body: "comment on old file".to_string(),
-            user: crate::github::User {
-                login: "reviewer".to_string(),
-            },
-            created_at: "2024-01-01T00:00:00Z".to_string(),
-        }]);
+        // 一覧に戻っても受信箱のまま
+        app.back_to_pr_list();
+        assert_eq!(app.state, AppState::PullRequestList);
+        assert!(app.pr_list_query.inbox);
+    }
 
-        // Pre-populate stale comment positions for the old file
-        app.file_comment_positions = vec![CommentPosition {
-            diff_line_index: 2,
-            comment_index: 0,
-        }];
-        app.file_comment_lines.insert(2);
-        app.comment_panel_open = true;
-        app.comment_panel_scroll = 5;
+    #[tokio::test]
+    async fn test_opening_another_pr_drops_in_flight_merge() {
+        use crate::github::mock::MockGitHub;
 
-        // Refresh with only 2 files (selected_file will be clamped from 4 to 1)
-        let fewer_files: Vec<ChangedFile> = (0..2)
-            .map(|i| make_file(&format!("file_{}.rs", i)))
-            .collect();
+        let mut app = loaded_app_with_file();
+        app.set_github_client(Arc::new(MockGitHub::new()));
+        app.started_from_pr_list = true;
+        let (_merge_tx, merge_rx) = mpsc::channel(1);
+        app.merge_receiver = Some((1, merge_rx));
+        app.merging = true;
 
-        app.handle_data_result(
-            1,
-            DataLoadResult::Success {
-                pr,
-                files: fewer_files,
-            },
+        // マージ中に一覧へ戻り、同じリポジトリの別の PR を開く
+        app.back_to_pr_list();
+        app.select_pr(2);
+        assert!(!app.merging);
+        assert!(app.merge_receiver.is_none());
+        assert!(app.merge_status_receiver.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_merge_dialog_refreshes_mergeability_and_merges_confirmed_request() {
+        use crate::github::mock::MockGitHub;
+        use crate::github::{HttpMethod, Mergeability};
+
+        let mut app = loaded_app_with_file();
+        let mock = Arc::new(
+            MockGitHub::new()
+                .with_response(
+                    "repos/owner/repo/pulls/1",
+                    serde_json::json!({
+                        "number": 1, "title": "Test PR", "body": null, "state": "open",
+                        "head": { "ref": "feature", "sha": "abc123" },
+                        "base": { "ref": "main", "sha": "def456" },
+                        "user": { "login": "user" }, "updated_at": "2024-01-01T00:00:00Z",
+                        "node_id": "PR_1", "mergeable": true, "mergeable_state": "blocked"
+                    }),
+                )
+                .with_response(
+                    "repos/owner/repo/pulls/1/merge",
+                    serde_json::json!({ "merged": true }),
+                )
+                .with_graphql_response(serde_json::json!({
+                    "repository": { "ref": {
+                        "branchProtectionRule": {
+                            "requiresApprovingReviews": true,
+                            "requiredApprovingReviewCount": 2,
+                            "requiresStatusChecks": true,
+                            "requiredStatusCheckContexts": ["ci"]
+                        },
+                        "rules": { "nodes": [] }
+                    } }
+                })),
         );
+        app.set_github_client(mock.clone());
 
-        // selected_file clamped to 1
-        assert_eq!(app.selected_file, 1);
+        app.open_merge_dialog();
+        assert!(app.merge_dialog.is_some());
+        poll_until(&mut app, App::poll_merge_updates, |app| {
+            app.merge_status_receiver.is_none()
+        })
+        .await;
+        let pr = app.pr().unwrap();
+        assert_eq!(pr.mergeability(), Mergeability::Blocked);
+        assert_eq!(pr.node_id, "PR_1");
+        let protection = pr.protection.as_ref().unwrap();
+        assert_eq!(protection.required_approving_reviews, 2);
+        assert_eq!(protection.required_status_checks, vec!["ci"]);
+        let calls = mock.calls();
+        let variables = &calls.last().unwrap().2.as_ref().unwrap()["variables"];
+        assert_eq!(variables["branch"], "refs/heads/main");
+
+        app.merge_dialog = None;
+        app.start_merge(MergeRequest {
+            method: MergeMethod::Squash,
+            auto_merge: false,
+            message: Some(CommitMessage {
+                title: "Test PR (#1)".to_string(),
+                body: String::new(),
+            }),
+        });
+        assert!(app.merging);
+        poll_until(&mut app, App::poll_merge_updates, |app| !app.merging).await;
+        assert_eq!(
+            app.submission_result,
+            Some((true, "Merged PR #1".to_string()))
+        );
+        let calls = mock.calls();
+        let (method, _, body) = calls.last().unwrap();
+        assert_eq!(*method, HttpMethod::Put);
+        let body = body.as_ref().unwrap();
+        assert_eq!(body["sha"], "abc123");
+        assert_eq!(body["merge_method"], "squash");
+    }
 
-        // file_comment_positions should be recalculated for file_1.rs (no matching comments)
-        assert!(
-            app.file_comment_positions.is_empty(),
-            "file_comment_positions should be recalculated for new file (no comments for file_1.rs)"
+    #[tokio::test]
+    async fn test_apply_suggestion_batch_commits_on_pr_branch() {
+        use crate::github::mock::MockGitHub;
+        use base64::Engine;
+
+        let mut app = loaded_app_with_file();
+        let mut comment = thread_comment(10, None, false);
+        comment.path = "src/main.rs".to_string();
+        comment.body = "```suggestion\nrenamed\n```".to_string();
+        app.review_comments = Some(vec![comment]);
+        app.state = AppState::CommentList;
+        app.comment_tab = CommentTab::Review;
+        app.selected_comment = 0;
+
+        // PR の作成者以外は適用できない
+        app.viewer_login = Some("reviewer".to_string());
+        app.request_apply_suggestions();
+        assert!(app.confirm_suggestions.is_none());
+        assert_eq!(
+            app.submission_result,
+            Some((
+                false,
+                "Only the PR author can apply suggestions".to_string()
+            ))
         );
-        assert!(
-            app.file_comment_lines.is_empty(),
-            "file_comment_lines should be recalculated for new file"
+
+        app.viewer_login = Some("user".to_string());
+        app.toggle_suggestion_batch();
+        assert_eq!(app.suggestion_batch, vec![10]);
+        app.request_apply_suggestions();
+        assert_eq!(app.confirm_suggestions.as_ref().map(Vec::len), Some(1));
+
+        let content = base64::engine::general_purpose::STANDARD.encode("new\n");
+        let mock = Arc::new(
+            MockGitHub::new()
+                .with_response(
+                    "repos/owner/repo/pulls/1",
+                    serde_json::json!({ "head": { "repo": { "full_name": "fork/repo" } } }),
+                )
+                .with_response(
+                    "repos/fork/repo/contents/src/main.rs?ref=abc123",
+                    serde_json::json!({ "content": content }),
+                )
+                .with_graphql_response(serde_json::json!({
+                    "createCommitOnBranch": { "commit": { "oid": "0123456789abcdef" } }
+                })),
         );
+        app.set_github_client(mock.clone());
 
-        // comment_panel should be closed
-        assert!(
-            !app.comment_panel_open,
-            "comment_panel_open should be reset when selected_file changes"
+        let suggestions = app.confirm_suggestions.take().unwrap();
+        app.start_apply_suggestions(suggestions, false);
+        assert!(app.applying_suggestions);
+        poll_until(&mut app, App::poll_suggestion_apply, |app| {
+            !app.applying_suggestions
+        })
+        .await;
+        assert_eq!(
+            app.submission_result,
+            Some((true, "Committed 0123456 to the PR branch".to_string()))
         );
+        assert!(app.suggestion_batch.is_empty());
+
+        let calls = mock.calls();
+        let (_, _, body) = calls.iter().find(|(_, e, _)| e == "graphql").unwrap();
+        let input = &body.as_ref().unwrap()["variables"]["input"];
+        assert_eq!(input["expectedHeadOid"], "abc123");
+        assert_eq!(input["branch"]["repositoryNameWithOwner"], "fork/repo");
+        assert_eq!(input["branch"]["branchName"], "feature");
         assert_eq!(
-            app.comment_panel_scroll, 0,
-            "comment_panel_scroll should be reset when selected_file changes"
+            input["message"]["headline"],
+            "Apply suggestion from code review"
+        );
+        assert_eq!(
+            input["fileChanges"]["additions"][0]["contents"],
+            base64::engine::general_purpose::STANDARD.encode("renamed\n")
         );
     }
 
     #[tokio::test]
-    async fn test_handle_data_result_preserves_diff_state_when_selected_file_unchanged() {
-        let config = Config::default();
-        let (mut app, _tx) = App::new_loading("owner/repo", 1, config);
+    async fn test_selected_line_range_follows_anchor() {
+        let mut app = loaded_app_with_multiline_patch();
+        app.selected_line = 3;
+        assert_eq!(app.selected_line_range(), 3..=3);
 
-        let make_file = |name: &str| ChangedFile {
-            filename: name.to_string(),
-            status: "modified".to_string(),
-            additions: 1,
-            deletions: 1,
-            patch: Some("@@ -1,1 +1,1 @@\n-old\n+new".to_string()),
-        };
+        app.visual_anchor = Some(5);
+        assert_eq!(app.selected_line_range(), 3..=5);
 
-        let initial_files: Vec<ChangedFile> = (0..5)
-            .map(|i| make_file(&format!("file_{}.rs", i)))
-            .collect();
+        app.visual_anchor = Some(1);
+        assert_eq!(app.selected_line_range(), 1..=3);
+    }
 
-        let pr = Box::new(PullRequest {
-            number: 1,
-            title: "Test PR".to_string(),
-            body: None,
-            state: "open".to_string(),
-            head: crate::github::Branch {
-                ref_name: "feature".to_string(),
-                sha: "abc123".to_string(),
-            },
-            base: crate::github::Branch {
-                ref_name: "main".to_string(),
-                sha: "def456".to_string(),
-            },
-            user: crate::github::User {
-                login: "user".to_string(),
-            },
-            updated_at: "2024-01-01T00:00:00Z".to_string(),
-        });
+    #[tokio::test]
+    async fn test_visual_selection_creates_range_comment() {
+        let mut app = loaded_app_with_multiline_patch();
+        app.visual_anchor = Some(1);
+        app.selected_line = 4;
 
-        // Set initial loaded state
-        app.data_state = DataState::Loaded {
-            pr: pr.clone(),
-            files: initial_files,
-        };
-        app.selected_file = 1;
-        app.selected_line = 10;
-        app.scroll_offset = 5;
+        app.enter_comment_input();
 
-        // Set diff_cache for file index 1
-        app.diff_cache = Some(DiffCache {
-            file_index: 1,
-            patch_hash: 0,
-            lines: vec![],
-            interner: Rodeo::default(),
-            highlighted: false,
-        });
+        match &app.input_mode {
+            Some(InputMode::Comment(ctx)) => {
+                assert_eq!(ctx.range, CommentRange::range(1, 3));
+            }
+            other => panic!("unexpected input mode: {:?}", other),
+        }
+        // 選択は入力開始時に解除される
+        assert!(app.visual_anchor.is_none());
+    }
 
-        // Refresh with same or more files (selected_file stays at 1)
-        let same_files: Vec<ChangedFile> = (0..5)
-            .map(|i| make_file(&format!("file_{}.rs", i)))
-            .collect();
+    #[tokio::test]
+    async fn test_visual_selection_prefills_whole_range_for_suggestion() {
+        let mut app = loaded_app_with_multiline_patch();
+        app.visual_anchor = Some(3);
+        app.selected_line = 5;
 
-        app.handle_data_result(
-            1,
-            DataLoadResult::Success {
-                pr,
-                files: same_files,
-            },
-        );
+        app.enter_suggestion_input();
 
-        // selected_file unchanged
-        assert_eq!(app.selected_file, 1);
-        // diff_cache should NOT be invalidated (selected_file didn't change)
-        assert!(
-            app.diff_cache.is_some(),
-            "diff_cache should be preserved when selected_file is unchanged"
-        );
-        // selected_line and scroll_offset should be preserved
-        assert_eq!(app.selected_line, 10);
-        assert_eq!(app.scroll_offset, 5);
+        match &app.input_mode {
+            Some(InputMode::Suggestion {
+                context,
+                original_code,
+            }) => {
+                assert_eq!(context.range, CommentRange::range(2, 4));
+                assert_eq!(original_code, "    new();\n    more();\n}");
+            }
+            other => panic!("unexpected input mode: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_comment_on_removed_line_uses_left_side() {
+        let mut app = loaded_app_with_multiline_patch();
+        app.selected_line = 2;
+
+        app.enter_comment_input();
+
+        match &app.input_mode {
+            Some(InputMode::Comment(ctx)) => {
+                assert_eq!(ctx.range.side, DiffSide::Left);
+                assert_eq!(ctx.range.line, 2);
+                assert!(ctx.range.start_line.is_none());
+            }
+            other => panic!("unexpected input mode: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_range_from_removed_to_added_line_spans_sides() {
+        let mut app = loaded_app_with_multiline_patch();
+        app.visual_anchor = Some(2);
+        app.selected_line = 4;
+
+        app.enter_comment_input();
+
+        match &app.input_mode {
+            Some(InputMode::Comment(ctx)) => {
+                assert_eq!(
+                    ctx.range,
+                    CommentRange::between((DiffSide::Left, 2), (DiffSide::Right, 3))
+                );
+            }
+            other => panic!("unexpected input mode: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_suggestion_on_removed_line_is_rejected() {
+        let mut app = loaded_app_with_multiline_patch();
+        app.selected_line = 2;
+
+        app.enter_suggestion_input();
+
+        assert!(app.input_mode.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pending_review_is_scoped_per_pr() {
+        let mut app = loaded_app_with_file();
+        app.toggle_pending_review();
+        app.pr_number = Some(2);
+        assert!(app.pending_review().is_none());
+        app.pr_number = Some(1);
+        assert!(app.pending_review().is_some());
     }
 }
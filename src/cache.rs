@@ -74,6 +74,8 @@ pub fn cleanup_rally_sessions() {
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct PrCacheKey {
+    /// ホスト違いの同名リポジトリを区別する
+    pub host: String,
     pub repo: String,
     pub pr_number: u32,
 }
@@ -88,6 +90,8 @@ pub struct PrData {
     pub pr: Box<PullRequest>,
     pub files: Vec<ChangedFile>,
     pub pr_updated_at: String,
+    /// 変更ファイル一覧が GitHub の上限で打ち切られているか
+    pub files_truncated: bool,
 }
 
 /// インメモリセッションキャッシュ（LRU eviction 付き）。
@@ -222,6 +226,10 @@ mod tests {
                 login: "testuser".to_string(),
             },
             updated_at: updated_at.to_string(),
+            node_id: String::new(),
+            mergeable: None,
+            mergeable_state: None,
+            protection: None,
         }
     }
 
@@ -337,6 +345,7 @@ mod tests {
     fn test_session_cache_put_get_pr_data() {
         let mut cache = SessionCache::new();
         let key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -350,6 +359,7 @@ mod tests {
                 pr: Box::new(pr),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
 
@@ -358,10 +368,38 @@ mod tests {
         assert!(data.files.is_empty());
     }
 
+    #[test]
+    fn test_session_cache_keys_include_host() {
+        let mut cache = SessionCache::new();
+        let key = PrCacheKey {
+            host: "github.com".to_string(),
+            repo: "owner/repo".to_string(),
+            pr_number: 1,
+        };
+        let ghe_key = PrCacheKey {
+            host: "ghe.example.com".to_string(),
+            ..key.clone()
+        };
+
+        cache.put_pr_data(
+            key.clone(),
+            PrData {
+                pr: Box::new(make_test_pr("test", "2024-01-01")),
+                files: vec![],
+                pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
+            },
+        );
+
+        assert!(cache.get_pr_data(&key).is_some());
+        assert!(cache.get_pr_data(&ghe_key).is_none());
+    }
+
     #[test]
     fn test_session_cache_put_get_review_comments() {
         let mut cache = SessionCache::new();
         let key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -379,6 +417,7 @@ mod tests {
                 pr: Box::new(make_test_pr("test", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
         cache.put_review_comments(key.clone(), vec![]);
@@ -390,6 +429,7 @@ mod tests {
     fn test_session_cache_put_get_discussion_comments() {
         let mut cache = SessionCache::new();
         let key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -407,6 +447,7 @@ mod tests {
                 pr: Box::new(make_test_pr("test", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
         cache.put_discussion_comments(key.clone(), vec![]);
@@ -418,6 +459,7 @@ mod tests {
     fn test_session_cache_remove_review_comments() {
         let mut cache = SessionCache::new();
         let key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -428,6 +470,7 @@ mod tests {
                 pr: Box::new(make_test_pr("test", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
         cache.put_review_comments(key.clone(), vec![]);
@@ -441,6 +484,7 @@ mod tests {
     fn test_session_cache_remove_discussion_comments() {
         let mut cache = SessionCache::new();
         let key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -451,6 +495,7 @@ mod tests {
                 pr: Box::new(make_test_pr("test", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
         cache.put_discussion_comments(key.clone(), vec![]);
@@ -464,6 +509,7 @@ mod tests {
     fn test_session_cache_invalidate_all() {
         let mut cache = SessionCache::new();
         let key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -474,6 +520,7 @@ mod tests {
                 pr: Box::new(make_test_pr("test", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
         cache.put_review_comments(key.clone(), vec![]);
@@ -490,10 +537,12 @@ mod tests {
     fn test_session_cache_multiple_prs() {
         let mut cache = SessionCache::new();
         let key1 = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
         let key2 = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 2,
         };
@@ -504,6 +553,7 @@ mod tests {
                 pr: Box::new(make_test_pr("PR 1", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
         cache.put_pr_data(
@@ -512,6 +562,7 @@ mod tests {
                 pr: Box::new(make_test_pr("PR 2", "2024-01-02")),
                 files: vec![],
                 pr_updated_at: "2024-01-02".to_string(),
+                files_truncated: false,
             },
         );
 
@@ -526,6 +577,7 @@ mod tests {
         // MAX_PR_CACHE_ENTRIES + 1 個のエントリを追加
         for i in 0..=MAX_PR_CACHE_ENTRIES {
             let key = PrCacheKey {
+                host: "github.com".to_string(),
                 repo: "owner/repo".to_string(),
                 pr_number: i as u32,
             };
@@ -535,6 +587,7 @@ mod tests {
                     pr: Box::new(make_test_pr(&format!("PR {}", i), "2024-01-01")),
                     files: vec![],
                     pr_updated_at: "2024-01-01".to_string(),
+                    files_truncated: false,
                 },
             );
             cache.put_review_comments(key, vec![]);
@@ -545,6 +598,7 @@ mod tests {
 
         // 最初のエントリ（PR #0）が削除されていること
         let evicted_key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 0,
         };
@@ -554,6 +608,7 @@ mod tests {
 
         // 最後のエントリは残っていること
         let last_key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: MAX_PR_CACHE_ENTRIES as u32,
         };
@@ -567,6 +622,7 @@ mod tests {
         // MAX_PR_CACHE_ENTRIES 個のエントリを追加
         for i in 0..MAX_PR_CACHE_ENTRIES {
             let key = PrCacheKey {
+                host: "github.com".to_string(),
                 repo: "owner/repo".to_string(),
                 pr_number: i as u32,
             };
@@ -576,12 +632,14 @@ mod tests {
                     pr: Box::new(make_test_pr(&format!("PR {}", i), "2024-01-01")),
                     files: vec![],
                     pr_updated_at: "2024-01-01".to_string(),
+                    files_truncated: false,
                 },
             );
         }
 
         // PR #0 にアクセスして最新に昇格
         let key0 = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 0,
         };
@@ -589,6 +647,7 @@ mod tests {
 
         // 新しいエントリを追加（PR #1 が evict されるはず）
         let new_key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 100,
         };
@@ -598,6 +657,7 @@ mod tests {
                 pr: Box::new(make_test_pr("PR 100", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
 
@@ -605,6 +665,7 @@ mod tests {
         assert!(cache.get_pr_data(&key0).is_some());
         // PR #1 が削除されている
         let key1 = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 1,
         };
@@ -617,6 +678,7 @@ mod tests {
     fn test_session_cache_comments_rejected_without_pr_data() {
         let mut cache = SessionCache::new();
         let key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 99,
         };
@@ -634,6 +696,7 @@ mod tests {
                 pr: Box::new(make_test_pr("test", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
         cache.put_review_comments(key.clone(), vec![]);
@@ -649,6 +712,7 @@ mod tests {
         // MAX_PR_CACHE_ENTRIES 個のエントリを追加
         for i in 0..MAX_PR_CACHE_ENTRIES {
             let key = PrCacheKey {
+                host: "github.com".to_string(),
                 repo: "owner/repo".to_string(),
                 pr_number: i as u32,
             };
@@ -658,12 +722,14 @@ mod tests {
                     pr: Box::new(make_test_pr(&format!("PR {}", i), "2024-01-01")),
                     files: vec![],
                     pr_updated_at: "2024-01-01".to_string(),
+                    files_truncated: false,
                 },
             );
         }
 
         // 新しいエントリを追加して PR #0 を evict
         let new_key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 100,
         };
@@ -673,11 +739,13 @@ mod tests {
                 pr: Box::new(make_test_pr("PR 100", "2024-01-01")),
                 files: vec![],
                 pr_updated_at: "2024-01-01".to_string(),
+                files_truncated: false,
             },
         );
 
         // evict された PR #0 へのコメント保存は無視される
         let evicted_key = PrCacheKey {
+            host: "github.com".to_string(),
             repo: "owner/repo".to_string(),
             pr_number: 0,
         };
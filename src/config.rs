@@ -5,6 +5,7 @@ use std::fs;
 use std::path::PathBuf;
 use xdg::BaseDirectories;
 
+use crate::github::DEFAULT_HOST;
 use crate::keybinding::{KeyBinding, KeySequence, NamedKey};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +15,7 @@ pub struct Config {
     pub diff: DiffConfig,
     pub keybindings: KeybindingsConfig,
     pub ai: AiConfig,
+    pub github: GitHubConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +37,29 @@ pub struct AiConfig {
     pub reviewee_additional_tools: Vec<String>,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GitHubConfig {
+    /// API backend: "gh" (spawn the gh CLI) or "http" (call the REST/GraphQL API directly)
+    pub backend: GitHubBackend,
+    /// GitHub Enterprise Server hostname (default: github.com)
+    pub host: Option<String>,
+}
+
+impl GitHubConfig {
+    pub fn host(&self) -> &str {
+        self.host.as_deref().unwrap_or(DEFAULT_HOST)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitHubBackend {
+    #[default]
+    Gh,
+    Http,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct DiffConfig {
@@ -62,6 +87,8 @@ pub struct KeybindingsConfig {
     pub jump_back: KeySequence,
     pub next_comment: KeySequence,
     pub prev_comment: KeySequence,
+    pub next_annotation: KeySequence,
+    pub prev_annotation: KeySequence,
 
     // Actions
     pub approve: KeySequence,
@@ -71,13 +98,29 @@ pub struct KeybindingsConfig {
     pub reply: KeySequence,
     pub refresh: KeySequence,
     pub submit: KeySequence,
+    pub resolve_thread: KeySequence,
+    pub toggle_thread: KeySequence,
+    pub edit_comment: KeySequence,
+    pub delete_comment: KeySequence,
+    pub add_reaction: KeySequence,
+    pub apply_suggestion: KeySequence,
+    pub batch_suggestion: KeySequence,
 
     // Mode switching
     pub quit: KeySequence,
     pub help: KeySequence,
     pub comment_list: KeySequence,
+    pub checks: KeySequence,
+    pub commit_list: KeySequence,
+    pub since_review: KeySequence,
+    pub toggle_viewed: KeySequence,
+    pub next_unviewed: KeySequence,
+    pub pr_metadata: KeySequence,
+    pub merge: KeySequence,
     pub ai_rally: KeySequence,
     pub open_panel: KeySequence,
+    pub visual_select: KeySequence,
+    pub pending_review: KeySequence,
 
     // Diff operations
     pub go_to_definition: KeySequence,
@@ -91,6 +134,7 @@ impl Default for Config {
             diff: DiffConfig::default(),
             keybindings: KeybindingsConfig::default(),
             ai: AiConfig::default(),
+            github: GitHubConfig::default(),
         }
     }
 }
@@ -132,6 +176,8 @@ impl Default for KeybindingsConfig {
             jump_back: KeySequence::single(KeyBinding::ctrl('o')),
             next_comment: KeySequence::single(KeyBinding::char('n')),
             prev_comment: KeySequence::single(KeyBinding::char('N')),
+            next_annotation: KeySequence::double(KeyBinding::char(']'), KeyBinding::char('d')),
+            prev_annotation: KeySequence::double(KeyBinding::char('['), KeyBinding::char('d')),
 
             // Actions
             approve: KeySequence::single(KeyBinding::char('a')),
@@ -141,13 +187,29 @@ impl Default for KeybindingsConfig {
             reply: KeySequence::single(KeyBinding::char('r')),
             refresh: KeySequence::single(KeyBinding::char('R')),
             submit: KeySequence::single(KeyBinding::ctrl('s')),
+            resolve_thread: KeySequence::single(KeyBinding::char('x')),
+            toggle_thread: KeySequence::single(KeyBinding::char('z')),
+            edit_comment: KeySequence::single(KeyBinding::char('e')),
+            delete_comment: KeySequence::single(KeyBinding::char('d')),
+            add_reaction: KeySequence::single(KeyBinding::char('+')),
+            apply_suggestion: KeySequence::single(KeyBinding::char('a')),
+            batch_suggestion: KeySequence::single(KeyBinding::char('b')),
 
             // Mode switching
             quit: KeySequence::single(KeyBinding::char('q')),
             help: KeySequence::single(KeyBinding::char('?')),
             comment_list: KeySequence::single(KeyBinding::char('C')),
+            checks: KeySequence::single(KeyBinding::char('S')),
+            commit_list: KeySequence::single(KeyBinding::char('L')),
+            since_review: KeySequence::single(KeyBinding::char('U')),
+            toggle_viewed: KeySequence::single(KeyBinding::char('v')),
+            next_unviewed: KeySequence::single(KeyBinding::char('u')),
+            pr_metadata: KeySequence::single(KeyBinding::char('I')),
+            merge: KeySequence::single(KeyBinding::char('M')),
             ai_rally: KeySequence::single(KeyBinding::char('A')),
             open_panel: KeySequence::single(KeyBinding::named(NamedKey::Enter)),
+            visual_select: KeySequence::single(KeyBinding::char('v')),
+            pending_review: KeySequence::single(KeyBinding::char('P')),
 
             // Diff operations
             go_to_definition: KeySequence::double(KeyBinding::char('g'), KeyBinding::char('d')),
@@ -180,6 +242,8 @@ impl KeybindingsConfig {
             ("jump_back", &self.jump_back),
             ("next_comment", &self.next_comment),
             ("prev_comment", &self.prev_comment),
+            ("next_annotation", &self.next_annotation),
+            ("prev_annotation", &self.prev_annotation),
             ("approve", &self.approve),
             ("request_changes", &self.request_changes),
             ("comment", &self.comment),
@@ -187,11 +251,27 @@ impl KeybindingsConfig {
             ("reply", &self.reply),
             ("refresh", &self.refresh),
             ("submit", &self.submit),
+            ("resolve_thread", &self.resolve_thread),
+            ("toggle_thread", &self.toggle_thread),
+            ("edit_comment", &self.edit_comment),
+            ("delete_comment", &self.delete_comment),
+            ("add_reaction", &self.add_reaction),
+            ("apply_suggestion", &self.apply_suggestion),
+            ("batch_suggestion", &self.batch_suggestion),
             ("quit", &self.quit),
             ("help", &self.help),
             ("comment_list", &self.comment_list),
+            ("checks", &self.checks),
+            ("commit_list", &self.commit_list),
+            ("since_review", &self.since_review),
+            ("toggle_viewed", &self.toggle_viewed),
+            ("next_unviewed", &self.next_unviewed),
+            ("pr_metadata", &self.pr_metadata),
+            ("merge", &self.merge),
             ("ai_rally", &self.ai_rally),
             ("open_panel", &self.open_panel),
+            ("visual_select", &self.visual_select),
+            ("pending_review", &self.pending_review),
             ("go_to_definition", &self.go_to_definition),
             ("go_to_file", &self.go_to_file),
         ];
@@ -255,10 +335,16 @@ impl KeybindingsConfig {
 fn is_context_compatible(name1: &str, name2: &str) -> bool {
     // These keybindings are used in different contexts:
     // - 'r' is used for 'reply' in comment panel and 'request_changes' in file list
+    // - 'v' is used for 'visual_select' in diff view and 'toggle_viewed' in file list
+    // - 'a' is used for 'apply_suggestion' in comment panel / list and 'approve' in file list
     //
     // NOTE: 'comment' and 'suggestion' are NOT compatible - both are active in diff view
     // and comment panel contexts, so they must have different bindings.
-    let context_groups: &[&[&str]] = &[&["reply", "request_changes"]];
+    let context_groups: &[&[&str]] = &[
+        &["reply", "request_changes"],
+        &["visual_select", "toggle_viewed"],
+        &["apply_suggestion", "approve"],
+    ];
 
     for group in context_groups {
         if group.contains(&name1) && group.contains(&name2) {
@@ -305,6 +391,8 @@ impl Serialize for KeybindingsConfig {
         map.serialize_entry("jump_back", &seq_to_value(&self.jump_back))?;
         map.serialize_entry("next_comment", &seq_to_value(&self.next_comment))?;
         map.serialize_entry("prev_comment", &seq_to_value(&self.prev_comment))?;
+        map.serialize_entry("next_annotation", &seq_to_value(&self.next_annotation))?;
+        map.serialize_entry("prev_annotation", &seq_to_value(&self.prev_annotation))?;
         map.serialize_entry("approve", &seq_to_value(&self.approve))?;
         map.serialize_entry("request_changes", &seq_to_value(&self.request_changes))?;
         map.serialize_entry("comment", &seq_to_value(&self.comment))?;
@@ -312,11 +400,27 @@ impl Serialize for KeybindingsConfig {
         map.serialize_entry("reply", &seq_to_value(&self.reply))?;
         map.serialize_entry("refresh", &seq_to_value(&self.refresh))?;
         map.serialize_entry("submit", &seq_to_value(&self.submit))?;
+        map.serialize_entry("resolve_thread", &seq_to_value(&self.resolve_thread))?;
+        map.serialize_entry("toggle_thread", &seq_to_value(&self.toggle_thread))?;
+        map.serialize_entry("edit_comment", &seq_to_value(&self.edit_comment))?;
+        map.serialize_entry("delete_comment", &seq_to_value(&self.delete_comment))?;
+        map.serialize_entry("add_reaction", &seq_to_value(&self.add_reaction))?;
+        map.serialize_entry("apply_suggestion", &seq_to_value(&self.apply_suggestion))?;
+        map.serialize_entry("batch_suggestion", &seq_to_value(&self.batch_suggestion))?;
         map.serialize_entry("quit", &seq_to_value(&self.quit))?;
         map.serialize_entry("help", &seq_to_value(&self.help))?;
         map.serialize_entry("comment_list", &seq_to_value(&self.comment_list))?;
+        map.serialize_entry("checks", &seq_to_value(&self.checks))?;
+        map.serialize_entry("commit_list", &seq_to_value(&self.commit_list))?;
+        map.serialize_entry("since_review", &seq_to_value(&self.since_review))?;
+        map.serialize_entry("toggle_viewed", &seq_to_value(&self.toggle_viewed))?;
+        map.serialize_entry("next_unviewed", &seq_to_value(&self.next_unviewed))?;
+        map.serialize_entry("pr_metadata", &seq_to_value(&self.pr_metadata))?;
+        map.serialize_entry("merge", &seq_to_value(&self.merge))?;
         map.serialize_entry("ai_rally", &seq_to_value(&self.ai_rally))?;
         map.serialize_entry("open_panel", &seq_to_value(&self.open_panel))?;
+        map.serialize_entry("visual_select", &seq_to_value(&self.visual_select))?;
+        map.serialize_entry("pending_review", &seq_to_value(&self.pending_review))?;
         map.serialize_entry("go_to_definition", &seq_to_value(&self.go_to_definition))?;
         map.serialize_entry("go_to_file", &seq_to_value(&self.go_to_file))?;
 
@@ -394,6 +498,27 @@ mod tests {
         assert_eq!(config.keybindings.jump_to_first.display(), "gg");
     }
 
+    #[test]
+    fn test_parse_github_backend() {
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.github.backend, GitHubBackend::Gh);
+
+        let toml_str = r#"
+            [github]
+            backend = "http"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.github.backend, GitHubBackend::Http);
+        assert_eq!(config.github.host(), "github.com");
+
+        let toml_str = r#"
+            [github]
+            host = "ghe.example.com"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.github.host(), "ghe.example.com");
+    }
+
     #[test]
     fn test_backwards_compatible_defaults() {
         // Empty config should use all defaults
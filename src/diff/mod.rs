@@ -3,9 +3,10 @@
 //! This module provides functions to analyze patch content and extract:
 //! - Line content without diff prefixes (+/-)
 //! - Line type classification (Added, Removed, Context, Header)
-//! - New file line numbers for suggestion positioning
+//! - Old/new file line numbers for comment and suggestion positioning
 //! - Unified diff parsing for splitting multi-file diffs
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::warn;
 
@@ -24,6 +25,27 @@ pub enum LineType {
     Meta,
 }
 
+/// Side of a diff a line belongs to (GitHub's `side` / `start_side`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum DiffSide {
+    /// Old version of the file (removed lines)
+    Left,
+    /// New version of the file (added and context lines)
+    #[default]
+    Right,
+}
+
+impl DiffSide {
+    /// GitHub API での表記
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DiffSide::Left => "LEFT",
+            DiffSide::Right => "RIGHT",
+        }
+    }
+}
+
 /// Information extracted from a single line in a diff patch
 #[derive(Debug, Clone)]
 pub struct DiffLineInfo {
@@ -33,20 +55,37 @@ pub struct DiffLineInfo {
     pub line_type: LineType,
     /// Line number in the new file (None for removed lines and headers)
     pub new_line_number: Option<u32>,
+    /// Line number in the old file (None for added lines and headers)
+    pub old_line_number: Option<u32>,
 }
 
-/// Parse a hunk header to extract the starting line number for new file
-/// Format: @@ -old_start,old_count +new_start,new_count @@
-fn parse_hunk_header(line: &str) -> Option<u32> {
-    // Find the +new_start part
-    let plus_pos = line.find('+')?;
-    let after_plus = &line[plus_pos + 1..];
-
-    // Extract the number (stop at comma or space)
-    let end_pos = after_plus.find([',', ' ']).unwrap_or(after_plus.len());
-    let num_str = &after_plus[..end_pos];
+impl DiffLineInfo {
+    /// Side and line number to anchor a review comment on this line
+    ///
+    /// Removed lines only exist in the old file, so they are commented on the LEFT side.
+    pub fn comment_anchor(&self) -> Option<(DiffSide, u32)> {
+        match self.line_type {
+            LineType::Removed => self.old_line_number.map(|n| (DiffSide::Left, n)),
+            _ => self.new_line_number.map(|n| (DiffSide::Right, n)),
+        }
+    }
+}
 
-    num_str.parse().ok()
+/// Parse a hunk header to extract the starting line numbers
+/// Format: @@ -old_start,old_count +new_start,new_count @@
+///
+/// Returns `(old_start, new_start)`.
+pub(crate) fn parse_hunk_header(line: &str) -> Option<(u32, u32)> {
+    // Extract the number after `prefix` (stop at comma or space)
+    let parse_start = |prefix: char| -> Option<u32> {
+        let pos = line.find(prefix)?;
+        let after = &line[pos + 1..];
+        let end_pos = after.find([',', ' ']).unwrap_or(after.len());
+        after[..end_pos].parse().ok()
+    };
+
+    // Find the -old_start and +new_start parts
+    Some((parse_start('-')?, parse_start('+')?))
 }
 
 /// Get information about a specific line in a patch
@@ -65,7 +104,8 @@ pub fn get_line_info(patch: &str, line_index: usize) -> Option<DiffLineInfo> {
         return None;
     }
 
-    // Track the current new file line number
+    // Track the current old/new file line numbers
+    let mut old_line_number: Option<u32> = None;
     let mut new_line_number: Option<u32> = None;
 
     for (i, line) in lines.iter().enumerate() {
@@ -73,7 +113,9 @@ pub fn get_line_info(patch: &str, line_index: usize) -> Option<DiffLineInfo> {
 
         // Update line number tracking based on hunk headers
         if line_type == LineType::Header {
-            new_line_number = parse_hunk_header(line);
+            let starts = parse_hunk_header(line);
+            old_line_number = starts.map(|(old, _)| old);
+            new_line_number = starts.map(|(_, new)| new);
         }
 
         if i == line_index {
@@ -82,28 +124,94 @@ pub fn get_line_info(patch: &str, line_index: usize) -> Option<DiffLineInfo> {
                 LineType::Removed | LineType::Header | LineType::Meta => None,
                 _ => new_line_number,
             };
+            let current_old_line = match line_type {
+                LineType::Added | LineType::Header | LineType::Meta => None,
+                _ => old_line_number,
+            };
 
             return Some(DiffLineInfo {
                 line_content: content.to_string(),
                 line_type,
                 new_line_number: current_new_line,
+                old_line_number: current_old_line,
             });
         }
 
         // Update line numbers for next iteration
-        match line_type {
-            LineType::Added | LineType::Context => {
-                if let Some(n) = new_line_number {
-                    new_line_number = Some(n + 1);
-                }
-            }
-            _ => {}
+        if matches!(line_type, LineType::Added | LineType::Context) {
+            new_line_number = new_line_number.map(|n| n + 1);
+        }
+        if matches!(line_type, LineType::Removed | LineType::Context) {
+            old_line_number = old_line_number.map(|n| n + 1);
         }
     }
 
     None
 }
 
+/// Information about a contiguous range of lines in a diff patch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffRangeInfo {
+    /// Side of the first line in the range
+    pub start_side: DiffSide,
+    /// Line number (on `start_side`) of the first line in the range
+    pub start_line: u32,
+    /// Side of the last line in the range
+    pub end_side: DiffSide,
+    /// Line number (on `end_side`) of the last line in the range
+    pub end_line: u32,
+    /// New file content of the range (removed lines excluded), joined with `\n`
+    pub content: String,
+}
+
+/// Get information about a range of lines (`start_index..=end_index`) in a patch
+///
+/// GitHub requires both ends of a multi-line comment to be in the same hunk,
+/// so ranges crossing a hunk header are rejected. Removed lines are anchored on
+/// the LEFT side; a range cannot start on the RIGHT side and end on the LEFT.
+///
+/// # Returns
+/// * `Some(DiffRangeInfo)` - If both ends are commentable lines within one hunk
+/// * `None` - If the range is invalid, out of bounds, or crosses a hunk boundary
+pub fn get_range_info(patch: &str, start_index: usize, end_index: usize) -> Option<DiffRangeInfo> {
+    if start_index > end_index {
+        return None;
+    }
+
+    let start = get_line_info(patch, start_index)?;
+    let end = get_line_info(patch, end_index)?;
+    let (end_side, end_line) = end.comment_anchor()?;
+    let (start_side, start_line) = match (start.line_type, end_side) {
+        // Context lines exist on both sides; follow the end so the range stays on one side
+        (LineType::Context, DiffSide::Left) => (DiffSide::Left, start.old_line_number?),
+        _ => start.comment_anchor()?,
+    };
+    if start_side == DiffSide::Right && end_side == DiffSide::Left {
+        return None;
+    }
+
+    let mut content = Vec::new();
+    for line in patch
+        .lines()
+        .skip(start_index)
+        .take(end_index - start_index + 1)
+    {
+        match classify_line(line) {
+            (LineType::Added | LineType::Context, text) => content.push(text),
+            (LineType::Removed, _) => {}
+            (LineType::Header | LineType::Meta, _) => return None,
+        }
+    }
+
+    Some(DiffRangeInfo {
+        start_side,
+        start_line,
+        end_side,
+        end_line,
+        content: content.join("\n"),
+    })
+}
+
 /// Classify a line and extract its content without the prefix
 pub fn classify_line(line: &str) -> (LineType, &str) {
     if line.starts_with("@@") {
@@ -135,6 +243,91 @@ pub fn can_suggest_at_line(patch: &str, line_index: usize) -> bool {
         .unwrap_or(false)
 }
 
+/// Number of lines before the commented line in a `diff_hunk` compared when several
+/// patch lines match it
+const HUNK_ANCHOR_CONTEXT: usize = 3;
+
+/// Find the patch line a review comment is anchored to, using the comment's `diff_hunk`
+///
+/// GitHub's `diff_hunk` ends with the commented line. If the line at `line_hint` still
+/// has that content it is used as is. Otherwise lines on `side` with the same content
+/// are candidates: the one preceded by the most matching hunk lines wins, and ties go
+/// to the line number closest to `line_hint`. Returns `None` when the commented line
+/// is no longer in the patch.
+pub fn find_hunk_anchor(
+    patch: &str,
+    diff_hunk: &str,
+    side: DiffSide,
+    line_hint: u32,
+) -> Option<usize> {
+    let is_code = |line_type: LineType| !matches!(line_type, LineType::Header | LineType::Meta);
+    let hunk: Vec<&str> = diff_hunk
+        .lines()
+        .map(classify_line)
+        .filter(|(line_type, _)| is_code(*line_type))
+        .map(|(_, content)| content)
+        .collect();
+    let (anchor, context) = hunk.split_last()?;
+
+    let lines: Vec<&str> = patch.lines().collect();
+    // (一致した前方の行数, 行番号の差, 行インデックス)
+    let mut best: Option<(usize, u32, usize)> = None;
+    let mut old_line_number = 0u32;
+    let mut new_line_number = 0u32;
+
+    for (i, line) in lines.iter().enumerate() {
+        let (line_type, content) = classify_line(line);
+        match line_type {
+            LineType::Header => {
+                if let Some((old, new)) = parse_hunk_header(line) {
+                    old_line_number = old;
+                    new_line_number = new;
+                }
+                continue;
+            }
+            LineType::Meta => continue,
+            _ => {}
+        }
+
+        let number = match (side, line_type) {
+            (DiffSide::Right, LineType::Added | LineType::Context) => Some(new_line_number),
+            (DiffSide::Left, LineType::Removed | LineType::Context) => Some(old_line_number),
+            _ => None,
+        };
+        if let Some(number) = number.filter(|_| content == *anchor) {
+            if number == line_hint {
+                return Some(i);
+            }
+            let matched = context
+                .iter()
+                .rev()
+                .take(HUNK_ANCHOR_CONTEXT)
+                .zip(lines[..i].iter().rev().map(|l| classify_line(l)))
+                .take_while(|(expected, (line_type, actual))| {
+                    is_code(*line_type) && actual == *expected
+                })
+                .count();
+            let distance = number.abs_diff(line_hint);
+            let better = match best {
+                None => true,
+                Some((m, d, _)) => matched > m || (matched == m && distance < d),
+            };
+            if better {
+                best = Some((matched, distance, i));
+            }
+        }
+
+        if matches!(line_type, LineType::Added | LineType::Context) {
+            new_line_number += 1;
+        }
+        if matches!(line_type, LineType::Removed | LineType::Context) {
+            old_line_number += 1;
+        }
+    }
+
+    best.map(|(_, _, i)| i)
+}
+
 /// Parse a unified diff output into a map of filename -> patch content
 ///
 /// This function splits the output of `git diff` or `gh pr diff` into individual
@@ -297,11 +490,42 @@ index 0000000..1234567
 Binary files /dev/null and b/image.png differ
 "#;
 
+    #[test]
+    fn test_find_hunk_anchor_follows_shifted_code() {
+        // コメント時の hunk（コメント対象は最終行）
+        let diff_hunk = "@@ -1,1 +1,2 @@\n fn a() {\n+    target();";
+        // その後の push で上に 2 行追加され、同じ内容の行が別の場所にも現れた
+        let patch = "@@ -1,2 +1,7 @@\n+// header\n+\n fn a() {\n+    target();\n }\n+fn b() {\n+    target();";
+        // 元の行番号の行が同じ内容ならそのまま使う
+        assert_eq!(
+            find_hunk_anchor(patch, diff_hunk, DiffSide::Right, 7),
+            Some(7)
+        );
+        // 元の行がずれていれば、行番号が近い 7 行目より前の行まで一致する 4 行目（インデックス 4）を選ぶ
+        assert_eq!(
+            find_hunk_anchor(patch, diff_hunk, DiffSide::Right, 6),
+            Some(4)
+        );
+        // 前の行で区別できなければ行番号が近い方
+        let short_hunk = "@@ -0,0 +1 @@\n+    target();";
+        assert_eq!(
+            find_hunk_anchor(patch, short_hunk, DiffSide::Right, 7),
+            Some(7)
+        );
+        // 削除側・パッチにない行は見つからない
+        assert_eq!(find_hunk_anchor(patch, diff_hunk, DiffSide::Left, 2), None);
+        assert_eq!(
+            find_hunk_anchor(patch, "@@ -1 +1 @@\n+gone();", DiffSide::Right, 1),
+            None
+        );
+    }
+
     #[test]
     fn test_parse_hunk_header() {
-        assert_eq!(parse_hunk_header("@@ -1,4 +1,5 @@"), Some(1));
-        assert_eq!(parse_hunk_header("@@ -10,3 +15,7 @@"), Some(15));
-        assert_eq!(parse_hunk_header("@@ -1 +1 @@"), Some(1));
+        assert_eq!(parse_hunk_header("@@ -1,4 +1,5 @@"), Some((1, 1)));
+        assert_eq!(parse_hunk_header("@@ -10,3 +15,7 @@"), Some((10, 15)));
+        assert_eq!(parse_hunk_header("@@ -1 +1 @@"), Some((1, 1)));
+        assert_eq!(parse_hunk_header("@@ -0,0 +1,3 @@"), Some((0, 1)));
     }
 
     #[test]
@@ -317,6 +541,7 @@ Binary files /dev/null and b/image.png differ
         assert_eq!(info.line_type, LineType::Context);
         assert_eq!(info.line_content, "line 1");
         assert_eq!(info.new_line_number, Some(1));
+        assert_eq!(info.old_line_number, Some(1));
     }
 
     #[test]
@@ -325,6 +550,8 @@ Binary files /dev/null and b/image.png differ
         assert_eq!(info.line_type, LineType::Removed);
         assert_eq!(info.line_content, "old line 2");
         assert!(info.new_line_number.is_none());
+        assert_eq!(info.old_line_number, Some(2));
+        assert_eq!(info.comment_anchor(), Some((DiffSide::Left, 2)));
     }
 
     #[test]
@@ -333,6 +560,18 @@ Binary files /dev/null and b/image.png differ
         assert_eq!(info.line_type, LineType::Added);
         assert_eq!(info.line_content, "new line 2");
         assert_eq!(info.new_line_number, Some(2));
+        assert!(info.old_line_number.is_none());
+        assert_eq!(info.comment_anchor(), Some((DiffSide::Right, 2)));
+    }
+
+    #[test]
+    fn test_get_line_info_old_line_numbers_across_hunks() {
+        let patch = "@@ -3,2 +3,1 @@\n-gone\n kept\n@@ -20,1 +19,2 @@\n ctx\n+added";
+        assert_eq!(get_line_info(patch, 1).unwrap().old_line_number, Some(3));
+        assert_eq!(get_line_info(patch, 2).unwrap().old_line_number, Some(4));
+        assert_eq!(get_line_info(patch, 4).unwrap().old_line_number, Some(20));
+        assert_eq!(get_line_info(patch, 4).unwrap().new_line_number, Some(19));
+        assert!(get_line_info(patch, 5).unwrap().old_line_number.is_none());
     }
 
     #[test]
@@ -347,6 +586,63 @@ Binary files /dev/null and b/image.png differ
         assert!(can_suggest_at_line(SAMPLE_PATCH, 3));
     }
 
+    #[test]
+    fn test_get_range_info_single_line() {
+        let info = get_range_info(SAMPLE_PATCH, 3, 3).unwrap();
+        assert_eq!(info.start_side, DiffSide::Right);
+        assert_eq!(info.end_side, DiffSide::Right);
+        assert_eq!(info.start_line, 2);
+        assert_eq!(info.end_line, 2);
+        assert_eq!(info.content, "new line 2");
+    }
+
+    #[test]
+    fn test_get_range_info_skips_removed_lines() {
+        let info = get_range_info(SAMPLE_PATCH, 1, 4).unwrap();
+        assert_eq!(info.start_line, 1);
+        assert_eq!(info.end_line, 3);
+        assert_eq!(info.content, "line 1\nnew line 2\nadded line");
+    }
+
+    #[test]
+    fn test_get_range_info_removed_line_is_left_side() {
+        let info = get_range_info(SAMPLE_PATCH, 2, 2).unwrap();
+        assert_eq!(info.start_side, DiffSide::Left);
+        assert_eq!(info.end_side, DiffSide::Left);
+        assert_eq!(info.start_line, 2);
+        assert_eq!(info.end_line, 2);
+    }
+
+    #[test]
+    fn test_get_range_info_context_to_removed_stays_left() {
+        let info = get_range_info(SAMPLE_PATCH, 1, 2).unwrap();
+        assert_eq!((info.start_side, info.start_line), (DiffSide::Left, 1));
+        assert_eq!((info.end_side, info.end_line), (DiffSide::Left, 2));
+    }
+
+    #[test]
+    fn test_get_range_info_removed_to_added_spans_sides() {
+        let info = get_range_info(SAMPLE_PATCH, 2, 4).unwrap();
+        assert_eq!((info.start_side, info.start_line), (DiffSide::Left, 2));
+        assert_eq!((info.end_side, info.end_line), (DiffSide::Right, 3));
+    }
+
+    #[test]
+    fn test_get_range_info_rejects_invalid_endpoints() {
+        // Header endpoint
+        assert!(get_range_info(SAMPLE_PATCH, 0, 3).is_none());
+        // RIGHT start with LEFT end
+        let patch = "@@ -1,1 +1,1 @@\n+new\n-old";
+        assert!(get_range_info(patch, 1, 2).is_none());
+    }
+
+    #[test]
+    fn test_get_range_info_rejects_cross_hunk() {
+        let patch = "@@ -1,2 +1,2 @@\n line 1\n line 2\n@@ -10,2 +10,2 @@\n line 10\n line 11";
+        assert!(get_range_info(patch, 1, 2).is_some());
+        assert!(get_range_info(patch, 2, 4).is_none());
+    }
+
     #[test]
     fn test_out_of_bounds() {
         assert!(get_line_info(SAMPLE_PATCH, 100).is_none());
@@ -70,6 +70,43 @@ pub fn open_review_editor(editor: &str) -> Result<Option<String>> {
     )
 }
 
+/// Title and body of a merge commit
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitMessage {
+    pub title: String,
+    pub body: String,
+}
+
+/// Open external editor for merge commit message
+///
+/// The first non-empty line becomes the commit title, the rest the body.
+pub fn open_merge_commit_editor(
+    editor: &str,
+    default_title: &str,
+    default_body: &str,
+) -> Result<Option<CommitMessage>> {
+    let initial = format!("{}\n\n{}", default_title, default_body);
+    let content = open_editor_internal(
+        editor,
+        EditorTemplate {
+            header: Cow::Borrowed(
+                "<!-- octorus: Edit the merge commit message -->\n\
+                 <!-- First line is the title, the rest is the body -->\n\
+                 <!-- Save and close to continue, delete all content to cancel -->",
+            ),
+            initial_content: Some(Cow::Owned(initial)),
+        },
+    )?;
+    Ok(content.as_deref().and_then(parse_commit_message))
+}
+
+fn parse_commit_message(content: &str) -> Option<CommitMessage> {
+    let mut lines = content.lines().skip_while(|line| line.trim().is_empty());
+    let title = lines.next()?.trim().to_string();
+    let body = lines.collect::<Vec<_>>().join("\n").trim().to_string();
+    Some(CommitMessage { title, body })
+}
+
 fn resolve_editor(configured: &str) -> String {
     if !configured.is_empty() {
         return configured.to_string();
@@ -86,21 +123,30 @@ fn extract_comment_body(content: &str) -> String {
 }
 
 /// Open external editor for suggestion input
+///
+/// `original_code` is the whole `start_line..=end_line` range, so the resulting
+/// suggestion replaces every selected line.
 /// Returns the suggested code (without the original template comments)
 pub fn open_suggestion_editor(
     editor: &str,
     filename: &str,
-    line: usize,
+    start_line: usize,
+    end_line: usize,
     original_code: &str,
 ) -> Result<Option<String>> {
+    let location = if start_line < end_line {
+        format!("Lines: {}-{}", start_line, end_line)
+    } else {
+        format!("Line: {}", end_line)
+    };
     open_editor_internal(
         editor,
         EditorTemplate {
             header: Cow::Owned(format!(
                 "<!-- octorus: Edit the code below to create a suggestion -->\n\
-                 <!-- File: {} Line: {} -->\n\
+                 <!-- File: {} {} -->\n\
                  <!-- Save and close to submit, delete all content to cancel -->",
-                filename, line
+                filename, location
             )),
             initial_content: Some(Cow::Borrowed(original_code)),
         },
@@ -142,3 +188,20 @@ pub fn open_clarification_editor(editor: &str, question: &str) -> Result<Option<
         },
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commit_message_splits_title_and_body() {
+        let content = "\n\nAdd feature (#1)\n\n* first\n* second\n";
+        let message = parse_commit_message(content).unwrap();
+        assert_eq!(message.title, "Add feature (#1)");
+        assert_eq!(message.body, "* first\n* second");
+
+        let title_only = parse_commit_message("Fix typo").unwrap();
+        assert_eq!(title_only.body, "");
+        assert!(parse_commit_message("\n  \n").is_none());
+    }
+}
@@ -0,0 +1,60 @@
+//! Fuzzy matching for pickers (labels, reviewers).
+
+/// `query` の文字が順番通りに `candidate` に含まれていればスコアを返す（大文字小文字は無視）
+///
+/// 連続して一致するほど、また先頭に近いほどスコアが高い。空のクエリは全てに一致する。
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let candidate: Vec<char> = candidate.to_lowercase().chars().collect();
+    let mut score = 0i64;
+    let mut pos = 0usize;
+    let mut prev_match: Option<usize> = None;
+
+    for q in query.to_lowercase().chars() {
+        let offset = candidate[pos..].iter().position(|&c| c == q)?;
+        let index = pos + offset;
+        score += match prev_match {
+            Some(prev) if prev + 1 == index => 10,
+            _ => 1,
+        };
+        if index == 0 {
+            score += 5;
+        }
+        prev_match = Some(index);
+        pos = index + 1;
+    }
+    Some(score - candidate.len() as i64 / 10)
+}
+
+/// 候補を絞り込み、スコアの高い順（同点は元の順）のインデックスを返す
+pub fn fuzzy_filter<S: AsRef<str>>(query: &str, candidates: &[S]) -> Vec<usize> {
+    if query.is_empty() {
+        return (0..candidates.len()).collect();
+    }
+    let mut scored: Vec<(usize, i64)> = candidates
+        .iter()
+        .enumerate()
+        .filter_map(|(i, c)| fuzzy_score(query, c.as_ref()).map(|s| (i, s)))
+        .collect();
+    scored.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score_requires_ordered_subsequence() {
+        assert!(fuzzy_score("gfi", "good first issue").is_some());
+        assert!(fuzzy_score("BUG", "bug").is_some());
+        assert!(fuzzy_score("ifg", "good first issue").is_none());
+        assert!(fuzzy_score("", "anything").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_filter_prefers_contiguous_prefix_matches() {
+        let labels = ["documentation", "bug", "duplicate", "needs-docs"];
+        assert_eq!(fuzzy_filter("doc", &labels), vec![0, 3]);
+        assert_eq!(fuzzy_filter("", &labels), vec![0, 1, 2, 3]);
+    }
+}
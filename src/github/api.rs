@@ -0,0 +1,1509 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::checks::{CheckAnnotation, CheckRun, Checks, CombinedStatus};
+use super::client::GhClient;
+use super::comment::{
+    review_comment_payload, CommentRange, DiscussionComment, DraftComment, Review, ReviewComment,
+    ReviewThread, ThreadState,
+};
+use super::error::GitHubError;
+use super::http::HttpClient;
+use super::metadata::{encode_path_segment, PrMetadata};
+use super::pr::{
+    build_review_payload, review_event, BranchProtection, ChangedFile, Label, MergeMethod,
+    PrCommit, PrListPage, PrListQuery, PullRequest, PullRequestSummary, SearchPullRequest, User,
+    ViewedFiles, MAX_CHANGED_FILES, MAX_PR_COMMITS,
+};
+use super::rate_limit::RateLimit;
+use super::reaction::{ReactionKind, ReactionTarget};
+use crate::app::ReviewAction;
+use crate::config::{GitHubBackend, GitHubConfig};
+use crate::editor::CommitMessage;
+
+/// REST の 1 ページあたりの最大件数
+const PER_PAGE: usize = 100;
+
+const REVIEW_THREADS_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!, $cursor: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      reviewThreads(first: 100, after: $cursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes {
+          id
+          isResolved
+          isOutdated
+          comments(first: 100) { nodes { databaseId } }
+        }
+      }
+    }
+  }
+}
+"#;
+
+const RESOLVE_THREAD_MUTATION: &str = r#"
+mutation($threadId: ID!) {
+  resolveReviewThread(input: { threadId: $threadId }) { thread { id isResolved } }
+}
+"#;
+
+const UNRESOLVE_THREAD_MUTATION: &str = r#"
+mutation($threadId: ID!) {
+  unresolveReviewThread(input: { threadId: $threadId }) { thread { id isResolved } }
+}
+"#;
+
+/// PR 一覧の 1 行分の項目（検索・リポジトリの PR 一覧で共通）
+const PR_SUMMARY_FRAGMENT: &str = r#"
+fragment PrSummary on PullRequest {
+  number
+  title
+  state
+  author { login }
+  isDraft
+  labels(first: 20) { nodes { name } }
+  updatedAt
+  reviewDecision
+  additions
+  deletions
+  commits(last: 1) { nodes { commit { statusCheckRollup { state } } } }
+  repository { nameWithOwner }
+}
+"#;
+
+const PR_SEARCH_QUERY: &str = r#"
+query($query: String!, $first: Int!, $cursor: String) {
+  search(query: $query, type: ISSUE, first: $first, after: $cursor) {
+    issueCount
+    pageInfo { hasNextPage endCursor }
+    nodes { ...PrSummary }
+  }
+}
+"#;
+
+/// 検索修飾子がない場合の PR 一覧（検索 API の 1,000 件の上限がない）
+const REPOSITORY_PRS_QUERY: &str = r#"
+query($owner: String!, $name: String!, $first: Int!, $cursor: String, $states: [PullRequestState!], $orderBy: IssueOrder) {
+  repository(owner: $owner, name: $name) {
+    pullRequests(first: $first, after: $cursor, states: $states, orderBy: $orderBy) {
+      totalCount
+      pageInfo { hasNextPage endCursor }
+      nodes { ...PrSummary }
+    }
+  }
+}
+"#;
+
+const VIEWED_FILES_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!, $cursor: String) {
+  repository(owner: $owner, name: $name) {
+    pullRequest(number: $number) {
+      id
+      files(first: 100, after: $cursor) {
+        pageInfo { hasNextPage endCursor }
+        nodes { path viewerViewedState }
+      }
+    }
+  }
+}
+"#;
+
+const BRANCH_PROTECTION_QUERY: &str = r#"
+query($owner: String!, $name: String!, $branch: String!) {
+  repository(owner: $owner, name: $name) {
+    ref(qualifiedName: $branch) {
+      branchProtectionRule {
+        requiresApprovingReviews
+        requiredApprovingReviewCount
+        requiresStatusChecks
+        requiredStatusCheckContexts
+      }
+      rules(first: 100) {
+        nodes {
+          type
+          parameters {
+            ... on PullRequestParameters { requiredApprovingReviewCount }
+            ... on RequiredStatusChecksParameters { requiredStatusChecks { context } }
+          }
+        }
+      }
+    }
+  }
+}
+"#;
+
+const MARK_FILE_AS_VIEWED_MUTATION: &str = r#"
+mutation($pullRequestId: ID!, $path: String!) {
+  markFileAsViewed(input: { pullRequestId: $pullRequestId, path: $path }) { clientMutationId }
+}
+"#;
+
+const UNMARK_FILE_AS_VIEWED_MUTATION: &str = r#"
+mutation($pullRequestId: ID!, $path: String!) {
+  unmarkFileAsViewed(input: { pullRequestId: $pullRequestId, path: $path }) { clientMutationId }
+}
+"#;
+
+const CONVERT_TO_DRAFT_MUTATION: &str = r#"
+mutation($pullRequestId: ID!) {
+  convertPullRequestToDraft(input: { pullRequestId: $pullRequestId }) { pullRequest { isDraft } }
+}
+"#;
+
+const MARK_READY_FOR_REVIEW_MUTATION: &str = r#"
+mutation($pullRequestId: ID!) {
+  markPullRequestReadyForReview(input: { pullRequestId: $pullRequestId }) { pullRequest { isDraft } }
+}
+"#;
+
+const ENABLE_AUTO_MERGE_MUTATION: &str = r#"
+mutation($pullRequestId: ID!, $mergeMethod: PullRequestMergeMethod!, $commitHeadline: String, $commitBody: String) {
+  enablePullRequestAutoMerge(input: {
+    pullRequestId: $pullRequestId,
+    mergeMethod: $mergeMethod,
+    commitHeadline: $commitHeadline,
+    commitBody: $commitBody
+  }) { pullRequest { number } }
+}
+"#;
+
+const CREATE_COMMIT_ON_BRANCH_MUTATION: &str = r#"
+mutation($input: CreateCommitOnBranchInput!) {
+  createCommitOnBranch(input: $input) { commit { oid } }
+}
+"#;
+
+/// REST API の HTTP メソッド
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpMethod {
+    Get,
+    Post,
+    Patch,
+    Put,
+    Delete,
+}
+
+impl HttpMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Get => "GET",
+            Self::Post => "POST",
+            Self::Patch => "PATCH",
+            Self::Put => "PUT",
+            Self::Delete => "DELETE",
+        }
+    }
+}
+
+/// GitHub API へのアクセスを抽象化するトレイト
+///
+/// バックエンドは `rest` / `graphql` / `fetch_pr_diff` の 3 つのプリミティブだけを
+/// 実装すればよく、PR・コメント・レビュー操作はそれらの上のデフォルト実装で提供する。
+/// バックエンド固有の高速パスがある場合（`gh pr review` など）は個別に上書きできる。
+#[async_trait]
+pub trait GitHubApi: Send + Sync {
+    /// 接続先ホスト名（`github.com` または GitHub Enterprise Server のホスト）
+    fn host(&self) -> &str;
+
+    /// 直近のレスポンスから分かる API の残りクォータ（不明なら None）
+    fn rate_limit(&self) -> Option<RateLimit> {
+        None
+    }
+
+    /// REST API を呼び出す（`endpoint` は `repos/{owner}/{repo}/...` 形式の相対パス）
+    async fn rest(&self, method: HttpMethod, endpoint: &str, body: Option<&Value>)
+        -> Result<Value>;
+
+    /// GraphQL API を呼び出し、レスポンスの `data` を返す
+    async fn graphql(&self, query: &str, variables: Value) -> Result<Value>;
+
+    /// PR 全体の差分を unified diff 形式で取得
+    async fn fetch_pr_diff(&self, repo: &str, pr_number: u32) -> Result<String>;
+
+    /// 配列を返す REST エンドポイントを `per_page=100` でページを辿って取得する
+    ///
+    /// 最終ページ（100 件未満）に達するか `max_items` 件を超えた時点で打ち切る。
+    async fn rest_paginated(&self, endpoint: &str, max_items: usize) -> Result<Vec<Value>> {
+        let separator = if endpoint.contains('?') { '&' } else { '?' };
+        let mut all_items = Vec::new();
+        for page in 1.. {
+            let paged = format!(
+                "{}{}per_page={}&page={}",
+                endpoint, separator, PER_PAGE, page
+            );
+            let Value::Array(items) = self.rest(HttpMethod::Get, &paged, None).await? else {
+                anyhow::bail!("Expected a JSON array from {}", endpoint);
+            };
+            let exhausted = items.len() < PER_PAGE;
+            all_items.extend(items);
+            if exhausted || all_items.len() >= max_items {
+                break;
+            }
+        }
+        Ok(all_items)
+    }
+
+    async fn fetch_pr(&self, repo: &str, pr_number: u32) -> Result<PullRequest> {
+        let endpoint = format!("repos/{}/pulls/{}", repo, pr_number);
+        let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+        parse(json, "Failed to parse PR response")
+    }
+
+    /// ベースブランチの必須レビュー・必須チェック（ブランチ保護ルールとルールセット）
+    async fn fetch_branch_protection(&self, repo: &str, branch: &str) -> Result<BranchProtection> {
+        let (owner, name) = repo
+            .split_once('/')
+            .with_context(|| format!("Invalid repository: {}", repo))?;
+        let variables = serde_json::json!({
+            "owner": owner,
+            "name": name,
+            "branch": format!("refs/heads/{}", branch),
+        });
+        let data = self.graphql(BRANCH_PROTECTION_QUERY, variables).await?;
+        Ok(BranchProtection::from_graphql_ref(
+            &data["repository"]["ref"],
+        ))
+    }
+
+    /// 変更ファイル一覧（GitHub 側の上限 [`MAX_CHANGED_FILES`] 件まで）
+    async fn fetch_changed_files(&self, repo: &str, pr_number: u32) -> Result<Vec<ChangedFile>> {
+        let endpoint = format!("repos/{}/pulls/{}/files", repo, pr_number);
+        let items = self.rest_paginated(&endpoint, MAX_CHANGED_FILES).await?;
+        parse(
+            Value::Array(items),
+            "Failed to parse changed files response",
+        )
+    }
+
+    /// PR のコミット一覧（古い順、GitHub 側の上限 [`MAX_PR_COMMITS`] 件まで）
+    async fn fetch_pr_commits(&self, repo: &str, pr_number: u32) -> Result<Vec<PrCommit>> {
+        let endpoint = format!("repos/{}/pulls/{}/commits", repo, pr_number);
+        let items = self.rest_paginated(&endpoint, MAX_PR_COMMITS).await?;
+        parse(Value::Array(items), "Failed to parse PR commits response")
+    }
+
+    /// 1 コミットの変更ファイル（GitHub 側の上限 [`MAX_CHANGED_FILES`] 件まで）
+    ///
+    /// レスポンスはコミットのオブジェクトなので、`files` が 1 ページ分に満たなくなるまでページを辿る。
+    async fn fetch_commit_files(&self, repo: &str, sha: &str) -> Result<Vec<ChangedFile>> {
+        let mut files = Vec::new();
+        for page in 1.. {
+            let endpoint = format!(
+                "repos/{}/commits/{}?per_page={}&page={}",
+                repo, sha, PER_PAGE, page
+            );
+            let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+            let items: Vec<ChangedFile> = parse(
+                json.get("files").cloned().unwrap_or(Value::Array(vec![])),
+                "Failed to parse commit response",
+            )?;
+            let exhausted = items.len() < PER_PAGE;
+            files.extend(items);
+            if exhausted || files.len() >= MAX_CHANGED_FILES {
+                break;
+            }
+        }
+        Ok(files)
+    }
+
+    /// 2 つのコミット間の変更ファイル（`compare/{base}...{head}`）
+    ///
+    /// GitHub は最初の [`MAX_COMPARE_FILES`](super::pr::MAX_COMPARE_FILES) 件しか返さない（ページ送りでも続きは取れない）。
+    async fn fetch_compare_files(
+        &self,
+        repo: &str,
+        base: &str,
+        head: &str,
+    ) -> Result<Vec<ChangedFile>> {
+        let endpoint = format!("repos/{}/compare/{}...{}", repo, base, head);
+        let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+        parse(
+            json.get("files").cloned().unwrap_or(Value::Array(vec![])),
+            "Failed to parse compare response",
+        )
+    }
+
+    async fn fetch_review_comments(
+        &self,
+        repo: &str,
+        pr_number: u32,
+    ) -> Result<Vec<ReviewComment>> {
+        let endpoint = format!("repos/{}/pulls/{}/comments", repo, pr_number);
+        let items = self.rest_paginated(&endpoint, usize::MAX).await?;
+        parse(
+            Value::Array(items),
+            "Failed to parse review comments response",
+        )
+    }
+
+    async fn fetch_discussion_comments(
+        &self,
+        repo: &str,
+        pr_number: u32,
+    ) -> Result<Vec<DiscussionComment>> {
+        let endpoint = format!("repos/{}/issues/{}/comments", repo, pr_number);
+        let items = self.rest_paginated(&endpoint, usize::MAX).await?;
+        parse(
+            Value::Array(items),
+            "Failed to parse discussion comments response",
+        )
+    }
+
+    async fn fetch_reviews(&self, repo: &str, pr_number: u32) -> Result<Vec<Review>> {
+        let endpoint = format!("repos/{}/pulls/{}/reviews", repo, pr_number);
+        let items = self.rest_paginated(&endpoint, usize::MAX).await?;
+        parse(Value::Array(items), "Failed to parse reviews response")
+    }
+
+    /// 認証中ユーザーが最後にレビューを送信した時点の head コミット
+    ///
+    /// 送信前（PENDING）のレビューは対象外。該当するレビューがなければ None。
+    async fn fetch_last_reviewed_sha(&self, repo: &str, pr_number: u32) -> Result<Option<String>> {
+        let (login, reviews) = tokio::try_join!(
+            self.fetch_viewer_login(),
+            self.fetch_reviews(repo, pr_number)
+        )?;
+        Ok(reviews
+            .into_iter()
+            .filter(|r| r.user.login == login && r.state != "PENDING")
+            .filter_map(|r| r.commit_id)
+            .next_back())
+    }
+
+    async fn create_review_comment(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        commit_id: &str,
+        path: &str,
+        range: CommentRange,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        let endpoint = format!("repos/{}/pulls/{}/comments", repo, pr_number);
+        let payload = review_comment_payload(commit_id, path, range, body);
+        let json = self
+            .rest(HttpMethod::Post, &endpoint, Some(&payload))
+            .await?;
+        parse(json, "Failed to parse created comment response")
+    }
+
+    async fn create_reply_comment(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        let endpoint = format!(
+            "repos/{}/pulls/{}/comments/{}/replies",
+            repo, pr_number, comment_id
+        );
+        let payload = serde_json::json!({ "body": body });
+        let json = self
+            .rest(HttpMethod::Post, &endpoint, Some(&payload))
+            .await?;
+        parse(json, "Failed to parse reply comment response")
+    }
+
+    /// PR の会話タブにコメントを投稿する
+    async fn create_discussion_comment(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        body: &str,
+    ) -> Result<DiscussionComment> {
+        let endpoint = format!("repos/{}/issues/{}/comments", repo, pr_number);
+        let payload = serde_json::json!({ "body": body });
+        let json = self
+            .rest(HttpMethod::Post, &endpoint, Some(&payload))
+            .await?;
+        parse(json, "Failed to parse created comment response")
+    }
+
+    async fn update_review_comment(
+        &self,
+        repo: &str,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<ReviewComment> {
+        let endpoint = format!("repos/{}/pulls/comments/{}", repo, comment_id);
+        let payload = serde_json::json!({ "body": body });
+        let json = self
+            .rest(HttpMethod::Patch, &endpoint, Some(&payload))
+            .await?;
+        parse(json, "Failed to parse updated comment response")
+    }
+
+    async fn delete_review_comment(&self, repo: &str, comment_id: u64) -> Result<()> {
+        let endpoint = format!("repos/{}/pulls/comments/{}", repo, comment_id);
+        self.rest(HttpMethod::Delete, &endpoint, None).await?;
+        Ok(())
+    }
+
+    async fn update_discussion_comment(
+        &self,
+        repo: &str,
+        comment_id: u64,
+        body: &str,
+    ) -> Result<DiscussionComment> {
+        let endpoint = format!("repos/{}/issues/comments/{}", repo, comment_id);
+        let payload = serde_json::json!({ "body": body });
+        let json = self
+            .rest(HttpMethod::Patch, &endpoint, Some(&payload))
+            .await?;
+        parse(json, "Failed to parse updated comment response")
+    }
+
+    async fn delete_discussion_comment(&self, repo: &str, comment_id: u64) -> Result<()> {
+        let endpoint = format!("repos/{}/issues/comments/{}", repo, comment_id);
+        self.rest(HttpMethod::Delete, &endpoint, None).await?;
+        Ok(())
+    }
+
+    /// コメントまたは PR にリアクションを付ける（付与済みなら何もしない）
+    async fn add_reaction(
+        &self,
+        repo: &str,
+        target: ReactionTarget,
+        kind: ReactionKind,
+    ) -> Result<()> {
+        let payload = serde_json::json!({ "content": kind.content() });
+        self.rest(HttpMethod::Post, &target.endpoint(repo), Some(&payload))
+            .await?;
+        Ok(())
+    }
+
+    /// 認証中ユーザーのログイン名（自分のコメントの判定に使う）
+    async fn fetch_viewer_login(&self) -> Result<String> {
+        let json = self.rest(HttpMethod::Get, "user", None).await?;
+        let user: User = parse(json, "Failed to parse user response")?;
+        Ok(user.login)
+    }
+
+    /// head コミットの Check Run 一覧
+    ///
+    /// レスポンスは `{ total_count, check_runs }` のオブジェクトなので
+    /// [`GitHubApi::rest_paginated`] は使わず、`total_count` に達するまでページを辿る。
+    async fn fetch_check_runs(&self, repo: &str, sha: &str) -> Result<Vec<CheckRun>> {
+        let mut runs = Vec::new();
+        for page in 1.. {
+            let endpoint = format!(
+                "repos/{}/commits/{}/check-runs?per_page={}&page={}",
+                repo, sha, PER_PAGE, page
+            );
+            let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+            let total = json
+                .get("total_count")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as usize;
+            let items: Vec<CheckRun> = parse(
+                json.get("check_runs").cloned().unwrap_or(Value::Null),
+                "Failed to parse check runs response",
+            )?;
+            let exhausted = items.len() < PER_PAGE;
+            runs.extend(items);
+            if exhausted || runs.len() >= total {
+                break;
+            }
+        }
+        Ok(runs)
+    }
+
+    /// head コミットのコミットステータス（Statuses API で報告する外部 CI）
+    async fn fetch_combined_status(&self, repo: &str, sha: &str) -> Result<CombinedStatus> {
+        let endpoint = format!("repos/{}/commits/{}/status", repo, sha);
+        let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+        parse(json, "Failed to parse commit status response")
+    }
+
+    /// Check Run と コミットステータスをまとめて取得
+    async fn fetch_checks(&self, repo: &str, sha: &str) -> Result<Checks> {
+        let (runs, status) = tokio::try_join!(
+            self.fetch_check_runs(repo, sha),
+            self.fetch_combined_status(repo, sha)
+        )?;
+        Ok(Checks {
+            sha: sha.to_string(),
+            runs,
+            statuses: status.statuses,
+        })
+    }
+
+    /// Check Run のアノテーション（失敗箇所などのログ要約）
+    async fn fetch_check_annotations(
+        &self,
+        repo: &str,
+        check_run_id: u64,
+    ) -> Result<Vec<CheckAnnotation>> {
+        let endpoint = format!("repos/{}/check-runs/{}/annotations", repo, check_run_id);
+        let items = self.rest_paginated(&endpoint, usize::MAX).await?;
+        parse(
+            Value::Array(items),
+            "Failed to parse check annotations response",
+        )
+    }
+
+    /// 複数の Check Run のアノテーションをまとめて取得し、取得元の Check Run 名を付ける
+    async fn fetch_annotations_for_runs(
+        &self,
+        repo: &str,
+        runs: &[CheckRun],
+    ) -> Result<Vec<CheckAnnotation>> {
+        let mut all = Vec::new();
+        for run in runs.iter().filter(|r| r.output.annotations_count > 0) {
+            let mut annotations = self.fetch_check_annotations(repo, run.id).await?;
+            for annotation in &mut annotations {
+                annotation.check_name = run.name.clone();
+            }
+            all.extend(annotations);
+        }
+        Ok(all)
+    }
+
+    /// 保留中のインラインコメントを含めてレビューを一括送信する
+    ///
+    /// コメントとレビュー本文は1リクエストで送られるため、途中失敗で一部だけ
+    /// 投稿される状態にはならない。
+    async fn create_review(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        commit_id: &str,
+        action: ReviewAction,
+        body: &str,
+        comments: &[DraftComment],
+    ) -> Result<()> {
+        let endpoint = format!("repos/{}/pulls/{}/reviews", repo, pr_number);
+        let payload = build_review_payload(commit_id, action, body, comments);
+        self.rest(HttpMethod::Post, &endpoint, Some(&payload))
+            .await?;
+        Ok(())
+    }
+
+    /// レビュースレッド（解決状態・outdated・所属コメント ID）を GraphQL で取得
+    async fn fetch_review_threads(&self, repo: &str, pr_number: u32) -> Result<Vec<ReviewThread>> {
+        let (owner, name) = repo
+            .split_once('/')
+            .with_context(|| format!("Invalid repository: {}", repo))?;
+        let mut threads = Vec::new();
+        let mut cursor: Option<String> = None;
+        loop {
+            let variables = serde_json::json!({
+                "owner": owner,
+                "name": name,
+                "number": pr_number,
+                "cursor": cursor,
+            });
+            let data = self.graphql(REVIEW_THREADS_QUERY, variables).await?;
+            let connection = &data["repository"]["pullRequest"]["reviewThreads"];
+            let nodes = connection["nodes"]
+                .as_array()
+                .context("Failed to parse review threads response")?;
+            for node in nodes {
+                let comment_ids = node["comments"]["nodes"]
+                    .as_array()
+                    .map(|c| c.iter().filter_map(|c| c["databaseId"].as_u64()).collect())
+                    .unwrap_or_default();
+                threads.push(ReviewThread {
+                    state: ThreadState {
+                        id: node["id"].as_str().unwrap_or_default().to_string(),
+                        is_resolved: node["isResolved"].as_bool().unwrap_or(false),
+                        is_outdated: node["isOutdated"].as_bool().unwrap_or(false),
+                    },
+                    comment_ids,
+                });
+            }
+            let page_info = &connection["pageInfo"];
+            match page_info["endCursor"].as_str() {
+                Some(end) if page_info["hasNextPage"].as_bool() == Some(true) => {
+                    cursor = Some(end.to_string());
+                }
+                _ => break,
+            }
+        }
+        Ok(threads)
+    }
+
+    /// スレッドを解決済み / 未解決にする
+    async fn set_thread_resolved(&self, thread_id: &str, resolved: bool) -> Result<()> {
+        let mutation = if resolved {
+            RESOLVE_THREAD_MUTATION
+        } else {
+            UNRESOLVE_THREAD_MUTATION
+        };
+        self.graphql(mutation, serde_json::json!({ "threadId": thread_id }))
+            .await?;
+        Ok(())
+    }
+
+    /// 自分が「Viewed」にしたファイルの一覧を GraphQL で取得
+    async fn fetch_viewed_files(&self, repo: &str, pr_number: u32) -> Result<ViewedFiles> {
+        let (owner, name) = repo
+            .split_once('/')
+            .with_context(|| format!("Invalid repository: {}", repo))?;
+        let mut viewed = ViewedFiles::default();
+        let mut cursor: Option<String> = None;
+        loop {
+            let variables = serde_json::json!({
+                "owner": owner,
+                "name": name,
+                "number": pr_number,
+                "cursor": cursor,
+            });
+            let data = self.graphql(VIEWED_FILES_QUERY, variables).await?;
+            let pull_request = &data["repository"]["pullRequest"];
+            viewed.pull_request_id = pull_request["id"]
+                .as_str()
+                .context("Failed to parse viewed files response")?
+                .to_string();
+            let connection = &pull_request["files"];
+            let nodes = connection["nodes"]
+                .as_array()
+                .context("Failed to parse viewed files response")?;
+            viewed.paths.extend(
+                nodes
+                    .iter()
+                    .filter(|node| node["viewerViewedState"] == "VIEWED")
+                    .filter_map(|node| node["path"].as_str().map(str::to_string)),
+            );
+            let page_info = &connection["pageInfo"];
+            match page_info["endCursor"].as_str() {
+                Some(end) if page_info["hasNextPage"].as_bool() == Some(true) => {
+                    cursor = Some(end.to_string());
+                }
+                _ => break,
+            }
+        }
+        Ok(viewed)
+    }
+
+    /// ファイルの「Viewed」チェックを付ける / 外す
+    async fn set_file_viewed(&self, pull_request_id: &str, path: &str, viewed: bool) -> Result<()> {
+        let mutation = if viewed {
+            MARK_FILE_AS_VIEWED_MUTATION
+        } else {
+            UNMARK_FILE_AS_VIEWED_MUTATION
+        };
+        let variables = serde_json::json!({ "pullRequestId": pull_request_id, "path": path });
+        self.graphql(mutation, variables).await?;
+        Ok(())
+    }
+
+    /// レビュワー・担当者・ラベルなどのメタデータ
+    async fn fetch_pr_metadata(&self, repo: &str, pr_number: u32) -> Result<PrMetadata> {
+        let endpoint = format!("repos/{}/pulls/{}", repo, pr_number);
+        let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+        parse(json, "Failed to parse PR metadata response")
+    }
+
+    /// リポジトリのラベル名一覧
+    async fn fetch_repo_labels(&self, repo: &str) -> Result<Vec<String>> {
+        let endpoint = format!("repos/{}/labels", repo);
+        let items = self.rest_paginated(&endpoint, usize::MAX).await?;
+        let labels: Vec<Label> = parse(Value::Array(items), "Failed to parse labels response")?;
+        Ok(labels.into_iter().map(|l| l.name).collect())
+    }
+
+    /// レビュー依頼・アサインが可能なユーザーのログイン名一覧
+    async fn fetch_assignable_users(&self, repo: &str) -> Result<Vec<String>> {
+        let endpoint = format!("repos/{}/assignees", repo);
+        let items = self.rest_paginated(&endpoint, usize::MAX).await?;
+        let users: Vec<User> = parse(Value::Array(items), "Failed to parse assignees response")?;
+        Ok(users.into_iter().map(|u| u.login).collect())
+    }
+
+    async fn add_label(&self, repo: &str, pr_number: u32, label: &str) -> Result<()> {
+        let endpoint = format!("repos/{}/issues/{}/labels", repo, pr_number);
+        let payload = serde_json::json!({ "labels": [label] });
+        self.rest(HttpMethod::Post, &endpoint, Some(&payload))
+            .await?;
+        Ok(())
+    }
+
+    async fn remove_label(&self, repo: &str, pr_number: u32, label: &str) -> Result<()> {
+        let endpoint = format!(
+            "repos/{}/issues/{}/labels/{}",
+            repo,
+            pr_number,
+            encode_path_segment(label)
+        );
+        self.rest(HttpMethod::Delete, &endpoint, None).await?;
+        Ok(())
+    }
+
+    /// レビューを依頼する / 依頼を取り消す
+    async fn set_review_requested(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        login: &str,
+        requested: bool,
+    ) -> Result<()> {
+        let endpoint = format!("repos/{}/pulls/{}/requested_reviewers", repo, pr_number);
+        let method = if requested {
+            HttpMethod::Post
+        } else {
+            HttpMethod::Delete
+        };
+        let payload = serde_json::json!({ "reviewers": [login] });
+        self.rest(method, &endpoint, Some(&payload)).await?;
+        Ok(())
+    }
+
+    /// Draft ⇔ Ready for review を切り替える
+    async fn set_draft(&self, pull_request_id: &str, draft: bool) -> Result<()> {
+        let mutation = if draft {
+            CONVERT_TO_DRAFT_MUTATION
+        } else {
+            MARK_READY_FOR_REVIEW_MUTATION
+        };
+        self.graphql(
+            mutation,
+            serde_json::json!({ "pullRequestId": pull_request_id }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// PR をマージする
+    ///
+    /// `head_sha` を渡すことで、確認後に head が更新されていた場合は GitHub 側で拒否される。
+    /// `message` が None の場合（rebase など）は GitHub のデフォルトのコミットメッセージになる。
+    async fn merge_pr(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        method: MergeMethod,
+        head_sha: &str,
+        message: Option<&CommitMessage>,
+    ) -> Result<()> {
+        let endpoint = format!("repos/{}/pulls/{}/merge", repo, pr_number);
+        let mut payload = serde_json::json!({
+            "merge_method": method.api_value(),
+            "sha": head_sha,
+        });
+        if let Some(message) = message {
+            payload["commit_title"] = Value::String(message.title.clone());
+            payload["commit_message"] = Value::String(message.body.clone());
+        }
+        self.rest(HttpMethod::Put, &endpoint, Some(&payload))
+            .await?;
+        Ok(())
+    }
+
+    /// 必須チェック・レビューが揃ったら自動でマージされるようにする
+    async fn enable_auto_merge(
+        &self,
+        pull_request_id: &str,
+        method: MergeMethod,
+        message: Option<&CommitMessage>,
+    ) -> Result<()> {
+        let variables = serde_json::json!({
+            "pullRequestId": pull_request_id,
+            "mergeMethod": method.graphql_value(),
+            "commitHeadline": message.map(|m| m.title.as_str()),
+            "commitBody": message.map(|m| m.body.as_str()),
+        });
+        self.graphql(ENABLE_AUTO_MERGE_MUTATION, variables).await?;
+        Ok(())
+    }
+
+    /// インラインコメントなしでレビューを送信する（commit は PR の最新 head）
+    async fn submit_review(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        action: ReviewAction,
+        body: &str,
+    ) -> Result<()> {
+        let endpoint = format!("repos/{}/pulls/{}/reviews", repo, pr_number);
+        let payload = serde_json::json!({ "event": review_event(action), "body": body });
+        self.rest(HttpMethod::Post, &endpoint, Some(&payload))
+            .await?;
+        Ok(())
+    }
+
+    /// `git_ref` 時点のファイルの内容を取得する（contents API の上限は 1 MB）
+    async fn fetch_file_content(&self, repo: &str, path: &str, git_ref: &str) -> Result<String> {
+        let encoded_path: Vec<String> = path.split('/').map(encode_path_segment).collect();
+        let endpoint = format!(
+            "repos/{}/contents/{}?ref={}",
+            repo,
+            encoded_path.join("/"),
+            git_ref
+        );
+        let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+        let content = json["content"].as_str().with_context(|| {
+            format!("No content for {} (not a file, or larger than 1 MB)", path)
+        })?;
+        let bytes = BASE64
+            .decode(content.replace('\n', ""))
+            .with_context(|| format!("Failed to decode the content of {}", path))?;
+        String::from_utf8(bytes).with_context(|| format!("{} is not a UTF-8 text file", path))
+    }
+
+    /// PR の head ブランチがあるリポジトリ（フォークからの PR では base と異なる）
+    async fn fetch_pr_head_repo(&self, repo: &str, pr_number: u32) -> Result<String> {
+        let endpoint = format!("repos/{}/pulls/{}", repo, pr_number);
+        let json = self.rest(HttpMethod::Get, &endpoint, None).await?;
+        json["head"]["repo"]["full_name"]
+            .as_str()
+            .map(str::to_string)
+            .context("The head repository of this PR no longer exists")
+    }
+
+    /// `branch` にファイルの変更を 1 コミットとして積み、新しいコミットの SHA を返す
+    ///
+    /// `expected_head` から branch が進んでいた場合は GitHub 側で拒否される。
+    async fn commit_files(
+        &self,
+        repo: &str,
+        branch: &str,
+        expected_head: &str,
+        message: &CommitMessage,
+        files: &[(String, String)],
+    ) -> Result<String> {
+        let additions: Vec<Value> = files
+            .iter()
+            .map(|(path, content)| {
+                serde_json::json!({ "path": path, "contents": BASE64.encode(content) })
+            })
+            .collect();
+        let mut commit_message = serde_json::json!({ "headline": message.title });
+        if !message.body.is_empty() {
+            commit_message["body"] = Value::String(message.body.clone());
+        }
+        let variables = serde_json::json!({
+            "input": {
+                "branch": { "repositoryNameWithOwner": repo, "branchName": branch },
+                "expectedHeadOid": expected_head,
+                "message": commit_message,
+                "fileChanges": { "additions": additions },
+            }
+        });
+        let data = self
+            .graphql(CREATE_COMMIT_ON_BRANCH_MUTATION, variables)
+            .await?;
+        data["createCommitOnBranch"]["commit"]["oid"]
+            .as_str()
+            .map(str::to_string)
+            .context("Failed to parse createCommitOnBranch response")
+    }
+
+    /// PR一覧の先頭ページを取得
+    async fn fetch_pr_list(
+        &self,
+        repo: &str,
+        query: &PrListQuery,
+        limit: u32,
+    ) -> Result<PrListPage> {
+        self.fetch_pr_list_page(repo, query, None, limit).await
+    }
+
+    /// PR一覧の `cursor` 以降の 1 ページを取得（追加ロード用）
+    ///
+    /// 検索修飾子・並び順・レビュー判定や CI 状態の列を扱うため GraphQL の `search` を使う。
+    /// カーソルで続きから取得するので、何ページ目でも 1 リクエストで済む。
+    async fn fetch_pr_list_page(
+        &self,
+        repo: &str,
+        query: &PrListQuery,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> Result<PrListPage> {
+        let first = limit.min(PER_PAGE as u32);
+        let (mut connection, total_count) = if query.needs_search() {
+            let variables = serde_json::json!({
+                "query": query.search_query(repo),
+                "first": first,
+                "cursor": cursor,
+            });
+            let request = format!("{}{}", PR_SEARCH_QUERY, PR_SUMMARY_FRAGMENT);
+            let mut data = self.graphql(&request, variables).await?;
+            let connection = data["search"].take();
+            let total_count = connection["issueCount"].as_u64();
+            (connection, total_count)
+        } else {
+            let (owner, name) = repo
+                .split_once('/')
+                .with_context(|| format!("Invalid repository: {}", repo))?;
+            let variables = serde_json::json!({
+                "owner": owner,
+                "name": name,
+                "first": first,
+                "cursor": cursor,
+                "states": query.state.graphql_states(),
+                "orderBy": { "field": query.sort.order_field(), "direction": "DESC" },
+            });
+            let request = format!("{}{}", REPOSITORY_PRS_QUERY, PR_SUMMARY_FRAGMENT);
+            let mut data = self.graphql(&request, variables).await?;
+            let connection = data["repository"]["pullRequests"].take();
+            let total_count = connection["totalCount"].as_u64();
+            (connection, total_count)
+        };
+        let nodes: Vec<SearchPullRequest> = parse(
+            connection["nodes"].take(),
+            "Failed to parse PR list response",
+        )?;
+        let page_info = &connection["pageInfo"];
+        let end_cursor = page_info["endCursor"].as_str().map(str::to_string);
+        Ok(PrListPage {
+            items: nodes.into_iter().map(PullRequestSummary::from).collect(),
+            has_more: page_info["hasNextPage"].as_bool() == Some(true) && end_cursor.is_some(),
+            end_cursor,
+            total_count: total_count.map(|n| n as u32),
+        })
+    }
+}
+
+/// 設定されたバックエンド・ホストの API クライアントを生成する
+pub fn new_client(config: &GitHubConfig) -> Arc<dyn GitHubApi> {
+    match config.backend {
+        GitHubBackend::Gh => Arc::new(GhClient::new(config.host())),
+        GitHubBackend::Http => Arc::new(HttpClient::for_host(config.host())),
+    }
+}
+
+fn parse<T: DeserializeOwned>(json: Value, error_context: &'static str) -> Result<T> {
+    serde_json::from_value(json).context(error_context)
+}
+
+/// GraphQL レスポンスから `data` を取り出す（`errors` があればエラーにする）
+pub(super) fn graphql_data(mut response: Value) -> Result<Value> {
+    if let Some(errors) = response.get("errors").and_then(|e| e.as_array()) {
+        let messages: Vec<&str> = errors
+            .iter()
+            .filter_map(|e| e.get("message").and_then(|m| m.as_str()))
+            .collect();
+        if !errors.is_empty() {
+            let message = format!("GraphQL error: {}", messages.join("; "));
+            let classified = errors
+                .iter()
+                .filter_map(|e| e.get("type").and_then(|t| t.as_str()))
+                .find_map(|t| GitHubError::from_graphql_type(t, message.clone()));
+            return Err(match classified {
+                Some(error) => error.into(),
+                None => anyhow::anyhow!(message),
+            });
+        }
+    }
+    match response.get_mut("data") {
+        Some(data) => Ok(data.take()),
+        None => anyhow::bail!("GraphQL response has no data"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::mock::MockGitHub;
+
+    #[test]
+    fn test_graphql_data_extracts_data() {
+        let response = serde_json::json!({ "data": { "viewer": { "login": "octocat" } } });
+        let data = graphql_data(response).unwrap();
+        assert_eq!(data["viewer"]["login"], "octocat");
+    }
+
+    #[test]
+    fn test_graphql_data_surfaces_errors() {
+        let response = serde_json::json!({
+            "data": null,
+            "errors": [{ "message": "Could not resolve" }, { "message": "bad field" }]
+        });
+        let err = graphql_data(response).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GraphQL error: Could not resolve; bad field"
+        );
+    }
+
+    #[test]
+    fn test_graphql_data_classifies_error_types() {
+        let response = serde_json::json!({
+            "data": { "repository": { "pullRequest": null } },
+            "errors": [{ "type": "NOT_FOUND", "message": "Could not resolve to a PullRequest" }]
+        });
+        let err = graphql_data(response).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<GitHubError>(),
+            Some(GitHubError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_review_comment_posts_range() {
+        let mock = MockGitHub::new().with_response(
+            "repos/o/r/pulls/1/comments",
+            serde_json::json!({
+                "id": 10, "path": "a.rs", "line": 12, "side": "RIGHT",
+                "body": "nit", "user": { "login": "me" }, "created_at": "t"
+            }),
+        );
+        let comment = mock
+            .create_review_comment("o/r", 1, "abc", "a.rs", CommentRange::range(10, 12), "nit")
+            .await
+            .unwrap();
+        assert_eq!(comment.id, 10);
+
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 1);
+        let (method, endpoint, body) = &calls[0];
+        assert_eq!(*method, HttpMethod::Post);
+        assert_eq!(endpoint, "repos/o/r/pulls/1/comments");
+        let body = body.as_ref().unwrap();
+        assert_eq!(body["commit_id"], "abc");
+        assert_eq!(body["start_line"], 10);
+        assert_eq!(body["line"], 12);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_discussion_comments_merges_pages() {
+        let comment = |id: u64| {
+            serde_json::json!({
+                "id": id, "body": "hi", "user": { "login": "me" }, "created_at": "t"
+            })
+        };
+        let mock = MockGitHub::new()
+            .with_response(
+                "repos/o/r/issues/1/comments?per_page=100&page=1",
+                Value::Array((1..=100).map(comment).collect()),
+            )
+            .with_response(
+                "repos/o/r/issues/1/comments?per_page=100&page=2",
+                Value::Array((101..=101).map(comment).collect()),
+            );
+
+        let comments = mock.fetch_discussion_comments("o/r", 1).await.unwrap();
+        assert_eq!(comments.len(), 101);
+        assert_eq!(comments[100].id, 101);
+        assert_eq!(mock.calls().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_changed_files_stops_at_github_limit() {
+        let file = |i: usize| {
+            serde_json::json!({
+                "filename": format!("f{}.rs", i), "status": "added",
+                "additions": 1, "deletions": 0
+            })
+        };
+        let mut mock = MockGitHub::new();
+        for page in 1..=MAX_CHANGED_FILES / PER_PAGE {
+            let start = (page - 1) * PER_PAGE;
+            mock = mock.with_response(
+                &format!("repos/o/r/pulls/1/files?per_page=100&page={}", page),
+                Value::Array((start..start + PER_PAGE).map(file).collect()),
+            );
+        }
+
+        let files = mock.fetch_changed_files("o/r", 1).await.unwrap();
+        assert_eq!(files.len(), MAX_CHANGED_FILES);
+        // 上限に達したら次のページは要求しない
+        assert_eq!(mock.calls().len(), MAX_CHANGED_FILES / PER_PAGE);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_list_without_search_pages_repository_prs() {
+        let mock = MockGitHub::new().with_graphql_response(serde_json::json!({
+            "repository": { "pullRequests": {
+                "totalCount": 5000,
+                "pageInfo": { "hasNextPage": true, "endCursor": "c30" },
+                "nodes": []
+            } }
+        }));
+        let query = PrListQuery {
+            state: crate::github::PrStateFilter::Closed,
+            ..Default::default()
+        };
+        let page = mock.fetch_pr_list("o/r", &query, 30).await.unwrap();
+        assert!(page.has_more);
+        assert_eq!(page.total_count, Some(5000));
+
+        let calls = mock.calls();
+        let body = calls[0].2.as_ref().unwrap();
+        assert!(body["query"].as_str().unwrap().contains("pullRequests("));
+        let variables = &body["variables"];
+        assert_eq!(variables["owner"], "o");
+        assert_eq!(variables["name"], "r");
+        assert_eq!(variables["states"], serde_json::json!(["CLOSED", "MERGED"]));
+        assert_eq!(variables["orderBy"]["field"], "UPDATED_AT");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_list_page_resumes_from_cursor() {
+        let summary = |n: u32| {
+            serde_json::json!({
+                "number": n, "title": format!("PR {}", n), "state": "OPEN",
+                "author": { "login": "me" }, "isDraft": false, "labels": { "nodes": [] },
+                "updatedAt": "2024-01-01T00:00:00Z", "reviewDecision": null,
+                "additions": 1, "deletions": 0, "commits": { "nodes": [] },
+                "repository": { "nameWithOwner": "o/r" }
+            })
+        };
+        let page = |range: std::ops::RangeInclusive<u32>, has_next: bool, cursor: &str| {
+            let nodes: Vec<Value> = range.map(summary).collect();
+            serde_json::json!({ "search": {
+                "pageInfo": { "hasNextPage": has_next, "endCursor": cursor },
+                "nodes": nodes
+            } })
+        };
+        let mock = MockGitHub::new()
+            .with_graphql_response(page(1..=30, true, "c30"))
+            .with_graphql_response(page(31..=40, false, "c40"));
+
+        let query = PrListQuery {
+            search: PrListQuery::NEEDS_MY_REVIEW.to_string(),
+            ..Default::default()
+        };
+        let first = mock.fetch_pr_list("o/r", &query, 30).await.unwrap();
+        assert_eq!(first.items.len(), 30);
+        assert!(first.has_more);
+        assert_eq!(first.end_cursor.as_deref(), Some("c30"));
+
+        let second = mock
+            .fetch_pr_list_page("o/r", &query, first.end_cursor.as_deref(), 30)
+            .await
+            .unwrap();
+        assert_eq!(second.items[0].number, 31);
+        assert!(!second.has_more);
+
+        // 2 ページ目も 1 リクエストで、前のページは再取得しない
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 2);
+        let variables = &calls[0].2.as_ref().unwrap()["variables"];
+        assert_eq!(
+            variables["query"],
+            "repo:o/r is:pr is:open review-requested:@me sort:updated-desc"
+        );
+        assert_eq!(variables["first"], 30);
+        assert!(variables["cursor"].is_null());
+        assert_eq!(calls[1].2.as_ref().unwrap()["variables"]["cursor"], "c30");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_review_threads_follows_cursor() {
+        let page = |id: &str, resolved: bool, has_next: bool| {
+            serde_json::json!({ "repository": { "pullRequest": { "reviewThreads": {
+                "pageInfo": { "hasNextPage": has_next, "endCursor": "c1" },
+                "nodes": [{
+                    "id": id, "isResolved": resolved, "isOutdated": false,
+                    "comments": { "nodes": [{ "databaseId": 1 }, { "databaseId": 2 }] }
+                }]
+            } } } })
+        };
+        let mock = MockGitHub::new()
+            .with_graphql_response(page("T_1", true, true))
+            .with_graphql_response(page("T_2", false, false));
+
+        let threads = mock.fetch_review_threads("o/r", 7).await.unwrap();
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].state.id, "T_1");
+        assert!(threads[0].state.is_resolved);
+        assert_eq!(threads[0].comment_ids, vec![1, 2]);
+
+        let calls = mock.calls();
+        let first = calls[0].2.as_ref().unwrap();
+        assert_eq!(first["variables"]["owner"], "o");
+        assert_eq!(first["variables"]["number"], 7);
+        assert!(first["variables"]["cursor"].is_null());
+        assert_eq!(calls[1].2.as_ref().unwrap()["variables"]["cursor"], "c1");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_viewed_files_collects_viewed_paths() {
+        let page = |path: &str, state: &str, has_next: bool| {
+            serde_json::json!({ "repository": { "pullRequest": {
+                "id": "PR_1",
+                "files": {
+                    "pageInfo": { "hasNextPage": has_next, "endCursor": "c1" },
+                    "nodes": [
+                        { "path": path, "viewerViewedState": state },
+                        { "path": "other.rs", "viewerViewedState": "DISMISSED" }
+                    ]
+                }
+            } } })
+        };
+        let mock = MockGitHub::new()
+            .with_graphql_response(page("a.rs", "VIEWED", true))
+            .with_graphql_response(page("b.rs", "UNVIEWED", false))
+            .with_graphql_response(Value::Null);
+
+        let viewed = mock.fetch_viewed_files("o/r", 3).await.unwrap();
+        assert_eq!(viewed.pull_request_id, "PR_1");
+        assert_eq!(viewed.paths.len(), 1);
+        assert!(viewed.paths.contains("a.rs"));
+
+        mock.set_file_viewed("PR_1", "b.rs", false).await.unwrap();
+        let calls = mock.calls();
+        assert_eq!(calls[1].2.as_ref().unwrap()["variables"]["cursor"], "c1");
+        let unmark = calls[2].2.as_ref().unwrap();
+        assert!(unmark["query"]
+            .as_str()
+            .unwrap()
+            .contains("unmarkFileAsViewed("));
+        assert_eq!(unmark["variables"]["path"], "b.rs");
+    }
+
+    #[tokio::test]
+    async fn test_metadata_mutations_hit_expected_endpoints() {
+        let mock = MockGitHub::new()
+            .with_response("repos/o/r/issues/1/labels", serde_json::json!([]))
+            .with_response(
+                "repos/o/r/issues/1/labels/good%20first%20issue",
+                serde_json::json!([]),
+            )
+            .with_response(
+                "repos/o/r/pulls/1/requested_reviewers",
+                serde_json::json!({}),
+            )
+            .with_graphql_response(Value::Null);
+
+        mock.add_label("o/r", 1, "bug").await.unwrap();
+        mock.remove_label("o/r", 1, "good first issue")
+            .await
+            .unwrap();
+        mock.set_review_requested("o/r", 1, "alice", false)
+            .await
+            .unwrap();
+        mock.set_draft("PR_1", false).await.unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].0, HttpMethod::Post);
+        assert_eq!(calls[0].2.as_ref().unwrap()["labels"][0], "bug");
+        assert_eq!(calls[1].0, HttpMethod::Delete);
+        assert_eq!(calls[2].0, HttpMethod::Delete);
+        assert_eq!(calls[2].2.as_ref().unwrap()["reviewers"][0], "alice");
+        assert!(calls[3].2.as_ref().unwrap()["query"]
+            .as_str()
+            .unwrap()
+            .contains("markPullRequestReadyForReview("));
+    }
+
+    #[tokio::test]
+    async fn test_merge_pr_and_enable_auto_merge_payloads() {
+        let mock = MockGitHub::new()
+            .with_response(
+                "repos/o/r/pulls/1/merge",
+                serde_json::json!({ "merged": true }),
+            )
+            .with_graphql_response(Value::Null);
+        let message = CommitMessage {
+            title: "Add feature (#1)".to_string(),
+            body: "details".to_string(),
+        };
+
+        mock.merge_pr("o/r", 1, MergeMethod::Squash, "abc", Some(&message))
+            .await
+            .unwrap();
+        mock.enable_auto_merge("PR_1", MergeMethod::Rebase, None)
+            .await
+            .unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].0, HttpMethod::Put);
+        let merge = calls[0].2.as_ref().unwrap();
+        assert_eq!(merge["merge_method"], "squash");
+        assert_eq!(merge["sha"], "abc");
+        assert_eq!(merge["commit_title"], "Add feature (#1)");
+        let auto_merge = calls[1].2.as_ref().unwrap();
+        assert_eq!(auto_merge["variables"]["mergeMethod"], "REBASE");
+        assert!(auto_merge["variables"]["commitHeadline"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_set_thread_resolved_uses_matching_mutation() {
+        let mock = MockGitHub::new()
+            .with_graphql_response(Value::Null)
+            .with_graphql_response(Value::Null);
+        mock.set_thread_resolved("T_1", true).await.unwrap();
+        mock.set_thread_resolved("T_1", false).await.unwrap();
+
+        let calls = mock.calls();
+        let resolve = calls[0].2.as_ref().unwrap();
+        let query = resolve["query"].as_str().unwrap();
+        assert!(query.contains("resolveReviewThread(") && !query.contains("unresolve"));
+        assert_eq!(resolve["variables"]["threadId"], "T_1");
+        assert!(calls[1].2.as_ref().unwrap()["query"]
+            .as_str()
+            .unwrap()
+            .contains("unresolveReviewThread("));
+    }
+
+    #[tokio::test]
+    async fn test_edit_and_delete_comments_use_comment_endpoints() {
+        let mock = MockGitHub::new()
+            .with_response(
+                "repos/o/r/pulls/comments/10",
+                serde_json::json!({
+                    "id": 10, "path": "a.rs", "line": 1, "body": "fixed",
+                    "user": { "login": "me" }, "created_at": "t"
+                }),
+            )
+            .with_response("repos/o/r/issues/comments/20", Value::Null);
+
+        let updated = mock
+            .update_review_comment("o/r", 10, "fixed")
+            .await
+            .unwrap();
+        assert_eq!(updated.body, "fixed");
+        mock.delete_discussion_comment("o/r", 20).await.unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].0, HttpMethod::Patch);
+        assert_eq!(calls[0].2.as_ref().unwrap()["body"], "fixed");
+        assert_eq!(
+            (calls[1].0, calls[1].1.as_str()),
+            (HttpMethod::Delete, "repos/o/r/issues/comments/20")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_discussion_comment_posts_to_issue() {
+        let mock = MockGitHub::new().with_response(
+            "repos/o/r/issues/3/comments",
+            serde_json::json!({
+                "id": 5, "body": "Thanks!", "user": { "login": "me" }, "created_at": "t"
+            }),
+        );
+        let comment = mock
+            .create_discussion_comment("o/r", 3, "Thanks!")
+            .await
+            .unwrap();
+        assert_eq!(comment.id, 5);
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].0, HttpMethod::Post);
+        assert_eq!(calls[0].2.as_ref().unwrap()["body"], "Thanks!");
+    }
+
+    #[tokio::test]
+    async fn test_add_reaction_posts_content() {
+        let mock = MockGitHub::new().with_response("repos/o/r/issues/3/reactions", Value::Null);
+        mock.add_reaction("o/r", ReactionTarget::PullRequest(3), ReactionKind::Hooray)
+            .await
+            .unwrap();
+
+        let calls = mock.calls();
+        assert_eq!(calls[0].0, HttpMethod::Post);
+        assert_eq!(calls[0].2.as_ref().unwrap()["content"], "hooray");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_checks_combines_runs_and_statuses() {
+        let mock = MockGitHub::new()
+            .with_response(
+                "repos/o/r/commits/abc/check-runs?per_page=100&page=1",
+                serde_json::json!({
+                    "total_count": 1,
+                    "check_runs": [{
+                        "id": 7, "name": "test", "status": "completed", "conclusion": "failure",
+                        "started_at": null, "completed_at": null, "html_url": null,
+                        "output": { "title": "1 failed", "summary": "", "annotations_count": 2 }
+                    }]
+                }),
+            )
+            .with_response(
+                "repos/o/r/commits/abc/status",
+                serde_json::json!({
+                    "state": "success",
+                    "statuses": [{
+                        "context": "ci/ext", "state": "success",
+                        "description": null, "target_url": null
+                    }]
+                }),
+            );
+
+        let checks = mock.fetch_checks("o/r", "abc").await.unwrap();
+        assert_eq!(checks.sha, "abc");
+        assert_eq!(checks.runs[0].output.annotations_count, 2);
+        assert_eq!(checks.statuses[0].context, "ci/ext");
+        assert_eq!(
+            checks.overall(),
+            Some(crate::github::checks::CheckState::Failure)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_compare_files_reads_files_field() {
+        let mock = MockGitHub::new()
+            .with_response(
+                "repos/o/r/compare/aaa...bbb",
+                serde_json::json!({
+                    "status": "ahead",
+                    "files": [{
+                        "filename": "a.rs", "status": "modified",
+                        "additions": 1, "deletions": 0, "patch": "@@ -1 +1,2 @@"
+                    }]
+                }),
+            )
+            .with_response(
+                "repos/o/r/compare/bbb...bbb",
+                serde_json::json!({ "status": "identical" }),
+            );
+
+        let files = mock.fetch_compare_files("o/r", "aaa", "bbb").await.unwrap();
+        assert_eq!(files[0].filename, "a.rs");
+        let files = mock.fetch_compare_files("o/r", "bbb", "bbb").await.unwrap();
+        assert!(files.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_commit_files_follows_pages() {
+        let file = |i: usize| {
+            serde_json::json!({
+                "filename": format!("f{}.rs", i), "status": "added",
+                "additions": 1, "deletions": 0
+            })
+        };
+        let mock = MockGitHub::new()
+            .with_response(
+                "repos/o/r/commits/abc?per_page=100&page=1",
+                serde_json::json!({ "files": (0..100).map(file).collect::<Vec<_>>() }),
+            )
+            .with_response(
+                "repos/o/r/commits/abc?per_page=100&page=2",
+                serde_json::json!({ "files": [file(100)] }),
+            );
+
+        let files = mock.fetch_commit_files("o/r", "abc").await.unwrap();
+        assert_eq!(files.len(), 101);
+        assert_eq!(files[100].filename, "f100.rs");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_last_reviewed_sha_uses_latest_submitted_review_by_viewer() {
+        let review = |login: &str, state: &str, commit_id: &str| {
+            serde_json::json!({
+                "id": 1, "body": "", "state": state, "user": { "login": login },
+                "submitted_at": null, "commit_id": commit_id
+            })
+        };
+        let mock = MockGitHub::new()
+            .with_response("user", serde_json::json!({ "login": "me" }))
+            .with_response(
+                "repos/o/r/pulls/1/reviews?per_page=100&page=1",
+                serde_json::json!([
+                    review("me", "COMMENTED", "aaa"),
+                    review("me", "APPROVED", "bbb"),
+                    review("other", "APPROVED", "ccc"),
+                    review("me", "PENDING", "ddd")
+                ]),
+            )
+            .with_response(
+                "repos/o/r/pulls/2/reviews?per_page=100&page=1",
+                serde_json::json!([]),
+            );
+
+        let sha = mock.fetch_last_reviewed_sha("o/r", 1).await.unwrap();
+        assert_eq!(sha.as_deref(), Some("bbb"));
+        assert_eq!(mock.fetch_last_reviewed_sha("o/r", 2).await.unwrap(), None);
+    }
+}
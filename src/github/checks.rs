@@ -0,0 +1,265 @@
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// CI チェック（Check Run / Commit Status）の集約状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckState {
+    Success,
+    Failure,
+    Pending,
+    /// skipped / neutral / cancelled など、成否に影響しないもの
+    Neutral,
+}
+
+impl CheckState {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Success => "✓",
+            Self::Failure => "✗",
+            Self::Pending => "●",
+            Self::Neutral => "-",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Success => "passing",
+            Self::Failure => "failing",
+            Self::Pending => "pending",
+            Self::Neutral => "neutral",
+        }
+    }
+}
+
+/// GitHub Actions などの Check Run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckRun {
+    pub id: u64,
+    pub name: String,
+    /// queued / in_progress / completed
+    pub status: String,
+    /// success / failure / neutral / cancelled / skipped / timed_out / action_required など
+    pub conclusion: Option<String>,
+    pub started_at: Option<String>,
+    pub completed_at: Option<String>,
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub output: CheckRunOutput,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CheckRunOutput {
+    pub title: Option<String>,
+    pub summary: Option<String>,
+    pub annotations_count: u32,
+}
+
+impl CheckRun {
+    pub fn state(&self) -> CheckState {
+        if self.status != "completed" {
+            return CheckState::Pending;
+        }
+        match self.conclusion.as_deref() {
+            Some("success") => CheckState::Success,
+            Some("failure" | "timed_out" | "action_required" | "startup_failure") => {
+                CheckState::Failure
+            }
+            Some(_) => CheckState::Neutral,
+            None => CheckState::Pending,
+        }
+    }
+
+    /// 実行時間（完了していなければ None）
+    pub fn duration_secs(&self) -> Option<i64> {
+        let started = DateTime::parse_from_rfc3339(self.started_at.as_deref()?).ok()?;
+        let completed = DateTime::parse_from_rfc3339(self.completed_at.as_deref()?).ok()?;
+        Some((completed - started).num_seconds().max(0))
+    }
+}
+
+/// 外部 CI が Statuses API で報告するコミットステータス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitStatus {
+    pub context: String,
+    /// success / failure / error / pending
+    pub state: String,
+    pub description: Option<String>,
+    pub target_url: Option<String>,
+}
+
+impl CommitStatus {
+    pub fn state(&self) -> CheckState {
+        match self.state.as_str() {
+            "success" => CheckState::Success,
+            "failure" | "error" => CheckState::Failure,
+            _ => CheckState::Pending,
+        }
+    }
+}
+
+/// `GET /repos/{repo}/commits/{sha}/status` のレスポンス
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CombinedStatus {
+    pub state: String,
+    #[serde(default)]
+    pub statuses: Vec<CommitStatus>,
+}
+
+/// Check Run のアノテーション（ログの要約として表示する）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckAnnotation {
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// notice / warning / failure
+    pub annotation_level: String,
+    pub title: Option<String>,
+    pub message: String,
+    /// 取得元の Check Run 名（レスポンスには含まれないため取得時に設定する）
+    #[serde(skip)]
+    pub check_name: String,
+}
+
+/// アノテーションの重要度（重いものほど大きい）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AnnotationLevel {
+    Notice,
+    Warning,
+    Failure,
+}
+
+impl CheckAnnotation {
+    pub fn level(&self) -> AnnotationLevel {
+        match self.annotation_level.as_str() {
+            "failure" => AnnotationLevel::Failure,
+            "warning" => AnnotationLevel::Warning,
+            _ => AnnotationLevel::Notice,
+        }
+    }
+}
+
+/// PR の head コミットに対する CI の結果一式
+#[derive(Debug, Clone, Default)]
+pub struct Checks {
+    pub sha: String,
+    pub runs: Vec<CheckRun>,
+    pub statuses: Vec<CommitStatus>,
+}
+
+impl Checks {
+    pub fn is_empty(&self) -> bool {
+        self.runs.is_empty() && self.statuses.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.runs.len() + self.statuses.len()
+    }
+
+    /// 個々のチェックの状態（Check Run → Commit Status の順）
+    pub fn states(&self) -> impl Iterator<Item = CheckState> + '_ {
+        self.runs
+            .iter()
+            .map(CheckRun::state)
+            .chain(self.statuses.iter().map(CommitStatus::state))
+    }
+
+    pub fn count(&self, state: CheckState) -> usize {
+        self.states().filter(|s| *s == state).count()
+    }
+
+    /// 全体の状態: 1 つでも失敗があれば Failure、未完了があれば Pending。
+    /// チェックが 1 つもなければ None
+    pub fn overall(&self) -> Option<CheckState> {
+        if self.is_empty() {
+            return None;
+        }
+        let states: Vec<CheckState> = self.states().collect();
+        Some(if states.contains(&CheckState::Failure) {
+            CheckState::Failure
+        } else if states.contains(&CheckState::Pending) {
+            CheckState::Pending
+        } else {
+            CheckState::Success
+        })
+    }
+}
+
+/// 秒数を "1m 23s" 形式にする
+pub fn format_duration(secs: i64) -> String {
+    match secs {
+        s if s < 60 => format!("{}s", s),
+        s if s < 3600 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}h {}m", s / 3600, (s % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(status: &str, conclusion: Option<&str>) -> CheckRun {
+        CheckRun {
+            id: 1,
+            name: "build".to_string(),
+            status: status.to_string(),
+            conclusion: conclusion.map(str::to_string),
+            started_at: Some("2024-01-01T00:00:00Z".to_string()),
+            completed_at: Some("2024-01-01T00:01:23Z".to_string()),
+            html_url: None,
+            output: CheckRunOutput::default(),
+        }
+    }
+
+    fn status(state: &str) -> CommitStatus {
+        CommitStatus {
+            context: "ci/external".to_string(),
+            state: state.to_string(),
+            description: None,
+            target_url: None,
+        }
+    }
+
+    #[test]
+    fn test_check_run_state_and_duration() {
+        assert_eq!(run("in_progress", None).state(), CheckState::Pending);
+        assert_eq!(
+            run("completed", Some("success")).state(),
+            CheckState::Success
+        );
+        assert_eq!(
+            run("completed", Some("timed_out")).state(),
+            CheckState::Failure
+        );
+        assert_eq!(
+            run("completed", Some("skipped")).state(),
+            CheckState::Neutral
+        );
+        assert_eq!(run("completed", None).duration_secs(), Some(83));
+    }
+
+    #[test]
+    fn test_overall_prefers_failure_then_pending() {
+        let mut checks = Checks::default();
+        assert_eq!(checks.overall(), None);
+
+        checks.runs.push(run("completed", Some("success")));
+        checks.runs.push(run("completed", Some("skipped")));
+        assert_eq!(checks.overall(), Some(CheckState::Success));
+
+        checks.statuses.push(status("pending"));
+        assert_eq!(checks.overall(), Some(CheckState::Pending));
+
+        checks.statuses.push(status("error"));
+        assert_eq!(checks.overall(), Some(CheckState::Failure));
+        assert_eq!(checks.count(CheckState::Success), 1);
+        assert_eq!(checks.len(), 4);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(42), "42s");
+        assert_eq!(format_duration(83), "1m 23s");
+        assert_eq!(format_duration(3720), "1h 2m");
+    }
+}
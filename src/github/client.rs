@@ -1,7 +1,16 @@
 use anyhow::{Context, Result};
-use std::process::Command;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Command, Stdio};
 use thiserror::Error;
 
+use super::api::{graphql_data, GitHubApi, HttpMethod};
+use super::error::GitHubError;
+use super::rate_limit::{RateLimit, RateLimitHeaders, RateLimiter, RequestKind};
+use super::repo_ref::{RepoRef, DEFAULT_HOST};
+use crate::app::ReviewAction;
+
 #[derive(Debug, Error)]
 pub enum DetectRepoError {
     #[error("Not a git repository. Use --repo to specify.")]
@@ -12,28 +21,21 @@ pub enum DetectRepoError {
     GhError(String),
 }
 
-/// Detect the repository name from the current directory using `gh repo view`
-pub async fn detect_repo() -> std::result::Result<String, DetectRepoError> {
+/// Detect the repository (including its host) from the current directory using `gh repo view`
+pub async fn detect_repo() -> std::result::Result<RepoRef, DetectRepoError> {
     let result = tokio::task::spawn_blocking(|| {
         let output = Command::new("gh")
-            .args([
-                "repo",
-                "view",
-                "--json",
-                "nameWithOwner",
-                "-q",
-                ".nameWithOwner",
-            ])
+            .args(["repo", "view", "--json", "url", "-q", ".url"])
             .output();
 
         match output {
             Ok(output) => {
                 if output.status.success() {
-                    let repo = String::from_utf8_lossy(&output.stdout).trim().to_string();
-                    if repo.is_empty() {
+                    let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                    if url.is_empty() {
                         Err(DetectRepoError::NoGitHubRemote)
                     } else {
-                        Ok(repo)
+                        RepoRef::parse(&url).map_err(|e| DetectRepoError::GhError(e.to_string()))
                     }
                 } else {
                     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -65,6 +67,17 @@ pub async fn detect_repo() -> std::result::Result<String, DetectRepoError> {
     }
 }
 
+/// Resolve the PR number for the checked-out branch using `gh pr view`
+pub async fn detect_current_pr() -> Result<u32> {
+    let output = gh_command(&["pr", "view", "--json", "number", "-q", ".number"])
+        .await
+        .context("No pull request found for the current branch")?;
+    output
+        .trim()
+        .parse()
+        .with_context(|| format!("Unexpected gh pr view output: {}", output.trim()))
+}
+
 /// Execute gh CLI command and return stdout
 /// Uses spawn_blocking to avoid blocking the tokio runtime
 pub async fn gh_command(args: &[&str]) -> Result<String> {
@@ -74,11 +87,10 @@ pub async fn gh_command(args: &[&str]) -> Result<String> {
         let output = Command::new("gh")
             .args(&args)
             .output()
-            .context("Failed to execute gh CLI - is it installed?")?;
+            .map_err(spawn_error)?;
 
         if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            anyhow::bail!("gh command failed: {}", stderr);
+            return Err(gh_error(&output.stdout, &output.stderr));
         }
 
         String::from_utf8(output.stdout).context("gh output contains invalid UTF-8")
@@ -87,44 +99,293 @@ pub async fn gh_command(args: &[&str]) -> Result<String> {
     .context("spawn_blocking task panicked")?
 }
 
-/// Execute gh api command with JSON output
-pub async fn gh_api(endpoint: &str) -> Result<serde_json::Value> {
-    let output = gh_command(&["api", endpoint]).await?;
-    serde_json::from_str(&output).context("Failed to parse gh api response as JSON")
+/// Build the error for a failed gh command, classified into [`GitHubError`] where possible
+///
+/// `gh api` writes the JSON error body to stdout (after the headers with `-i`), which
+/// carries the field errors of a 422 response.
+fn gh_error(stdout: &[u8], stderr: &[u8]) -> anyhow::Error {
+    let stderr = String::from_utf8_lossy(stderr);
+    let stdout = String::from_utf8_lossy(stdout);
+    let (_, body) = split_gh_api_output(&stdout);
+    let body = serde_json::from_str::<serde_json::Value>(body).ok();
+    match GitHubError::from_gh_output(&stderr, body.as_ref()) {
+        Some(error) => error.into(),
+        None => anyhow::anyhow!("gh command failed: {}", stderr),
+    }
+}
+
+/// Build the error for a gh process that could not be started
+fn spawn_error(e: std::io::Error) -> anyhow::Error {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        GitHubError::GhNotInstalled.into()
+    } else {
+        anyhow::Error::new(e).context("Failed to execute gh CLI")
+    }
 }
 
-/// Field type for gh api command
-pub enum FieldValue<'a> {
-    /// String field (-f)
-    String(&'a str),
-    /// Raw/typed field (-F) - for integers, booleans, null
-    Raw(&'a str),
+/// Execute gh CLI command with `input` piped to stdin and return stdout
+async fn gh_command_with_stdin(args: &[&str], input: String) -> Result<String> {
+    let args: Vec<String> = args.iter().map(|s| s.to_string()).collect();
+
+    tokio::task::spawn_blocking(move || {
+        let mut child = Command::new("gh")
+            .args(&args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(spawn_error)?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin
+                .write_all(input.as_bytes())
+                .context("Failed to write gh stdin")?;
+        }
+
+        let output = child
+            .wait_with_output()
+            .context("Failed to wait for gh CLI")?;
+
+        if !output.status.success() {
+            return Err(gh_error(&output.stdout, &output.stderr));
+        }
+
+        String::from_utf8(output.stdout).context("gh output contains invalid UTF-8")
+    })
+    .await
+    .context("spawn_blocking task panicked")?
 }
 
-/// Execute gh api with method and fields
-pub async fn gh_api_post(
+/// Execute `gh api` with the given method and optional JSON request body
+///
+/// `-i` makes gh print the response headers, so the remaining quota is recorded in
+/// `limiter`. Empty responses (e.g. `204 No Content`) are returned as `Value::Null`.
+pub async fn gh_api(
+    host: &str,
+    method: HttpMethod,
     endpoint: &str,
-    fields: &[(&str, FieldValue<'_>)],
+    body: Option<&serde_json::Value>,
+    limiter: &RateLimiter,
 ) -> Result<serde_json::Value> {
-    let mut args = vec![
-        "api".to_string(),
-        "--method".to_string(),
-        "POST".to_string(),
-        endpoint.to_string(),
+    let args = [
+        "api",
+        "-i",
+        "--hostname",
+        host,
+        "--method",
+        method.as_str(),
+        endpoint,
     ];
-    for (key, value) in fields {
-        match value {
-            FieldValue::String(v) => {
-                args.push("-f".to_string());
-                args.push(format!("{}={}", key, v));
-            }
-            FieldValue::Raw(v) => {
-                args.push("-F".to_string());
-                args.push(format!("{}={}", key, v));
-            }
+    let output = match body {
+        Some(body) => {
+            let args: Vec<&str> = args.iter().copied().chain(["--input", "-"]).collect();
+            gh_command_with_stdin(&args, body.to_string()).await?
+        }
+        None => gh_command(&args).await?,
+    };
+    let (headers, output) = split_gh_api_output(&output);
+    if let Some(snapshot) = headers.snapshot() {
+        limiter.record(snapshot);
+    }
+    if output.trim().is_empty() {
+        return Ok(serde_json::Value::Null);
+    }
+    serde_json::from_str(output).context("Failed to parse gh api response as JSON")
+}
+
+/// Split `gh api -i` output into the rate limit headers and the response body
+///
+/// The status line and headers come first, followed by an empty line and the body.
+fn split_gh_api_output(output: &str) -> (RateLimitHeaders, &str) {
+    if !output.starts_with("HTTP/") {
+        return (RateLimitHeaders::default(), output);
+    }
+    let mut headers = HashMap::new();
+    let mut body_start = output.len();
+    let mut offset = 0;
+    for line in output.split_inclusive('\n') {
+        offset += line.len();
+        let line = line.trim_end();
+        if line.is_empty() {
+            body_start = offset;
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    let headers = RateLimitHeaders::parse(|name| headers.get(name).cloned());
+    (headers, &output[body_start..])
+}
+
+/// `gh` CLI をバックエンドにした [`GitHubApi`] 実装
+///
+/// 認証は `gh` に任せる。呼び出しごとにプロセスを起動するため
+/// [`HttpClient`](super::HttpClient) より遅いが、追加設定なしで動作する。
+/// 残りクォータは `gh api -i` のレスポンスヘッダーから読み取る。
+#[derive(Debug)]
+pub struct GhClient {
+    host: String,
+    limiter: RateLimiter,
+}
+
+impl Default for GhClient {
+    fn default() -> Self {
+        Self::new(DEFAULT_HOST)
+    }
+}
+
+impl GhClient {
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            limiter: RateLimiter::default(),
         }
     }
-    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    let output = gh_command(&args_refs).await?;
-    serde_json::from_str(&output).context("Failed to parse gh api response as JSON")
+
+    /// `-R` に渡す `HOST/OWNER/REPO`
+    fn repo_arg(&self, repo: &str) -> String {
+        format!("{}/{}", self.host, repo)
+    }
+}
+
+#[async_trait]
+impl GitHubApi for GhClient {
+    fn host(&self) -> &str {
+        &self.host
+    }
+
+    fn rate_limit(&self) -> Option<RateLimit> {
+        self.limiter.snapshot()
+    }
+
+    async fn rest(
+        &self,
+        method: HttpMethod,
+        endpoint: &str,
+        body: Option<&serde_json::Value>,
+    ) -> Result<serde_json::Value> {
+        let kind = match method {
+            HttpMethod::Get => RequestKind::Read,
+            _ => RequestKind::Write,
+        };
+        self.limiter
+            .run(kind, || {
+                gh_api(&self.host, method, endpoint, body, &self.limiter)
+            })
+            .await
+    }
+
+    async fn graphql(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let request = serde_json::json!({ "query": query, "variables": variables });
+        let response = self
+            .limiter
+            .run(RequestKind::of_graphql(query), || {
+                gh_api(
+                    &self.host,
+                    HttpMethod::Post,
+                    "graphql",
+                    Some(&request),
+                    &self.limiter,
+                )
+            })
+            .await?;
+        graphql_data(response)
+    }
+
+    async fn fetch_pr_diff(&self, repo: &str, pr_number: u32) -> Result<String> {
+        let pr_number = pr_number.to_string();
+        let repo_arg = self.repo_arg(repo);
+        let args = ["pr", "diff", &pr_number, "-R", &repo_arg];
+        self.limiter
+            .run(RequestKind::Read, || gh_command(&args))
+            .await
+    }
+
+    async fn submit_review(
+        &self,
+        repo: &str,
+        pr_number: u32,
+        action: ReviewAction,
+        body: &str,
+    ) -> Result<()> {
+        let action_flag = match action {
+            ReviewAction::Approve => "--approve",
+            ReviewAction::RequestChanges => "--request-changes",
+            ReviewAction::Comment => "--comment",
+        };
+
+        let pr_number = pr_number.to_string();
+        let repo_arg = self.repo_arg(repo);
+        let args = [
+            "pr",
+            "review",
+            &pr_number,
+            action_flag,
+            "-b",
+            body,
+            "-R",
+            &repo_arg,
+        ];
+        self.limiter
+            .run(RequestKind::Write, || gh_command(&args))
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_gh_api_output_reads_rate_limit_headers() {
+        let output = "HTTP/2.0 200 OK\r\nContent-Type: application/json\r\n\
+            X-Ratelimit-Limit: 5000\r\nX-Ratelimit-Remaining: 4321\r\n\
+            X-Ratelimit-Reset: 1700000000\r\nX-Ratelimit-Resource: core\r\n\r\n{\"id\":1}";
+        let (headers, body) = split_gh_api_output(output);
+        assert_eq!(
+            headers.snapshot(),
+            Some(RateLimit {
+                resource: "core".to_string(),
+                limit: 5000,
+                remaining: 4321,
+                reset_at: 1_700_000_000,
+            })
+        );
+        assert_eq!(body, "{\"id\":1}");
+
+        // 204 などボディのないレスポンス
+        let (headers, body) =
+            split_gh_api_output("HTTP/2.0 204 No Content\r\nX-Ratelimit-Limit: 5000\r\n\r\n");
+        assert_eq!(headers.limit, Some(5000));
+        assert!(body.is_empty());
+    }
+
+    #[test]
+    fn test_gh_error_reads_field_errors_from_stdout() {
+        // `gh api -i` が 422 で失敗したときの実際の出力
+        let stdout = "HTTP/2.0 422 Unprocessable Entity\r\nContent-Type: application/json; charset=utf-8\r\n\r\n\
+            {\"message\":\"Validation Failed\",\"errors\":[{\"resource\":\"PullRequestReviewComment\",\
+            \"code\":\"custom\",\"field\":\"pull_request_review_thread.line\",\
+            \"message\":\"could not be resolved\"}],\
+            \"documentation_url\":\"https://docs.github.com/rest/pulls/comments#create-a-review-comment-for-a-pull-request\",\
+            \"status\":\"422\"}";
+        let stderr = "gh: Validation Failed (HTTP 422)\n";
+        let error = gh_error(stdout.as_bytes(), stderr.as_bytes());
+        let Some(GitHubError::Validation { errors, .. }) = error.downcast_ref::<GitHubError>()
+        else {
+            panic!("expected a validation error: {:?}", error);
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "PullRequestReviewComment.pull_request_review_thread.line: could not be resolved"
+        );
+    }
 }
@@ -1,35 +1,130 @@
-use anyhow::{Context, Result};
-use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use super::client::{gh_api, gh_api_post, FieldValue};
 use super::pr::User;
-
-/// ジェネリックなfetch & parse関数
-async fn fetch_and_parse<T: DeserializeOwned>(
-    endpoint: &str,
-    error_context: &'static str,
-) -> Result<T> {
-    let json = gh_api(endpoint).await?;
-    serde_json::from_value(json).context(error_context)
-}
+use super::reaction::Reactions;
+use crate::diff::DiffSide;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ReviewComment {
     pub id: u64,
     pub path: String,
     pub line: Option<u32>,
+    /// 複数行コメントの開始行（単一行なら None）
+    #[serde(default)]
+    pub start_line: Option<u32>,
+    /// コメント対象の diff 側（削除行へのコメントは LEFT）
+    #[serde(default)]
+    pub side: Option<DiffSide>,
+    /// コメント時のコミットでの行番号（outdated になっても残る）
+    #[serde(default)]
+    pub original_line: Option<u32>,
+    /// コメント時の head コミット
+    #[serde(default)]
+    pub original_commit_id: Option<String>,
+    /// 最新の diff 内での位置（outdated なら None）
+    #[serde(default)]
+    pub position: Option<u32>,
+    /// コメント時の diff の hunk（最終行がコメント対象の行）
+    #[serde(default)]
+    pub diff_hunk: Option<String>,
     pub body: String,
     pub user: User,
     pub created_at: String,
+    /// 返信先（スレッド先頭）のコメント ID。スレッド先頭のコメントは None
+    #[serde(default)]
+    pub in_reply_to_id: Option<u64>,
+    /// 所属するレビュースレッドの状態（GraphQL で別途取得して付与する）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread: Option<ThreadState>,
+    #[serde(default)]
+    pub reactions: Reactions,
+}
+
+impl ReviewComment {
+    /// 所属スレッドの先頭コメント ID
+    pub fn thread_root_id(&self) -> u64 {
+        self.in_reply_to_id.unwrap_or(self.id)
+    }
+
+    pub fn is_reply(&self) -> bool {
+        self.in_reply_to_id.is_some()
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| t.is_resolved)
+    }
+
+    /// コメント対象の行が最新の diff にない
+    ///
+    /// スレッドの状態が取れない場合も REST の値で判断する。outdated になると
+    /// `position`（と `line`）が null になり、コメント時の `original_line` だけが残る。
+    pub fn is_outdated(&self) -> bool {
+        self.thread.as_ref().is_some_and(|t| t.is_outdated)
+            || (self.original_line.is_some() && (self.position.is_none() || self.line.is_none()))
+    }
+}
+
+/// レビュースレッドの状態（スレッド内の全コメントで共有）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThreadState {
+    /// GraphQL の node ID（`resolveReviewThread` の `threadId`）
+    pub id: String,
+    pub is_resolved: bool,
+    pub is_outdated: bool,
 }
 
-pub async fn fetch_review_comments(repo: &str, pr_number: u32) -> Result<Vec<ReviewComment>> {
-    fetch_and_parse(
-        &format!("repos/{}/pulls/{}/comments", repo, pr_number),
-        "Failed to parse review comments response",
-    )
-    .await
+/// GraphQL `reviewThreads` から取得したスレッド
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReviewThread {
+    pub state: ThreadState,
+    /// スレッド内コメントの REST ID（`databaseId`）
+    pub comment_ids: Vec<u64>,
+}
+
+/// コメントをスレッド単位に並べ替え、スレッドの状態を付与する
+///
+/// スレッドは先頭コメントの作成日時順（outdated なスレッドは最後にまとめる）、
+/// スレッド内は作成日時順に並ぶ。
+/// スレッドに属さないコメント（PR レビュー本文など）は単独のスレッドとして扱う。
+pub fn group_into_threads(
+    comments: Vec<ReviewComment>,
+    threads: &[ReviewThread],
+) -> Vec<ReviewComment> {
+    let states: HashMap<u64, &ThreadState> = threads
+        .iter()
+        .flat_map(|t| t.comment_ids.iter().map(move |id| (*id, &t.state)))
+        .collect();
+
+    let mut group_index: HashMap<u64, usize> = HashMap::new();
+    let mut groups: Vec<Vec<ReviewComment>> = Vec::new();
+    for mut comment in comments {
+        if let Some(state) = states.get(&comment.id) {
+            comment.thread = Some((*state).clone());
+        }
+        let index = *group_index
+            .entry(comment.thread_root_id())
+            .or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+        groups[index].push(comment);
+    }
+
+    for group in &mut groups {
+        group.sort_by(|a, b| {
+            a.is_reply()
+                .cmp(&b.is_reply())
+                .then_with(|| a.created_at.cmp(&b.created_at))
+        });
+    }
+    groups.sort_by(|a, b| {
+        a[0].is_outdated()
+            .cmp(&b[0].is_outdated())
+            .then_with(|| a[0].created_at.cmp(&b[0].created_at))
+    });
+    groups.into_iter().flatten().collect()
 }
 
 /// ディスカッションコメント（PRの会話タブのコメント）
@@ -39,17 +134,8 @@ pub struct DiscussionComment {
     pub body: String,
     pub user: User,
     pub created_at: String,
-}
-
-pub async fn fetch_discussion_comments(
-    repo: &str,
-    pr_number: u32,
-) -> Result<Vec<DiscussionComment>> {
-    fetch_and_parse(
-        &format!("repos/{}/issues/{}/comments", repo, pr_number),
-        "Failed to parse discussion comments response",
-    )
-    .await
+    #[serde(default)]
+    pub reactions: Reactions,
 }
 
 /// PR レビュー（全体コメント）
@@ -60,50 +146,241 @@ pub struct Review {
     pub state: String,
     pub user: User,
     pub submitted_at: Option<String>,
+    /// レビュー時点の head コミット
+    #[serde(default)]
+    pub commit_id: Option<String>,
+}
+
+/// インラインコメントの対象行
+///
+/// `start_line` が Some の場合は `start_line..=line` の複数行コメントになる。
+/// 削除行は旧ファイル側（`side = LEFT`）の行番号で指定する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommentRange {
+    pub line: u32,
+    #[serde(default)]
+    pub side: DiffSide,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_side: Option<DiffSide>,
+}
+
+impl CommentRange {
+    /// 新ファイル側の単一行
+    pub fn single(line: u32) -> Self {
+        Self::between((DiffSide::Right, line), (DiffSide::Right, line))
+    }
+
+    /// 新ファイル側の `start..=end` の範囲（同一行なら単一行として扱う）
+    pub fn range(start: u32, end: u32) -> Self {
+        Self::between((DiffSide::Right, start), (DiffSide::Right, end))
+    }
+
+    /// `(side, line)` で指定した両端の範囲（同一行なら単一行として扱う）
+    pub fn between(start: (DiffSide, u32), end: (DiffSide, u32)) -> Self {
+        let is_single = start == end;
+        Self {
+            line: end.1,
+            side: end.0,
+            start_line: (!is_single).then_some(start.1),
+            start_side: (!is_single).then_some(start.0),
+        }
+    }
+
+    /// 表示用ラベル（"12" / "10-12"、旧ファイル側を含む場合は "L10-R12" 形式）
+    pub fn label(&self) -> String {
+        let start_side = self.start_side.unwrap_or(self.side);
+        let all_right = self.side == DiffSide::Right && start_side == DiffSide::Right;
+        let fmt = |side: DiffSide, line: u32| match (all_right, side) {
+            (true, _) => line.to_string(),
+            (false, DiffSide::Left) => format!("L{}", line),
+            (false, DiffSide::Right) => format!("R{}", line),
+        };
+        match self.start_line {
+            Some(start) => format!("{}-{}", fmt(start_side, start), fmt(self.side, self.line)),
+            None => fmt(self.side, self.line),
+        }
+    }
 }
 
-pub async fn fetch_reviews(repo: &str, pr_number: u32) -> Result<Vec<Review>> {
-    fetch_and_parse(
-        &format!("repos/{}/pulls/{}/reviews", repo, pr_number),
-        "Failed to parse reviews response",
-    )
-    .await
+/// 保留中レビューに積まれた未送信のインラインコメント
+///
+/// レビュー送信時に `POST /pulls/{n}/reviews` の `comments[]` としてまとめて送られる。
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DraftComment {
+    pub path: String,
+    #[serde(flatten)]
+    pub range: CommentRange,
+    pub body: String,
 }
 
-pub async fn create_review_comment(
-    repo: &str,
-    pr_number: u32,
+/// `POST /pulls/{n}/comments` のリクエストボディを組み立てる
+pub(super) fn review_comment_payload(
     commit_id: &str,
     path: &str,
-    line: u32,
+    range: CommentRange,
     body: &str,
-) -> Result<ReviewComment> {
-    let endpoint = format!("repos/{}/pulls/{}/comments", repo, pr_number);
-    let line_str = line.to_string();
-    let json = gh_api_post(
-        &endpoint,
-        &[
-            ("body", FieldValue::String(body)),
-            ("commit_id", FieldValue::String(commit_id)),
-            ("path", FieldValue::String(path)),
-            ("line", FieldValue::Raw(&line_str)),
-            ("side", FieldValue::String("RIGHT")),
-        ],
-    )
-    .await?;
-    serde_json::from_value(json).context("Failed to parse created comment response")
+) -> serde_json::Value {
+    let mut payload = serde_json::json!({
+        "body": body,
+        "commit_id": commit_id,
+        "path": path,
+        "line": range.line,
+        "side": range.side,
+    });
+    // 複数行コメント
+    if let Some(start_line) = range.start_line {
+        payload["start_line"] = start_line.into();
+        payload["start_side"] = serde_json::json!(range.start_side.unwrap_or(range.side));
+    }
+    payload
 }
 
-pub async fn create_reply_comment(
-    repo: &str,
-    pr_number: u32,
-    comment_id: u64,
-    body: &str,
-) -> Result<ReviewComment> {
-    let endpoint = format!(
-        "repos/{}/pulls/{}/comments/{}/replies",
-        repo, pr_number, comment_id
-    );
-    let json = gh_api_post(&endpoint, &[("body", FieldValue::String(body))]).await?;
-    serde_json::from_value(json).context("Failed to parse reply comment response")
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_range_label() {
+        assert_eq!(CommentRange::single(12).label(), "12");
+        assert_eq!(CommentRange::range(10, 12).label(), "10-12");
+        assert_eq!(
+            CommentRange::between((DiffSide::Left, 7), (DiffSide::Left, 7)).label(),
+            "L7"
+        );
+        assert_eq!(
+            CommentRange::between((DiffSide::Left, 10), (DiffSide::Right, 12)).label(),
+            "L10-R12"
+        );
+    }
+
+    #[test]
+    fn test_comment_range_serializes_sides() {
+        let json = serde_json::to_value(CommentRange::between(
+            (DiffSide::Left, 10),
+            (DiffSide::Right, 12),
+        ))
+        .unwrap();
+        assert_eq!(json["line"], 12);
+        assert_eq!(json["side"], "RIGHT");
+        assert_eq!(json["start_line"], 10);
+        assert_eq!(json["start_side"], "LEFT");
+
+        let json = serde_json::to_value(CommentRange::single(3)).unwrap();
+        assert_eq!(json["side"], "RIGHT");
+        assert!(json.get("start_line").is_none());
+        assert!(json.get("start_side").is_none());
+    }
+
+    #[test]
+    fn test_review_comment_payload() {
+        let payload = review_comment_payload("abc", "a.rs", CommentRange::single(3), "nit");
+        assert_eq!(payload["commit_id"], "abc");
+        assert_eq!(payload["path"], "a.rs");
+        assert_eq!(payload["line"], 3);
+        assert_eq!(payload["side"], "RIGHT");
+        assert!(payload.get("start_line").is_none());
+
+        let range = CommentRange::between((DiffSide::Left, 10), (DiffSide::Right, 12));
+        let payload = review_comment_payload("abc", "a.rs", range, "nit");
+        assert_eq!(payload["start_line"], 10);
+        assert_eq!(payload["start_side"], "LEFT");
+        assert_eq!(payload["line"], 12);
+    }
+
+    #[test]
+    fn test_review_comment_side_is_optional() {
+        let json =
+            r#"{"id":1,"path":"a.rs","line":3,"body":"b","user":{"login":"u"},"created_at":"t"}"#;
+        let comment: ReviewComment = serde_json::from_str(json).unwrap();
+        assert!(comment.side.is_none());
+
+        let json = r#"{"id":1,"path":"a.rs","line":3,"side":"LEFT","body":"b","user":{"login":"u"},"created_at":"t"}"#;
+        let comment: ReviewComment = serde_json::from_str(json).unwrap();
+        assert_eq!(comment.side, Some(DiffSide::Left));
+    }
+
+    fn comment(id: u64, in_reply_to_id: Option<u64>, created_at: &str) -> ReviewComment {
+        ReviewComment {
+            id,
+            path: "a.rs".to_string(),
+            line: Some(1),
+            start_line: None,
+            side: None,
+            original_line: None,
+            original_commit_id: None,
+            position: None,
+            diff_hunk: None,
+            body: String::new(),
+            user: User {
+                login: "u".to_string(),
+            },
+            created_at: created_at.to_string(),
+            in_reply_to_id,
+            thread: None,
+            reactions: Reactions::default(),
+        }
+    }
+
+    #[test]
+    fn test_group_into_threads_orders_replies_after_root() {
+        let comments = vec![
+            comment(1, None, "2024-01-01"),
+            comment(2, None, "2024-01-02"),
+            comment(3, Some(1), "2024-01-03"),
+            comment(4, Some(2), "2024-01-04"),
+            comment(5, Some(1), "2024-01-05"),
+        ];
+        let threads = vec![ReviewThread {
+            state: ThreadState {
+                id: "T_1".to_string(),
+                is_resolved: true,
+                is_outdated: false,
+            },
+            comment_ids: vec![1, 3, 5],
+        }];
+
+        let grouped = group_into_threads(comments, &threads);
+        let ids: Vec<u64> = grouped.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 3, 5, 2, 4]);
+        assert!(grouped[..3].iter().all(|c| c.is_resolved()));
+        assert!(grouped[3..].iter().all(|c| c.thread.is_none()));
+    }
+
+    #[test]
+    fn test_outdated_comments_are_grouped_last() {
+        let json = r#"{"id":3,"path":"a.rs","line":null,"original_line":7,"position":null,
+            "original_commit_id":"abc","diff_hunk":"@@ -1 +1 @@\n+x","body":"b",
+            "user":{"login":"u"},"created_at":"2024-01-01"}"#;
+        let outdated: ReviewComment = serde_json::from_str(json).unwrap();
+        assert!(outdated.is_outdated());
+        assert_eq!(outdated.original_line, Some(7));
+
+        // `line` が残っていても `position` が null なら outdated
+        let mut current = outdated.clone();
+        current.line = Some(7);
+        current.position = Some(2);
+        assert!(!current.is_outdated());
+        current.position = None;
+        assert!(current.is_outdated());
+
+        let comments = vec![
+            outdated,
+            comment(1, None, "2024-01-02"),
+            comment(2, Some(3), "2024-01-03"),
+        ];
+        let grouped = group_into_threads(comments, &[]);
+        let ids: Vec<u64> = grouped.iter().map(|c| c.id).collect();
+        assert_eq!(ids, vec![1, 3, 2]);
+    }
+
+    #[test]
+    fn test_review_comment_thread_fields_are_optional() {
+        let json = r#"{"id":2,"path":"a.rs","line":3,"body":"b","user":{"login":"u"},"created_at":"t","in_reply_to_id":1}"#;
+        let comment: ReviewComment = serde_json::from_str(json).unwrap();
+        assert_eq!(comment.thread_root_id(), 1);
+        assert!(comment.is_reply());
+        assert!(!comment.is_resolved());
+    }
 }
@@ -0,0 +1,301 @@
+use std::fmt;
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+/// レートリミットで `Retry-After` が分からない場合に自動再試行まで待つ時間
+const RATE_LIMIT_RETRY_WAIT: Duration = Duration::from_secs(60);
+/// ネットワークエラー・5xx の自動再試行までの待ち時間
+const NETWORK_RETRY_WAIT: Duration = Duration::from_secs(5);
+/// `Retry-After` のないセカンダリレートリミットの待ち時間（GitHub の推奨は 1 分以上）
+pub(super) const SECONDARY_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// 422 レスポンスの `errors` の 1 件
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct FieldError {
+    #[serde(default)]
+    pub resource: Option<String>,
+    #[serde(default)]
+    pub field: Option<String>,
+    #[serde(default)]
+    pub code: Option<String>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl FieldError {
+    /// レスポンスボディの `errors` を読み取る（オブジェクトではなく文字列の場合もある）
+    pub fn parse_all(body: &Value) -> Vec<Self> {
+        let Some(errors) = body.get("errors").and_then(|e| e.as_array()) else {
+            return Vec::new();
+        };
+        errors
+            .iter()
+            .filter_map(|e| match e {
+                Value::String(s) => Some(Self {
+                    message: Some(s.clone()),
+                    ..Self::default()
+                }),
+                _ => serde_json::from_value(e.clone()).ok(),
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.resource, &self.field) {
+            (Some(resource), Some(field)) => write!(f, "{}.{}: ", resource, field)?,
+            (None, Some(field)) => write!(f, "{}: ", field)?,
+            _ => {}
+        }
+        match (&self.message, &self.code) {
+            (Some(message), _) => write!(f, "{}", message),
+            (None, Some(code)) => write!(f, "{}", code),
+            (None, None) => write!(f, "invalid"),
+        }
+    }
+}
+
+/// クライアント層で分類した GitHub API のエラー
+///
+/// `anyhow::Error` に包んで返し、呼び出し側は `downcast_ref` で取り出す
+/// （再試行の判断・エラー画面の案内に使う）。分類できない失敗は従来通り文字列のまま。
+#[derive(Debug, Clone, Error)]
+pub enum GitHubError {
+    /// 404 / GraphQL の `NOT_FOUND`（存在しない、またはトークンから見えない）
+    #[error("{0}")]
+    NotFound(String),
+    /// 401 / レートリミット以外の 403 / トークンが見つからない
+    #[error("{0}")]
+    Unauthorized(String),
+    /// プライマリ / セカンダリレートリミット（待ち時間が分かれば `retry_after`）
+    #[error("GitHub API rate limit exceeded: {message}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        message: String,
+    },
+    /// 送信失敗・5xx など、時間をおけば成功しうる失敗
+    #[error("{0}")]
+    Network(String),
+    /// 422（入力値の不備）。`errors` にフィールド単位の詳細が入る
+    #[error("{message}{}", field_errors_suffix(errors))]
+    Validation {
+        message: String,
+        errors: Vec<FieldError>,
+    },
+    /// `gh` コマンドを起動できない
+    #[error("gh CLI not found - is it installed?")]
+    GhNotInstalled,
+}
+
+fn field_errors_suffix(errors: &[FieldError]) -> String {
+    if errors.is_empty() {
+        return String::new();
+    }
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    format!(" ({})", errors.join("; "))
+}
+
+/// エラーメッセージがレートリミットを示すか（ヘッダーが得られない場合の判定）
+pub(super) fn is_rate_limit_message(message: &str) -> bool {
+    message.to_ascii_lowercase().contains("rate limit")
+}
+
+/// メッセージから分かるレートリミットの待ち時間（セカンダリレートリミットのみ）
+pub(super) fn rate_limit_wait_from_message(message: &str) -> Option<Duration> {
+    if message
+        .to_ascii_lowercase()
+        .contains("secondary rate limit")
+    {
+        Some(SECONDARY_RATE_LIMIT_WAIT)
+    } else {
+        None
+    }
+}
+
+/// エラー画面での再試行方針
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryPolicy {
+    /// 指定時間後に自動で再試行する（`r` で即時再試行も可）
+    Auto(Duration),
+    /// 原因を解消してから `r` で再試行する
+    Manual,
+    /// 再試行しても結果が変わらない
+    Never,
+}
+
+impl GitHubError {
+    /// レートリミット以外の失敗ステータスを分類する（対象外のステータスは `None`）
+    pub fn from_status(status: u16, message: String, body: Option<&Value>) -> Option<Self> {
+        match status {
+            401 | 403 => Some(Self::Unauthorized(message)),
+            404 => Some(Self::NotFound(message)),
+            422 => Some(Self::Validation {
+                message,
+                errors: body.map(FieldError::parse_all).unwrap_or_default(),
+            }),
+            500..=599 => Some(Self::Network(message)),
+            _ => None,
+        }
+    }
+
+    /// `gh` のエラー出力（stderr）と、stdout に出たレスポンスボディ（JSON なら）を分類する
+    ///
+    /// 種類は stderr のメッセージと `(HTTP nnn)` から判定し、422 のフィールド単位の詳細は
+    /// ボディの `errors` から読み取る。
+    pub fn from_gh_output(stderr: &str, body: Option<&Value>) -> Option<Self> {
+        let stderr = stderr.trim();
+        if is_rate_limit_message(stderr) || stderr.contains("(HTTP 429)") {
+            return Some(Self::RateLimited {
+                retry_after: rate_limit_wait_from_message(stderr),
+                message: stderr.to_string(),
+            });
+        }
+        let message = format!("gh command failed: {}", stderr);
+        let lower = stderr.to_ascii_lowercase();
+        if stderr.contains("(HTTP 404)") || stderr.contains("Could not resolve to a") {
+            Some(Self::NotFound(message))
+        } else if stderr.contains("(HTTP 401)")
+            || stderr.contains("(HTTP 403)")
+            || lower.contains("gh auth login")
+        {
+            Some(Self::Unauthorized(message))
+        } else if stderr.contains("(HTTP 422)") {
+            Some(Self::Validation {
+                message,
+                errors: body.map(FieldError::parse_all).unwrap_or_default(),
+            })
+        } else if stderr.contains("(HTTP 5")
+            || lower.contains("error connecting to")
+            || lower.contains("i/o timeout")
+        {
+            Some(Self::Network(message))
+        } else {
+            None
+        }
+    }
+
+    /// GraphQL の `errors[].type` を分類する
+    pub fn from_graphql_type(error_type: &str, message: String) -> Option<Self> {
+        match error_type {
+            "NOT_FOUND" => Some(Self::NotFound(message)),
+            "FORBIDDEN" => Some(Self::Unauthorized(message)),
+            "RATE_LIMITED" => Some(Self::RateLimited {
+                retry_after: None,
+                message,
+            }),
+            _ => None,
+        }
+    }
+
+    /// エラー画面での再試行方針
+    pub fn retry_policy(&self) -> RetryPolicy {
+        match self {
+            Self::RateLimited { retry_after, .. } => {
+                RetryPolicy::Auto(retry_after.unwrap_or(RATE_LIMIT_RETRY_WAIT))
+            }
+            Self::Network(_) => RetryPolicy::Auto(NETWORK_RETRY_WAIT),
+            Self::Unauthorized(_) => RetryPolicy::Manual,
+            Self::NotFound(_) | Self::Validation { .. } | Self::GhNotInstalled => {
+                RetryPolicy::Never
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status_parses_validation_field_errors() {
+        let body = serde_json::json!({
+            "message": "Validation Failed",
+            "errors": [
+                { "resource": "PullRequestReviewComment", "field": "line", "code": "invalid" },
+                "pull_request_review_thread.path is missing"
+            ]
+        });
+        let error = GitHubError::from_status(422, "Validation Failed".to_string(), Some(&body));
+        let Some(GitHubError::Validation { errors, .. }) = &error else {
+            panic!("expected validation error: {:?}", error);
+        };
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            error.unwrap().to_string(),
+            "Validation Failed (PullRequestReviewComment.line: invalid; \
+             pull_request_review_thread.path is missing)"
+        );
+
+        assert!(matches!(
+            GitHubError::from_status(404, "Not Found".to_string(), None),
+            Some(GitHubError::NotFound(_))
+        ));
+        assert!(matches!(
+            GitHubError::from_status(401, "Bad credentials".to_string(), None),
+            Some(GitHubError::Unauthorized(_))
+        ));
+        assert!(GitHubError::from_status(409, "Conflict".to_string(), None).is_none());
+    }
+
+    #[test]
+    fn test_from_gh_output() {
+        assert!(matches!(
+            GitHubError::from_gh_output(
+                "gh: API rate limit exceeded for user ID 1. (HTTP 403)",
+                None
+            ),
+            Some(GitHubError::RateLimited {
+                retry_after: None,
+                ..
+            })
+        ));
+        assert!(matches!(
+            GitHubError::from_gh_output("gh: Server Error (HTTP 502)", None),
+            Some(GitHubError::Network(_))
+        ));
+        assert!(matches!(
+            GitHubError::from_gh_output("gh: Not Found (HTTP 404)", None),
+            Some(GitHubError::NotFound(_))
+        ));
+        assert!(matches!(
+            GitHubError::from_gh_output("GraphQL: Could not resolve to a PullRequest with the number of 9. (repository.pullRequest)", None),
+            Some(GitHubError::NotFound(_))
+        ));
+        assert!(matches!(
+            GitHubError::from_gh_output(
+                "To get started with GitHub CLI, please run:  gh auth login",
+                None
+            ),
+            Some(GitHubError::Unauthorized(_))
+        ));
+        assert!(GitHubError::from_gh_output("unknown flag: --foo", None).is_none());
+    }
+
+    #[test]
+    fn test_retry_policy_per_kind() {
+        let rate_limited = GitHubError::RateLimited {
+            retry_after: Some(Duration::from_secs(42)),
+            message: String::new(),
+        };
+        assert_eq!(
+            rate_limited.retry_policy(),
+            RetryPolicy::Auto(Duration::from_secs(42))
+        );
+        assert_eq!(
+            GitHubError::Network(String::new()).retry_policy(),
+            RetryPolicy::Auto(NETWORK_RETRY_WAIT)
+        );
+        assert_eq!(
+            GitHubError::Unauthorized(String::new()).retry_policy(),
+            RetryPolicy::Manual
+        );
+        assert_eq!(
+            GitHubError::GhNotInstalled.retry_policy(),
+            RetryPolicy::Never
+        );
+    }
+}
@@ -0,0 +1,470 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
+use reqwest::{Method, RequestBuilder, Response};
+use serde_json::Value;
+use tokio::sync::OnceCell;
+
+use super::api::{graphql_data, GitHubApi, HttpMethod};
+use super::client::gh_command;
+use super::error::GitHubError;
+use super::rate_limit::{RateLimit, RateLimitHeaders, RateLimiter, RequestKind};
+use super::repo_ref::DEFAULT_HOST;
+
+const API_VERSION: &str = "2022-11-28";
+const JSON_MEDIA_TYPE: &str = "application/vnd.github+json";
+const DIFF_MEDIA_TYPE: &str = "application/vnd.github.diff";
+
+/// GitHub REST/GraphQL API を直接呼び出す [`GitHubApi`] 実装
+///
+/// トークンは最初のリクエスト時に環境変数 → `gh auth token --hostname` の順で解決し、
+/// 以降は使い回す（github.com は `GH_TOKEN` / `GITHUB_TOKEN`、
+/// GitHub Enterprise Server は `GH_ENTERPRISE_TOKEN` / `GITHUB_ENTERPRISE_TOKEN`）。
+///
+/// レートリミット（`X-RateLimit-*` / `Retry-After`）は [`RateLimiter`] で扱う。
+pub struct HttpClient {
+    client: reqwest::Client,
+    host: String,
+    base_url: String,
+    graphql_url: String,
+    token: OnceCell<String>,
+    limiter: RateLimiter,
+}
+
+impl Default for HttpClient {
+    fn default() -> Self {
+        Self::for_host(DEFAULT_HOST)
+    }
+}
+
+impl HttpClient {
+    /// ホストに応じた API エンドポイントで生成
+    ///
+    /// github.com は `https://api.github.com`、GitHub Enterprise Server は
+    /// `https://{host}/api/v3`（GraphQL は `https://{host}/api/graphql`）。
+    pub fn for_host(host: &str) -> Self {
+        let (base_url, graphql_url) = if host == DEFAULT_HOST {
+            (
+                "https://api.github.com".to_string(),
+                "https://api.github.com/graphql".to_string(),
+            )
+        } else {
+            (
+                format!("https://{}/api/v3", host),
+                format!("https://{}/api/graphql", host),
+            )
+        };
+        Self {
+            client: reqwest::Client::new(),
+            host: host.to_string(),
+            base_url,
+            graphql_url,
+            token: OnceCell::new(),
+            limiter: RateLimiter::default(),
+        }
+    }
+
+    /// API のベース URL を指定して生成（テスト用のローカルサーバーなど）
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into().trim_end_matches('/').to_string();
+        Self {
+            graphql_url: format!("{}/graphql", base_url),
+            base_url,
+            ..Self::default()
+        }
+    }
+
+    /// トークンを明示的に指定する（環境変数・`gh auth token` を参照しない）
+    pub fn with_token(self, token: impl Into<String>) -> Self {
+        Self {
+            token: OnceCell::new_with(Some(token.into())),
+            ..self
+        }
+    }
+
+    async fn token(&self) -> Result<&str> {
+        self.token
+            .get_or_try_init(|| resolve_token(&self.host))
+            .await
+            .map(String::as_str)
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        accept: &str,
+    ) -> Result<RequestBuilder> {
+        let url = format!("{}/{}", self.base_url, endpoint.trim_start_matches('/'));
+        self.request_url(method, url, accept).await
+    }
+
+    async fn request_url(
+        &self,
+        method: Method,
+        url: String,
+        accept: &str,
+    ) -> Result<RequestBuilder> {
+        let token = self.token().await?;
+        Ok(self
+            .client
+            .request(method, url)
+            .header(AUTHORIZATION, format!("Bearer {}", token))
+            .header(ACCEPT, accept)
+            .header(USER_AGENT, "octorus")
+            .header("X-GitHub-Api-Version", API_VERSION))
+    }
+
+    /// リクエストを送信し、残りクォータを記録する
+    ///
+    /// 失敗は可能な限り [`GitHubError`] に分類して返し、[`RateLimiter`] が再試行を判断する。
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let response = request.send().await.map_err(|e| {
+            GitHubError::Network(format!("Failed to send GitHub API request: {}", e))
+        })?;
+        let status = response.status();
+        let headers = RateLimitHeaders::parse(|name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_string)
+        });
+        if let Some(snapshot) = headers.snapshot() {
+            self.limiter.record(snapshot);
+        }
+        if status.is_success() {
+            return Ok(response);
+        }
+        let text = response.text().await.unwrap_or_default();
+        let body = serde_json::from_str::<Value>(&text).ok();
+        let message = body
+            .as_ref()
+            .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(String::from))
+            .unwrap_or(text);
+        let now = chrono::Utc::now().timestamp();
+        if let Some(error) = headers.rate_limit_error(status.as_u16(), &message, now) {
+            return Err(error.into());
+        }
+        let error = format!("GitHub API request failed ({}): {}", status, message);
+        match GitHubError::from_status(status.as_u16(), error.clone(), body.as_ref()) {
+            Some(error) => Err(error.into()),
+            None => anyhow::bail!(error),
+        }
+    }
+}
+
+#[async_trait]
+impl GitHubApi for HttpClient {
+    fn host(&self) -> &str {
+        &self.host
+    }
+
+    fn rate_limit(&self) -> Option<RateLimit> {
+        self.limiter.snapshot()
+    }
+
+    async fn rest(
+        &self,
+        method: HttpMethod,
+        endpoint: &str,
+        body: Option<&Value>,
+    ) -> Result<Value> {
+        let kind = match method {
+            HttpMethod::Get => RequestKind::Read,
+            _ => RequestKind::Write,
+        };
+        let method = match method {
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Patch => Method::PATCH,
+            HttpMethod::Put => Method::PUT,
+            HttpMethod::Delete => Method::DELETE,
+        };
+        let text = self
+            .limiter
+            .run(kind, || {
+                let method = method.clone();
+                async move {
+                    let mut request = self.request(method, endpoint, JSON_MEDIA_TYPE).await?;
+                    if let Some(body) = body {
+                        request = request.json(body);
+                    }
+                    self.send(request)
+                        .await?
+                        .text()
+                        .await
+                        .context("Failed to read GitHub API response")
+                }
+            })
+            .await?;
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).context("Failed to parse GitHub API response as JSON")
+    }
+
+    async fn graphql(&self, query: &str, variables: Value) -> Result<Value> {
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        let response: Value = self
+            .limiter
+            .run(RequestKind::of_graphql(query), || async {
+                let request = self
+                    .request_url(Method::POST, self.graphql_url.clone(), JSON_MEDIA_TYPE)
+                    .await?
+                    .json(&body);
+                self.send(request)
+                    .await?
+                    .json()
+                    .await
+                    .context("Failed to parse GraphQL response")
+            })
+            .await?;
+        graphql_data(response)
+    }
+
+    async fn fetch_pr_diff(&self, repo: &str, pr_number: u32) -> Result<String> {
+        let endpoint = format!("repos/{}/pulls/{}", repo, pr_number);
+        self.limiter
+            .run(RequestKind::Read, || async {
+                let request = self
+                    .request(Method::GET, &endpoint, DIFF_MEDIA_TYPE)
+                    .await?;
+                self.send(request)
+                    .await?
+                    .text()
+                    .await
+                    .context("Failed to read PR diff")
+            })
+            .await
+    }
+}
+
+/// 環境変数 → `gh auth token --hostname` の順にトークンを探す（`gh` と同じ変数名）
+async fn resolve_token(host: &str) -> Result<String> {
+    let vars = if host == DEFAULT_HOST {
+        ["GH_TOKEN", "GITHUB_TOKEN"]
+    } else {
+        ["GH_ENTERPRISE_TOKEN", "GITHUB_ENTERPRISE_TOKEN"]
+    };
+    for var in vars {
+        if let Ok(token) = std::env::var(var) {
+            if !token.trim().is_empty() {
+                return Ok(token.trim().to_string());
+            }
+        }
+    }
+    let token = gh_command(&["auth", "token", "--hostname", host])
+        .await
+        .map_err(|_| {
+            GitHubError::Unauthorized(
+                "No GitHub token found. Set GH_TOKEN or run `gh auth login`.".to_string(),
+            )
+        })?;
+    Ok(token.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// 1 リクエストだけ受け付けて固定レスポンスを返すローカルサーバー
+    ///
+    /// 受信したリクエスト（ヘッダー + ボディ）を返す JoinHandle を返す。
+    async fn serve_once(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, tokio::task::JoinHandle<String>) {
+        let (url, handle) = serve_sequence(vec![(status, "", body)]).await;
+        (
+            url,
+            tokio::spawn(async move { handle.await.unwrap().remove(0) }),
+        )
+    }
+
+    /// `(ステータス, 追加ヘッダー, ボディ)` を接続ごとに順に返すローカルサーバー
+    ///
+    /// 受信したリクエストの一覧を返す JoinHandle を返す。
+    async fn serve_sequence(
+        responses: Vec<(&'static str, &'static str, &'static str)>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for (status, headers, body) in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut received = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    received.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&received).to_string();
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let content_length = text[..header_end]
+                            .lines()
+                            .find_map(|l| {
+                                l.to_ascii_lowercase()
+                                    .strip_prefix("content-length:")
+                                    .map(|v| v.trim().parse::<usize>().unwrap_or(0))
+                            })
+                            .unwrap_or(0);
+                        if received.len() >= header_end + 4 + content_length {
+                            break;
+                        }
+                    }
+                    if n == 0 {
+                        break;
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 {}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n{}\r\n{}",
+                    status,
+                    body.len(),
+                    headers,
+                    body
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                requests.push(String::from_utf8_lossy(&received).to_string());
+            }
+            requests
+        });
+        (format!("http://{}", addr), handle)
+    }
+
+    #[tokio::test]
+    async fn test_rest_get_sends_auth_headers() {
+        let (url, server) = serve_once("200 OK", r#"{"login":"octocat"}"#).await;
+        let client = HttpClient::with_base_url(url).with_token("secret");
+
+        let json = client.rest(HttpMethod::Get, "user", None).await.unwrap();
+        assert_eq!(json["login"], "octocat");
+
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("get /user http/1.1"));
+        assert!(request.contains("authorization: bearer secret"));
+        assert!(request.contains("accept: application/vnd.github+json"));
+        assert!(request.contains("x-github-api-version: 2022-11-28"));
+    }
+
+    #[tokio::test]
+    async fn test_rest_post_sends_json_body() {
+        let (url, server) = serve_once("201 Created", r#"{"id":1}"#).await;
+        let client = HttpClient::with_base_url(url).with_token("secret");
+
+        let body = serde_json::json!({ "body": "LGTM" });
+        client
+            .rest(HttpMethod::Post, "repos/o/r/issues/1/comments", Some(&body))
+            .await
+            .unwrap();
+
+        let request = server.await.unwrap();
+        assert!(request.starts_with("POST /repos/o/r/issues/1/comments HTTP/1.1"));
+        assert!(request.ends_with(r#"{"body":"LGTM"}"#));
+    }
+
+    #[tokio::test]
+    async fn test_rest_error_includes_status_and_message() {
+        let (url, _server) = serve_once("404 Not Found", r#"{"message":"Not Found"}"#).await;
+        let client = HttpClient::with_base_url(url).with_token("secret");
+
+        let err = client
+            .rest(HttpMethod::Get, "repos/o/r/pulls/9", None)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GitHub API request failed (404 Not Found): Not Found"
+        );
+        assert!(matches!(
+            err.downcast_ref::<GitHubError>(),
+            Some(GitHubError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rest_empty_response_is_null() {
+        let (url, _server) = serve_once("204 No Content", "").await;
+        let client = HttpClient::with_base_url(url).with_token("secret");
+
+        let json = client
+            .rest(HttpMethod::Delete, "repos/o/r/pulls/comments/1", None)
+            .await
+            .unwrap();
+        assert!(json.is_null());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_read_is_retried_and_quota_recorded() {
+        let (url, server) = serve_sequence(vec![
+            (
+                "429 Too Many Requests",
+                "retry-after: 0\r\n",
+                r#"{"message":"You have exceeded a secondary rate limit"}"#,
+            ),
+            (
+                "200 OK",
+                "x-ratelimit-limit: 5000\r\nx-ratelimit-remaining: 4999\r\nx-ratelimit-reset: 1700000000\r\nx-ratelimit-resource: core\r\n",
+                r#"{"login":"octocat"}"#,
+            ),
+        ])
+        .await;
+        let client = HttpClient::with_base_url(url).with_token("secret");
+        assert!(client.rate_limit().is_none());
+
+        let json = client.rest(HttpMethod::Get, "user", None).await.unwrap();
+        assert_eq!(json["login"], "octocat");
+        assert_eq!(server.await.unwrap().len(), 2);
+
+        let quota = client.rate_limit().unwrap();
+        assert_eq!((quota.remaining, quota.limit), (4999, 5000));
+        assert_eq!(quota.resource, "core");
+    }
+
+    #[tokio::test]
+    async fn test_rest_error_for_exhausted_quota_is_rate_limit_error() {
+        let (url, _server) = serve_sequence(vec![(
+            "403 Forbidden",
+            "x-ratelimit-remaining: 0\r\nx-ratelimit-reset: 9999999999\r\n",
+            r#"{"message":"API rate limit exceeded"}"#,
+        )])
+        .await;
+        let client = HttpClient::with_base_url(url).with_token("secret");
+
+        // リセットが遠すぎるので待たずにエラーになる
+        let err = client
+            .rest(HttpMethod::Get, "repos/o/r/pulls/1", None)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<GitHubError>(),
+            Some(GitHubError::RateLimited { .. })
+        ));
+    }
+
+    #[test]
+    fn test_enterprise_host_api_urls() {
+        let client = HttpClient::for_host("ghe.example.com");
+        assert_eq!(client.base_url, "https://ghe.example.com/api/v3");
+        assert_eq!(client.graphql_url, "https://ghe.example.com/api/graphql");
+
+        let client = HttpClient::for_host("github.com");
+        assert_eq!(client.base_url, "https://api.github.com");
+        assert_eq!(client.graphql_url, "https://api.github.com/graphql");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pr_diff_requests_diff_media_type() {
+        let (url, server) = serve_once("200 OK", "diff --git a/x b/x").await;
+        let client = HttpClient::with_base_url(url).with_token("secret");
+
+        let diff = client.fetch_pr_diff("o/r", 3).await.unwrap();
+        assert_eq!(diff, "diff --git a/x b/x");
+
+        let request = server.await.unwrap().to_ascii_lowercase();
+        assert!(request.starts_with("get /repos/o/r/pulls/3 http/1.1"));
+        assert!(request.contains("accept: application/vnd.github.diff"));
+    }
+}
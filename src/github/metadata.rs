@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use super::pr::{Label, User};
+
+/// PR のトリアージ用メタデータ（`GET /repos/{repo}/pulls/{n}` の一部）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrMetadata {
+    /// GraphQL の node ID（Draft 切り替えのミューテーションに必要）
+    pub node_id: String,
+    #[serde(default)]
+    pub draft: bool,
+    #[serde(default)]
+    pub requested_reviewers: Vec<User>,
+    #[serde(default)]
+    pub requested_teams: Vec<Team>,
+    #[serde(default)]
+    pub assignees: Vec<User>,
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    pub milestone: Option<Milestone>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Team {
+    pub slug: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Milestone {
+    pub title: String,
+}
+
+impl PrMetadata {
+    pub fn has_label(&self, name: &str) -> bool {
+        self.labels.iter().any(|l| l.name == name)
+    }
+
+    pub fn has_requested_reviewer(&self, login: &str) -> bool {
+        self.requested_reviewers.iter().any(|u| u.login == login)
+    }
+}
+
+/// URL のパスセグメント用にパーセントエンコードする（ラベル名に空白や記号を含むため）
+pub(super) fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pr_metadata_deserializes_from_pull_response() {
+        let json = r#"{
+            "number": 1, "node_id": "PR_kw", "draft": true, "title": "t",
+            "requested_reviewers": [{ "login": "alice" }],
+            "requested_teams": [{ "slug": "core" }],
+            "assignees": [], "labels": [{ "name": "bug" }], "milestone": null
+        }"#;
+        let metadata: PrMetadata = serde_json::from_str(json).unwrap();
+        assert!(metadata.draft);
+        assert!(metadata.has_label("bug"));
+        assert!(metadata.has_requested_reviewer("alice"));
+        assert_eq!(metadata.requested_teams[0].slug, "core");
+        assert!(metadata.milestone.is_none());
+    }
+
+    #[test]
+    fn test_encode_path_segment() {
+        assert_eq!(encode_path_segment("bug"), "bug");
+        assert_eq!(
+            encode_path_segment("good first issue"),
+            "good%20first%20issue"
+        );
+        assert_eq!(encode_path_segment("area/ui"), "area%2Fui");
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use serde_json::Value;
+
+use super::api::{GitHubApi, HttpMethod};
+use super::repo_ref::DEFAULT_HOST;
+
+/// 呼び出し記録: (method, endpoint, body)
+pub(crate) type RecordedCall = (HttpMethod, String, Option<Value>);
+
+/// テスト用のインメモリ GitHub API
+///
+/// エンドポイントごとに固定レスポンスを返し、全ての REST 呼び出しを記録する。
+/// レスポンス未登録のエンドポイントはエラーになる。
+/// GraphQL は登録順にレスポンス（`data`）を返し、`(Post, "graphql", {query, variables})` として記録する。
+#[derive(Default)]
+pub(crate) struct MockGitHub {
+    responses: HashMap<String, Value>,
+    graphql_responses: Mutex<VecDeque<Value>>,
+    diff: Option<String>,
+    calls: Mutex<Vec<RecordedCall>>,
+}
+
+impl MockGitHub {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with_response(mut self, endpoint: &str, response: Value) -> Self {
+        self.responses.insert(endpoint.to_string(), response);
+        self
+    }
+
+    pub(crate) fn with_graphql_response(self, data: Value) -> Self {
+        self.graphql_responses.lock().unwrap().push_back(data);
+        self
+    }
+
+    pub(crate) fn with_diff(mut self, diff: &str) -> Self {
+        self.diff = Some(diff.to_string());
+        self
+    }
+
+    pub(crate) fn calls(&self) -> Vec<RecordedCall> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl GitHubApi for MockGitHub {
+    fn host(&self) -> &str {
+        DEFAULT_HOST
+    }
+
+    async fn rest(
+        &self,
+        method: HttpMethod,
+        endpoint: &str,
+        body: Option<&Value>,
+    ) -> Result<Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push((method, endpoint.to_string(), body.cloned()));
+        self.responses
+            .get(endpoint)
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("no mock response for {}", endpoint))
+    }
+
+    async fn graphql(&self, query: &str, variables: Value) -> Result<Value> {
+        self.calls.lock().unwrap().push((
+            HttpMethod::Post,
+            "graphql".to_string(),
+            Some(serde_json::json!({ "query": query, "variables": variables })),
+        ));
+        self.graphql_responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .ok_or_else(|| anyhow::anyhow!("no mock graphql response"))
+    }
+
+    async fn fetch_pr_diff(&self, _repo: &str, _pr_number: u32) -> Result<String> {
+        self.diff
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("no mock diff"))
+    }
+}
@@ -1,12 +1,27 @@
+mod api;
+pub mod checks;
 mod client;
 pub mod comment;
+mod error;
+mod http;
+pub mod metadata;
+#[cfg(test)]
+pub(crate) mod mock;
 mod pr;
+mod rate_limit;
+pub mod reaction;
+mod repo_ref;
 
 // Explicit re-exports - only export what is actually used
-pub use client::{detect_repo, DetectRepoError};
-pub use comment::{create_reply_comment, create_review_comment};
+pub use api::{new_client, GitHubApi, HttpMethod};
+pub use client::{detect_current_pr, detect_repo, DetectRepoError, GhClient};
+pub use comment::{CommentRange, DraftComment};
+pub use error::{FieldError, GitHubError, RetryPolicy};
+pub use http::HttpClient;
 pub use pr::{
-    fetch_changed_files, fetch_pr, fetch_pr_diff, fetch_pr_list, fetch_pr_list_with_offset,
-    submit_review, Branch, ChangedFile, Label, PrListPage, PrStateFilter, PullRequest,
-    PullRequestSummary, User,
+    Branch, BranchProtection, ChangedFile, Label, MergeMethod, Mergeability, PrCommit, PrListPage,
+    PrListQuery, PrSort, PrStateFilter, PullRequest, PullRequestSummary, ReviewDecision, User,
+    ViewedFiles, MAX_CHANGED_FILES, MAX_COMPARE_FILES, SEARCH_RESULT_LIMIT,
 };
+pub use rate_limit::RateLimit;
+pub use repo_ref::{qualified_repo, RepoRef, DEFAULT_HOST};
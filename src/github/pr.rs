@@ -1,9 +1,17 @@
-use anyhow::{Context, Result};
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 
-use super::client::{gh_api, gh_command};
+use super::checks::CheckState;
+use super::comment::DraftComment;
 use crate::app::ReviewAction;
 
+/// `GET /pulls/{n}/files` が返す変更ファイル数の上限（これを超える分は API から取得できない）
+pub const MAX_CHANGED_FILES: usize = 3000;
+
+/// `GET /compare/{base}...{head}` が返す変更ファイル数の上限（ページを送っても増えない）
+pub const MAX_COMPARE_FILES: usize = 300;
+
 /// PR状態フィルタ（型安全）
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum PrStateFilter {
@@ -30,6 +38,17 @@ impl PrStateFilter {
         }
     }
 
+    /// GraphQL `pullRequests(states:)` に渡す状態（All は指定しない）
+    ///
+    /// 検索の `is:closed` と同じく、マージ済みも closed に含める。
+    pub fn graphql_states(&self) -> Option<&'static [&'static str]> {
+        match self {
+            Self::Open => Some(&["OPEN"]),
+            Self::Closed => Some(&["CLOSED", "MERGED"]),
+            Self::All => None,
+        }
+    }
+
     pub fn next(&self) -> Self {
         match self {
             Self::Open => Self::Closed,
@@ -39,17 +58,230 @@ impl PrStateFilter {
     }
 }
 
+/// PR一覧の並び順（検索 API の `sort:` 修飾子）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrSort {
+    #[default]
+    Updated,
+    Created,
+    Comments,
+}
+
+impl PrSort {
+    pub fn qualifier(&self) -> &'static str {
+        match self {
+            Self::Updated => "sort:updated-desc",
+            Self::Created => "sort:created-desc",
+            Self::Comments => "sort:comments-desc",
+        }
+    }
+
+    /// GraphQL `IssueOrder` の `field`（`sort:` 修飾子に対応する並び順）
+    pub fn order_field(&self) -> &'static str {
+        match self {
+            Self::Updated => "UPDATED_AT",
+            Self::Created => "CREATED_AT",
+            Self::Comments => "COMMENTS",
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Updated => "updated",
+            Self::Created => "created",
+            Self::Comments => "comments",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Updated => Self::Created,
+            Self::Created => Self::Comments,
+            Self::Comments => Self::Updated,
+        }
+    }
+}
+
+/// PR一覧の検索条件（状態フィルタ + 検索修飾子 + 並び順）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrListQuery {
+    pub state: PrStateFilter,
+    /// `review-requested:@me` `author:` `label:` やフリーテキストなど、GitHub の検索構文そのまま
+    pub search: String,
+    pub sort: PrSort,
+    /// リポジトリを限定せず、自分（または所属チーム）にレビュー依頼が来ている PR を横断検索する
+    pub inbox: bool,
+}
+
+impl PrListQuery {
+    /// 「自分のレビュー待ち」プリセット
+    pub const NEEDS_MY_REVIEW: &'static str = "review-requested:@me";
+    /// 「自分の PR」プリセット
+    pub const MY_PRS: &'static str = "author:@me";
+
+    /// 検索 API を使う必要があるか
+    ///
+    /// 検索 API は 1,000 件までしか返さないため、検索修飾子もフリーテキストも
+    /// なければリポジトリの PR 一覧（`repository.pullRequests`）を直接ページングする。
+    pub fn needs_search(&self) -> bool {
+        self.inbox || !self.search.trim().is_empty()
+    }
+
+    /// GitHub の検索 API に渡すクエリ文字列を組み立てる
+    pub fn search_query(&self, repo: &str) -> String {
+        let mut parts = if self.inbox {
+            vec!["is:pr".to_string(), "archived:false".to_string()]
+        } else {
+            vec![format!("repo:{}", repo), "is:pr".to_string()]
+        };
+        if self.state != PrStateFilter::All {
+            parts.push(format!("is:{}", self.state.as_gh_arg()));
+        }
+        let search = self.search.trim();
+        if !search.is_empty() {
+            parts.push(search.to_string());
+        } else if self.inbox {
+            // `review-requested:` はチーム経由のレビュー依頼も含む
+            parts.push(Self::NEEDS_MY_REVIEW.to_string());
+        }
+        // ユーザーが `sort:` を直接書いた場合はそちらを優先する
+        if !search.contains("sort:") {
+            parts.push(self.sort.qualifier().to_string());
+        }
+        parts.join(" ")
+    }
+}
+
+/// PR のレビュー判定（GraphQL の `reviewDecision`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewDecision {
+    Approved,
+    ChangesRequested,
+    ReviewRequired,
+}
+
+impl ReviewDecision {
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Approved => "✓",
+            Self::ChangesRequested => "±",
+            Self::ReviewRequired => "●",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PullRequestSummary {
     pub number: u32,
     pub title: String,
     pub state: String,
+    /// `gh pr list` は `author`、REST API は `user`
+    #[serde(alias = "user")]
     pub author: User,
-    #[serde(rename = "isDraft")]
+    #[serde(rename = "isDraft", alias = "draft")]
     pub is_draft: bool,
     pub labels: Vec<Label>,
-    #[serde(rename = "updatedAt")]
+    #[serde(rename = "updatedAt", alias = "updated_at")]
     pub updated_at: String,
+    /// 以下は検索 API（GraphQL）でのみ取得できる列
+    #[serde(default, rename = "reviewDecision")]
+    pub review_decision: Option<ReviewDecision>,
+    /// 最新コミットの `statusCheckRollup.state`（SUCCESS / FAILURE / PENDING など）
+    #[serde(default)]
+    pub ci_status: Option<String>,
+    #[serde(default)]
+    pub additions: Option<u32>,
+    #[serde(default)]
+    pub deletions: Option<u32>,
+    /// `owner/repo`（リポジトリ横断の検索結果で、どのリポジトリの PR かを示す）
+    #[serde(default)]
+    pub repo: Option<String>,
+}
+
+impl PullRequestSummary {
+    pub fn ci_state(&self) -> Option<CheckState> {
+        Some(match self.ci_status.as_deref()? {
+            "SUCCESS" => CheckState::Success,
+            "FAILURE" | "ERROR" => CheckState::Failure,
+            "PENDING" | "EXPECTED" => CheckState::Pending,
+            _ => CheckState::Neutral,
+        })
+    }
+}
+
+/// GraphQL `search` の PullRequest ノード
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct SearchPullRequest {
+    number: u32,
+    title: String,
+    state: String,
+    /// 削除済みユーザーは null
+    author: Option<User>,
+    is_draft: bool,
+    labels: Nodes<Label>,
+    updated_at: String,
+    review_decision: Option<ReviewDecision>,
+    additions: u32,
+    deletions: u32,
+    commits: Nodes<SearchCommitNode>,
+    repository: SearchRepository,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchRepository {
+    name_with_owner: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Nodes<T> {
+    nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchCommitNode {
+    commit: SearchCommit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchCommit {
+    status_check_rollup: Option<StatusCheckRollup>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StatusCheckRollup {
+    state: String,
+}
+
+impl From<SearchPullRequest> for PullRequestSummary {
+    fn from(pr: SearchPullRequest) -> Self {
+        let ci_status = pr
+            .commits
+            .nodes
+            .into_iter()
+            .next_back()
+            .and_then(|node| node.commit.status_check_rollup)
+            .map(|rollup| rollup.state);
+        Self {
+            number: pr.number,
+            title: pr.title,
+            state: pr.state.to_lowercase(),
+            author: pr.author.unwrap_or_else(|| User {
+                login: "ghost".to_string(),
+            }),
+            is_draft: pr.is_draft,
+            labels: pr.labels.nodes,
+            updated_at: pr.updated_at,
+            review_decision: pr.review_decision,
+            ci_status,
+            additions: Some(pr.additions),
+            deletions: Some(pr.deletions),
+            repo: Some(pr.repository.name_with_owner),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +299,184 @@ pub struct PullRequest {
     pub base: Branch,
     pub user: User,
     pub updated_at: String,
+    /// GraphQL の node ID（自動マージの有効化に必要）
+    #[serde(default)]
+    pub node_id: String,
+    /// GitHub がバックグラウンドで計算するため、取得直後は None のことがある
+    #[serde(default)]
+    pub mergeable: Option<bool>,
+    /// clean / unstable / blocked / behind / dirty / draft / unknown など
+    #[serde(default)]
+    pub mergeable_state: Option<String>,
+    /// ベースブランチの保護ルール（マージダイアログを開いたときに取得する）
+    #[serde(skip)]
+    pub protection: Option<BranchProtection>,
+}
+
+/// ベースブランチでマージに必要な条件（ブランチ保護ルールとルールセットの合算）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchProtection {
+    /// 必要な承認レビューの数（0 なら不要）
+    pub required_approving_reviews: u32,
+    /// 成功が必要なステータスチェック名
+    pub required_status_checks: Vec<String>,
+}
+
+impl BranchProtection {
+    /// GraphQL の `Ref`（`branchProtectionRule` と `rules`）から読み取る
+    ///
+    /// 権限がなく `branchProtectionRule` が null の場合はルールセットだけで判断する。
+    pub fn from_graphql_ref(git_ref: &serde_json::Value) -> Self {
+        let mut protection = Self::default();
+        let rule = &git_ref["branchProtectionRule"];
+        if rule["requiresApprovingReviews"].as_bool() == Some(true) {
+            protection.add_reviews(&rule["requiredApprovingReviewCount"]);
+        }
+        if rule["requiresStatusChecks"].as_bool() == Some(true) {
+            for context in rule["requiredStatusCheckContexts"]
+                .as_array()
+                .into_iter()
+                .flatten()
+            {
+                protection.add_check(context);
+            }
+        }
+        for node in git_ref["rules"]["nodes"].as_array().into_iter().flatten() {
+            let parameters = &node["parameters"];
+            match node["type"].as_str() {
+                Some("PULL_REQUEST") => {
+                    protection.add_reviews(&parameters["requiredApprovingReviewCount"]);
+                }
+                Some("REQUIRED_STATUS_CHECKS") => {
+                    for check in parameters["requiredStatusChecks"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                    {
+                        protection.add_check(&check["context"]);
+                    }
+                }
+                _ => {}
+            }
+        }
+        protection
+    }
+
+    /// 複数のルールが当たる場合は最も厳しい承認数を採る
+    fn add_reviews(&mut self, count: &serde_json::Value) {
+        let count = count.as_u64().unwrap_or(0) as u32;
+        self.required_approving_reviews = self.required_approving_reviews.max(count);
+    }
+
+    fn add_check(&mut self, context: &serde_json::Value) {
+        if let Some(context) = context.as_str() {
+            if !self.required_status_checks.iter().any(|c| c == context) {
+                self.required_status_checks.push(context.to_string());
+            }
+        }
+    }
+}
+
+/// マージ可否（`mergeable` / `mergeable_state` の要約）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mergeability {
+    Clean,
+    /// 必須でないチェックが失敗している
+    Unstable,
+    /// GitHub がマージを止めている（`mergeable_state = blocked`）
+    ///
+    /// 原因となる必須レビュー・必須チェックは [`PullRequest::protection`] を参照。
+    Blocked,
+    /// ベースブランチに追従が必要
+    Behind,
+    Conflicting,
+    Draft,
+    /// 計算中または不明
+    Unknown,
+}
+
+impl Mergeability {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Clean => "Ready to merge",
+            Self::Unstable => "Mergeable (some checks failing)",
+            Self::Blocked => "Merging is blocked (required reviews or checks)",
+            Self::Behind => "Head branch is out of date",
+            Self::Conflicting => "Has conflicts",
+            Self::Draft => "Draft PR",
+            Self::Unknown => "Checking mergeability...",
+        }
+    }
+
+    /// 今すぐマージできるか（不明な場合は GitHub 側の判定に任せる）
+    pub fn can_merge(&self) -> bool {
+        matches!(self, Self::Clean | Self::Unstable | Self::Unknown)
+    }
+
+    /// 自動マージを有効にできるか（ブロック中こそ自動マージの出番）
+    pub fn can_enable_auto_merge(&self) -> bool {
+        !matches!(self, Self::Conflicting | Self::Draft)
+    }
+}
+
+impl PullRequest {
+    pub fn mergeability(&self) -> Mergeability {
+        if self.mergeable == Some(false) {
+            return Mergeability::Conflicting;
+        }
+        match self.mergeable_state.as_deref() {
+            Some("clean" | "has_hooks") => Mergeability::Clean,
+            Some("unstable") => Mergeability::Unstable,
+            Some("blocked") => Mergeability::Blocked,
+            Some("behind") => Mergeability::Behind,
+            Some("dirty") => Mergeability::Conflicting,
+            Some("draft") => Mergeability::Draft,
+            _ => Mergeability::Unknown,
+        }
+    }
+}
+
+/// マージ方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeMethod {
+    Merge,
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub const ALL: [MergeMethod; 3] = [Self::Merge, Self::Squash, Self::Rebase];
+
+    /// REST API の `merge_method` 値
+    pub fn api_value(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
+        }
+    }
+
+    /// GraphQL の `PullRequestMergeMethod` 値
+    pub fn graphql_value(&self) -> &'static str {
+        match self {
+            Self::Merge => "MERGE",
+            Self::Squash => "SQUASH",
+            Self::Rebase => "REBASE",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Merge => "Create a merge commit",
+            Self::Squash => "Squash and merge",
+            Self::Rebase => "Rebase and merge",
+        }
+    }
+
+    /// コミットタイトル・本文を指定できるか（rebase はコミットをそのまま積む）
+    pub fn has_commit_message(&self) -> bool {
+        !matches!(self, Self::Rebase)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,115 +500,294 @@ pub struct ChangedFile {
     pub patch: Option<String>,
 }
 
-pub async fn fetch_pr(repo: &str, pr_number: u32) -> Result<PullRequest> {
-    let endpoint = format!("repos/{}/pulls/{}", repo, pr_number);
-    let json = gh_api(&endpoint).await?;
-    serde_json::from_value(json).context("Failed to parse PR response")
+/// 自分が「Viewed」にしたファイル（GraphQL の `viewerViewedState`）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewedFiles {
+    /// PR の node ID（`markFileAsViewed` などのミューテーションに必要）
+    pub pull_request_id: String,
+    pub paths: HashSet<String>,
 }
 
-pub async fn fetch_changed_files(repo: &str, pr_number: u32) -> Result<Vec<ChangedFile>> {
-    let endpoint = format!("repos/{}/pulls/{}/files", repo, pr_number);
-    let json = gh_api(&endpoint).await?;
-    serde_json::from_value(json).context("Failed to parse changed files response")
+/// `GET /pulls/{n}/commits` が返すコミット数の上限
+pub const MAX_PR_COMMITS: usize = 250;
+
+/// PR に含まれるコミット（`GET /pulls/{n}/commits`）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrCommit {
+    pub sha: String,
+    pub commit: CommitDetail,
+    /// GitHub アカウントに紐付かないコミットは None
+    pub author: Option<User>,
+    #[serde(default)]
+    pub parents: Vec<CommitParent>,
 }
 
-pub async fn submit_review(
-    repo: &str,
-    pr_number: u32,
-    action: ReviewAction,
-    body: &str,
-) -> Result<()> {
-    let action_flag = match action {
-        ReviewAction::Approve => "--approve",
-        ReviewAction::RequestChanges => "--request-changes",
-        ReviewAction::Comment => "--comment",
-    };
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitDetail {
+    pub message: String,
+    pub author: Option<GitActor>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitActor {
+    pub name: String,
+    pub date: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitParent {
+    pub sha: String,
+}
 
-    gh_command(&[
-        "pr",
-        "review",
-        &pr_number.to_string(),
-        action_flag,
-        "-b",
-        body,
-        "-R",
-        repo,
-    ])
-    .await?;
+impl PrCommit {
+    pub fn short_sha(&self) -> &str {
+        &self.sha[..self.sha.len().min(7)]
+    }
+
+    /// コミットメッセージの 1 行目
+    pub fn summary(&self) -> &str {
+        self.commit.message.lines().next().unwrap_or_default()
+    }
 
-    Ok(())
+    /// GitHub のログイン名、なければ git の author 名
+    pub fn author_name(&self) -> &str {
+        match (&self.author, &self.commit.author) {
+            (Some(user), _) => &user.login,
+            (None, Some(actor)) => &actor.name,
+            (None, None) => "unknown",
+        }
+    }
 }
 
-/// Fetch the raw diff for a PR using `gh pr diff`
-pub async fn fetch_pr_diff(repo: &str, pr_number: u32) -> Result<String> {
-    gh_command(&["pr", "diff", &pr_number.to_string(), "-R", repo]).await
+/// Reviews API の event 値
+pub(super) fn review_event(action: ReviewAction) -> &'static str {
+    match action {
+        ReviewAction::Approve => "APPROVE",
+        ReviewAction::RequestChanges => "REQUEST_CHANGES",
+        ReviewAction::Comment => "COMMENT",
+    }
 }
 
+/// `POST /pulls/{n}/reviews` のリクエストボディを組み立てる
+pub(super) fn build_review_payload(
+    commit_id: &str,
+    action: ReviewAction,
+    body: &str,
+    comments: &[DraftComment],
+) -> serde_json::Value {
+    serde_json::json!({
+        "commit_id": commit_id,
+        "event": review_event(action),
+        "body": body,
+        "comments": comments,
+    })
+}
+
+/// 検索 API が返す結果の上限（これを超える分は次のページがないものとして返される）
+pub const SEARCH_RESULT_LIMIT: usize = 1000;
+
 /// ページネーション結果
 pub struct PrListPage {
     pub items: Vec<PullRequestSummary>,
     pub has_more: bool,
+    /// 次のページを取得するためのカーソル（GraphQL の `pageInfo.endCursor`）
+    pub end_cursor: Option<String>,
+    /// 条件に一致する PR の総数（`issueCount` / `totalCount`）
+    pub total_count: Option<u32>,
 }
 
-/// PR一覧取得（limit+1件取得してhas_moreを判定）
-pub async fn fetch_pr_list(repo: &str, state: PrStateFilter, limit: u32) -> Result<PrListPage> {
-    let output = gh_command(&[
-        "pr",
-        "list",
-        "-R",
-        repo,
-        "-s",
-        state.as_gh_arg(),
-        "--json",
-        "number,title,state,author,isDraft,labels,updatedAt",
-        "--limit",
-        &(limit + 1).to_string(),
-    ])
-    .await?;
-
-    let mut items: Vec<PullRequestSummary> =
-        serde_json::from_str(&output).context("Failed to parse PR list response")?;
-    let has_more = items.len() > limit as usize;
-    items.truncate(limit as usize);
-
-    Ok(PrListPage { items, has_more })
-}
-
-/// PR一覧取得（オフセット付き、追加ロード用）
-pub async fn fetch_pr_list_with_offset(
-    repo: &str,
-    state: PrStateFilter,
-    offset: u32,
-    limit: u32,
-) -> Result<PrListPage> {
-    // gh pr list doesn't support offset directly, so we fetch offset+limit+1 and skip
-    let fetch_count = offset + limit + 1;
-    let output = gh_command(&[
-        "pr",
-        "list",
-        "-R",
-        repo,
-        "-s",
-        state.as_gh_arg(),
-        "--json",
-        "number,title,state,author,isDraft,labels,updatedAt",
-        "--limit",
-        &fetch_count.to_string(),
-    ])
-    .await?;
-
-    let all_items: Vec<PullRequestSummary> =
-        serde_json::from_str(&output).context("Failed to parse PR list response")?;
-
-    // Check if there are more items beyond what we're returning
-    let has_more = all_items.len() > (offset + limit) as usize;
-
-    // Skip the offset items and take limit items
-    let items: Vec<PullRequestSummary> = all_items
-        .into_iter()
-        .skip(offset as usize)
-        .take(limit as usize)
-        .collect();
-
-    Ok(PrListPage { items, has_more })
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::comment::CommentRange;
+
+    #[test]
+    fn test_mergeability_from_mergeable_state() {
+        let mut pr: PullRequest = serde_json::from_str(
+            r#"{
+                "number": 1, "title": "t", "body": null, "state": "open",
+                "head": { "ref": "f", "sha": "a" }, "base": { "ref": "main", "sha": "b" },
+                "user": { "login": "u" }, "updated_at": "2024-01-01T00:00:00Z"
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(pr.mergeability(), Mergeability::Unknown);
+        assert!(pr.mergeability().can_merge());
+
+        pr.mergeable = Some(true);
+        pr.mergeable_state = Some("blocked".to_string());
+        assert_eq!(pr.mergeability(), Mergeability::Blocked);
+        assert!(!pr.mergeability().can_merge());
+        assert!(pr.mergeability().can_enable_auto_merge());
+
+        pr.mergeable = Some(false);
+        assert_eq!(pr.mergeability(), Mergeability::Conflicting);
+        assert!(!pr.mergeability().can_enable_auto_merge());
+    }
+
+    #[test]
+    fn test_branch_protection_merges_classic_rule_and_rulesets() {
+        let git_ref = serde_json::json!({
+            "branchProtectionRule": {
+                "requiresApprovingReviews": true,
+                "requiredApprovingReviewCount": 1,
+                "requiresStatusChecks": true,
+                "requiredStatusCheckContexts": ["build", "lint"]
+            },
+            "rules": { "nodes": [
+                { "type": "PULL_REQUEST", "parameters": { "requiredApprovingReviewCount": 2 } },
+                { "type": "REQUIRED_STATUS_CHECKS", "parameters": {
+                    "requiredStatusChecks": [{ "context": "lint" }, { "context": "test" }]
+                } },
+                { "type": "DELETION", "parameters": null }
+            ] }
+        });
+        let protection = BranchProtection::from_graphql_ref(&git_ref);
+        assert_eq!(protection.required_approving_reviews, 2);
+        assert_eq!(
+            protection.required_status_checks,
+            vec!["build", "lint", "test"]
+        );
+
+        // 保護ルールを読む権限がない・ルールがない
+        let unprotected =
+            serde_json::json!({ "branchProtectionRule": null, "rules": { "nodes": [] } });
+        assert_eq!(
+            BranchProtection::from_graphql_ref(&unprotected),
+            BranchProtection::default()
+        );
+    }
+
+    #[test]
+    fn test_pr_commit_summary_and_author_fallback() {
+        let json = r#"{
+            "sha": "0123456789abcdef",
+            "commit": {
+                "message": "Fix parser\n\nLonger description",
+                "author": { "name": "Jane Doe", "email": "j@example.com", "date": "2024-01-01T00:00:00Z" }
+            },
+            "author": null,
+            "parents": [{ "sha": "fedcba" }]
+        }"#;
+        let commit: PrCommit = serde_json::from_str(json).unwrap();
+        assert_eq!(commit.short_sha(), "0123456");
+        assert_eq!(commit.summary(), "Fix parser");
+        assert_eq!(commit.author_name(), "Jane Doe");
+        assert_eq!(commit.parents[0].sha, "fedcba");
+    }
+
+    #[test]
+    fn test_build_review_payload_includes_drafts() {
+        let comments = vec![DraftComment {
+            path: "src/main.rs".to_string(),
+            range: CommentRange::single(12),
+            body: "nit".to_string(),
+        }];
+        let payload = build_review_payload(
+            "abc123",
+            ReviewAction::RequestChanges,
+            "see inline",
+            &comments,
+        );
+
+        assert_eq!(payload["commit_id"], "abc123");
+        assert_eq!(payload["event"], "REQUEST_CHANGES");
+        assert_eq!(payload["body"], "see inline");
+        assert_eq!(payload["comments"][0]["path"], "src/main.rs");
+        assert_eq!(payload["comments"][0]["line"], 12);
+        assert_eq!(payload["comments"][0]["body"], "nit");
+        assert!(payload["comments"][0].get("start_line").is_none());
+    }
+
+    #[test]
+    fn test_build_review_payload_multi_line_draft() {
+        let comments = vec![DraftComment {
+            path: "src/main.rs".to_string(),
+            range: CommentRange::range(10, 12),
+            body: "extract this".to_string(),
+        }];
+        let payload = build_review_payload("abc123", ReviewAction::Comment, "", &comments);
+
+        assert_eq!(payload["comments"][0]["start_line"], 10);
+        assert_eq!(payload["comments"][0]["line"], 12);
+    }
+
+    #[test]
+    fn test_pr_summary_parses_rest_fields() {
+        let json = r#"{"number":1,"title":"t","state":"open","user":{"login":"u"},"draft":true,"labels":[],"updated_at":"2024-01-01T00:00:00Z"}"#;
+        let pr: PullRequestSummary = serde_json::from_str(json).unwrap();
+        assert_eq!(pr.author.login, "u");
+        assert!(pr.is_draft);
+        assert_eq!(pr.updated_at, "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_pr_list_query_builds_search_qualifiers() {
+        let mut query = PrListQuery::default();
+        assert_eq!(
+            query.search_query("o/r"),
+            "repo:o/r is:pr is:open sort:updated-desc"
+        );
+
+        query.state = PrStateFilter::All;
+        query.sort = PrSort::Comments;
+        query.search = format!(" {} label:bug ", PrListQuery::NEEDS_MY_REVIEW);
+        assert_eq!(
+            query.search_query("o/r"),
+            "repo:o/r is:pr review-requested:@me label:bug sort:comments-desc"
+        );
+
+        // 明示的な sort: 修飾子は上書きしない
+        query.search = "sort:reactions-+1-desc".to_string();
+        assert_eq!(
+            query.search_query("o/r"),
+            "repo:o/r is:pr sort:reactions-+1-desc"
+        );
+    }
+
+    #[test]
+    fn test_inbox_query_spans_repositories() {
+        let mut query = PrListQuery {
+            inbox: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            query.search_query(""),
+            "is:pr archived:false is:open review-requested:@me sort:updated-desc"
+        );
+
+        // 検索条件を指定した場合はそちらで置き換える
+        query.search = PrListQuery::MY_PRS.to_string();
+        assert_eq!(
+            query.search_query(""),
+            "is:pr archived:false is:open author:@me sort:updated-desc"
+        );
+    }
+
+    #[test]
+    fn test_search_pull_request_converts_to_summary() {
+        let json = r#"{
+            "number": 5, "title": "t", "state": "OPEN", "author": null, "isDraft": false,
+            "labels": { "nodes": [{ "name": "bug" }] }, "updatedAt": "2024-01-01T00:00:00Z",
+            "reviewDecision": "CHANGES_REQUESTED", "additions": 10, "deletions": 2,
+            "commits": { "nodes": [{ "commit": { "statusCheckRollup": { "state": "FAILURE" } } }] },
+            "repository": { "nameWithOwner": "o/r" }
+        }"#;
+        let node: SearchPullRequest = serde_json::from_str(json).unwrap();
+        let pr = PullRequestSummary::from(node);
+        assert_eq!(pr.state, "open");
+        assert_eq!(pr.author.login, "ghost");
+        assert_eq!(pr.labels[0].name, "bug");
+        assert_eq!(pr.review_decision, Some(ReviewDecision::ChangesRequested));
+        assert_eq!(pr.ci_state(), Some(CheckState::Failure));
+        assert_eq!((pr.additions, pr.deletions), (Some(10), Some(2)));
+        assert_eq!(pr.repo.as_deref(), Some("o/r"));
+    }
+
+    #[test]
+    fn test_build_review_payload_without_drafts() {
+        let payload = build_review_payload("abc123", ReviewAction::Approve, "LGTM", &[]);
+        assert_eq!(payload["event"], "APPROVE");
+        assert_eq!(payload["comments"].as_array().map(|a| a.len()), Some(0));
+    }
 }
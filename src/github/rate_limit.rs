@@ -0,0 +1,385 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::time::Instant;
+
+use super::error::{is_rate_limit_message, rate_limit_wait_from_message, GitHubError};
+
+/// 読み取りリクエストの最大試行回数（初回を含む）
+const MAX_READ_ATTEMPTS: u32 = 4;
+/// 書き込みリクエストの最大試行回数（レートリミットで拒否された場合のみ再試行する）
+const MAX_WRITE_ATTEMPTS: u32 = 3;
+/// 指数バックオフの初期値と上限
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// これより長く待つ必要があるレートリミットは待たずにエラーにする
+const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(5 * 60);
+/// 書き込み同士の最小間隔（セカンダリレートリミット対策）
+const WRITE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// リクエストの種類（再試行してよいか、書き込みキューに並べるか）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestKind {
+    /// 冪等な読み取り（GET / GraphQL クエリ）
+    Read,
+    /// 書き込み（POST / PATCH / PUT / DELETE / GraphQL ミューテーション）
+    Write,
+}
+
+impl RequestKind {
+    /// GraphQL はクエリ文字列がミューテーションかどうかで判定する
+    pub fn of_graphql(query: &str) -> Self {
+        if query.trim_start().starts_with("mutation") {
+            Self::Write
+        } else {
+            Self::Read
+        }
+    }
+}
+
+/// API の残りクォータ（`X-RateLimit-*` ヘッダー）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    /// `core` / `graphql` など
+    pub resource: String,
+    pub limit: u32,
+    pub remaining: u32,
+    /// リセット時刻（UNIX 秒）
+    pub reset_at: i64,
+}
+
+impl RateLimit {
+    /// 残りの割合が少ないほど小さい（複数リソースのうち表示するものを選ぶため）
+    fn remaining_ratio(&self) -> f64 {
+        if self.limit == 0 {
+            return 1.0;
+        }
+        self.remaining as f64 / self.limit as f64
+    }
+
+    /// リセットまでの秒数
+    pub fn reset_in_secs(&self, now: i64) -> i64 {
+        (self.reset_at - now).max(0)
+    }
+}
+
+/// レスポンスヘッダーから読み取ったレートリミット情報
+#[derive(Debug, Default, Clone)]
+pub struct RateLimitHeaders {
+    pub resource: Option<String>,
+    pub limit: Option<u32>,
+    pub remaining: Option<u32>,
+    pub reset: Option<i64>,
+    pub retry_after: Option<u64>,
+}
+
+impl RateLimitHeaders {
+    /// ヘッダー名（小文字）から値を引く関数で読み取る
+    pub fn parse(header: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            resource: header("x-ratelimit-resource"),
+            limit: header("x-ratelimit-limit").and_then(|v| v.trim().parse().ok()),
+            remaining: header("x-ratelimit-remaining").and_then(|v| v.trim().parse().ok()),
+            reset: header("x-ratelimit-reset").and_then(|v| v.trim().parse().ok()),
+            retry_after: header("retry-after").and_then(|v| v.trim().parse().ok()),
+        }
+    }
+
+    pub fn snapshot(&self) -> Option<RateLimit> {
+        Some(RateLimit {
+            resource: self.resource.clone().unwrap_or_else(|| "core".to_string()),
+            limit: self.limit?,
+            remaining: self.remaining?,
+            reset_at: self.reset?,
+        })
+    }
+
+    /// 403 / 429 がレートリミットによるものなら [`GitHubError::RateLimited`] を返す
+    ///
+    /// 権限不足などの 403 と区別するため、`Retry-After`・残り 0・メッセージのいずれかで判定する。
+    pub fn rate_limit_error(&self, status: u16, message: &str, now: i64) -> Option<GitHubError> {
+        if status != 403 && status != 429 {
+            return None;
+        }
+        let retry_after = if let Some(secs) = self.retry_after {
+            Some(Duration::from_secs(secs))
+        } else if self.remaining == Some(0) {
+            let reset_in = self.reset.map(|reset| (reset - now).max(1)).unwrap_or(1);
+            Some(Duration::from_secs(reset_in as u64))
+        } else if is_rate_limit_message(message) {
+            rate_limit_wait_from_message(message)
+        } else if status == 429 {
+            None
+        } else {
+            return None;
+        };
+        Some(GitHubError::RateLimited {
+            retry_after,
+            message: message.to_string(),
+        })
+    }
+}
+
+/// レートリミットを考慮してリクエストを実行する
+///
+/// - 読み取りはレートリミットと一時的な失敗を指数バックオフで再試行する
+/// - 書き込みは 1 件ずつ間隔をあけて実行し（キュー）、レートリミットで拒否された場合のみ再試行する
+/// - 直近のレスポンスの残りクォータを保持する（フッター表示用）
+#[derive(Debug)]
+pub struct RateLimiter {
+    snapshots: Mutex<HashMap<String, RateLimit>>,
+    /// 書き込みキュー（直前の書き込みの完了時刻）
+    last_write: tokio::sync::Mutex<Option<Instant>>,
+    backoff_base: Duration,
+    write_interval: Duration,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            snapshots: Mutex::new(HashMap::new()),
+            last_write: tokio::sync::Mutex::new(None),
+            backoff_base: BACKOFF_BASE,
+            write_interval: WRITE_INTERVAL,
+        }
+    }
+}
+
+impl RateLimiter {
+    /// 待ち時間を短縮したインスタンス（テスト用）
+    #[cfg(test)]
+    pub fn without_delays() -> Self {
+        Self {
+            backoff_base: Duration::ZERO,
+            write_interval: Duration::ZERO,
+            ..Self::default()
+        }
+    }
+
+    pub fn record(&self, snapshot: RateLimit) {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .insert(snapshot.resource.clone(), snapshot);
+    }
+
+    /// 最も残りの少ない `core` / `graphql` のクォータ
+    ///
+    /// `search` は 1 分ごとにリセットされる小さな枠なので表示しない。
+    pub fn snapshot(&self) -> Option<RateLimit> {
+        self.snapshots
+            .lock()
+            .unwrap()
+            .values()
+            .filter(|s| s.resource != "search")
+            .min_by(|a, b| a.remaining_ratio().total_cmp(&b.remaining_ratio()))
+            .cloned()
+    }
+
+    pub async fn run<T, F, Fut>(&self, kind: RequestKind, mut request: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        // 書き込みは直列化し、前回の書き込みから一定時間あける
+        let mut write_slot = match kind {
+            RequestKind::Read => None,
+            RequestKind::Write => {
+                let slot = self.last_write.lock().await;
+                if let Some(last) = *slot {
+                    tokio::time::sleep_until(last + self.write_interval).await;
+                }
+                Some(slot)
+            }
+        };
+
+        let mut attempt = 0;
+        let result = loop {
+            let result = request().await;
+            attempt += 1;
+            match result {
+                Err(e) => match self.retry_delay(&e, kind, attempt) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => break Err(e),
+                },
+                ok => break ok,
+            }
+        };
+        if let Some(ref mut slot) = write_slot {
+            **slot = Some(Instant::now());
+        }
+        result
+    }
+
+    /// `attempt` 回目の失敗後に再試行するなら、その前に待つ時間を返す
+    fn retry_delay(
+        &self,
+        error: &anyhow::Error,
+        kind: RequestKind,
+        attempt: u32,
+    ) -> Option<Duration> {
+        let max_attempts = match kind {
+            RequestKind::Read => MAX_READ_ATTEMPTS,
+            RequestKind::Write => MAX_WRITE_ATTEMPTS,
+        };
+        if attempt >= max_attempts {
+            return None;
+        }
+        let backoff = self
+            .backoff_base
+            .saturating_mul(1 << (attempt - 1))
+            .min(BACKOFF_MAX);
+        match error.downcast_ref::<GitHubError>()? {
+            GitHubError::RateLimited { retry_after, .. } => {
+                let wait = retry_after.unwrap_or(backoff);
+                (wait <= MAX_RATE_LIMIT_WAIT).then_some(wait)
+            }
+            // 書き込みは失敗してもサーバー側で処理された可能性があるので再試行しない
+            GitHubError::Network(_) => (kind == RequestKind::Read).then_some(backoff),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::error::SECONDARY_RATE_LIMIT_WAIT;
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn headers(pairs: &[(&str, &str)]) -> RateLimitHeaders {
+        RateLimitHeaders::parse(|name| {
+            pairs
+                .iter()
+                .find(|(k, _)| *k == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn test_rate_limit_error_detects_primary_and_secondary_limits() {
+        let now = 1_000;
+        // プライマリ: 残り 0 → リセットまで待つ
+        let primary = headers(&[
+            ("x-ratelimit-remaining", "0"),
+            ("x-ratelimit-reset", "1030"),
+        ]);
+        assert!(matches!(
+            primary.rate_limit_error(403, "API rate limit exceeded", now),
+            Some(GitHubError::RateLimited { retry_after: Some(d), .. }) if d == Duration::from_secs(30)
+        ));
+
+        // セカンダリ: Retry-After を優先
+        let secondary = headers(&[("retry-after", "7"), ("x-ratelimit-remaining", "4000")]);
+        assert!(matches!(
+            secondary.rate_limit_error(429, "", now),
+            Some(GitHubError::RateLimited { retry_after: Some(d), .. }) if d == Duration::from_secs(7)
+        ));
+        let message = "You have exceeded a secondary rate limit";
+        assert!(matches!(
+            headers(&[]).rate_limit_error(403, message, now),
+            Some(GitHubError::RateLimited { retry_after: Some(d), .. }) if d == SECONDARY_RATE_LIMIT_WAIT
+        ));
+
+        // 権限不足の 403 や 404 はレートリミットではない
+        let plenty = headers(&[("x-ratelimit-remaining", "4000")]);
+        assert!(plenty
+            .rate_limit_error(403, "Resource not accessible", now)
+            .is_none());
+        assert!(primary.rate_limit_error(404, "Not Found", now).is_none());
+    }
+
+    #[test]
+    fn test_snapshot_prefers_most_constrained_resource() {
+        let limiter = RateLimiter::default();
+        assert!(limiter.snapshot().is_none());
+        for (resource, remaining) in [("core", 4000), ("graphql", 100), ("search", 1)] {
+            limiter.record(RateLimit {
+                resource: resource.to_string(),
+                limit: 5000,
+                remaining,
+                reset_at: 0,
+            });
+        }
+        assert_eq!(limiter.snapshot().unwrap().resource, "graphql");
+    }
+
+    #[tokio::test]
+    async fn test_reads_retry_transient_errors_but_writes_do_not() {
+        let limiter = RateLimiter::without_delays();
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result: Result<u32> = limiter
+            .run(RequestKind::Read, || async move {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(GitHubError::Network("502".to_string()).into())
+                } else {
+                    Ok(42)
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result: Result<()> = limiter
+            .run(RequestKind::Write, || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(GitHubError::Network("502".to_string()).into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_requests_retry_until_attempts_run_out() {
+        let limiter = RateLimiter::without_delays();
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result: Result<()> = limiter
+            .run(RequestKind::Write, || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(GitHubError::RateLimited {
+                    retry_after: Some(Duration::ZERO),
+                    message: "secondary rate limit".to_string(),
+                }
+                .into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), MAX_WRITE_ATTEMPTS);
+
+        // 待ち時間が長すぎるものは即座に諦める
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result: Result<()> = limiter
+            .run(RequestKind::Read, || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(GitHubError::RateLimited {
+                    retry_after: Some(Duration::from_secs(3600)),
+                    message: "API rate limit exceeded".to_string(),
+                }
+                .into())
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // 通常のエラーは再試行しない
+        let counter = AtomicU32::new(0);
+        let calls = &counter;
+        let result: Result<()> = limiter
+            .run(RequestKind::Read, || async move {
+                calls.fetch_add(1, Ordering::SeqCst);
+                anyhow::bail!("Not Found")
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+/// コメント・PR に付けられるリアクションの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionKind {
+    ThumbsUp,
+    ThumbsDown,
+    Laugh,
+    Hooray,
+    Confused,
+    Heart,
+    Rocket,
+    Eyes,
+}
+
+impl ReactionKind {
+    /// ピッカーの表示順（GitHub の Web UI と同じ）
+    pub const ALL: [ReactionKind; 8] = [
+        Self::ThumbsUp,
+        Self::ThumbsDown,
+        Self::Laugh,
+        Self::Hooray,
+        Self::Confused,
+        Self::Heart,
+        Self::Rocket,
+        Self::Eyes,
+    ];
+
+    /// API の `content` 値
+    pub fn content(&self) -> &'static str {
+        match self {
+            Self::ThumbsUp => "+1",
+            Self::ThumbsDown => "-1",
+            Self::Laugh => "laugh",
+            Self::Hooray => "hooray",
+            Self::Confused => "confused",
+            Self::Heart => "heart",
+            Self::Rocket => "rocket",
+            Self::Eyes => "eyes",
+        }
+    }
+
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::ThumbsUp => "👍",
+            Self::ThumbsDown => "👎",
+            Self::Laugh => "😄",
+            Self::Hooray => "🎉",
+            Self::Confused => "😕",
+            Self::Heart => "❤️",
+            Self::Rocket => "🚀",
+            Self::Eyes => "👀",
+        }
+    }
+}
+
+/// REST レスポンスに含まれる `reactions` の集計
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Reactions {
+    pub total_count: u32,
+    #[serde(rename = "+1")]
+    pub thumbs_up: u32,
+    #[serde(rename = "-1")]
+    pub thumbs_down: u32,
+    pub laugh: u32,
+    pub hooray: u32,
+    pub confused: u32,
+    pub heart: u32,
+    pub rocket: u32,
+    pub eyes: u32,
+}
+
+impl Reactions {
+    pub fn count(&self, kind: ReactionKind) -> u32 {
+        match kind {
+            ReactionKind::ThumbsUp => self.thumbs_up,
+            ReactionKind::ThumbsDown => self.thumbs_down,
+            ReactionKind::Laugh => self.laugh,
+            ReactionKind::Hooray => self.hooray,
+            ReactionKind::Confused => self.confused,
+            ReactionKind::Heart => self.heart,
+            ReactionKind::Rocket => self.rocket,
+            ReactionKind::Eyes => self.eyes,
+        }
+    }
+
+    /// 表示用の要約（"👍 2  🎉 1"）。リアクションがなければ None
+    pub fn summary(&self) -> Option<String> {
+        let parts: Vec<String> = ReactionKind::ALL
+            .iter()
+            .filter(|kind| self.count(**kind) > 0)
+            .map(|kind| format!("{} {}", kind.emoji(), self.count(*kind)))
+            .collect();
+        (!parts.is_empty()).then(|| parts.join("  "))
+    }
+}
+
+/// リアクションの対象
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionTarget {
+    /// インラインのレビューコメント
+    ReviewComment(u64),
+    /// ディスカッション（会話タブ）のコメント
+    DiscussionComment(u64),
+    /// PR 本体
+    PullRequest(u32),
+}
+
+impl ReactionTarget {
+    /// `POST .../reactions` のエンドポイント
+    pub(super) fn endpoint(&self, repo: &str) -> String {
+        match self {
+            Self::ReviewComment(id) => format!("repos/{}/pulls/comments/{}/reactions", repo, id),
+            Self::DiscussionComment(id) => {
+                format!("repos/{}/issues/comments/{}/reactions", repo, id)
+            }
+            Self::PullRequest(number) => format!("repos/{}/issues/{}/reactions", repo, number),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reactions_deserialize_rollup() {
+        let json = r#"{"url":"u","total_count":3,"+1":2,"-1":0,"laugh":0,"hooray":1,"confused":0,"heart":0,"rocket":0,"eyes":0}"#;
+        let reactions: Reactions = serde_json::from_str(json).unwrap();
+        assert_eq!(reactions.count(ReactionKind::ThumbsUp), 2);
+        assert_eq!(reactions.summary().as_deref(), Some("👍 2  🎉 1"));
+        assert_eq!(Reactions::default().summary(), None);
+    }
+
+    #[test]
+    fn test_reaction_target_endpoints() {
+        assert_eq!(
+            ReactionTarget::ReviewComment(1).endpoint("o/r"),
+            "repos/o/r/pulls/comments/1/reactions"
+        );
+        assert_eq!(
+            ReactionTarget::DiscussionComment(2).endpoint("o/r"),
+            "repos/o/r/issues/comments/2/reactions"
+        );
+        assert_eq!(
+            ReactionTarget::PullRequest(3).endpoint("o/r"),
+            "repos/o/r/issues/3/reactions"
+        );
+    }
+}
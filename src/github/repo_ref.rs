@@ -0,0 +1,122 @@
+use anyhow::Result;
+
+/// ホスト指定がない場合の GitHub ホスト
+pub const DEFAULT_HOST: &str = "github.com";
+
+/// `--repo` などで指定されたリポジトリ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoRef {
+    /// ホスト名（`owner/repo` 形式で省略された場合は None）
+    pub host: Option<String>,
+    /// `owner/repo`
+    pub repo: String,
+    /// PR URL から取り出した PR 番号
+    pub pr_number: Option<u32>,
+}
+
+impl RepoRef {
+    /// `owner/repo`、`host/owner/repo`、`https://host/owner/repo[/pull/123]` を解釈する
+    pub fn parse(input: &str) -> Result<Self> {
+        let invalid = || {
+            anyhow::anyhow!(
+                "Invalid repository '{}': expected owner/repo, host/owner/repo or a PR URL",
+                input
+            )
+        };
+
+        let trimmed = input.trim().trim_end_matches('/');
+        let (is_url, path) = match trimmed
+            .strip_prefix("https://")
+            .or_else(|| trimmed.strip_prefix("http://"))
+        {
+            Some(rest) => (true, rest),
+            None => (false, trimmed),
+        };
+        let parts: Vec<&str> = path.split('/').collect();
+        if parts.iter().any(|p| p.is_empty()) {
+            return Err(invalid());
+        }
+
+        let (host, owner, name, rest) = match (is_url, parts.as_slice()) {
+            (false, [owner, name]) => (None, *owner, *name, &[][..]),
+            (false, [host, owner, name]) => (Some(*host), *owner, *name, &[][..]),
+            (true, [host, owner, name, rest @ ..]) => (Some(*host), *owner, *name, rest),
+            _ => return Err(invalid()),
+        };
+
+        let pr_number = match rest {
+            [] => None,
+            ["pull", number, ..] => Some(number.parse::<u32>().map_err(|_| invalid())?),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self {
+            host: host.map(str::to_string),
+            repo: format!("{}/{}", owner, name.trim_end_matches(".git")),
+            pr_number,
+        })
+    }
+}
+
+/// ホストを含めたリポジトリ名（github.com の場合は従来どおり `owner/repo`）
+///
+/// キャッシュや Rally セッションのディレクトリ名に使い、ホスト違いの同名リポジトリを区別する。
+pub fn qualified_repo(host: &str, repo: &str) -> String {
+    if host == DEFAULT_HOST {
+        repo.to_string()
+    } else {
+        format!("{}/{}", host, repo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_owner_repo() {
+        let r = RepoRef::parse("owner/repo").unwrap();
+        assert_eq!(r.host, None);
+        assert_eq!(r.repo, "owner/repo");
+        assert_eq!(r.pr_number, None);
+    }
+
+    #[test]
+    fn test_parse_host_owner_repo() {
+        let r = RepoRef::parse("ghe.example.com/owner/repo").unwrap();
+        assert_eq!(r.host.as_deref(), Some("ghe.example.com"));
+        assert_eq!(r.repo, "owner/repo");
+    }
+
+    #[test]
+    fn test_parse_urls() {
+        let r = RepoRef::parse("https://ghe.example.com/owner/repo/pull/123/files").unwrap();
+        assert_eq!(r.host.as_deref(), Some("ghe.example.com"));
+        assert_eq!(r.repo, "owner/repo");
+        assert_eq!(r.pr_number, Some(123));
+
+        let r = RepoRef::parse("https://github.com/owner/repo.git").unwrap();
+        assert_eq!(r.host.as_deref(), Some("github.com"));
+        assert_eq!(r.repo, "owner/repo");
+        assert_eq!(r.pr_number, None);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(RepoRef::parse("repo").is_err());
+        assert!(RepoRef::parse("a/b/c/d").is_err());
+        assert!(RepoRef::parse("owner//repo").is_err());
+        assert!(RepoRef::parse("https://github.com/owner").is_err());
+        assert!(RepoRef::parse("https://github.com/owner/repo/pull/abc").is_err());
+        assert!(RepoRef::parse("https://github.com/owner/repo/issues/1").is_err());
+    }
+
+    #[test]
+    fn test_qualified_repo() {
+        assert_eq!(qualified_repo("github.com", "owner/repo"), "owner/repo");
+        assert_eq!(
+            qualified_repo("ghe.example.com", "owner/repo"),
+            "ghe.example.com/owner/repo"
+        );
+    }
+}
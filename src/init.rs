@@ -9,6 +9,10 @@ const DEFAULT_CONFIG: &str = r#"editor = "vi"
 [diff]
 theme = "base16-ocean.dark"
 
+[github]
+backend = "gh"  # "gh" (GitHub CLI) or "http" (direct REST/GraphQL API)
+# host = "github.example.com"  # Optional: GitHub Enterprise Server hostname
+
 [keybindings]
 approve = 'a'
 request_changes = 'r'
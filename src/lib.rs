@@ -10,10 +10,12 @@ pub mod cache;
 pub mod config;
 pub mod diff;
 pub mod editor;
+pub mod fuzzy;
 pub mod github;
 pub mod keybinding;
 pub mod language;
 pub mod loader;
+pub mod suggestion;
 pub mod symbol;
 pub mod syntax;
 pub mod ui;
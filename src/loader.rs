@@ -1,23 +1,58 @@
+use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::warn;
 
 use crate::diff;
-use crate::github::{self, ChangedFile, PullRequest};
+use crate::github::{
+    ChangedFile, GitHubApi, GitHubError, PullRequest, RetryPolicy, MAX_CHANGED_FILES,
+};
 
 pub enum DataLoadResult {
     /// APIからデータ取得成功
     Success {
         pr: Box<PullRequest>,
         files: Vec<ChangedFile>,
+        /// GitHub の上限（3000 ファイル）に達し、ファイル一覧が欠けている可能性がある
+        truncated: bool,
     },
     /// エラー
-    Error(String),
+    Error(LoadError),
+}
+
+/// PRデータの読み込みエラー
+///
+/// エラー画面で種類ごとの案内と再試行方針を出すため、分類できたものは [`GitHubError`] を保持する。
+#[derive(Debug, Clone)]
+pub struct LoadError {
+    pub message: String,
+    pub github: Option<GitHubError>,
+}
+
+impl LoadError {
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.github
+            .as_ref()
+            .map_or(RetryPolicy::Manual, GitHubError::retry_policy)
+    }
+}
+
+impl From<&anyhow::Error> for LoadError {
+    fn from(e: &anyhow::Error) -> Self {
+        Self {
+            message: e.to_string(),
+            github: e.downcast_ref::<GitHubError>().cloned(),
+        }
+    }
 }
 
 /// コメント送信結果
 pub enum CommentSubmitResult {
     /// 送信成功
     Success,
+    /// ディスカッションコメントの編集・削除に成功（ディスカッションを再取得する）
+    DiscussionUpdated,
+    /// 送信成功（コメントの再取得は不要）
+    SuccessNoRefresh,
     /// エラー
     Error(String),
 }
@@ -32,6 +67,7 @@ pub enum FetchMode {
 
 /// バックグラウンドでPRデータを取得
 pub async fn fetch_pr_data(
+    client: Arc<dyn GitHubApi>,
     repo: String,
     pr_number: u32,
     mode: FetchMode,
@@ -39,26 +75,31 @@ pub async fn fetch_pr_data(
 ) {
     match mode {
         FetchMode::Fresh => {
-            fetch_and_send(&repo, pr_number, tx).await;
+            fetch_and_send(client.as_ref(), &repo, pr_number, tx).await;
         }
         FetchMode::CheckUpdate(cached_updated_at) => {
-            check_for_updates(&repo, pr_number, &cached_updated_at, tx).await;
+            check_for_updates(client.as_ref(), &repo, pr_number, &cached_updated_at, tx).await;
         }
     }
 }
 
-async fn fetch_and_send(repo: &str, pr_number: u32, tx: mpsc::Sender<DataLoadResult>) {
+async fn fetch_and_send(
+    client: &dyn GitHubApi,
+    repo: &str,
+    pr_number: u32,
+    tx: mpsc::Sender<DataLoadResult>,
+) {
     match tokio::try_join!(
-        github::fetch_pr(repo, pr_number),
-        github::fetch_changed_files(repo, pr_number)
+        client.fetch_pr(repo, pr_number),
+        client.fetch_changed_files(repo, pr_number)
     ) {
         Ok((pr, mut files)) => {
             // Check if any files have missing patches (large file limitation)
             let has_missing_patches = files.iter().any(|f| f.patch.is_none());
 
             if has_missing_patches {
-                // Fetch full diff using gh pr diff as fallback
-                match github::fetch_pr_diff(repo, pr_number).await {
+                // Fetch full diff as fallback
+                match client.fetch_pr_diff(repo, pr_number).await {
                     Ok(full_diff) => {
                         let mut patch_map = diff::parse_unified_diff(&full_diff);
 
@@ -78,31 +119,97 @@ async fn fetch_and_send(repo: &str, pr_number: u32, tx: mpsc::Sender<DataLoadRes
                 }
             }
 
+            let truncated = files.len() >= MAX_CHANGED_FILES;
             let _ = tx
                 .send(DataLoadResult::Success {
                     pr: Box::new(pr),
                     files,
+                    truncated,
                 })
                 .await;
         }
         Err(e) => {
-            let _ = tx.send(DataLoadResult::Error(e.to_string())).await;
+            let _ = tx.send(DataLoadResult::Error(LoadError::from(&e))).await;
         }
     }
 }
 
 async fn check_for_updates(
+    client: &dyn GitHubApi,
     repo: &str,
     pr_number: u32,
     cached_updated_at: &str,
     tx: mpsc::Sender<DataLoadResult>,
 ) {
     // PRの基本情報だけ取得してupdated_atを比較
-    if let Ok(fresh_pr) = github::fetch_pr(repo, pr_number).await {
+    if let Ok(fresh_pr) = client.fetch_pr(repo, pr_number).await {
         if fresh_pr.updated_at != cached_updated_at {
             // 更新あり → 全データ再取得
-            fetch_and_send(repo, pr_number, tx).await;
+            fetch_and_send(client, repo, pr_number, tx).await;
         }
         // 更新なし → 何もしない（キャッシュデータをそのまま使用）
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::mock::MockGitHub;
+
+    fn pr_json(updated_at: &str) -> serde_json::Value {
+        serde_json::json!({
+            "number": 1, "title": "t", "body": null, "state": "open",
+            "head": { "ref": "feature", "sha": "abc" },
+            "base": { "ref": "main", "sha": "def" },
+            "user": { "login": "me" },
+            "updated_at": updated_at
+        })
+    }
+
+    #[tokio::test]
+    async fn test_fetch_fills_missing_patch_from_full_diff() {
+        let mock = MockGitHub::new()
+            .with_response("repos/o/r/pulls/1", pr_json("t1"))
+            .with_response(
+                "repos/o/r/pulls/1/files?per_page=100&page=1",
+                serde_json::json!([
+                    { "filename": "big.rs", "status": "modified", "additions": 1, "deletions": 0 }
+                ]),
+            )
+            .with_diff("diff --git a/big.rs b/big.rs\n--- a/big.rs\n+++ b/big.rs\n@@ -1,1 +1,2 @@\n a\n+b\n");
+        let (tx, mut rx) = mpsc::channel(1);
+
+        fetch_pr_data(Arc::new(mock), "o/r".to_string(), 1, FetchMode::Fresh, tx).await;
+
+        match rx.recv().await {
+            Some(DataLoadResult::Success {
+                pr,
+                files,
+                truncated,
+            }) => {
+                assert_eq!(pr.head.sha, "abc");
+                assert!(!truncated);
+                assert!(files[0].patch.as_deref().unwrap().contains("+b"));
+            }
+            _ => panic!("expected Success"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_update_skips_refetch_when_unchanged() {
+        let mock = Arc::new(MockGitHub::new().with_response("repos/o/r/pulls/1", pr_json("t1")));
+        let (tx, mut rx) = mpsc::channel(1);
+
+        fetch_pr_data(
+            mock.clone(),
+            "o/r".to_string(),
+            1,
+            FetchMode::CheckUpdate("t1".to_string()),
+            tx,
+        )
+        .await;
+
+        assert!(rx.recv().await.is_none());
+        assert_eq!(mock.calls().len(), 1);
+    }
+}
@@ -23,7 +23,11 @@ struct Args {
     #[command(subcommand)]
     command: Option<Commands>,
 
-    /// Repository name (e.g., "owner/repo"). Auto-detected from current directory if omitted.
+    /// Pull request URL (e.g., "https://github.com/owner/repo/pull/123")
+    #[arg(value_name = "PR_URL", conflicts_with_all = ["repo", "pr", "current"])]
+    pr_url: Option<String>,
+
+    /// Repository ("owner/repo", "host/owner/repo" or a PR URL). Auto-detected from current directory if omitted.
     #[arg(short, long)]
     repo: Option<String>,
 
@@ -31,6 +35,10 @@ struct Args {
     #[arg(short, long)]
     pr: Option<u32>,
 
+    /// Open the pull request associated with the checked-out branch
+    #[arg(long, conflicts_with_all = ["repo", "pr"])]
+    current: bool,
+
     /// Start AI Rally mode directly
     #[arg(long, default_value = "false")]
     ai_rally: bool,
@@ -38,6 +46,10 @@ struct Args {
     /// Working directory for AI agents (default: current directory)
     #[arg(long)]
     working_dir: Option<String>,
+
+    /// List PRs across all repositories where review is requested from you or your teams
+    #[arg(long, conflicts_with_all = ["pr_url", "repo", "pr", "current", "ai_rally", "working_dir"])]
+    inbox: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -87,36 +99,70 @@ async fn main() -> Result<()> {
         };
     }
 
+    // --inbox: リポジトリを横断するため、リポジトリの検出は不要
+    if args.inbox {
+        preload_syntax();
+        let config = config::Config::load()?;
+        return run_with_pr_list(app::App::new_inbox(config), &args).await;
+    }
+
     // Detect or use provided repo
-    let repo = match args.repo.clone() {
-        Some(r) => r,
-        None => match github::detect_repo().await {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
-            }
+    let repo_ref = match (args.pr_url.as_deref(), args.repo.as_deref()) {
+        (Some(url), _) => match github::RepoRef::parse(url) {
+            Ok(r) if r.pr_number.is_some() => r,
+            Ok(_) => exit_with_error(format!("Not a pull request URL: {}", url)),
+            Err(e) => exit_with_error(e),
         },
+        (None, Some(r)) => github::RepoRef::parse(r).unwrap_or_else(|e| exit_with_error(e)),
+        (None, None) => github::detect_repo()
+            .await
+            .unwrap_or_else(|e| exit_with_error(e)),
     };
 
-    // Pre-initialize syntax highlighting in background to avoid delay on first diff view
-    std::thread::spawn(|| {
-        let _ = syntax::syntax_set();
-        let _ = syntax::theme_set();
-    });
+    // --current: チェックアウト中のブランチに紐づく PR を開く
+    let current_pr = if args.current {
+        Some(
+            github::detect_current_pr()
+                .await
+                .unwrap_or_else(|e| exit_with_error(e)),
+        )
+    } else {
+        None
+    };
+
+    preload_syntax();
 
-    let config = config::Config::load()?;
+    let mut config = config::Config::load()?;
+    // --repo やリモートにホストが含まれていれば設定より優先する
+    if let Some(host) = repo_ref.host {
+        config.github.host = Some(host);
+    }
+    let repo = repo_ref.repo;
 
-    // Check if we have a specific PR number
-    if let Some(pr) = args.pr {
+    // Check if we have a specific PR number (--pr, PR URL or --current)
+    if let Some(pr) = args.pr.or(repo_ref.pr_number).or(current_pr) {
         // Existing flow: open specific PR
         run_with_pr(&repo, pr, &config, &args).await
     } else {
         // New flow: show PR list
-        run_with_pr_list(&repo, config, &args).await
+        run_with_pr_list(app::App::new_pr_list(&repo, config), &args).await
     }
 }
 
+/// Pre-initialize syntax highlighting in background to avoid delay on first diff view
+fn preload_syntax() {
+    std::thread::spawn(|| {
+        let _ = syntax::syntax_set();
+        let _ = syntax::theme_set();
+    });
+}
+
+/// Print an error and exit (before the TUI has started)
+fn exit_with_error(e: impl std::fmt::Display) -> ! {
+    eprintln!("Error: {}", e);
+    std::process::exit(1);
+}
+
 /// Run the app with a specific PR number (existing flow)
 async fn run_with_pr(repo: &str, pr: u32, config: &config::Config, args: &Args) -> Result<()> {
     // リトライ用のチャンネル
@@ -140,16 +186,17 @@ async fn run_with_pr(repo: &str, pr: u32, config: &config::Config, args: &Args)
     // バックグラウンドでAPI取得
     let repo_clone = repo.to_string();
     let pr_number = pr;
+    let client = app.github_client();
 
     tokio::spawn(async move {
         tokio::select! {
             _ = token_clone.cancelled() => {}
             _ = async {
-                loader::fetch_pr_data(repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx.clone()).await;
+                loader::fetch_pr_data(client.clone(), repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx.clone()).await;
 
                 while retry_rx.recv().await.is_some() {
                     let tx_retry = tx.clone();
-                    loader::fetch_pr_data(repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx_retry)
+                    loader::fetch_pr_data(client.clone(), repo_clone.clone(), pr_number, loader::FetchMode::Fresh, tx_retry)
                         .await;
                 }
             } => {}
@@ -176,8 +223,7 @@ async fn run_with_pr(repo: &str, pr: u32, config: &config::Config, args: &Args)
 }
 
 /// Run the app with PR list (new flow)
-async fn run_with_pr_list(repo: &str, config: config::Config, args: &Args) -> Result<()> {
-    let mut app = app::App::new_pr_list(repo, config);
+async fn run_with_pr_list(mut app: app::App, args: &Args) -> Result<()> {
     setup_working_dir(&mut app, args);
 
     // Set pending AI Rally flag if --ai-rally was passed
@@ -189,11 +235,12 @@ async fn run_with_pr_list(repo: &str, config: config::Config, args: &Args) -> Re
     let (tx, rx) = mpsc::channel(2);
     app.set_pr_list_receiver(rx);
 
-    let repo_clone = repo.to_string();
-    let state_filter = app.pr_list_state_filter;
+    let repo_clone = app.repo.clone();
+    let query = app.pr_list_query.clone();
+    let client = app.github_client();
 
     tokio::spawn(async move {
-        let result = github::fetch_pr_list(&repo_clone, state_filter, 30).await;
+        let result = client.fetch_pr_list(&repo_clone, &query, 30).await;
         let _ = tx.send(result.map_err(|e| e.to_string())).await;
     });
 
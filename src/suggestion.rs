@@ -0,0 +1,391 @@
+//! Review suggestions (```suggestion blocks) and applying them to the PR branch.
+
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::diff::{classify_line, parse_hunk_header, DiffSide, LineType};
+use crate::editor::CommitMessage;
+use crate::github::comment::ReviewComment;
+use crate::github::{GitHubApi, PullRequest};
+
+/// コメント本文を suggestion ブロックの前後と中身に分けたもの
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuggestionBlock<'a> {
+    pub before: Vec<&'a str>,
+    pub lines: Vec<&'a str>,
+    pub after: Vec<&'a str>,
+}
+
+/// 本文から最初の ```suggestion ブロックを探す（閉じられていなければ None）
+pub fn find_suggestion_block(body: &str) -> Option<SuggestionBlock<'_>> {
+    let lines: Vec<&str> = body.lines().collect();
+    let (open, fence) = lines.iter().enumerate().find_map(|(i, line)| {
+        let line = line.trim_start();
+        let ticks = line.chars().take_while(|&c| c == '`').count();
+        (ticks >= 3 && line[ticks..].trim() == "suggestion").then(|| (i, &line[..ticks]))
+    })?;
+    let close = lines[open + 1..]
+        .iter()
+        .position(|line| {
+            let line = line.trim();
+            line.starts_with(fence) && line.chars().all(|c| c == '`')
+        })
+        .map(|offset| open + 1 + offset)?;
+    Some(SuggestionBlock {
+        before: lines[..open].to_vec(),
+        lines: lines[open + 1..close].to_vec(),
+        after: lines[close + 1..].to_vec(),
+    })
+}
+
+/// レビューコメントの suggestion（新しい側の `start_line..=end_line` を `lines` で置き換える）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    pub comment_id: u64,
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// 置き換え後の行（空なら対象行の削除）
+    pub lines: Vec<String>,
+}
+
+impl Suggestion {
+    /// 適用できる suggestion を含むコメントなら取り出す
+    ///
+    /// GitHub と同様、削除行（LEFT）へのコメントや outdated なスレッドのものは対象外。
+    pub fn from_comment(comment: &ReviewComment) -> Option<Self> {
+        if comment.side == Some(DiffSide::Left) || comment.is_outdated() {
+            return None;
+        }
+        let end_line = comment.line?;
+        let block = find_suggestion_block(&comment.body)?;
+        Some(Self {
+            comment_id: comment.id,
+            path: comment.path.clone(),
+            start_line: comment.start_line.unwrap_or(end_line).min(end_line),
+            end_line,
+            lines: block.lines.iter().map(|l| l.to_string()).collect(),
+        })
+    }
+}
+
+/// パッチから新しい側の `start..=end` 行を取り出す（範囲がパッチに含まれなければ None）
+///
+/// suggestion のミニ diff で置き換え前の行を表示するのに使う。
+pub fn new_side_lines(patch: &str, start: u32, end: u32) -> Option<Vec<String>> {
+    let mut found = BTreeMap::new();
+    let mut new_line: Option<u32> = None;
+    for line in patch.lines() {
+        let (line_type, content) = classify_line(line);
+        match line_type {
+            LineType::Header => new_line = parse_hunk_header(line).map(|(_, new)| new),
+            LineType::Added | LineType::Context => {
+                if let Some(n) = new_line.as_mut() {
+                    if (start..=end).contains(n) {
+                        found.insert(*n, content.to_string());
+                    }
+                    *n += 1;
+                }
+            }
+            LineType::Removed | LineType::Meta => {}
+        }
+    }
+    (found.len() == (end - start + 1) as usize).then(|| found.into_values().collect())
+}
+
+/// ファイルの内容に suggestion を適用する
+///
+/// 下の行から置き換えるので行番号はずれない。範囲が重なる・ファイルの外を指す場合はエラー。
+pub fn apply_to_content(content: &str, suggestions: &[&Suggestion]) -> Result<String> {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut sorted = suggestions.to_vec();
+    sorted.sort_by_key(|s| Reverse(s.start_line));
+
+    let mut applied_start = u32::MAX;
+    for s in sorted {
+        if s.end_line >= applied_start {
+            anyhow::bail!(
+                "Suggestions on {} overlap at line {}; apply them separately",
+                s.path,
+                applied_start
+            );
+        }
+        if s.start_line == 0 || s.end_line as usize > lines.len() {
+            anyhow::bail!(
+                "Suggestion for {}:{} is outside the file; the branch may have changed",
+                s.path,
+                s.end_line
+            );
+        }
+        lines.splice(
+            s.start_line as usize - 1..s.end_line as usize,
+            s.lines.iter().cloned(),
+        );
+        applied_start = s.start_line;
+    }
+
+    let mut result = lines.join(newline);
+    if content.ends_with('\n') && !lines.is_empty() {
+        result.push_str(newline);
+    }
+    Ok(result)
+}
+
+/// GitHub の「Commit suggestion」と同じデフォルトのコミットメッセージ
+pub fn commit_message(count: usize) -> CommitMessage {
+    let title = if count == 1 {
+        "Apply suggestion from code review"
+    } else {
+        "Apply suggestions from code review"
+    };
+    CommitMessage {
+        title: title.to_string(),
+        body: String::new(),
+    }
+}
+
+fn group_by_path(suggestions: &[Suggestion]) -> BTreeMap<&str, Vec<&Suggestion>> {
+    let mut groups: BTreeMap<&str, Vec<&Suggestion>> = BTreeMap::new();
+    for s in suggestions {
+        groups.entry(s.path.as_str()).or_default().push(s);
+    }
+    groups
+}
+
+/// API 経由で PR の head ブランチに 1 コミットとして適用し、新しいコミットの SHA を返す
+///
+/// 読み込み済みの head から branch が進んでいた場合は GitHub 側で拒否される。
+pub async fn apply_remote(
+    client: &dyn GitHubApi,
+    repo: &str,
+    pr: &PullRequest,
+    suggestions: &[Suggestion],
+) -> Result<String> {
+    let head_repo = client.fetch_pr_head_repo(repo, pr.number).await?;
+    let mut files = Vec::new();
+    for (path, group) in group_by_path(suggestions) {
+        let content = client
+            .fetch_file_content(&head_repo, path, &pr.head.sha)
+            .await?;
+        files.push((path.to_string(), apply_to_content(&content, &group)?));
+    }
+    client
+        .commit_files(
+            &head_repo,
+            &pr.head.ref_name,
+            &pr.head.sha,
+            &commit_message(suggestions.len()),
+            &files,
+        )
+        .await
+}
+
+/// `working_dir` のチェックアウトに適用してコミットし、新しいコミットの短縮 SHA を返す
+///
+/// 行番号がずれないよう、チェックアウトが PR の head にあり、対象ファイルに
+/// 未コミットの変更がないことを確認してから書き換える。push は行わない。
+pub async fn apply_local(
+    working_dir: &str,
+    head_sha: &str,
+    suggestions: &[Suggestion],
+) -> Result<String> {
+    let root = git(working_dir, &["rev-parse", "--show-toplevel"]).await?;
+    let head = git(&root, &["rev-parse", "HEAD"]).await?;
+    if head != head_sha {
+        anyhow::bail!(
+            "Local checkout is at {}, not the PR head {}. Check out and pull the PR branch first.",
+            short_sha(&head),
+            short_sha(head_sha)
+        );
+    }
+
+    let groups = group_by_path(suggestions);
+    let paths: Vec<&str> = groups.keys().copied().collect();
+    let status = git(
+        &root,
+        &[&["status", "--porcelain", "--"], paths.as_slice()].concat(),
+    )
+    .await?;
+    if !status.is_empty() {
+        anyhow::bail!("Uncommitted changes in files to patch: {}", status);
+    }
+
+    // 全ファイルの適用が成功してから書き込む
+    let mut patched = Vec::new();
+    for (path, group) in &groups {
+        let full_path = Path::new(&root).join(path);
+        let content = std::fs::read_to_string(&full_path)
+            .with_context(|| format!("Failed to read {}", path))?;
+        patched.push((full_path, apply_to_content(&content, group)?));
+    }
+    for (full_path, content) in patched {
+        std::fs::write(&full_path, content)
+            .with_context(|| format!("Failed to write {}", full_path.display()))?;
+    }
+
+    let message = commit_message(suggestions.len());
+    git(
+        &root,
+        &[
+            &["commit", "-m", message.title.as_str(), "--"],
+            paths.as_slice(),
+        ]
+        .concat(),
+    )
+    .await?;
+    git(&root, &["rev-parse", "--short", "HEAD"]).await
+}
+
+async fn git(dir: &str, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args[0],
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 表示用の短縮 SHA（先頭 7 文字）
+pub fn short_sha(sha: &str) -> &str {
+    &sha[..sha.len().min(7)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::github::User;
+
+    fn suggestion(start_line: u32, end_line: u32, lines: &[&str]) -> Suggestion {
+        Suggestion {
+            comment_id: 1,
+            path: "src/lib.rs".to_string(),
+            start_line,
+            end_line,
+            lines: lines.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_find_suggestion_block_splits_body() {
+        let body = "Rename this:\r\n```suggestion\r\nlet total = 1;\r\n```\r\nThanks";
+        let block = find_suggestion_block(body).unwrap();
+        assert_eq!(block.before, vec!["Rename this:"]);
+        assert_eq!(block.lines, vec!["let total = 1;"]);
+        assert_eq!(block.after, vec!["Thanks"]);
+
+        // 空の suggestion は行の削除
+        let block = find_suggestion_block("````suggestion\n````").unwrap();
+        assert!(block.lines.is_empty());
+
+        assert!(find_suggestion_block("```rust\nfn main() {}\n```").is_none());
+        assert!(find_suggestion_block("```suggestion\nunterminated").is_none());
+    }
+
+    #[test]
+    fn test_suggestion_from_comment_uses_line_range() {
+        let mut comment = ReviewComment {
+            id: 7,
+            path: "src/lib.rs".to_string(),
+            line: Some(12),
+            start_line: Some(10),
+            side: None,
+            original_line: None,
+            original_commit_id: None,
+            position: None,
+            diff_hunk: None,
+            body: "```suggestion\nfoo\n```".to_string(),
+            user: User {
+                login: "reviewer".to_string(),
+            },
+            created_at: String::new(),
+            in_reply_to_id: None,
+            thread: None,
+            reactions: Default::default(),
+        };
+        let s = Suggestion::from_comment(&comment).unwrap();
+        assert_eq!((s.start_line, s.end_line), (10, 12));
+        assert_eq!(s.lines, vec!["foo"]);
+
+        comment.side = Some(DiffSide::Left);
+        assert!(Suggestion::from_comment(&comment).is_none());
+    }
+
+    #[test]
+    fn test_new_side_lines_reads_range_from_patch() {
+        let patch = "@@ -1,3 +1,4 @@\n a\n-b\n+B\n+C\n d";
+        assert_eq!(
+            new_side_lines(patch, 2, 3),
+            Some(vec!["B".to_string(), "C".to_string()])
+        );
+        assert_eq!(new_side_lines(patch, 4, 4), Some(vec!["d".to_string()]));
+        assert_eq!(new_side_lines(patch, 4, 5), None);
+    }
+
+    #[test]
+    fn test_apply_to_content_applies_batch_bottom_up() {
+        let content = "a\nb\nc\nd\n";
+        let first = suggestion(1, 1, &["A1", "A2"]);
+        let last = suggestion(3, 4, &[]);
+        assert_eq!(
+            apply_to_content(content, &[&first, &last]).unwrap(),
+            "A1\nA2\nb\n"
+        );
+
+        // CRLF は保持する
+        assert_eq!(
+            apply_to_content("a\r\nb\r\n", &[&suggestion(2, 2, &["B"])]).unwrap(),
+            "a\r\nB\r\n"
+        );
+
+        let overlapping = suggestion(1, 3, &["x"]);
+        assert!(apply_to_content(content, &[&first, &overlapping]).is_err());
+        assert!(apply_to_content(content, &[&suggestion(4, 5, &["x"])]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_apply_local_commits_in_working_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_str().unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["config", "user.name", "octorus"],
+            &["config", "user.email", "octorus@example.com"],
+        ] {
+            git(root, args).await.unwrap();
+        }
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        std::fs::write(dir.path().join("src/lib.rs"), "a\nb\nc\n").unwrap();
+        git(root, &["add", "."]).await.unwrap();
+        git(root, &["commit", "-q", "-m", "init"]).await.unwrap();
+        let head = git(root, &["rev-parse", "HEAD"]).await.unwrap();
+
+        let suggestions = [suggestion(2, 2, &["B"])];
+        assert!(apply_local(root, "0000000", &suggestions).await.is_err());
+
+        apply_local(root, &head, &suggestions).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("src/lib.rs")).unwrap(),
+            "a\nB\nc\n"
+        );
+        assert_eq!(
+            git(root, &["log", "-1", "--format=%s"]).await.unwrap(),
+            "Apply suggestion from code review"
+        );
+    }
+}
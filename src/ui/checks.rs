@@ -0,0 +1,262 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::common::{
+    annotation_level_color, check_state_color, checks_badge, render_rally_status_bar,
+};
+use crate::app::{App, CheckDetailState};
+use crate::github::checks::{format_duration, CheckAnnotation, Checks};
+
+pub fn render(frame: &mut Frame, app: &mut App) {
+    let has_rally = app.has_background_rally();
+    let constraints = if has_rally {
+        vec![
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Checks (+ detail pane)
+            Constraint::Length(1), // Rally status bar
+            Constraint::Length(3), // Footer
+        ]
+    } else {
+        vec![
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Checks (+ detail pane)
+            Constraint::Length(3), // Footer
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(frame.area());
+
+    // Header
+    let mut header_spans = vec![Span::raw(match app.pr() {
+        Some(pr) => format!(
+            "PR #{} @ {}",
+            pr.number,
+            &pr.head.sha[..pr.head.sha.len().min(7)]
+        ),
+        None => "PR".to_string(),
+    })];
+    if let Some(badge) = checks_badge(app) {
+        header_spans.push(Span::raw("  "));
+        header_spans.push(badge);
+    }
+    let header = Paragraph::new(Line::from(header_spans))
+        .block(Block::default().borders(Borders::ALL).title("CI Checks"));
+    frame.render_widget(header, chunks[0]);
+
+    // Checks list (+ detail pane)
+    match (&app.checks, &app.check_detail) {
+        (Some(checks), Some(detail)) => {
+            let panes = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(chunks[1]);
+            render_check_list(frame, panes[0], checks, app.selected_check);
+            render_check_detail(frame, panes[1], checks, detail);
+        }
+        (Some(checks), None) if !checks.is_empty() => {
+            render_check_list(frame, chunks[1], checks, app.selected_check);
+        }
+        (checks, _) => {
+            let message = if let Some(ref e) = app.checks_error {
+                Span::styled(
+                    format!("Failed to load checks: {}", e),
+                    Style::default().fg(Color::Red),
+                )
+            } else if app.checks_loading || checks.is_none() {
+                Span::styled("Loading...", Style::default().fg(Color::Yellow))
+            } else {
+                Span::styled(
+                    "No checks reported for this commit",
+                    Style::default().fg(Color::DarkGray),
+                )
+            };
+            let content = Paragraph::new(Line::from(message))
+                .block(Block::default().borders(Borders::ALL).title("Checks"));
+            frame.render_widget(content, chunks[1]);
+        }
+    }
+
+    // Rally status bar (if background rally exists)
+    if has_rally {
+        render_rally_status_bar(frame, chunks[2], app);
+    }
+
+    // Footer
+    let footer_chunk_idx = if has_rally { 3 } else { 2 };
+    let help_text = if app.check_detail.is_some() {
+        "j/k/↑↓: scroll | Ctrl+d/u: page | Esc/h: close details"
+    } else {
+        "j/k/↑↓: move | Enter/l: details | R: refresh | q/Esc: back"
+    };
+    let footer_line = super::footer::build_footer_line(app, help_text);
+    let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[footer_chunk_idx]);
+}
+
+fn render_check_list(frame: &mut Frame, area: Rect, checks: &Checks, selected: usize) {
+    let runs = checks.runs.iter().map(|run| {
+        let state = run.state();
+        let result = run.conclusion.as_deref().unwrap_or(&run.status);
+        let mut spans = vec![
+            Span::styled(
+                format!("{} ", state.icon()),
+                Style::default().fg(check_state_color(state)),
+            ),
+            Span::styled(
+                run.name.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {}", result),
+                Style::default().fg(check_state_color(state)),
+            ),
+        ];
+        if let Some(secs) = run.duration_secs() {
+            spans.push(Span::styled(
+                format!("  {}", format_duration(secs)),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        if run.output.annotations_count > 0 {
+            spans.push(Span::styled(
+                format!("  [{} annotations]", run.output.annotations_count),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        ListItem::new(Line::from(spans))
+    });
+    let statuses = checks.statuses.iter().map(|status| {
+        let state = status.state();
+        let mut spans = vec![
+            Span::styled(
+                format!("{} ", state.icon()),
+                Style::default().fg(check_state_color(state)),
+            ),
+            Span::styled(
+                status.context.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("  {}", status.state),
+                Style::default().fg(check_state_color(state)),
+            ),
+        ];
+        if let Some(ref description) = status.description {
+            spans.push(Span::styled(
+                format!("  {}", description),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        ListItem::new(Line::from(spans))
+    });
+    let items: Vec<ListItem> = runs.chain(statuses).collect();
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Checks ({})", checks.len())),
+        )
+        .highlight_style(Style::default().bg(Color::DarkGray));
+    let mut list_state = ListState::default().with_selected(Some(selected));
+    frame.render_stateful_widget(list, area, &mut list_state);
+}
+
+fn render_check_detail(frame: &mut Frame, area: Rect, checks: &Checks, detail: &CheckDetailState) {
+    let mut lines: Vec<Line> = Vec::new();
+    let title = if let Some(run) = checks.runs.get(detail.index) {
+        if let Some(ref output_title) = run.output.title {
+            lines.push(Line::from(Span::styled(
+                output_title.clone(),
+                Style::default().add_modifier(Modifier::BOLD),
+            )));
+        }
+        if let Some(ref summary) = run.output.summary {
+            lines.extend(summary.lines().map(|l| Line::from(l.to_string())));
+        }
+        if let Some(ref url) = run.html_url {
+            lines.push(Line::from(Span::styled(
+                url.clone(),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        lines.push(Line::from(""));
+        match &detail.annotations {
+            None => lines.push(Line::from(Span::styled(
+                "Loading annotations...",
+                Style::default().fg(Color::Yellow),
+            ))),
+            Some(Err(e)) => lines.push(Line::from(Span::styled(
+                format!("Failed to load annotations: {}", e),
+                Style::default().fg(Color::Red),
+            ))),
+            Some(Ok(annotations)) if annotations.is_empty() => lines.push(Line::from(
+                Span::styled("No annotations", Style::default().fg(Color::DarkGray)),
+            )),
+            Some(Ok(annotations)) => {
+                for annotation in annotations {
+                    lines.extend(annotation_lines(annotation));
+                }
+            }
+        }
+        run.name.clone()
+    } else if let Some(status) = checks.statuses.get(detail.index - checks.runs.len()) {
+        if let Some(ref description) = status.description {
+            lines.push(Line::from(description.clone()));
+        }
+        if let Some(ref url) = status.target_url {
+            lines.push(Line::from(Span::styled(
+                url.clone(),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+        status.context.clone()
+    } else {
+        return;
+    };
+
+    let content = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+        .scroll((detail.scroll.min(u16::MAX as usize) as u16, 0));
+    frame.render_widget(content, area);
+}
+
+fn annotation_lines(annotation: &CheckAnnotation) -> Vec<Line<'static>> {
+    let color = annotation_level_color(annotation.level());
+    let location = if annotation.start_line == annotation.end_line {
+        format!("{}:{}", annotation.path, annotation.start_line)
+    } else {
+        format!(
+            "{}:{}-{}",
+            annotation.path, annotation.start_line, annotation.end_line
+        )
+    };
+    let mut header = vec![
+        Span::styled(
+            format!("[{}] ", annotation.annotation_level),
+            Style::default().fg(color),
+        ),
+        Span::styled(location, Style::default().fg(Color::Cyan)),
+    ];
+    if let Some(ref title) = annotation.title {
+        header.push(Span::raw(format!("  {}", title)));
+    }
+    let mut lines = vec![Line::from(header)];
+    lines.extend(
+        annotation
+            .message
+            .lines()
+            .map(|l| Line::from(format!("  {}", l))),
+    );
+    lines.push(Line::from(""));
+    lines
+}
@@ -10,7 +10,7 @@ use ratatui::{
 };
 use unicode_width::UnicodeWidthChar;
 
-use super::common::render_rally_status_bar;
+use super::common::{render_rally_status_bar, thread_badges};
 use crate::app::{App, CommentTab};
 
 /// Wrap text to fit within the specified width, handling multibyte characters
@@ -53,6 +53,29 @@ fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
     lines
 }
 
+/// outdated なコメントの下に表示する、コメント時の hunk の末尾の行数
+const ORIGINAL_HUNK_LINES: usize = 4;
+
+/// コメント時の hunk の末尾（最終行がコメント対象）を diff の色で表示する
+fn original_hunk_lines(diff_hunk: &str, max_width: usize) -> Vec<Line<'static>> {
+    let lines: Vec<&str> = diff_hunk.lines().filter(|l| !l.starts_with("@@")).collect();
+    lines[lines.len().saturating_sub(ORIGINAL_HUNK_LINES)..]
+        .iter()
+        .map(|line| {
+            let color = match line.chars().next() {
+                Some('+') => Color::Green,
+                Some('-') => Color::Red,
+                _ => Color::DarkGray,
+            };
+            let text = wrap_text(line, max_width).swap_remove(0);
+            Line::from(vec![
+                Span::raw("    "),
+                Span::styled(text, Style::default().fg(color)),
+            ])
+        })
+        .collect()
+}
+
 pub fn render(frame: &mut Frame, app: &mut App) {
     // Handle detail mode separately
     if app.discussion_comment_detail_mode {
@@ -88,6 +111,7 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     match app.comment_tab {
         CommentTab::Review => render_review_comments(frame, app, chunks[1]),
         CommentTab::Discussion => render_discussion_comments(frame, app, chunks[1]),
+        CommentTab::Pending => render_pending_comments(frame, app, chunks[1]),
     }
 
     // Rally status bar (if background rally exists)
@@ -98,10 +122,16 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     // Footer
     let footer_chunk_idx = if has_rally { 3 } else { 2 };
     let footer_text = match app.comment_tab {
-        CommentTab::Review => "j/k/↑↓: move | Enter: jump to file | [/]: switch tab | q: back",
-        CommentTab::Discussion => "j/k/↑↓: move | Enter: view detail | [/]: switch tab | q: back",
+        CommentTab::Review => {
+            "j/k/↑↓: move | Enter: jump to file | e/d: edit/delete | +: react | x: resolve | z: fold | a/b: apply/batch suggestion | [/]: switch tab | q: back"
+        }
+        CommentTab::Discussion => {
+            "j/k/↑↓: move | Enter: view detail | c: comment | e/d: edit/delete | +: react | [/]: switch tab | q: back"
+        }
+        CommentTab::Pending => "j/k/↑↓: move | Enter: edit | d: delete | [/]: switch tab | q: back",
     };
-    let footer = Paragraph::new(footer_text).block(Block::default().borders(Borders::ALL));
+    let footer_line = super::footer::build_footer_line(app, footer_text);
+    let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[footer_chunk_idx]);
 }
 
@@ -229,6 +259,18 @@ fn render_tab_header(frame: &mut Frame, app: &App, area: ratatui::layout::Rect)
         Style::default().fg(Color::DarkGray)
     };
 
+    let pending_style = if app.comment_tab == CommentTab::Pending {
+        Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::DarkGray)
+    };
+    let pending_label = match app.pending_review() {
+        Some(review) => format!("[Pending ({})]", review.comments.len()),
+        None => "[Pending (-)]".to_string(),
+    };
+
     let loading_indicator = |loading: bool| -> String {
         if loading {
             format!(" {}", app.spinner_char())
@@ -256,6 +298,8 @@ fn render_tab_header(frame: &mut Frame, app: &App, area: ratatui::layout::Rect)
             ),
             discussion_style,
         ),
+        Span::raw("  "),
+        Span::styled(pending_label, pending_style),
     ]);
 
     let header =
@@ -266,19 +310,56 @@ fn render_tab_header(frame: &mut Frame, app: &App, area: ratatui::layout::Rect)
 fn render_review_comments(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
     use crate::github::comment::ReviewComment;
 
+    // 折りたたまれたスレッドの返信を除いて表示する
+    let visible = app.visible_review_comment_indices();
+    let selected = visible
+        .iter()
+        .position(|&i| i == app.selected_comment)
+        .unwrap_or(0);
+    let visible_comments: Option<Vec<ReviewComment>> = app
+        .review_comments
+        .as_ref()
+        .map(|comments| visible.iter().map(|&i| comments[i].clone()).collect());
+    let reply_counts = app.thread_reply_counts();
+    let badges: Vec<Vec<Span<'static>>> = visible_comments
+        .iter()
+        .flatten()
+        .map(|comment| thread_badges(app, comment, &reply_counts))
+        .collect();
+    // outdated なスレッドは末尾にまとまっているので、最初の 1 件の前に見出しを出す
+    let first_outdated = visible_comments
+        .iter()
+        .flatten()
+        .position(|comment| !comment.is_reply() && comment.is_outdated());
+
     render_comment_list_generic(
         frame,
         area,
-        app.review_comments.as_deref(),
+        visible_comments.as_deref(),
         app.comments_loading,
-        app.selected_comment,
+        selected,
         &mut app.comment_list_scroll_offset,
         "review comments",
-        |comment: &ReviewComment, _i: usize, is_selected: bool, body_width: usize| {
+        |comment: &ReviewComment, i: usize, is_selected: bool, body_width: usize| {
             let prefix = if is_selected { "> " } else { "  " };
-            let line_info = comment.line.map(|l| format!(":{}", l)).unwrap_or_default();
-            let header_line = Line::from(vec![
-                Span::raw(prefix),
+            // 返信はスレッド先頭より一段下げる
+            let indent = if comment.is_reply() { "  " } else { "" };
+            // outdated なコメントはコメント時の行番号とコミットを出す
+            let mut line_info = comment
+                .line
+                .or(comment.original_line)
+                .map(|l| format!(":{}", l))
+                .unwrap_or_default();
+            if let Some(sha) = comment
+                .original_commit_id
+                .as_deref()
+                .filter(|_| !comment.is_reply() && comment.is_outdated())
+            {
+                line_info.push_str(&format!(" @ {}", crate::suggestion::short_sha(sha)));
+            }
+            let mut header_spans = vec![Span::raw(prefix), Span::raw(indent)];
+            header_spans.extend(badges[i].iter().cloned());
+            header_spans.extend([
                 Span::styled(
                     format!("@{}", comment.user.login),
                     Style::default().fg(Color::Cyan),
@@ -289,13 +370,32 @@ fn render_review_comments(frame: &mut Frame, app: &mut App, area: ratatui::layou
                     Style::default().fg(Color::Green),
                 ),
             ]);
+            let header_line = Line::from(header_spans);
 
             let body_text: String = comment.body.lines().collect::<Vec<_>>().join(" ");
-            let wrapped_lines = wrap_text(&body_text, body_width);
-
-            let mut lines = vec![header_line];
+            let wrapped_lines = wrap_text(&body_text, body_width.saturating_sub(indent.len()));
+
+            let mut lines = Vec::new();
+            if first_outdated == Some(i) {
+                lines.push(Line::from(Span::styled(
+                    "── Outdated (code changed since the comment) ──",
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            lines.push(header_line);
+            if let Some(diff_hunk) = comment
+                .diff_hunk
+                .as_deref()
+                .filter(|_| !comment.is_reply() && comment.is_outdated())
+            {
+                lines.extend(original_hunk_lines(diff_hunk, body_width));
+            }
             for wrapped_line in wrapped_lines {
-                lines.push(Line::from(vec![Span::raw("    "), Span::raw(wrapped_line)]));
+                lines.push(Line::from(vec![
+                    Span::raw("    "),
+                    Span::raw(indent),
+                    Span::raw(wrapped_line),
+                ]));
             }
             lines.push(Line::from(""));
 
@@ -355,6 +455,57 @@ fn render_discussion_comments(frame: &mut Frame, app: &mut App, area: ratatui::l
     );
 }
 
+fn render_pending_comments(frame: &mut Frame, app: &mut App, area: ratatui::layout::Rect) {
+    use crate::github::DraftComment;
+
+    // 保留レビュー未開始の場合は案内を表示
+    let Some(drafts) = app.pending_review().map(|review| review.comments.clone()) else {
+        let hint = Paragraph::new(format!(
+            "No pending review. Press {} to start one.",
+            app.config.keybindings.pending_review.display()
+        ))
+        .style(Style::default().fg(Color::DarkGray))
+        .block(Block::default().borders(Borders::ALL));
+        frame.render_widget(hint, area);
+        return;
+    };
+
+    render_comment_list_generic(
+        frame,
+        area,
+        Some(drafts.as_slice()),
+        false,
+        app.selected_pending_comment,
+        &mut app.pending_comment_list_scroll_offset,
+        "pending comments",
+        |draft: &DraftComment, _i: usize, is_selected: bool, body_width: usize| {
+            let prefix = if is_selected { "> " } else { "  " };
+
+            let header_line = Line::from(vec![
+                Span::raw(prefix),
+                Span::styled(draft.path.clone(), Style::default().fg(Color::Cyan)),
+                Span::styled(
+                    format!(":{}", draft.range.label()),
+                    Style::default().fg(Color::Yellow),
+                ),
+                Span::raw("  "),
+                Span::styled("(pending)", Style::default().fg(Color::Magenta)),
+            ]);
+
+            let body_text: String = draft.body.lines().collect::<Vec<_>>().join(" ");
+            let wrapped_lines = wrap_text(&body_text, body_width);
+
+            let mut lines = vec![header_line];
+            for wrapped_line in wrapped_lines {
+                lines.push(Line::from(vec![Span::raw("    "), Span::raw(wrapped_line)]));
+            }
+            lines.push(Line::from(""));
+
+            ListItem::new(lines)
+        },
+    );
+}
+
 fn render_discussion_detail(frame: &mut Frame, app: &App) {
     let Some(ref comments) = app.discussion_comments else {
         return;
@@ -390,15 +541,19 @@ fn render_discussion_detail(frame: &mut Frame, app: &App) {
         .split('T')
         .next()
         .unwrap_or(&comment.created_at);
-    let header = Paragraph::new(Line::from(vec![
+    let mut header_spans = vec![
         Span::styled(
             format!("@{}", comment.user.login),
             Style::default().fg(Color::Cyan),
         ),
         Span::raw("  "),
         Span::styled(date.to_string(), Style::default().fg(Color::DarkGray)),
-    ]))
-    .block(
+    ];
+    if let Some(reactions) = comment.reactions.summary() {
+        header_spans.push(Span::raw("  "));
+        header_spans.push(Span::raw(reactions));
+    }
+    let header = Paragraph::new(Line::from(header_spans)).block(
         Block::default()
             .borders(Borders::ALL)
             .title("Comment Detail"),
@@ -442,7 +597,10 @@ fn render_discussion_detail(frame: &mut Frame, app: &App) {
 
     // Footer
     let footer_chunk_idx = if has_rally { 3 } else { 2 };
-    let footer = Paragraph::new("j/k/↑↓: scroll | Ctrl+d/u: page | Enter/Esc: back to list")
-        .block(Block::default().borders(Borders::ALL));
+    let footer_line = super::footer::build_footer_line(
+        app,
+        "j/k/↑↓: scroll | Ctrl+d/u: page | +: react | Enter/Esc: back to list",
+    );
+    let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[footer_chunk_idx]);
 }
@@ -0,0 +1,143 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    Frame,
+};
+
+use super::common::{commit_scope_badge, render_rally_status_bar};
+use crate::app::App;
+
+pub fn render(frame: &mut Frame, app: &mut App) {
+    let has_rally = app.has_background_rally();
+    let constraints = if has_rally {
+        vec![
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Commit list
+            Constraint::Length(1), // Rally status bar
+            Constraint::Length(3), // Footer
+        ]
+    } else {
+        vec![
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Commit list
+            Constraint::Length(3), // Footer
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(frame.area());
+
+    // Header
+    let mut header_spans = vec![Span::raw(match app.pr() {
+        Some(pr) => format!("PR #{}: {}", pr.number, pr.title),
+        None => "PR".to_string(),
+    })];
+    if let Some(badge) = commit_scope_badge(app) {
+        header_spans.push(Span::raw("  "));
+        header_spans.push(badge);
+    }
+    let header = Paragraph::new(Line::from(header_spans))
+        .block(Block::default().borders(Borders::ALL).title("Commits"));
+    frame.render_widget(header, chunks[0]);
+
+    // Commit list
+    match app.pr_commits {
+        Some(ref commits) => {
+            let range = app.commit_range_anchor.map(|anchor| {
+                (
+                    anchor.min(app.selected_commit),
+                    anchor.max(app.selected_commit),
+                )
+            });
+            let scope_head = app
+                .commit_scope
+                .as_ref()
+                .filter(|s| !s.since_review)
+                .map(|s| s.head_sha.as_str());
+
+            let all_changes = ListItem::new(Line::from(vec![
+                Span::raw(if app.commit_scope.is_none() {
+                    "* "
+                } else {
+                    "  "
+                }),
+                Span::styled("All changes", Style::default().add_modifier(Modifier::BOLD)),
+            ]));
+            let rows = commits.iter().enumerate().map(|(i, commit)| {
+                let row = i + 1;
+                let in_range = range.is_some_and(|(from, to)| (from..=to).contains(&row));
+                let date = commit
+                    .commit
+                    .author
+                    .as_ref()
+                    .and_then(|a| a.date.split('T').next())
+                    .unwrap_or_default();
+                let item = ListItem::new(Line::from(vec![
+                    Span::raw(if scope_head == Some(commit.sha.as_str()) {
+                        "* "
+                    } else {
+                        "  "
+                    }),
+                    Span::styled(
+                        commit.short_sha().to_string(),
+                        Style::default().fg(Color::Yellow),
+                    ),
+                    Span::raw(format!(" {}", commit.summary())),
+                    Span::styled(
+                        format!("  @{} {}", commit.author_name(), date),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                ]));
+                if in_range {
+                    item.style(Style::default().bg(Color::Rgb(40, 40, 80)))
+                } else {
+                    item
+                }
+            });
+            let items: Vec<ListItem> = std::iter::once(all_changes).chain(rows).collect();
+
+            let list = List::new(items)
+                .block(
+                    Block::default()
+                        .borders(Borders::ALL)
+                        .title(format!("Commits ({})", commits.len())),
+                )
+                .highlight_style(Style::default().bg(Color::DarkGray));
+            let mut list_state = ListState::default().with_selected(Some(app.selected_commit));
+            frame.render_stateful_widget(list, chunks[1], &mut list_state);
+        }
+        None => {
+            let message = if app.pr_commits_loading {
+                Span::styled("Loading...", Style::default().fg(Color::Yellow))
+            } else {
+                Span::styled(
+                    "Failed to load commits (R: retry)",
+                    Style::default().fg(Color::Red),
+                )
+            };
+            let content = Paragraph::new(Line::from(message))
+                .block(Block::default().borders(Borders::ALL).title("Commits"));
+            frame.render_widget(content, chunks[1]);
+        }
+    }
+
+    // Rally status bar (if background rally exists)
+    if has_rally {
+        render_rally_status_bar(frame, chunks[2], app);
+    }
+
+    // Footer
+    let footer_chunk_idx = if has_rally { 3 } else { 2 };
+    let help_text = if app.commit_range_anchor.is_some() {
+        "j/k/↑↓: extend range | Enter: view range | Esc: cancel range"
+    } else {
+        "j/k/↑↓: move | Enter: view diff | v: select range | R: refresh | q/Esc: back"
+    };
+    let footer_line = super::footer::build_footer_line(app, help_text);
+    let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[footer_chunk_idx]);
+}
@@ -1,12 +1,187 @@
+use std::collections::HashMap;
+
 use ratatui::{
     layout::{Alignment, Rect},
     style::{Color, Modifier, Style},
+    text::{Line, Span},
     widgets::Paragraph,
     Frame,
 };
 
 use crate::ai::RallyState;
 use crate::app::App;
+use crate::github::checks::{AnnotationLevel, CheckState};
+use crate::github::comment::ReviewComment;
+use crate::suggestion::{find_suggestion_block, new_side_lines, Suggestion};
+
+/// スレッドの状態バッジ（`[Resolved]` / `[Outdated]`）とスレッド先頭の折りたたみ表示
+///
+/// 返信には `↳` を付けてスレッド内であることを示す。`reply_counts` は
+/// [`App::thread_reply_counts`] を描画ごとに 1 回だけ求めて渡す。
+pub fn thread_badges(
+    app: &App,
+    comment: &ReviewComment,
+    reply_counts: &HashMap<u64, usize>,
+) -> Vec<Span<'static>> {
+    if comment.is_reply() {
+        let mut spans = vec![Span::styled("↳ ", Style::default().fg(Color::DarkGray))];
+        spans.extend(suggestion_badge(app, comment));
+        return spans;
+    }
+    let mut spans = Vec::new();
+    if comment.is_resolved() {
+        spans.push(Span::styled(
+            "[Resolved] ",
+            Style::default().fg(Color::Green),
+        ));
+    }
+    if comment.is_outdated() {
+        spans.push(Span::styled(
+            "[Outdated] ",
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    let replies = reply_counts.get(&comment.id).copied().unwrap_or(0);
+    if replies > 0 && app.is_thread_collapsed(comment) {
+        spans.push(Span::styled(
+            format!("[+{} replies] ", replies),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    spans.extend(suggestion_badge(app, comment));
+    spans
+}
+
+/// 適用できる suggestion を含むコメントのバッジ（バッチに追加済みなら ✓ 付き）
+fn suggestion_badge(app: &App, comment: &ReviewComment) -> Option<Span<'static>> {
+    Suggestion::from_comment(comment)?;
+    let text = if app.suggestion_batch.contains(&comment.id) {
+        "[Suggestion ✓] "
+    } else {
+        "[Suggestion] "
+    };
+    Some(Span::styled(text, Style::default().fg(Color::Magenta)))
+}
+
+/// コメント本文の表示行（suggestion ブロックは対象行とのミニ diff で表示する）
+///
+/// 行数はコメントパネルのスクロール計算にも使うため、`App` 側もこの関数で数える。
+pub fn comment_body_lines(app: &App, comment: &ReviewComment) -> Vec<Line<'static>> {
+    let plain = |text: &str| Line::from(text.to_string());
+    let Some(block) = find_suggestion_block(&comment.body) else {
+        return comment.body.lines().map(plain).collect();
+    };
+    let current = Suggestion::from_comment(comment).and_then(|s| {
+        let patch = app
+            .files()
+            .iter()
+            .find(|f| f.filename == s.path)?
+            .patch
+            .as_deref()?;
+        new_side_lines(patch, s.start_line, s.end_line)
+    });
+
+    let mut lines: Vec<Line<'static>> = block.before.iter().copied().map(plain).collect();
+    lines.push(Line::from(Span::styled(
+        "Suggested change",
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    )));
+    let removed = Style::default().fg(Color::Red);
+    let added = Style::default().fg(Color::Green);
+    lines.extend(
+        current
+            .iter()
+            .flatten()
+            .map(|line| Line::from(Span::styled(format!("- {}", line), removed))),
+    );
+    lines.extend(
+        block
+            .lines
+            .iter()
+            .map(|line| Line::from(Span::styled(format!("+ {}", line), added))),
+    );
+    lines.extend(block.after.iter().copied().map(plain));
+    lines
+}
+
+pub fn check_state_color(state: CheckState) -> Color {
+    match state {
+        CheckState::Success => Color::Green,
+        CheckState::Failure => Color::Red,
+        CheckState::Pending => Color::Yellow,
+        CheckState::Neutral => Color::DarkGray,
+    }
+}
+
+pub fn annotation_level_color(level: AnnotationLevel) -> Color {
+    match level {
+        AnnotationLevel::Failure => Color::Red,
+        AnnotationLevel::Warning => Color::Yellow,
+        AnnotationLevel::Notice => Color::Blue,
+    }
+}
+
+/// Viewed の進捗（"12/40 viewed"）。Viewed 状態が未取得なら None
+pub fn viewed_badge(app: &App) -> Option<Span<'static>> {
+    let viewed = app.viewed_count()?;
+    let total = app.files().len();
+    let color = if total > 0 && viewed == total {
+        Color::Green
+    } else {
+        Color::DarkGray
+    };
+    Some(Span::styled(
+        format!("{}/{} viewed", viewed, total),
+        Style::default().fg(color),
+    ))
+}
+
+/// コミット範囲で diff を表示中であることを示すバッジ
+pub fn commit_scope_badge(app: &App) -> Option<Span<'static>> {
+    let text = match app.commit_scope {
+        _ if app.commit_files_loading => "Commits …".to_string(),
+        Some(ref scope) if scope.since_review => scope.label.clone(),
+        Some(ref scope) => format!("Commits {}", scope.label),
+        None => return None,
+    };
+    Some(Span::styled(
+        text,
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    ))
+}
+
+/// ヘッダーに表示する CI の集約バッジ（`CI ✓ passing` / `CI ✗ 2 failing` など）
+///
+/// チェックが 1 つもない場合は None。
+pub fn checks_badge(app: &App) -> Option<Span<'static>> {
+    let Some(ref checks) = app.checks else {
+        return app
+            .checks_loading
+            .then(|| Span::styled("CI …", Style::default().fg(Color::DarkGray)));
+    };
+    let overall = checks.overall()?;
+    let text = match overall {
+        CheckState::Success | CheckState::Neutral => {
+            format!("CI {} {}", overall.icon(), overall.label())
+        }
+        _ => format!(
+            "CI {} {} {}",
+            overall.icon(),
+            checks.count(overall),
+            overall.label()
+        ),
+    };
+    Some(Span::styled(
+        text,
+        Style::default()
+            .fg(check_state_color(overall))
+            .add_modifier(Modifier::BOLD),
+    ))
+}
 
 /// Render rally status bar for background rally indication
 pub fn render_rally_status_bar(frame: &mut Frame, area: Rect, app: &App) {
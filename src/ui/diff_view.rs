@@ -1,4 +1,4 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use lasso::Rodeo;
 use ratatui::{
@@ -10,11 +10,15 @@ use ratatui::{
 };
 use syntect::easy::HighlightLines;
 
-use super::common::render_rally_status_bar;
+use super::common::{
+    annotation_level_color, comment_body_lines, render_rally_status_bar, thread_badges,
+};
 use crate::app::{
     hash_string, App, CachedDiffLine, DiffCache, InputMode, InternedSpan, LineInputContext,
 };
 use crate::diff::{classify_line, LineType};
+use crate::github::checks::AnnotationLevel;
+use crate::github::CommentRange;
 use crate::syntax::{
     apply_line_highlights, collect_line_highlights, collect_line_highlights_with_injections,
     get_theme, highlight_code_line, syntax_for_file, Highlighter, ParserPool,
@@ -495,13 +499,17 @@ fn build_lines_with_syntect(
 ///
 /// * `cache` – the DiffCache containing both lines and the interner.
 /// * `range` – the range of lines to render (may be a sub-range).
-/// * `selected_line` – absolute index of the currently selected line.
+/// * `selected` – absolute indices of the selected lines (the cursor line, or
+///   the whole visual selection).
 /// * `comment_lines` – set of diff line indices that have comments (for `●` marker).
+/// * `annotation_lines` – diff line indices with CI annotations and their most
+///   severe level (for the `▲` marker).
 pub fn render_cached_lines<'a>(
     cache: &'a DiffCache,
     range: std::ops::Range<usize>,
-    selected_line: usize,
+    selected: std::ops::RangeInclusive<usize>,
     comment_lines: &HashSet<usize>,
+    annotation_lines: &HashMap<usize, AnnotationLevel>,
 ) -> Vec<Line<'a>> {
     // Clamp range to valid bounds to prevent out-of-bounds panic
     let len = cache.lines.len();
@@ -515,18 +523,25 @@ pub fn render_cached_lines<'a>(
         .enumerate()
         .map(|(rel_idx, cached)| {
             let abs_idx = safe_range.start + rel_idx;
-            let is_selected = abs_idx == selected_line;
+            let is_selected = selected.contains(&abs_idx);
 
             let marker = if comment_lines.contains(&abs_idx) {
                 Some(Span::styled("● ", Style::default().fg(Color::Yellow)))
             } else {
                 None
             };
+            let annotation_marker = annotation_lines.get(&abs_idx).map(|level| {
+                Span::styled("▲ ", Style::default().fg(annotation_level_color(*level)))
+            });
             let base = cached
                 .spans
                 .iter()
                 .map(|s| Span::styled(cache.resolve(s.content), s.style));
-            let all_spans: Vec<Span<'_>> = marker.into_iter().chain(base).collect();
+            let all_spans: Vec<Span<'_>> = marker
+                .into_iter()
+                .chain(annotation_marker)
+                .chain(base)
+                .collect();
 
             if is_selected {
                 Line::from(all_spans).style(Style::default().add_modifier(Modifier::REVERSED))
@@ -650,8 +665,9 @@ pub(crate) fn render_diff_content(frame: &mut Frame, app: &App, area: ratatui::l
         render_cached_lines(
             cache,
             visible_start..visible_end,
-            app.selected_line,
+            app.selected_line_range(),
             &app.file_comment_lines,
+            &app.file_annotation_lines,
         )
     } else {
         // Fallback: parse without cache (should rarely happen)
@@ -852,9 +868,11 @@ fn highlight_or_fallback(
 
 fn render_footer(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
     let help_text = if app.comment_panel_open {
-        "j/k/↑↓: scroll | n/N: jump | Tab: switch | r: reply | c: comment | s: suggest | ←/h: back | Esc/q: close"
+        "j/k/↑↓: scroll | n/N: jump | Tab: switch | r: reply | e/d: edit/delete | +: react | x: resolve | z: fold | a/b: apply/batch suggestion | c: comment | s: suggest | ←/h: back | Esc/q: close"
+    } else if app.visual_anchor.is_some() {
+        "-- VISUAL -- j/k/↑↓: extend | c: comment range | s: suggest range | v/Esc: cancel"
     } else {
-        "j/k/↑↓: move | n/N: next/prev comment | Enter: comments | Ctrl-d/u: page | ←/h/q: back"
+        "j/k/↑↓: move | n/N: next/prev comment | Enter: comments | v: select | Ctrl-d/u: page | ←/h/q: back"
     };
 
     let footer_line = super::footer::build_footer_line(app, help_text);
@@ -876,6 +894,7 @@ fn render_inline_comments(frame: &mut Frame, app: &App, area: ratatui::layout::R
         )));
     } else if let Some(ref comments) = app.review_comments {
         let has_multiple = indices.len() > 1;
+        let reply_counts = app.thread_reply_counts();
 
         for (i, &idx) in indices.iter().enumerate() {
             let Some(comment) = comments.get(idx) else {
@@ -901,9 +920,10 @@ fn render_inline_comments(frame: &mut Frame, app: &App, area: ratatui::layout::R
                 Span::raw("")
             };
 
-            // Header: [>] @user (line N)
-            lines.push(Line::from(vec![
-                indicator,
+            // Header: [>] [badges] @user (line N)
+            let mut header = vec![indicator];
+            header.extend(thread_badges(app, comment, &reply_counts));
+            header.extend([
                 Span::styled(
                     format!("@{}", comment.user.login),
                     Style::default().fg(Color::Cyan),
@@ -912,15 +932,21 @@ fn render_inline_comments(frame: &mut Frame, app: &App, area: ratatui::layout::R
                     format!(" (line {})", comment.line.unwrap_or(0)),
                     Style::default().fg(Color::DarkGray),
                 ),
-            ]));
+            ]);
+            lines.push(Line::from(header));
 
             // Body
-            for line in comment.body.lines() {
-                lines.push(Line::from(line.to_string()));
+            lines.extend(comment_body_lines(app, comment));
+            if let Some(reactions) = comment.reactions.summary() {
+                lines.push(Line::from(Span::styled(
+                    reactions,
+                    Style::default().fg(Color::DarkGray),
+                )));
             }
             lines.push(Line::from("")); // Spacing after comment body
         }
     }
+    lines.extend(annotation_panel_lines(app));
 
     let title = "Comments (j/k/↑↓: scroll, c: comment, s: suggest, r: reply)";
     let total_lines = lines.len();
@@ -958,6 +984,50 @@ fn render_inline_comments(frame: &mut Frame, app: &App, area: ratatui::layout::R
     }
 }
 
+/// コメントパネル末尾に表示する、現在行の CI アノテーション
+///
+/// 行数は `App::annotation_panel_lines` と一致させること（スクロール上限の計算に使う）。
+pub(crate) fn annotation_panel_lines(app: &App) -> Vec<Line<'static>> {
+    let annotations = app.annotations_at_current_line();
+    if annotations.is_empty() {
+        return vec![];
+    }
+    let mut lines = vec![Line::from(Span::styled(
+        "CI annotations",
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    for annotation in annotations {
+        let mut header = vec![
+            Span::styled(
+                format!("▲ [{}] ", annotation.annotation_level),
+                Style::default().fg(annotation_level_color(annotation.level())),
+            ),
+            Span::styled(
+                annotation.check_name.clone(),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::styled(
+                format!(" (line {})", annotation.start_line),
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        if let Some(ref title) = annotation.title {
+            header.push(Span::raw(format!("  {}", title)));
+        }
+        lines.push(Line::from(header));
+        lines.extend(
+            annotation
+                .message
+                .lines()
+                .map(|line| Line::from(line.to_string())),
+        );
+        lines.push(Line::from(""));
+    }
+    lines
+}
+
 /// Render unified text input view (comment/suggestion/reply)
 pub fn render_text_input(frame: &mut Frame, app: &App) {
     let chunks = Layout::default()
@@ -974,13 +1044,12 @@ pub fn render_text_input(frame: &mut Frame, app: &App) {
     match &app.input_mode {
         Some(InputMode::Comment(ctx)) => {
             render_comment_context(frame, app, chunks[1], ctx);
-            render_text_input_area(
-                frame,
-                app,
-                chunks[2],
-                "Comment",
-                "Type your comment here...",
-            );
+            let label = if app.pending_review().is_some() {
+                "Pending comment"
+            } else {
+                "Comment"
+            };
+            render_text_input_area(frame, app, chunks[2], label, "Type your comment here...");
         }
         Some(InputMode::Suggestion {
             context,
@@ -997,6 +1066,36 @@ pub fn render_text_input(frame: &mut Frame, app: &App) {
             render_reply_context(frame, chunks[1], reply_to_user, reply_to_body);
             render_text_input_area(frame, app, chunks[2], "Reply", "Type your reply here...");
         }
+        Some(InputMode::EditDraft { index }) => {
+            render_draft_context(frame, app, chunks[1], *index);
+            render_text_input_area(
+                frame,
+                app,
+                chunks[2],
+                "Pending comment",
+                "Type your comment here...",
+            );
+        }
+        Some(InputMode::DiscussionComment) => {
+            render_discussion_context(frame, app, chunks[1]);
+            render_text_input_area(
+                frame,
+                app,
+                chunks[2],
+                "Discussion comment",
+                "Type your comment here...",
+            );
+        }
+        Some(InputMode::EditComment { location, .. }) => {
+            render_edit_context(frame, chunks[1], location);
+            render_text_input_area(
+                frame,
+                app,
+                chunks[2],
+                "Edit comment",
+                "Type your comment here...",
+            );
+        }
         None => {}
     }
 }
@@ -1020,24 +1119,104 @@ fn render_comment_context(
             Span::styled(filename, Style::default().fg(Color::Cyan)),
         ]),
         Line::from(vec![
-            Span::styled("Line: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(line_label(ctx.range), Style::default().fg(Color::DarkGray)),
+            Span::styled(ctx.range.label(), Style::default().fg(Color::Yellow)),
+        ]),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Comment Location"),
+        )
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
+/// "Line: " / "Lines: " label for a comment target
+fn line_label(range: CommentRange) -> &'static str {
+    if range.start_line.is_some() {
+        "Lines: "
+    } else {
+        "Line: "
+    }
+}
+
+/// Render context info for editing a pending review comment
+fn render_draft_context(frame: &mut Frame, app: &App, area: ratatui::layout::Rect, index: usize) {
+    let Some(draft) = app
+        .pending_review()
+        .and_then(|review| review.comments.get(index))
+    else {
+        return;
+    };
+
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("File: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(draft.path.as_str(), Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(vec![
             Span::styled(
-                ctx.line_number.to_string(),
-                Style::default().fg(Color::Yellow),
+                line_label(draft.range),
+                Style::default().fg(Color::DarkGray),
             ),
+            Span::styled(draft.range.label(), Style::default().fg(Color::Yellow)),
         ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Not sent yet. It will be posted when the review is submitted.",
+            Style::default().fg(Color::DarkGray),
+        )]),
     ];
 
     let paragraph = Paragraph::new(lines)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("Comment Location"),
+                .title("Pending Comment"),
         )
         .wrap(Wrap { trim: true });
     frame.render_widget(paragraph, area);
 }
 
+/// Render context info for a new PR conversation comment
+fn render_discussion_context(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let lines = vec![Line::from(vec![
+        Span::styled("Pull request: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(
+            format!("{}#{}", app.repo, app.pr_number()),
+            Style::default().fg(Color::Cyan),
+        ),
+    ])];
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Conversation"))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
+/// Render context info for editing a posted comment
+fn render_edit_context(frame: &mut Frame, area: ratatui::layout::Rect, location: &str) {
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Comment: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(location, Style::default().fg(Color::Cyan)),
+        ]),
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "Submitting replaces the posted comment body.",
+            Style::default().fg(Color::DarkGray),
+        )]),
+    ];
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Edit Comment"))
+        .wrap(Wrap { trim: true });
+    frame.render_widget(paragraph, area);
+}
+
 /// Render TextArea with dynamic title and placeholder
 fn render_text_input_area(
     frame: &mut Frame,
@@ -1072,11 +1251,8 @@ fn render_suggestion_context(
             Span::styled(filename, Style::default().fg(Color::Cyan)),
         ]),
         Line::from(vec![
-            Span::styled("Line: ", Style::default().fg(Color::DarkGray)),
-            Span::styled(
-                ctx.line_number.to_string(),
-                Style::default().fg(Color::Yellow),
-            ),
+            Span::styled(line_label(ctx.range), Style::default().fg(Color::DarkGray)),
+            Span::styled(ctx.range.label(), Style::default().fg(Color::Yellow)),
         ]),
         Line::from(""),
         Line::from(vec![Span::styled(
@@ -1085,11 +1261,14 @@ fn render_suggestion_context(
                 .fg(Color::Yellow)
                 .add_modifier(Modifier::BOLD),
         )]),
+    ];
+    // 複数行の選択範囲は1行ずつ表示
+    lines.extend(original_code.lines().map(|code| {
         Line::from(vec![Span::styled(
-            format!("  {}", original_code),
+            format!("  {}", code),
             Style::default().fg(Color::Red),
-        )]),
-    ];
+        )])
+    }));
 
     // Add hint about what will be submitted
     lines.push(Line::from(""));
@@ -1495,9 +1674,20 @@ mod tests {
         );
 
         // render_cached_lines でコメントマーカーが挿入されること
-        let plain_rendered = render_cached_lines(&plain, 0..plain.lines.len(), 0, &comment_lines);
-        let hl_rendered =
-            render_cached_lines(&highlighted, 0..highlighted.lines.len(), 0, &comment_lines);
+        let plain_rendered = render_cached_lines(
+            &plain,
+            0..plain.lines.len(),
+            0..=0,
+            &comment_lines,
+            &HashMap::new(),
+        );
+        let hl_rendered = render_cached_lines(
+            &highlighted,
+            0..highlighted.lines.len(),
+            0..=0,
+            &comment_lines,
+            &HashMap::new(),
+        );
 
         for &line_idx in &[4usize, 6] {
             let plain_line_text: String = plain_rendered[line_idx]
@@ -1535,6 +1725,34 @@ mod tests {
             "non-comment line should not have marker"
         );
     }
+
+    #[test]
+    fn render_cached_lines_inserts_annotation_markers_after_comment_marker() {
+        let patch = "@@ -1,2 +1,2 @@\n fn main() {\n+    let x = 1;";
+        let cache = build_plain_diff_cache(patch);
+        let comment_lines = HashSet::from([2]);
+        let annotation_lines =
+            HashMap::from([(1, AnnotationLevel::Warning), (2, AnnotationLevel::Failure)]);
+
+        let rendered = render_cached_lines(
+            &cache,
+            0..cache.lines.len(),
+            0..=0,
+            &comment_lines,
+            &annotation_lines,
+        );
+        let text = |i: usize| -> String {
+            rendered[i]
+                .spans
+                .iter()
+                .map(|s| s.content.as_ref())
+                .collect()
+        };
+        assert!(text(1).starts_with("▲ "));
+        assert!(text(2).starts_with("● ▲ "));
+        assert_eq!(rendered[2].spans[1].style.fg, Some(Color::Red));
+        assert!(!text(0).contains('▲'));
+    }
 }
 
 #[cfg(test)]
@@ -1,17 +1,20 @@
+use std::collections::HashSet;
+
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Margin},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{
         Block, Borders, List, ListItem, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
-        ScrollbarState,
+        ScrollbarState, Wrap,
     },
     Frame,
 };
 
-use super::common::render_rally_status_bar;
+use super::common::{checks_badge, commit_scope_badge, render_rally_status_bar, viewed_badge};
 use crate::app::{App, DataState};
-use crate::github::ChangedFile;
+use crate::github::{ChangedFile, GitHubError, RetryPolicy};
+use crate::loader::LoadError;
 
 pub fn render(frame: &mut Frame, app: &mut App) {
     let has_rally = app.has_background_rally();
@@ -46,20 +49,37 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         },
     };
 
-    let header =
-        Paragraph::new(pr_info).block(Block::default().borders(Borders::ALL).title("octorus"));
+    let mut header_spans = vec![Span::raw(pr_info)];
+    for badge in [
+        viewed_badge(app),
+        commit_scope_badge(app),
+        checks_badge(app),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        header_spans.push(Span::raw("  "));
+        header_spans.push(badge);
+    }
+    let header = Paragraph::new(Line::from(header_spans))
+        .block(Block::default().borders(Borders::ALL).title("octorus"));
     frame.render_widget(header, chunks[0]);
 
     // File list
     let files = app.files();
     let total_files = files.len();
-    let items = build_file_list_items(files, app.selected_file);
+    let viewed = app.viewed_files.as_ref().map(|v| &v.paths);
+    let items = build_file_list_items(files, app.selected_file, viewed);
 
     let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(format!("Changed Files ({})", total_files)),
+                .title(file_list_title(
+                    "Changed Files",
+                    total_files,
+                    app.files_truncated,
+                )),
         )
         .highlight_style(Style::default().bg(Color::DarkGray));
 
@@ -108,10 +128,11 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         "A: AI Rally"
     };
     let footer_text = format!(
-        "j/k/↑↓: move | Enter/→/l: split view | a: approve | r: request changes | c: comment | C: comments | {} | R: refresh | q: quit | ?: help",
+        "j/k/↑↓: move | Enter/→/l: split view | a: approve | r: request changes | c: comment | C: comments | v: viewed | u: next unviewed | S: checks | L: commits | U: since review | I: metadata | M: merge | P: pending review | {} | R: refresh | q: quit | ?: help",
         ai_rally_text
     );
-    let footer = Paragraph::new(footer_text).block(Block::default().borders(Borders::ALL));
+    let footer_line = super::footer::build_footer_line(app, &footer_text);
+    let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[footer_chunk_idx]);
 }
 
@@ -152,8 +173,8 @@ pub fn render_loading(frame: &mut Frame, app: &App) {
     frame.render_widget(footer, chunks[2]);
 }
 
-/// Error状態の表示
-pub fn render_error(frame: &mut Frame, app: &App, error_msg: &str) {
+/// Error状態の表示（エラーの種類ごとに対処方法と再試行方針を出す）
+pub fn render_error(frame: &mut Frame, app: &App, error: &LoadError) {
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -172,21 +193,123 @@ pub fn render_error(frame: &mut Frame, app: &App, error_msg: &str) {
         Paragraph::new(header_text).block(Block::default().borders(Borders::ALL).title("octorus"));
     frame.render_widget(header, chunks[0]);
 
-    // Error message
-    let error = Paragraph::new(format!("Error: {}", error_msg))
-        .style(Style::default().fg(Color::Red))
+    // Error message and guidance
+    let (title, guidance) = error_guidance(error.github.as_ref());
+    let mut lines = vec![
+        Line::from(Span::styled(
+            title,
+            Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            error.message.clone(),
+            Style::default().fg(Color::Red),
+        )),
+    ];
+    if let Some(GitHubError::Validation { errors, .. }) = &error.github {
+        lines.extend(
+            errors
+                .iter()
+                .map(|e| Line::from(Span::raw(format!("  • {}", e)))),
+        );
+    }
+    lines.push(Line::default());
+    lines.extend(guidance.iter().map(|g| Line::from(*g)));
+
+    let retry_in = app.load_retry_in();
+    let policy = error.retry_policy();
+    if let Some(wait) = retry_in {
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{} Retrying in {}s...",
+                app.spinner_char(),
+                wait.as_secs() + 1
+            ),
+            Style::default().fg(Color::Yellow),
+        )));
+    } else if matches!(policy, RetryPolicy::Auto(_)) {
+        lines.push(Line::default());
+        lines.push(Line::from(Span::styled(
+            "Automatic retries exhausted.",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    let body = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
         .block(Block::default().borders(Borders::ALL).title("Error"));
-    frame.render_widget(error, chunks[1]);
+    frame.render_widget(body, chunks[1]);
 
     // Footer
-    let footer = Paragraph::new("r: retry | q: quit").block(Block::default().borders(Borders::ALL));
+    let help_text = match policy {
+        RetryPolicy::Never => "q: quit",
+        _ if retry_in.is_some() => "r: retry now | q: quit",
+        _ => "r: retry | q: quit",
+    };
+    let footer = Paragraph::new(help_text).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[2]);
 }
 
+/// エラーの種類ごとの見出しと対処方法
+fn error_guidance(error: Option<&GitHubError>) -> (&'static str, &'static [&'static str]) {
+    match error {
+        Some(GitHubError::NotFound(_)) => (
+            "Not found",
+            &[
+                "The repository or pull request does not exist, or your account cannot see it.",
+                "Check the repository name and PR number. Private repositories require a token with access to them.",
+            ],
+        ),
+        Some(GitHubError::Unauthorized(_)) => (
+            "Authentication required",
+            &[
+                "GitHub rejected the credentials or they lack permission for this repository.",
+                "Run `gh auth login` (or `gh auth refresh -s repo`), or set GH_TOKEN, then press r.",
+            ],
+        ),
+        Some(GitHubError::RateLimited { .. }) => (
+            "Rate limited",
+            &["The GitHub API rate limit was reached. Loading resumes automatically when the limit resets."],
+        ),
+        Some(GitHubError::Network(_)) => (
+            "Network error",
+            &[
+                "Could not get a response from GitHub. This is usually temporary.",
+                "Check your connection, proxy or VPN. Loading is retried automatically.",
+            ],
+        ),
+        Some(GitHubError::Validation { .. }) => (
+            "Invalid request",
+            &["GitHub rejected the request. Retrying will not help until the fields above are fixed."],
+        ),
+        Some(GitHubError::GhNotInstalled) => (
+            "gh CLI not installed",
+            &[
+                "Install the GitHub CLI from https://cli.github.com/ and run `gh auth login`,",
+                "or set `backend = \"http\"` under [github] in config.toml and provide GH_TOKEN.",
+            ],
+        ),
+        None => ("Error", &["Press r to retry."]),
+    }
+}
+
+/// ファイル一覧のタイトル（GitHub の上限で打ち切られている場合は警告を付ける）
+pub(crate) fn file_list_title(label: &str, total_files: usize, truncated: bool) -> Line<'static> {
+    let mut spans = vec![Span::raw(format!("{} ({})", label, total_files))];
+    if truncated {
+        spans.push(Span::styled(
+            format!(" ⚠ truncated: GitHub returns at most {} files", total_files),
+            Style::default().fg(Color::Yellow),
+        ));
+    }
+    Line::from(spans)
+}
+
 /// ファイル一覧のリストアイテムを構築する（side_by_side でも再利用）
 pub(crate) fn build_file_list_items<'a>(
     files: &'a [ChangedFile],
     selected_file: usize,
+    viewed: Option<&HashSet<String>>,
 ) -> Vec<ListItem<'a>> {
     files
         .iter()
@@ -215,7 +338,15 @@ pub(crate) fn build_file_list_items<'a>(
                 _ => '?',
             };
 
-            let line = Line::from(vec![
+            let mut spans = Vec::with_capacity(4);
+            if let Some(viewed) = viewed {
+                spans.push(if viewed.contains(&file.filename) {
+                    Span::styled("✓ ", Style::default().fg(Color::Green))
+                } else {
+                    Span::raw("  ")
+                });
+            }
+            spans.extend([
                 Span::styled(
                     format!("[{}] ", status_char),
                     Style::default().fg(status_color),
@@ -223,8 +354,30 @@ pub(crate) fn build_file_list_items<'a>(
                 Span::styled(&file.filename, style),
                 Span::raw(format!(" +{} -{}", file.additions, file.deletions)),
             ]);
+            let line = Line::from(spans);
 
             ListItem::new(line)
         })
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_to_string(line: &Line) -> String {
+        line.spans.iter().map(|s| s.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_file_list_title_warns_when_truncated() {
+        let title = file_list_title("Changed Files", 12, false);
+        assert_eq!(line_to_string(&title), "Changed Files (12)");
+
+        let title = file_list_title("Changed Files", 3000, true);
+        let text = line_to_string(&title);
+        assert!(text.starts_with("Changed Files (3000)"));
+        assert!(text.contains("truncated"));
+        assert_eq!(title.spans[1].style.fg, Some(Color::Yellow));
+    }
+}
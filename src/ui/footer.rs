@@ -4,14 +4,59 @@ use ratatui::{
 };
 
 use crate::app::App;
+use crate::github::RateLimit;
 
 /// Build footer line content based on app state.
 ///
-/// During submission or result display, the footer shows only the status
-/// (full-width override). Otherwise, it shows the normal help text with
-/// optional comments loading indicator appended.
+/// During delete / merge / suggestion confirmation, submission or result display, the footer shows
+/// only the prompt / status (full-width override). Otherwise, it shows the normal help text with
+/// optional comments loading / pending review / API quota indicators appended.
 pub fn build_footer_line<'a>(app: &'a App, help_text: &'a str) -> Line<'a> {
-    if app.is_submitting_comment() {
+    if app.confirm_delete.is_some() {
+        Line::from(Span::styled(
+            "Delete this comment? y: delete | any other key: cancel",
+            Style::default().fg(Color::Red),
+        ))
+    } else if let Some(ref request) = app.confirm_merge {
+        let action = if request.auto_merge {
+            "Enable auto-merge"
+        } else {
+            "Merge"
+        };
+        Line::from(Span::styled(
+            format!(
+                "{} PR #{} ({})? y: confirm | any other key: cancel",
+                action,
+                app.pr_number.unwrap_or_default(),
+                request.method.api_value()
+            ),
+            Style::default().fg(Color::Red),
+        ))
+    } else if let Some(ref suggestions) = app.confirm_suggestions {
+        let local = if app.working_dir.is_some() {
+            "l: commit in working dir | "
+        } else {
+            ""
+        };
+        Line::from(Span::styled(
+            format!(
+                "Commit {} suggestion(s)? {}g: commit on GitHub | any other key: cancel",
+                suggestions.len(),
+                local
+            ),
+            Style::default().fg(Color::Red),
+        ))
+    } else if app.applying_suggestions {
+        Line::from(Span::styled(
+            format!("{} Applying suggestions...", app.spinner_char()),
+            Style::default().fg(Color::Yellow),
+        ))
+    } else if app.merging {
+        Line::from(Span::styled(
+            format!("{} Merging...", app.spinner_char()),
+            Style::default().fg(Color::Yellow),
+        ))
+    } else if app.is_submitting_comment() {
         Line::from(Span::styled(
             format!("{} Submitting...", app.spinner_char()),
             Style::default().fg(Color::Yellow),
@@ -35,10 +80,44 @@ pub fn build_footer_line<'a>(app: &'a App, help_text: &'a str) -> Line<'a> {
                 Style::default().fg(Color::Yellow),
             ));
         }
+        if let Some(review) = app.pending_review() {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                format!("[Pending review: {}]", review.comments.len()),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+        if let Some(limit) = app.rate_limit() {
+            spans.push(Span::raw("  "));
+            spans.push(rate_limit_span(&limit, chrono::Utc::now().timestamp()));
+        }
         Line::from(spans)
     }
 }
 
+/// 残りクォータ（少なくなったら黄色、使い切ったらリセットまでの時間を赤で表示）
+fn rate_limit_span(limit: &RateLimit, now: i64) -> Span<'static> {
+    if limit.remaining == 0 {
+        let minutes = (limit.reset_in_secs(now) + 59) / 60;
+        return Span::styled(
+            format!(
+                "[API {} quota exhausted, resets in {}m]",
+                limit.resource, minutes
+            ),
+            Style::default().fg(Color::Red),
+        );
+    }
+    let color = if limit.remaining * 10 < limit.limit {
+        Color::Yellow
+    } else {
+        Color::DarkGray
+    };
+    Span::styled(
+        format!("[API {}/{}]", limit.remaining, limit.limit),
+        Style::default().fg(color),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -112,4 +191,50 @@ mod tests {
         let style = line.spans[0].style;
         assert_eq!(style.fg, Some(Color::Red));
     }
+    #[test]
+    fn test_merge_confirmation_shows_prompt_only() {
+        let (mut app, _tx) = App::new_loading("owner/repo", 7, Default::default());
+        app.confirm_merge = Some(crate::app::MergeRequest {
+            method: crate::github::MergeMethod::Squash,
+            auto_merge: false,
+            message: None,
+        });
+        let line = build_footer_line(&app, HELP);
+        let text = line_to_string(&line);
+        assert_eq!(
+            text,
+            "Merge PR #7 (squash)? y: confirm | any other key: cancel"
+        );
+    }
+
+    #[test]
+    fn test_rate_limit_span_warns_when_low() {
+        let limit = |remaining| RateLimit {
+            resource: "core".to_string(),
+            limit: 5000,
+            remaining,
+            reset_at: 1_600,
+        };
+        let span = rate_limit_span(&limit(4321), 1_000);
+        assert_eq!(span.content, "[API 4321/5000]");
+        assert_eq!(span.style.fg, Some(Color::DarkGray));
+        assert_eq!(
+            rate_limit_span(&limit(100), 1_000).style.fg,
+            Some(Color::Yellow)
+        );
+
+        let span = rate_limit_span(&limit(0), 1_000);
+        assert_eq!(span.content, "[API core quota exhausted, resets in 10m]");
+        assert_eq!(span.style.fg, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_pending_review_appends_indicator() {
+        let (mut app, _tx) = App::new_loading("owner/repo", 1, Default::default());
+        app.start_pending_review_for_test();
+        let line = build_footer_line(&app, HELP);
+        let text = line_to_string(&line);
+        assert!(text.starts_with(HELP));
+        assert!(text.contains("[Pending review: 0]"));
+    }
 }
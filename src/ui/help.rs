@@ -48,6 +48,18 @@ fn build_help_lines(kb: &KeybindingsConfig) -> Vec<Line<'static>> {
     let key_width = 14; // Width for key column
 
     let lines = vec![
+        Line::from(""),
+        Line::from(vec![Span::styled(
+            "PR List View",
+            Style::default()
+                .fg(Color::Yellow)
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from("  o / c / a       Show open / closed / all PRs"),
+        Line::from("  /               Search (review-requested:@me, author:, label:, text)"),
+        Line::from("  n               Toggle \"needs my review\" (review-requested:@me)"),
+        Line::from("  m               Toggle \"my PRs\" (author:@me)"),
+        Line::from("  s               Cycle sort: updated / created / comments"),
         Line::from(""),
         Line::from(vec![Span::styled(
             "File List View",
@@ -82,10 +94,46 @@ fn build_help_lines(kb: &KeybindingsConfig) -> Vec<Line<'static>> {
             "{}  View review comments",
             fmt_key(&kb.comment_list.display(), key_width)
         )),
+        Line::from(format!(
+            "{}  View CI checks",
+            fmt_key(&kb.checks.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Browse commits (diff per commit / range)",
+            fmt_key(&kb.commit_list.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Toggle changes since your last review",
+            fmt_key(&kb.since_review.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Toggle viewed on selected file",
+            fmt_key(&kb.toggle_viewed.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Jump to next unviewed file",
+            fmt_key(&kb.next_unviewed.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  PR metadata (reviewers, labels, draft)",
+            fmt_key(&kb.pr_metadata.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Merge PR / enable auto-merge",
+            fmt_key(&kb.merge.display(), key_width)
+        )),
         Line::from(format!(
             "{}  Start AI Rally",
             fmt_key(&kb.ai_rally.display(), key_width)
         )),
+        Line::from(format!(
+            "{}  Start/discard pending review",
+            fmt_key(&kb.pending_review.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  React to the PR",
+            fmt_key(&kb.add_reaction.display(), key_width)
+        )),
         Line::from(format!(
             "{}  Refresh (clear cache and reload)",
             fmt_key(&kb.refresh.display(), key_width)
@@ -163,6 +211,11 @@ fn build_help_lines(kb: &KeybindingsConfig) -> Vec<Line<'static>> {
             fmt_key(&kb.next_comment.display(), 10),
             kb.prev_comment.display()
         )),
+        Line::from(format!(
+            "{}/{}  Next/prev CI annotation",
+            fmt_key(&kb.next_annotation.display(), 10),
+            kb.prev_annotation.display()
+        )),
         Line::from(format!(
             "{}  Open comment panel",
             fmt_key(&kb.open_panel.display(), key_width)
@@ -218,6 +271,14 @@ fn build_help_lines(kb: &KeybindingsConfig) -> Vec<Line<'static>> {
             "{}  Jump to previous comment",
             fmt_key(&kb.prev_comment.display(), key_width)
         )),
+        Line::from(format!(
+            "{}  Jump to next CI annotation",
+            fmt_key(&kb.next_annotation.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Jump to previous CI annotation",
+            fmt_key(&kb.prev_annotation.display(), key_width)
+        )),
         Line::from(format!(
             "{}  Open comment panel",
             fmt_key(&kb.open_panel.display(), key_width)
@@ -238,6 +299,10 @@ fn build_help_lines(kb: &KeybindingsConfig) -> Vec<Line<'static>> {
             "{}  Add suggestion at line",
             fmt_key(&kb.suggestion.display(), key_width)
         )),
+        Line::from(format!(
+            "{}  Select line range (comment/suggest on range)",
+            fmt_key(&kb.visual_select.display(), key_width)
+        )),
         Line::from(format!(
             "{}, Esc       Back to file list",
             fmt_key(&kb.quit.display(), key_width)
@@ -263,6 +328,34 @@ fn build_help_lines(kb: &KeybindingsConfig) -> Vec<Line<'static>> {
             "{}  Reply to comment",
             fmt_key(&kb.reply.display(), key_width)
         )),
+        Line::from(format!(
+            "{}  Edit your comment",
+            fmt_key(&kb.edit_comment.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Delete your comment",
+            fmt_key(&kb.delete_comment.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Add reaction",
+            fmt_key(&kb.add_reaction.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Resolve / unresolve thread",
+            fmt_key(&kb.resolve_thread.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Collapse / expand thread",
+            fmt_key(&kb.toggle_thread.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Apply suggestion (or the batch, PR author only)",
+            fmt_key(&kb.apply_suggestion.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Add / remove suggestion to the batch",
+            fmt_key(&kb.batch_suggestion.display(), key_width)
+        )),
         Line::from("  Tab/Shift-Tab   Select reply target (multiple)"),
         Line::from(format!(
             "{}/{}  Jump to next/prev comment",
@@ -289,6 +382,38 @@ fn build_help_lines(kb: &KeybindingsConfig) -> Vec<Line<'static>> {
             "{}  Review: Jump to file | Discussion: View detail",
             fmt_key(&kb.open_panel.display(), key_width)
         )),
+        Line::from(format!(
+            "{}  Discussion: Post a comment",
+            fmt_key(&kb.comment.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Edit your comment",
+            fmt_key(&kb.edit_comment.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Delete your comment (Pending: delete draft)",
+            fmt_key(&kb.delete_comment.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Add reaction",
+            fmt_key(&kb.add_reaction.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Review: Resolve / unresolve thread",
+            fmt_key(&kb.resolve_thread.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Review: Collapse / expand thread",
+            fmt_key(&kb.toggle_thread.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Review: Apply suggestion (or the batch)",
+            fmt_key(&kb.apply_suggestion.display(), key_width)
+        )),
+        Line::from(format!(
+            "{}  Review: Add / remove suggestion to the batch",
+            fmt_key(&kb.batch_suggestion.display(), key_width)
+        )),
         Line::from(format!(
             "{}, Esc       Back to file list",
             fmt_key(&kb.quit.display(), key_width)
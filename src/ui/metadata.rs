@@ -0,0 +1,229 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use super::common::render_rally_status_bar;
+use crate::app::{App, MetadataPickerKind, MetadataPickerState};
+use crate::github::metadata::PrMetadata;
+
+pub fn render(frame: &mut Frame, app: &mut App) {
+    let has_rally = app.has_background_rally();
+    let constraints = if has_rally {
+        vec![
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Metadata
+            Constraint::Length(1), // Rally status bar
+            Constraint::Length(3), // Footer
+        ]
+    } else {
+        vec![
+            Constraint::Length(3), // Header
+            Constraint::Min(0),    // Metadata
+            Constraint::Length(3), // Footer
+        ]
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(frame.area());
+
+    // Header
+    let title = match app.pr() {
+        Some(pr) => format!("PR #{}: {}", pr.number, pr.title),
+        None => "PR".to_string(),
+    };
+    let header =
+        Paragraph::new(title).block(Block::default().borders(Borders::ALL).title("PR Metadata"));
+    frame.render_widget(header, chunks[0]);
+
+    // Metadata
+    let lines = match app.pr_metadata {
+        Some(ref metadata) => metadata_lines(metadata),
+        None if app.pr_metadata_loading => vec![Line::from(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        ))],
+        None => vec![Line::from(Span::styled(
+            "Failed to load metadata (R: retry)",
+            Style::default().fg(Color::Red),
+        ))],
+    };
+    let content = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL))
+        .wrap(Wrap { trim: false });
+    frame.render_widget(content, chunks[1]);
+
+    // Rally status bar (if background rally exists)
+    if has_rally {
+        render_rally_status_bar(frame, chunks[2], app);
+    }
+
+    // Footer
+    let footer_chunk_idx = if has_rally { 3 } else { 2 };
+    let help_text = if app.metadata_picker.is_some() {
+        "type to filter | ↑↓/Ctrl+n/p: move | Enter: add/remove | Esc: close"
+    } else if app.pr_metadata_updating {
+        "Updating..."
+    } else {
+        "l: labels | r: request reviewers | d: toggle draft | R: refresh | q/Esc: back"
+    };
+    let footer_line = super::footer::build_footer_line(app, help_text);
+    let footer = Paragraph::new(footer_line).block(Block::default().borders(Borders::ALL));
+    frame.render_widget(footer, chunks[footer_chunk_idx]);
+
+    if let (Some(picker), Some(metadata)) = (&app.metadata_picker, &app.pr_metadata) {
+        render_picker(frame, picker, metadata);
+    }
+}
+
+fn metadata_lines(metadata: &PrMetadata) -> Vec<Line<'static>> {
+    let field = |name: &str| {
+        Span::styled(
+            format!("{:<12}", name),
+            Style::default().add_modifier(Modifier::BOLD),
+        )
+    };
+    let none = || Span::styled("None", Style::default().fg(Color::DarkGray));
+    let names = |names: Vec<String>| {
+        if names.is_empty() {
+            none()
+        } else {
+            Span::raw(names.join(", "))
+        }
+    };
+
+    let status = if metadata.draft {
+        Span::styled("Draft", Style::default().fg(Color::DarkGray))
+    } else {
+        Span::styled("Ready for review", Style::default().fg(Color::Green))
+    };
+    let reviewers = metadata
+        .requested_reviewers
+        .iter()
+        .map(|u| format!("@{}", u.login))
+        .chain(
+            metadata
+                .requested_teams
+                .iter()
+                .map(|t| format!("team:{}", t.slug)),
+        )
+        .collect();
+    let assignees = metadata
+        .assignees
+        .iter()
+        .map(|u| format!("@{}", u.login))
+        .collect();
+    let labels = if metadata.labels.is_empty() {
+        vec![none()]
+    } else {
+        metadata
+            .labels
+            .iter()
+            .flat_map(|l| {
+                [
+                    Span::styled(
+                        format!(" {} ", l.name),
+                        Style::default().fg(Color::Black).bg(Color::Cyan),
+                    ),
+                    Span::raw(" "),
+                ]
+            })
+            .collect()
+    };
+    let milestone = match metadata.milestone {
+        Some(ref m) => Span::raw(m.title.clone()),
+        None => none(),
+    };
+
+    vec![
+        Line::from(vec![field("Status"), status]),
+        Line::from(vec![field("Reviewers"), names(reviewers)]),
+        Line::from(vec![field("Assignees"), names(assignees)]),
+        Line::from([vec![field("Labels")], labels].concat()),
+        Line::from(vec![field("Milestone"), milestone]),
+    ]
+}
+
+fn render_picker(frame: &mut Frame, picker: &MetadataPickerState, metadata: &PrMetadata) {
+    let area = frame.area();
+    let width = 50.min(area.width.saturating_sub(4));
+    let height = 16.min(area.height.saturating_sub(4));
+    let popup_area = super::centered_rect(width, height, area);
+    frame.render_widget(Clear, popup_area);
+
+    let title = match picker.kind {
+        MetadataPickerKind::Labels => "Labels",
+        MetadataPickerKind::Reviewers => "Request reviewers",
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Min(0)])
+        .split(inner);
+    let query = Paragraph::new(Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::Cyan)),
+        Span::raw(picker.query.clone()),
+    ]));
+    frame.render_widget(query, rows[0]);
+    render_candidates(frame, rows[1], picker, metadata);
+}
+
+fn render_candidates(
+    frame: &mut Frame,
+    area: Rect,
+    picker: &MetadataPickerState,
+    metadata: &PrMetadata,
+) {
+    let message = match picker.candidates {
+        None => Some(Span::styled(
+            "Loading...",
+            Style::default().fg(Color::Yellow),
+        )),
+        Some(Err(ref e)) => Some(Span::styled(
+            format!("Failed: {}", e),
+            Style::default().fg(Color::Red),
+        )),
+        Some(Ok(_)) => None,
+    };
+    if let Some(message) = message {
+        frame.render_widget(Paragraph::new(Line::from(message)), area);
+        return;
+    }
+
+    let items: Vec<ListItem> = picker
+        .filtered()
+        .into_iter()
+        .map(|item| {
+            let checked = match picker.kind {
+                MetadataPickerKind::Labels => metadata.has_label(item),
+                MetadataPickerKind::Reviewers => metadata.has_requested_reviewer(item),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(
+                    if checked { "✓ " } else { "  " },
+                    Style::default().fg(Color::Green),
+                ),
+                Span::raw(item.to_string()),
+            ]))
+        })
+        .collect();
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .fg(Color::Black)
+            .bg(Color::Cyan)
+            .add_modifier(Modifier::BOLD),
+    );
+    let mut state = ListState::default().with_selected(Some(picker.selected));
+    frame.render_stateful_widget(list, area, &mut state);
+}
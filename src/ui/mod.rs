@@ -1,10 +1,13 @@
 mod ai_rally;
+mod checks;
 mod comment_list;
-mod common;
+mod commit_list;
+pub(crate) mod common;
 pub mod diff_view;
 mod file_list;
 mod footer;
 mod help;
+mod metadata;
 mod pr_list;
 mod split_view;
 pub mod text_area;
@@ -19,7 +22,7 @@ use ratatui::{
     layout::Rect,
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame, Terminal,
 };
 use std::io::{self, Stdout};
@@ -50,8 +53,8 @@ pub fn render(frame: &mut Frame, app: &mut App) {
             file_list::render_loading(frame, app);
             return;
         }
-        if let DataState::Error(ref msg) = app.data_state {
-            file_list::render_error(frame, app, msg);
+        if let DataState::Error(ref error) = app.data_state {
+            file_list::render_error(frame, app, error);
             return;
         }
     }
@@ -65,12 +68,25 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         AppState::Help => help::render(frame, app),
         AppState::AiRally => ai_rally::render(frame, app),
         AppState::SplitViewFileList | AppState::SplitViewDiff => split_view::render(frame, app),
+        AppState::Checks => checks::render(frame, app),
+        AppState::CommitList => commit_list::render(frame, app),
+        AppState::Metadata => metadata::render(frame, app),
     }
 
     // シンボル選択ポップアップ（最前面に描画）
     if let Some(ref popup) = app.symbol_popup {
         render_symbol_popup(frame, popup);
     }
+
+    // リアクション選択ポップアップ
+    if let Some(ref popup) = app.reaction_popup {
+        render_reaction_popup(frame, popup);
+    }
+
+    // マージ方法の選択ダイアログ
+    if let Some(ref dialog) = app.merge_dialog {
+        render_merge_dialog(frame, app, dialog);
+    }
 }
 
 /// 中央配置のフローティングポップアップ領域を計算
@@ -80,6 +96,125 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     Rect::new(x, y, width.min(area.width), height.min(area.height))
 }
 
+/// リアクション選択ポップアップを描画
+fn render_reaction_popup(frame: &mut Frame, popup: &crate::app::ReactionPopupState) {
+    use crate::github::reaction::{ReactionKind, ReactionTarget};
+
+    let area = frame.area();
+    let height = (ReactionKind::ALL.len() as u16 + 2).min(area.height.saturating_sub(4));
+    let width = 44.min(area.width.saturating_sub(4));
+    let popup_area = centered_rect(width, height, area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let items: Vec<ListItem> = ReactionKind::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, kind)| {
+            let style = if i == popup.selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(Span::styled(
+                format!("  {}  {}  {}  ", i + 1, kind.emoji(), kind.content()),
+                style,
+            )))
+        })
+        .collect();
+
+    let title = match popup.target {
+        ReactionTarget::PullRequest(number) => format!("React to PR #{}", number),
+        _ => "React to comment".to_string(),
+    };
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("{} (Enter/1-8: react, Esc: cancel)", title))
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+
+    frame.render_widget(list, popup_area);
+}
+
+/// マージ方法の選択ダイアログを描画
+fn render_merge_dialog(frame: &mut Frame, app: &App, dialog: &crate::app::MergeDialogState) {
+    use crate::github::{MergeMethod, Mergeability};
+
+    let Some(pr) = app.pr() else {
+        return;
+    };
+    // ベースブランチの保護ルール（取得前・ルールなしの場合は出さない）
+    let mut requirements = Vec::new();
+    if let Some(protection) = pr.protection.as_ref() {
+        if protection.required_approving_reviews > 0 {
+            requirements.push(format!(
+                "Requires {} approving review(s)",
+                protection.required_approving_reviews
+            ));
+        }
+        if !protection.required_status_checks.is_empty() {
+            requirements.push(format!(
+                "Required checks: {}",
+                protection.required_status_checks.join(", ")
+            ));
+        }
+    }
+
+    let area = frame.area();
+    let height = (MergeMethod::ALL.len() as u16 + 6 + requirements.len() as u16)
+        .min(area.height.saturating_sub(4));
+    let width = 56.min(area.width.saturating_sub(4));
+    let popup_area = centered_rect(width, height, area);
+
+    frame.render_widget(Clear, popup_area);
+
+    let mergeability = pr.mergeability();
+    let status_color = match mergeability {
+        Mergeability::Clean => Color::Green,
+        Mergeability::Unknown => Color::Yellow,
+        _ if mergeability.can_merge() => Color::Yellow,
+        _ => Color::Red,
+    };
+    let mut lines = vec![Line::from(Span::styled(
+        mergeability.description(),
+        Style::default().fg(status_color),
+    ))];
+    lines.extend(
+        requirements
+            .into_iter()
+            .map(|r| Line::from(Span::styled(r, Style::default().fg(Color::DarkGray)))),
+    );
+    lines.push(Line::from(""));
+    lines.extend(MergeMethod::ALL.iter().enumerate().map(|(i, method)| {
+        let style = if i == dialog.selected {
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::Cyan)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        Line::from(Span::styled(format!("  {}  ", method.label()), style))
+    }));
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        "Enter: merge | a: enable auto-merge | Esc: cancel",
+        Style::default().fg(Color::DarkGray),
+    )));
+
+    let paragraph = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .title(format!("Merge PR #{} into {}", pr.number, pr.base.ref_name))
+            .border_style(Style::default().fg(Color::Cyan)),
+    );
+    frame.render_widget(paragraph, popup_area);
+}
+
 /// シンボル選択ポップアップを描画
 fn render_symbol_popup(frame: &mut Frame, popup: &crate::app::SymbolPopupState) {
     let area = frame.area();
@@ -9,8 +9,9 @@ use ratatui::{
     Frame,
 };
 
+use super::common::check_state_color;
 use crate::app::App;
-use crate::github::PullRequestSummary;
+use crate::github::{PullRequestSummary, ReviewDecision};
 
 pub fn render(frame: &mut Frame, app: &mut App) {
     let chunks = Layout::default()
@@ -22,11 +23,42 @@ pub fn render(frame: &mut Frame, app: &mut App) {
         ])
         .split(frame.area());
 
-    // Header
-    let filter_str = app.pr_list_state_filter.display_name();
-    let header_text = format!("PR List: {} ({})", app.repo, filter_str);
-    let header =
-        Paragraph::new(header_text).block(Block::default().borders(Borders::ALL).title("octorus"));
+    // Header（検索バー入力中はそのまま検索バーになる）
+    let header = if let Some(ref input) = app.pr_list_search_input {
+        Paragraph::new(Line::from(vec![
+            Span::styled("/ ", Style::default().fg(Color::Cyan)),
+            Span::raw(input.clone()),
+            Span::styled("_", Style::default().add_modifier(Modifier::SLOW_BLINK)),
+        ]))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Search (e.g. review-requested:@me author:alice label:bug)")
+                .border_style(Style::default().fg(Color::Cyan)),
+        )
+    } else {
+        let query = &app.pr_list_query;
+        let scope = if query.inbox {
+            "Review inbox".to_string()
+        } else {
+            format!("PR List: {}", app.repo)
+        };
+        let mut spans = vec![Span::raw(format!(
+            "{} ({}, sort: {})",
+            scope,
+            query.state.display_name(),
+            query.sort.display_name()
+        ))];
+        if !query.search.is_empty() {
+            spans.push(Span::raw("  "));
+            spans.push(Span::styled(
+                format!("/ {}", query.search),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+        Paragraph::new(Line::from(spans))
+            .block(Block::default().borders(Borders::ALL).title("octorus"))
+    };
     frame.render_widget(header, chunks[0]);
 
     // PR list
@@ -48,12 +80,19 @@ pub fn render(frame: &mut Frame, app: &mut App) {
             frame.render_widget(empty, chunks[1]);
         } else {
             let total_prs = prs.len();
-            let items = build_pr_list_items(prs, app.selected_pr);
+            let items = build_pr_list_items(prs, app.selected_pr, app.pr_list_query.inbox);
 
             let title = if app.pr_list_loading {
                 format!("Pull Requests ({}) {}", total_prs, app.spinner_char())
             } else if app.pr_list_has_more {
                 format!("Pull Requests ({}+)", total_prs)
+            } else if let Some(total) = app.pr_list_truncated_total() {
+                format!(
+                    "Pull Requests ({} of {} - search is limited to the first {}; narrow the filter)",
+                    total_prs,
+                    total,
+                    crate::github::SEARCH_RESULT_LIMIT
+                )
             } else {
                 format!("Pull Requests ({})", total_prs)
             };
@@ -101,13 +140,29 @@ pub fn render(frame: &mut Frame, app: &mut App) {
     }
 
     // Footer
-    let footer_text =
-        "j/k/↑↓: move | Enter: select | gg/G: top/bottom | o: open | c: closed | a: all | r: refresh | q: quit | ?: help";
+    let footer_text = if app.pr_list_search_input.is_some() {
+        "Enter: search | Esc: cancel"
+    } else {
+        "j/k/↑↓: move | Enter: select | gg/G: top/bottom | o/c/a: open/closed/all | /: search | n: needs my review | m: my PRs | s: sort | r: refresh | q: quit | ?: help"
+    };
     let footer = Paragraph::new(footer_text).block(Block::default().borders(Borders::ALL));
     frame.render_widget(footer, chunks[2]);
 }
 
-fn build_pr_list_items(prs: &[PullRequestSummary], selected: usize) -> Vec<ListItem<'static>> {
+fn build_pr_list_items(
+    prs: &[PullRequestSummary],
+    selected: usize,
+    show_repo: bool,
+) -> Vec<ListItem<'static>> {
+    // リポジトリ列の幅は表示中の PR の最長に合わせる
+    let repo_width = prs
+        .iter()
+        .filter_map(|pr| pr.repo.as_ref())
+        .map(|r| r.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(30);
+
     prs.iter()
         .enumerate()
         .map(|(i, pr)| {
@@ -164,20 +219,72 @@ fn build_pr_list_items(prs: &[PullRequestSummary], selected: usize) -> Vec<ListI
             };
             let labels_span = Span::styled(labels_str, Style::default().fg(Color::Blue));
 
-            let line = Line::from(vec![
+            // CI status / review decision（検索結果に含まれない場合は空欄）
+            let ci_span = match pr.ci_state() {
+                Some(state) => Span::styled(
+                    format!("{} ", state.icon()),
+                    Style::default().fg(check_state_color(state)),
+                ),
+                None => Span::raw("  "),
+            };
+            let review_span = match pr.review_decision {
+                Some(decision) => Span::styled(
+                    format!("{} ", decision.icon()),
+                    Style::default().fg(review_decision_color(decision)),
+                ),
+                None => Span::raw("  "),
+            };
+
+            // Size (+additions/-deletions)
+            let size_spans = match (pr.additions, pr.deletions) {
+                (Some(additions), Some(deletions)) => {
+                    let added = format!("+{}", additions);
+                    let deleted = format!("-{}", deletions);
+                    let padding = 12usize.saturating_sub(added.len() + deleted.len() + 1);
+                    vec![
+                        Span::styled(added, Style::default().fg(Color::Green)),
+                        Span::raw("/"),
+                        Span::styled(deleted, Style::default().fg(Color::Red)),
+                        Span::raw(" ".repeat(padding + 2)),
+                    ]
+                }
+                _ => vec![],
+            };
+
+            let mut spans = Vec::new();
+            if show_repo {
+                let repo = truncate_string(pr.repo.as_deref().unwrap_or(""), repo_width);
+                spans.push(Span::styled(
+                    format!("{:<width$}  ", repo, width = repo_width),
+                    Style::default().fg(Color::Magenta),
+                ));
+            }
+            spans.extend([
                 number_span,
-                Span::raw("  "),
+                Span::raw(" "),
+                ci_span,
+                review_span,
                 title_span,
                 Span::raw("  "),
-                author_span,
-                labels_span,
             ]);
+            spans.extend(size_spans);
+            spans.push(author_span);
+            spans.push(labels_span);
+            let line = Line::from(spans);
 
             ListItem::new(line)
         })
         .collect()
 }
 
+fn review_decision_color(decision: ReviewDecision) -> Color {
+    match decision {
+        ReviewDecision::Approved => Color::Green,
+        ReviewDecision::ChangesRequested => Color::Red,
+        ReviewDecision::ReviewRequired => Color::Yellow,
+    }
+}
+
 /// Truncate a string to fit within a given width, respecting char boundaries
 fn truncate_string(s: &str, max_width: usize) -> String {
     if s.chars().count() <= max_width {
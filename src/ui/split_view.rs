@@ -9,9 +9,11 @@ use ratatui::{
     Frame,
 };
 
-use super::common::render_rally_status_bar;
+use super::common::{
+    checks_badge, comment_body_lines, commit_scope_badge, render_rally_status_bar, viewed_badge,
+};
 use super::diff_view;
-use super::file_list::build_file_list_items;
+use super::file_list::{build_file_list_items, file_list_title};
 use crate::app::{App, AppState, DataState};
 
 pub fn render(frame: &mut Frame, app: &mut App) {
@@ -82,7 +84,19 @@ fn render_file_list_pane(
         },
     };
 
-    let header = Paragraph::new(pr_info).block(
+    let mut header_spans = vec![Span::raw(pr_info)];
+    for badge in [
+        viewed_badge(app),
+        commit_scope_badge(app),
+        checks_badge(app),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        header_spans.push(Span::raw("  "));
+        header_spans.push(badge);
+    }
+    let header = Paragraph::new(Line::from(header_spans)).block(
         Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(border_color))
@@ -93,14 +107,15 @@ fn render_file_list_pane(
     // File list
     let files = app.files();
     let total_files = files.len();
-    let items = build_file_list_items(files, app.selected_file);
+    let viewed = app.viewed_files.as_ref().map(|v| &v.paths);
+    let items = build_file_list_items(files, app.selected_file, viewed);
 
     let list = List::new(items)
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(border_color))
-                .title(format!("Files ({})", total_files)),
+                .title(file_list_title("Files", total_files, app.files_truncated)),
         )
         .highlight_style(Style::default().bg(Color::DarkGray));
 
@@ -187,7 +202,9 @@ fn render_diff_pane_normal(
     render_diff_body(frame, app, chunks[1], border_color);
 
     // Footer
-    let footer_text = if is_focused {
+    let footer_text = if is_focused && app.visual_anchor.is_some() {
+        "-- VISUAL -- j/k/↑↓: extend | c: comment range | s: suggest range | v/Esc: cancel"
+    } else if is_focused {
         "j/k/↑↓: scroll | n/N: next/prev comment | Enter: comments | →/l: fullscreen | ←/h: files | q: back"
     } else {
         "Enter/→: focus diff"
@@ -264,12 +281,11 @@ fn render_diff_pane_with_comments(
                 ),
             ]));
 
-            for line in comment.body.lines() {
-                lines.push(Line::from(line.to_string()));
-            }
+            lines.extend(comment_body_lines(app, comment));
             lines.push(Line::from(""));
         }
     }
+    lines.extend(diff_view::annotation_panel_lines(app));
 
     let title = "Comments (j/k/↑↓: scroll, c: comment, s: suggest, r: reply)";
     let total_lines = lines.len();
@@ -351,8 +367,9 @@ fn render_diff_body(
         diff_view::render_cached_lines(
             cache,
             visible_start..visible_end,
-            app.selected_line,
+            app.selected_line_range(),
             &app.file_comment_lines,
+            &app.file_annotation_lines,
         )
     } else {
         let file = app.files().get(app.selected_file);